@@ -0,0 +1,619 @@
+use crate::debug_log;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+
+/// Placeholder in a keyword bookmark's URL that is replaced by the text typed after the keyword.
+const KEYWORD_PLACEHOLDER: &str = "%s";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: u64,
+    pub url: String,
+    pub title: String,
+    /// Folder holding the bookmark, or `None` at the top level.
+    #[serde(default, rename = "folderId")]
+    pub folder_id: Option<u64>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: String,
+    /// Shortcut typed in the command prompt to open the bookmark, e.g. `gh rust`.
+    #[serde(default)]
+    pub keyword: Option<String>,
+    #[serde(rename = "addedAt")]
+    pub added_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkFolder {
+    pub id: u64,
+    pub name: String,
+    #[serde(default, rename = "parentId")]
+    pub parent_id: Option<u64>,
+}
+
+/// A folder or bookmark in a nested tree, the shape bookmarks are imported and exported in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BookmarkNode {
+    Folder {
+        name: String,
+        #[serde(default)]
+        children: Vec<BookmarkNode>,
+    },
+    Bookmark {
+        url: String,
+        #[serde(default)]
+        title: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        notes: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        keyword: Option<String>,
+        /// Seconds since the Unix epoch, or 0 when unknown.
+        #[serde(default, rename = "addedAt")]
+        added_at: i64,
+    },
+}
+
+/// Fields of a bookmark that can be edited after it was added.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BookmarkEdit {
+    pub url: Option<String>,
+    pub title: Option<String>,
+    /// `Some(None)` moves the bookmark to the top level.
+    #[serde(default, rename = "folderId", deserialize_with = "deserialize_some")]
+    pub folder_id: Option<Option<u64>>,
+    pub tags: Option<Vec<String>>,
+    pub notes: Option<String>,
+    pub keyword: Option<String>,
+}
+
+/// Distinguishes a `null` field, which deserializes to `Some(None)`, from a missing one.
+fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Bookmarks and their folder tree, shared by all windows and stored in `~/.calm/bookmarks.yml`.
+/// Folders and bookmarks are kept flat and point at their parent folder.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Bookmarks {
+    #[serde(default)]
+    folders: Vec<BookmarkFolder>,
+    #[serde(default)]
+    bookmarks: Vec<Bookmark>,
+    #[serde(default)]
+    next_id: u64,
+}
+
+thread_local! {
+    static BOOKMARKS: RefCell<Bookmarks> = RefCell::new(Bookmarks::load());
+}
+
+/// Runs `f` against the bookmarks shared by all windows.
+pub fn with_bookmarks<R>(f: impl FnOnce(&Bookmarks) -> R) -> R {
+    BOOKMARKS.with(|store| f(&store.borrow()))
+}
+
+/// Runs `f` to change the bookmarks shared by all windows, then saves them.
+pub fn update_bookmarks<R>(f: impl FnOnce(&mut Bookmarks) -> R) -> R {
+    BOOKMARKS.with(|store| {
+        let mut store = store.borrow_mut();
+        let result = f(&mut store);
+        if let Err(e) = store.save() {
+            debug_log!("Failed to save bookmarks: {}", e);
+        }
+        result
+    })
+}
+
+impl Bookmarks {
+    pub fn load() -> Self {
+        let path = Self::get_path();
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => serde_yaml::from_str::<Bookmarks>(&contents).unwrap_or_else(|e| {
+                debug_log!("Failed to parse {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::get_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let yaml = serde_yaml::to_string(self)?;
+        fs::write(path, yaml)?;
+        Ok(())
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Bookmarks `url` in `folder_id`, or at the top level if the folder does not exist.
+    /// Returns the id of the new bookmark.
+    pub fn add(&mut self, url: &str, title: &str, folder_id: Option<u64>) -> u64 {
+        let id = self.next_id();
+        let folder_id = folder_id.filter(|&folder_id| self.folder(folder_id).is_some());
+        self.bookmarks.push(Bookmark {
+            id,
+            url: url.to_string(),
+            title: title.to_string(),
+            folder_id,
+            tags: Vec::new(),
+            notes: String::new(),
+            keyword: None,
+            added_at: unix_now(),
+        });
+        id
+    }
+
+    /// Applies `edit` to a bookmark. A keyword already used by another bookmark is moved to this
+    /// one. Returns whether the bookmark exists.
+    pub fn update(&mut self, id: u64, edit: BookmarkEdit) -> bool {
+        let keyword = edit.keyword.as_deref().map(normalize_keyword);
+        let folder_id = edit
+            .folder_id
+            .map(|folder_id| folder_id.filter(|&folder_id| self.folder(folder_id).is_some()));
+
+        if let Some(Some(ref keyword)) = keyword {
+            for bookmark in self.bookmarks.iter_mut().filter(|b| b.id != id) {
+                if bookmark.keyword.as_ref() == Some(keyword) {
+                    bookmark.keyword = None;
+                }
+            }
+        }
+
+        let Some(bookmark) = self.bookmarks.iter_mut().find(|b| b.id == id) else {
+            return false;
+        };
+        if let Some(url) = edit.url.filter(|url| !url.trim().is_empty()) {
+            bookmark.url = url.trim().to_string();
+        }
+        if let Some(title) = edit.title {
+            bookmark.title = title.trim().to_string();
+        }
+        if let Some(folder_id) = folder_id {
+            bookmark.folder_id = folder_id;
+        }
+        if let Some(tags) = edit.tags {
+            bookmark.tags = normalize_tags(&tags);
+        }
+        if let Some(notes) = edit.notes {
+            bookmark.notes = notes;
+        }
+        if let Some(keyword) = keyword {
+            bookmark.keyword = keyword;
+        }
+
+        true
+    }
+
+    /// Removes a bookmark. Returns whether it existed.
+    pub fn remove(&mut self, id: u64) -> bool {
+        let count = self.bookmarks.len();
+        self.bookmarks.retain(|bookmark| bookmark.id != id);
+        self.bookmarks.len() != count
+    }
+
+    /// Creates a folder inside `parent_id`, or at the top level. Returns the id of the new folder.
+    pub fn add_folder(&mut self, name: &str, parent_id: Option<u64>) -> u64 {
+        let id = self.next_id();
+        let parent_id = parent_id.filter(|&parent_id| self.folder(parent_id).is_some());
+        let name = name.trim();
+        self.folders.push(BookmarkFolder {
+            id,
+            name: if name.is_empty() { "New folder" } else { name }.to_string(),
+            parent_id,
+        });
+        id
+    }
+
+    pub fn rename_folder(&mut self, id: u64, name: &str) -> bool {
+        let name = name.trim();
+        match self.folders.iter_mut().find(|folder| folder.id == id) {
+            Some(folder) if !name.is_empty() => {
+                folder.name = name.to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Moves a folder into `parent_id`, refusing to move it into itself or one of its subfolders.
+    pub fn move_folder(&mut self, id: u64, parent_id: Option<u64>) -> bool {
+        if let Some(parent_id) = parent_id {
+            if self.folder(parent_id).is_none() || self.folder_subtree(id).contains(&parent_id) {
+                return false;
+            }
+        }
+        match self.folders.iter_mut().find(|folder| folder.id == id) {
+            Some(folder) => {
+                folder.parent_id = parent_id;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes a folder with its subfolders and every bookmark they hold.
+    /// Returns the number of bookmarks removed.
+    pub fn remove_folder(&mut self, id: u64) -> usize {
+        let subtree = self.folder_subtree(id);
+        if subtree.is_empty() {
+            return 0;
+        }
+
+        let count = self.bookmarks.len();
+        self.bookmarks
+            .retain(|bookmark| !bookmark.folder_id.is_some_and(|folder_id| subtree.contains(&folder_id)));
+        self.folders.retain(|folder| !subtree.contains(&folder.id));
+        count - self.bookmarks.len()
+    }
+
+    /// Returns the id of a folder followed by those of all its subfolders, or nothing if it
+    /// does not exist.
+    fn folder_subtree(&self, id: u64) -> Vec<u64> {
+        if self.folder(id).is_none() {
+            return Vec::new();
+        }
+
+        let mut subtree = vec![id];
+        let mut index = 0;
+        while index < subtree.len() {
+            let parent = subtree[index];
+            subtree.extend(
+                self.folders
+                    .iter()
+                    .filter(|folder| folder.parent_id == Some(parent))
+                    .map(|folder| folder.id),
+            );
+            index += 1;
+        }
+        subtree
+    }
+
+    fn folder(&self, id: u64) -> Option<&BookmarkFolder> {
+        self.folders.iter().find(|folder| folder.id == id)
+    }
+
+    pub fn find_by_url(&self, url: &str) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|bookmark| bookmark.url == url)
+    }
+
+    /// Expands command prompt input starting with a bookmark keyword, e.g. `gh rust`, into the
+    /// bookmark's URL with `%s` replaced by the encoded rest of the input.
+    pub fn expand_keyword(&self, input: &str) -> Option<String> {
+        let input = input.trim();
+        let (keyword, terms) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let keyword = keyword.to_lowercase();
+        let bookmark = self
+            .bookmarks
+            .iter()
+            .find(|bookmark| bookmark.keyword.as_deref() == Some(keyword.as_str()))?;
+
+        if bookmark.url.contains(KEYWORD_PLACEHOLDER) {
+            Some(
+                bookmark
+                    .url
+                    .replace(KEYWORD_PLACEHOLDER, &urlencoding::encode(terms.trim())),
+            )
+        } else if terms.trim().is_empty() {
+            Some(bookmark.url.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Returns bookmarks matching `query` in their URL, title, tags or keyword, with how well
+    /// they match on the scale of `history::match_quality`. Keyword bookmarks with a `%s`
+    /// placeholder are left out as they only make sense with search terms.
+    pub fn search(&self, query: &str) -> Vec<(Bookmark, f64)> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        self.bookmarks
+            .iter()
+            .filter(|bookmark| !bookmark.url.contains(KEYWORD_PLACEHOLDER))
+            .filter_map(|bookmark| {
+                let quality = if bookmark.keyword.as_deref() == Some(query.as_str()) {
+                    Some(4.0)
+                } else if bookmark.tags.iter().any(|tag| tag.starts_with(&query)) {
+                    Some(2.0)
+                } else {
+                    crate::history::match_quality(&bookmark.url, &bookmark.title, &query)
+                };
+                quality.map(|quality| (bookmark.clone(), quality))
+            })
+            .collect()
+    }
+
+    /// Adds `nodes` inside a new top-level folder named `folder_name`, skipping URLs that are
+    /// already bookmarked. Imported keywords are only kept when no other bookmark uses them.
+    /// Returns the number of folders and bookmarks added.
+    pub fn import(&mut self, folder_name: &str, nodes: Vec<BookmarkNode>) -> (usize, usize) {
+        let folder_count = self.folders.len();
+        let bookmark_count = self.bookmarks.len();
+
+        let root = self.next_id();
+        self.folders.push(BookmarkFolder {
+            id: root,
+            name: folder_name.to_string(),
+            parent_id: None,
+        });
+        self.import_nodes(nodes, root);
+
+        if self.bookmarks.len() == bookmark_count {
+            self.folders.truncate(folder_count);
+            return (0, 0);
+        }
+        (self.folders.len() - folder_count, self.bookmarks.len() - bookmark_count)
+    }
+
+    fn import_nodes(&mut self, nodes: Vec<BookmarkNode>, folder_id: u64) {
+        for node in nodes {
+            match node {
+                BookmarkNode::Folder { name, children } => {
+                    let id = self.next_id();
+                    let name = name.trim();
+                    self.folders.push(BookmarkFolder {
+                        id,
+                        name: if name.is_empty() { "Untitled folder" } else { name }.to_string(),
+                        parent_id: Some(folder_id),
+                    });
+                    self.import_nodes(children, id);
+                }
+                BookmarkNode::Bookmark {
+                    url,
+                    title,
+                    tags,
+                    notes,
+                    keyword,
+                    added_at,
+                } => {
+                    if self.find_by_url(&url).is_some() {
+                        continue;
+                    }
+                    let keyword = keyword.as_deref().and_then(normalize_keyword).filter(|keyword| {
+                        !self
+                            .bookmarks
+                            .iter()
+                            .any(|bookmark| bookmark.keyword.as_ref() == Some(keyword))
+                    });
+                    let id = self.next_id();
+                    self.bookmarks.push(Bookmark {
+                        id,
+                        url,
+                        title: title.trim().to_string(),
+                        folder_id: Some(folder_id),
+                        tags: normalize_tags(&tags),
+                        notes,
+                        keyword,
+                        added_at: if added_at > 0 { added_at } else { unix_now() },
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns the bookmarks as a tree of nested folders, folders first at every level.
+    pub fn to_tree(&self) -> Vec<BookmarkNode> {
+        self.subtree(None)
+    }
+
+    fn subtree(&self, folder_id: Option<u64>) -> Vec<BookmarkNode> {
+        let folders = self
+            .folders
+            .iter()
+            .filter(|folder| folder.parent_id == folder_id)
+            .map(|folder| BookmarkNode::Folder {
+                name: folder.name.clone(),
+                children: self.subtree(Some(folder.id)),
+            });
+        let bookmarks = self
+            .bookmarks
+            .iter()
+            .filter(|bookmark| bookmark.folder_id == folder_id)
+            .map(|bookmark| BookmarkNode::Bookmark {
+                url: bookmark.url.clone(),
+                title: bookmark.title.clone(),
+                tags: bookmark.tags.clone(),
+                notes: bookmark.notes.clone(),
+                keyword: bookmark.keyword.clone(),
+                added_at: bookmark.added_at,
+            });
+        folders.chain(bookmarks).collect()
+    }
+
+    /// Returns the folders and bookmarks as JSON for the bookmarks panel.
+    pub fn to_json(&self) -> String {
+        serde_json::json!({
+            "folders": self.folders,
+            "bookmarks": self.bookmarks,
+        })
+        .to_string()
+    }
+
+    fn get_path() -> PathBuf {
+        crate::paths::data_dir().join("bookmarks.yml")
+    }
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// Lowercases a keyword and drops it when empty. Keywords are a single word.
+fn normalize_keyword(keyword: &str) -> Option<String> {
+    let keyword = keyword.split_whitespace().next()?.to_lowercase();
+    Some(keyword)
+}
+
+/// Trims and lowercases tags, dropping empty and repeated ones.
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_expansion() {
+        let mut store = Bookmarks::default();
+        let id = store.add("https://github.com/search?q=%s", "GitHub search", None);
+        store.update(
+            id,
+            BookmarkEdit {
+                keyword: Some("GH".to_string()),
+                ..Default::default()
+            },
+        );
+        let plain = store.add("https://news.ycombinator.com", "HN", None);
+        store.update(
+            plain,
+            BookmarkEdit {
+                keyword: Some("hn".to_string()),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            store.expand_keyword("gh rust lang").as_deref(),
+            Some("https://github.com/search?q=rust%20lang")
+        );
+        assert_eq!(store.expand_keyword("hn").as_deref(), Some("https://news.ycombinator.com"));
+        assert_eq!(store.expand_keyword("hn rust"), None);
+        assert_eq!(store.expand_keyword("github.com"), None);
+    }
+
+    #[test]
+    fn test_keyword_moves_to_latest_bookmark() {
+        let mut store = Bookmarks::default();
+        let first = store.add("https://a.example/%s", "A", None);
+        let second = store.add("https://b.example/%s", "B", None);
+        for id in [first, second] {
+            store.update(
+                id,
+                BookmarkEdit {
+                    keyword: Some("x".to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+
+        assert_eq!(store.expand_keyword("x 1").as_deref(), Some("https://b.example/1"));
+        assert!(store.bookmarks.iter().find(|b| b.id == first).unwrap().keyword.is_none());
+    }
+
+    #[test]
+    fn test_remove_folder_removes_subtree() {
+        let mut store = Bookmarks::default();
+        let work = store.add_folder("Work", None);
+        let docs = store.add_folder("Docs", Some(work));
+        let other = store.add_folder("Other", None);
+        store.add("https://a.example", "A", Some(docs));
+        store.add("https://b.example", "B", Some(work));
+        store.add("https://c.example", "C", Some(other));
+
+        assert!(!store.move_folder(work, Some(docs)));
+        assert_eq!(store.remove_folder(work), 2);
+        assert_eq!(store.folders.len(), 1);
+        assert!(store.find_by_url("https://c.example").is_some());
+    }
+
+    #[test]
+    fn test_import_skips_known_urls_and_round_trips() {
+        let mut store = Bookmarks::default();
+        let id = store.add("https://a.example", "A", None);
+        store.update(
+            id,
+            BookmarkEdit {
+                keyword: Some("a".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let nodes = vec![
+            BookmarkNode::Folder {
+                name: "Toolbar".to_string(),
+                children: vec![BookmarkNode::Bookmark {
+                    url: "https://b.example".to_string(),
+                    title: "B".to_string(),
+                    tags: vec!["Docs".to_string()],
+                    notes: String::new(),
+                    keyword: Some("a".to_string()),
+                    added_at: 1_600_000_000,
+                }],
+            },
+            BookmarkNode::Bookmark {
+                url: "https://a.example".to_string(),
+                title: "Duplicate".to_string(),
+                tags: Vec::new(),
+                notes: String::new(),
+                keyword: None,
+                added_at: 0,
+            },
+        ];
+        assert_eq!(store.import("Imported", nodes.clone()), (2, 1));
+        assert_eq!(store.import("Imported", nodes), (0, 0));
+        assert_eq!(store.folders.len(), 2);
+
+        let imported = store.find_by_url("https://b.example").unwrap();
+        assert_eq!(imported.keyword, None);
+        assert_eq!(imported.tags, vec!["docs"]);
+        assert_eq!(imported.added_at, 1_600_000_000);
+
+        let tree = store.to_tree();
+        let BookmarkNode::Folder { name, children } = &tree[0] else {
+            panic!("expected the imported folder first");
+        };
+        assert_eq!(name, "Imported");
+        assert!(matches!(&children[0], BookmarkNode::Folder { name, children } if name == "Toolbar" && children.len() == 1));
+        assert!(matches!(&tree[1], BookmarkNode::Bookmark { url, .. } if url == "https://a.example"));
+    }
+
+    #[test]
+    fn test_update_normalizes_tags_and_folder() {
+        let mut store = Bookmarks::default();
+        let folder = store.add_folder("Reading", None);
+        let id = store.add("https://a.example", "A", None);
+        let edit: BookmarkEdit =
+            serde_json::from_str(&format!(r#"{{"folderId": {}, "tags": [" Rust", "rust", ""]}}"#, folder)).unwrap();
+        assert!(store.update(id, edit));
+
+        let bookmark = store.find_by_url("https://a.example").unwrap();
+        assert_eq!(bookmark.folder_id, Some(folder));
+        assert_eq!(bookmark.tags, vec!["rust"]);
+
+        let edit: BookmarkEdit = serde_json::from_str(r#"{"folderId": null}"#).unwrap();
+        store.update(id, edit);
+        assert_eq!(store.find_by_url("https://a.example").unwrap().folder_id, None);
+        assert!(!store.update(999, BookmarkEdit::default()));
+    }
+}
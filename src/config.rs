@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -42,6 +43,34 @@ pub struct PrivacySettings {
     pub font_enumeration_restriction: bool,
     #[serde(default = "default_true")]
     pub adblock_enabled: bool,
+    /// Adblock Plus or uBlock Origin filter list files, such as a downloaded EasyList, whose
+    /// network rules are enforced when `adblock_enabled` is set.
+    #[serde(default)]
+    pub filter_lists: Vec<String>,
+    /// Filter lists downloaded from the web and kept up to date, enforced alongside
+    /// `filter_lists`.
+    #[serde(default)]
+    pub filter_subscriptions: Vec<FilterSubscription>,
+    /// Hours between two updates of a subscribed filter list.
+    #[serde(default = "default_filter_update_interval_hours")]
+    pub filter_update_interval_hours: u64,
+    /// Defenses turned on or off for particular sites, keyed by host and then by setting name,
+    /// as in `github.com: { webgl_fingerprint_protection: false }`. A host also covers its
+    /// subdomains, and the most specific host wins.
+    #[serde(default)]
+    pub site_overrides: BTreeMap<String, BTreeMap<String, bool>>,
+}
+
+/// A filter list Calm downloads from `url`, such as EasyList.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterSubscription {
+    pub url: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_filter_update_interval_hours() -> u64 {
+    24
 }
 
 /// Default value function for boolean fields (returns true).
@@ -69,8 +98,16 @@ pub struct KeyboardShortcuts {
     pub find: String,
     #[serde(default = "default_shortcut_new_window")]
     pub new_window: String,
+    #[serde(default = "default_shortcut_new_private_window")]
+    pub new_private_window: String,
     #[serde(default = "default_shortcut_toggle_split_view")]
     pub toggle_split_view: String,
+    #[serde(default = "default_shortcut_reopen_closed_tab")]
+    pub reopen_closed_tab: String,
+    #[serde(default = "default_shortcut_bookmark_page")]
+    pub bookmark_page: String,
+    #[serde(default = "default_shortcut_toggle_bookmarks")]
+    pub toggle_bookmarks: String,
 }
 
 fn default_shortcut_new_tab() -> String {
@@ -105,10 +142,26 @@ fn default_shortcut_new_window() -> String {
     "Cmd+N".to_string()
 }
 
+fn default_shortcut_new_private_window() -> String {
+    "Cmd+Shift+N".to_string()
+}
+
 fn default_shortcut_toggle_split_view() -> String {
     "Cmd+Shift+S".to_string()
 }
 
+fn default_shortcut_reopen_closed_tab() -> String {
+    "Cmd+Shift+T".to_string()
+}
+
+fn default_shortcut_bookmark_page() -> String {
+    "Cmd+D".to_string()
+}
+
+fn default_shortcut_toggle_bookmarks() -> String {
+    "Cmd+Shift+B".to_string()
+}
+
 fn normalize_shortcut(value: &str, default: &str) -> String {
     if value.trim().is_empty() {
         default.to_string()
@@ -129,10 +182,22 @@ impl KeyboardShortcuts {
             normalize_shortcut(&self.focus_sidebar, &default_shortcut_focus_sidebar());
         self.find = normalize_shortcut(&self.find, &default_shortcut_find());
         self.new_window = normalize_shortcut(&self.new_window, &default_shortcut_new_window());
+        self.new_private_window = normalize_shortcut(
+            &self.new_private_window,
+            &default_shortcut_new_private_window(),
+        );
         self.toggle_split_view = normalize_shortcut(
             &self.toggle_split_view,
             &default_shortcut_toggle_split_view(),
         );
+        self.reopen_closed_tab = normalize_shortcut(
+            &self.reopen_closed_tab,
+            &default_shortcut_reopen_closed_tab(),
+        );
+        self.bookmark_page =
+            normalize_shortcut(&self.bookmark_page, &default_shortcut_bookmark_page());
+        self.toggle_bookmarks =
+            normalize_shortcut(&self.toggle_bookmarks, &default_shortcut_toggle_bookmarks());
     }
 }
 
@@ -147,7 +212,11 @@ impl Default for KeyboardShortcuts {
             focus_sidebar: default_shortcut_focus_sidebar(),
             find: default_shortcut_find(),
             new_window: default_shortcut_new_window(),
+            new_private_window: default_shortcut_new_private_window(),
             toggle_split_view: default_shortcut_toggle_split_view(),
+            reopen_closed_tab: default_shortcut_reopen_closed_tab(),
+            bookmark_page: default_shortcut_bookmark_page(),
+            toggle_bookmarks: default_shortcut_toggle_bookmarks(),
         }
     }
 }
@@ -167,6 +236,8 @@ pub struct PerformanceSettings {
     pub session_save_interval_seconds: u64,
     #[serde(default = "default_max_memory_per_tab")]
     pub max_memory_per_tab_mb: usize,
+    #[serde(default = "default_min_available_memory")]
+    pub min_available_memory_mb: usize,
 }
 
 fn default_suspension_timeout() -> u64 {
@@ -181,6 +252,10 @@ fn default_max_memory_per_tab() -> usize {
     512
 }
 
+fn default_min_available_memory() -> usize {
+    512
+}
+
 impl Default for PerformanceSettings {
     fn default() -> Self {
         PerformanceSettings {
@@ -190,6 +265,7 @@ impl Default for PerformanceSettings {
             session_restore: true,
             session_save_interval_seconds: 30,
             max_memory_per_tab_mb: 512,
+            min_available_memory_mb: 512,
         }
     }
 }
@@ -244,12 +320,63 @@ impl Default for PrivacySettings {
             audio_fingerprint_protection: true,
             font_enumeration_restriction: true,
             adblock_enabled: true,
+            filter_lists: Vec::new(),
+            filter_subscriptions: Vec::new(),
+            filter_update_interval_hours: default_filter_update_interval_hours(),
+            site_overrides: BTreeMap::new(),
         }
     }
 }
 
+/// Rules deciding which visits are kept in browsing history and for how long.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistorySettings {
+    /// Host patterns that are never recorded. A plain host such as `bank.com` also covers its
+    /// subdomains, and `*` matches any run of characters, as in `intranet.*`.
+    #[serde(default)]
+    pub excluded_hosts: Vec<String>,
+    /// Visits older than this many days are pruned. 0 keeps history forever.
+    #[serde(default)]
+    pub max_age_days: u64,
+    /// Records pages without their query string and fragment, keeping only the page and its title.
+    #[serde(default = "default_false")]
+    pub strip_query_strings: bool,
+}
+
+/// A named set of cookies and site data that tabs can be opened in, isolated from the rest of
+/// the profile and from other containers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerDefinition {
+    pub name: String,
+    /// Color of the stripe on the container's tabs, one of the tab group colors.
+    #[serde(default = "default_container_color")]
+    pub color: String,
+}
+
+/// Opens pages on matching hosts in a container. `host` is matched like the excluded hosts of
+/// [`HistorySettings`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerRule {
+    pub host: String,
+    pub container: String,
+}
+
+fn default_container_color() -> String {
+    "grey".to_string()
+}
+
+fn default_containers() -> Vec<ContainerDefinition> {
+    [("Personal", "blue"), ("Work", "orange"), ("Throwaway", "red")]
+        .into_iter()
+        .map(|(name, color)| ContainerDefinition {
+            name: name.to_string(),
+            color: color.to_string(),
+        })
+        .collect()
+}
+
 /// Main configuration structure for the Calm browser.
-/// Contains search engine, default URL, privacy settings, UI settings, and redirect settings.
+/// Contains search engine, default URL, privacy settings, UI settings, history rules, containers, and redirect settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default = "default_search_engine")]
@@ -264,6 +391,12 @@ pub struct Config {
     pub ui: UiSettings,
     #[serde(default)]
     pub performance: PerformanceSettings,
+    #[serde(default)]
+    pub history: HistorySettings,
+    #[serde(default = "default_containers")]
+    pub containers: Vec<ContainerDefinition>,
+    #[serde(default)]
+    pub container_rules: Vec<ContainerRule>,
     #[serde(default = "default_false")]
     pub redirect_youtube_to_invidious: bool,
     #[serde(default = "default_invidious_instance")]
@@ -299,6 +432,9 @@ impl Default for Config {
             privacy: PrivacySettings::default(),
             ui: UiSettings::default(),
             performance: PerformanceSettings::default(),
+            history: HistorySettings::default(),
+            containers: default_containers(),
+            container_rules: Vec::new(),
             redirect_youtube_to_invidious: false,
             invidious_instance: default_invidious_instance(),
         }
@@ -306,7 +442,7 @@ impl Default for Config {
 }
 
 impl Config {
-    /// Loads configuration from ~/.calm.yml file.
+    /// Loads the configuration of the current profile (`~/.calm.yml` for the default profile).
     /// Creates a default configuration file if one doesn't exist.
     pub fn load() -> Self {
         let config_path = Self::get_config_path();
@@ -314,6 +450,7 @@ impl Config {
         if let Ok(contents) = fs::read_to_string(&config_path) {
             if let Ok(mut config) = serde_yaml::from_str::<Config>(&contents) {
                 config.ui.shortcuts.normalize();
+                crate::tabs::containers::remove_colliding(&mut config.containers);
                 return config;
             }
         }
@@ -323,18 +460,20 @@ impl Config {
         default_config
     }
 
-    /// Saves the current configuration to ~/.calm.yml file.
+    /// Saves the current configuration to the current profile's configuration file.
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
         let config_path = Self::get_config_path();
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
         let yaml = serde_yaml::to_string(self)?;
         fs::write(config_path, yaml)?;
         Ok(())
     }
 
-    /// Returns the path to the configuration file (~/.calm.yml).
+    /// Returns the path to the configuration file of the current profile.
     fn get_config_path() -> PathBuf {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        PathBuf::from(home).join(".calm.yml")
+        crate::paths::config_file()
     }
 
     /// Formats a search query into a complete search engine URL.
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +18,10 @@ pub struct DownloadEntry {
 pub struct DownloadHistory {
     pub downloads: Vec<DownloadEntry>,
     pub next_id: usize,
+    /// Whether changes are written to disk. Only the history loaded from disk is, so downloads
+    /// made in private windows are never recorded.
+    #[serde(skip)]
+    persist: bool,
 }
 
 impl Default for DownloadHistory {
@@ -25,30 +29,40 @@ impl Default for DownloadHistory {
         Self {
             downloads: Vec::new(),
             next_id: 1,
+            persist: false,
         }
     }
 }
 
 impl DownloadHistory {
     fn get_history_path() -> PathBuf {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        PathBuf::from(home).join(".calm/downloads.yml")
+        crate::paths::data_dir().join("downloads.yml")
     }
 
     pub fn load() -> Self {
         let path = Self::get_history_path();
 
         if let Ok(contents) = fs::read_to_string(&path) {
-            if let Ok(history) = serde_yaml::from_str(&contents) {
+            if let Ok(mut history) = serde_yaml::from_str::<Self>(&contents) {
+                history.persist = true;
                 return history;
             }
         }
 
-        Self::default()
+        Self {
+            persist: true,
+            ..Self::default()
+        }
     }
 
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let path = Self::get_history_path();
+        self.save_to(&Self::get_history_path())
+    }
+
+    fn save_to(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.persist {
+            return Ok(());
+        }
 
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
@@ -107,7 +121,15 @@ pub struct DownloadManager {
 
 impl DownloadManager {
     pub fn new() -> Self {
-        let history = DownloadHistory::load();
+        Self::with_history(DownloadHistory::load())
+    }
+
+    /// Creates a manager for a private window, starting from an empty history that is never saved.
+    pub fn new_private() -> Self {
+        Self::with_history(DownloadHistory::default())
+    }
+
+    fn with_history(history: DownloadHistory) -> Self {
         let next_id = history.next_id;
 
         Self {
@@ -130,3 +152,52 @@ impl Default for DownloadManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("calm-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_private_download_history_is_never_written() {
+        let manager = DownloadManager::new_private();
+        let history = manager.get_history();
+        let mut history = history.lock().unwrap();
+        let id = history.add_download("report.pdf".to_string(), "/tmp/report.pdf".to_string(), 42);
+        history.update_download(id, true, false, None);
+        assert_eq!(history.downloads.len(), 1);
+
+        let path = scratch_file("private-downloads.yml");
+        history.save_to(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_loaded_download_history_is_written() {
+        let mut history = DownloadHistory {
+            persist: true,
+            ..DownloadHistory::default()
+        };
+        history.downloads.push(DownloadEntry {
+            id: 1,
+            filename: "report.pdf".to_string(),
+            file_path: "/tmp/report.pdf".to_string(),
+            total_bytes: 42,
+            completed: true,
+            failed: false,
+            timestamp: 0,
+        });
+
+        let path = scratch_file("downloads.yml");
+        history.save_to(&path).unwrap();
+        let saved: DownloadHistory =
+            serde_yaml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.downloads.len(), 1);
+        let _ = fs::remove_file(path);
+    }
+}
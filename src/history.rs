@@ -1,98 +1,1003 @@
+use crate::config::HistorySettings;
+use crate::debug_log;
+use rusqlite::{params, Connection, OptionalExtension, Params};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// Tables of the history database. Each URL is stored once in `urls` with its visit count, every
+/// visit is appended to `visits`, and `urls_fts` indexes URLs and titles by trigram so substring
+/// searches do not scan the whole table.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS urls (
+        id INTEGER PRIMARY KEY,
+        url TEXT NOT NULL UNIQUE,
+        title TEXT NOT NULL DEFAULT '',
+        visit_count INTEGER NOT NULL DEFAULT 0,
+        last_visit INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX IF NOT EXISTS urls_last_visit ON urls (last_visit DESC);
+
+    CREATE TABLE IF NOT EXISTS visits (
+        id INTEGER PRIMARY KEY,
+        url_id INTEGER NOT NULL REFERENCES urls (id) ON DELETE CASCADE,
+        timestamp INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS visits_url_id ON visits (url_id, timestamp);
+
+    CREATE VIRTUAL TABLE IF NOT EXISTS urls_fts USING fts5 (
+        url, title, content = 'urls', content_rowid = 'id', tokenize = 'trigram'
+    );
+    CREATE TRIGGER IF NOT EXISTS urls_fts_insert AFTER INSERT ON urls BEGIN
+        INSERT INTO urls_fts (rowid, url, title) VALUES (new.id, new.url, new.title);
+    END;
+    CREATE TRIGGER IF NOT EXISTS urls_fts_delete AFTER DELETE ON urls BEGIN
+        INSERT INTO urls_fts (urls_fts, rowid, url, title) VALUES ('delete', old.id, old.url, old.title);
+    END;
+    CREATE TRIGGER IF NOT EXISTS urls_fts_update AFTER UPDATE OF url, title ON urls BEGIN
+        INSERT INTO urls_fts (urls_fts, rowid, url, title) VALUES ('delete', old.id, old.url, old.title);
+        INSERT INTO urls_fts (rowid, url, title) VALUES (new.id, new.url, new.title);
+    END;
+";
+
+/// Schema changes applied in order to databases created by older versions, tracked with
+/// `PRAGMA user_version`.
+const MIGRATIONS: &[&str] = &["ALTER TABLE visits ADD COLUMN typed INTEGER NOT NULL DEFAULT 0;"];
+
+/// Shortest query the trigram index can answer; shorter ones fall back to a table scan.
+const MIN_INDEXED_QUERY_LENGTH: usize = 3;
+
+/// Number of matches ranked by frecency before the best ones are returned.
+const SEARCH_CANDIDATES: usize = 200;
+
+/// Number of latest visits sampled to compute a URL's frecency.
+const FRECENCY_SAMPLE_VISITS: usize = 10;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
     pub url: String,
     pub title: String,
+    /// Time of the latest visit, in seconds since the Unix epoch.
     pub timestamp: i64,
+    #[serde(default, rename = "visitCount")]
+    pub visit_count: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A single visit to a page, as listed on the history page.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryVisit {
+    pub id: i64,
+    pub url: String,
+    pub title: String,
+    /// Time of the visit, in seconds since the Unix epoch.
+    pub timestamp: i64,
+}
+
+/// URLs of the history affected by a change of the history rules.
+#[derive(Default)]
+struct RuleMatches {
+    /// URLs on an excluded host.
+    excluded: Vec<i64>,
+    /// URLs with a query string, along with the URL they become once it is stripped.
+    with_query: Vec<(i64, String)>,
+}
+
+/// Layout of `history.yml`, where history was kept before the SQLite store.
+#[derive(Deserialize)]
+struct LegacyHistory {
+    entries: Vec<HistoryEntry>,
+}
+
+/// Browsing history backed by an SQLite database at `~/.calm/history.db`.
+/// Visits are written one row at a time instead of rewriting the whole history.
 pub struct History {
-    pub entries: Vec<HistoryEntry>,
+    conn: Connection,
 }
 
 impl History {
-    pub fn new() -> Self {
-        Self {
-            entries: Vec::new(),
+    /// Opens the history database, importing `history.yml` if an older version left one behind.
+    /// Falls back to an in-memory store when the database cannot be opened.
+    pub fn load() -> Self {
+        let _ = fs::create_dir_all(Self::get_history_dir());
+
+        let mut history = match Connection::open(Self::get_history_path()).and_then(Self::with_connection) {
+            Ok(history) => history,
+            Err(e) => {
+                debug_log!("Failed to open history database, keeping history in memory: {}", e);
+                Self::in_memory()
+            }
+        };
+        history.migrate_from_yaml();
+        history
+    }
+
+    fn in_memory() -> Self {
+        Connection::open_in_memory()
+            .and_then(Self::with_connection)
+            .expect("Failed to create in-memory history database")
+    }
+
+    fn with_connection(conn: Connection) -> rusqlite::Result<Self> {
+        conn.busy_timeout(Duration::from_secs(2))?;
+        let _ = conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()));
+        conn.pragma_update(None, "foreign_keys", true)?;
+        conn.execute_batch(SCHEMA)?;
+
+        let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            conn.execute_batch(migration)?;
+            conn.pragma_update(None, "user_version", index + 1)?;
         }
+
+        Ok(Self { conn })
     }
 
-    pub fn load() -> Self {
-        let history_path = Self::get_history_path();
+    /// Imports the entries of `history.yml` and renames it to `history.yml.bak` so it is only
+    /// imported once.
+    fn migrate_from_yaml(&mut self) {
+        let yaml_path = Self::get_legacy_history_path();
+        let Ok(contents) = fs::read_to_string(&yaml_path) else {
+            return;
+        };
+        let entries = match serde_yaml::from_str::<LegacyHistory>(&contents) {
+            Ok(legacy) => legacy.entries,
+            Err(e) => {
+                debug_log!("Failed to parse {}: {}", yaml_path.display(), e);
+                return;
+            }
+        };
 
-        if let Ok(contents) = fs::read_to_string(&history_path) {
-            if let Ok(history) = serde_yaml::from_str::<History>(&contents) {
-                return history;
+        match self.import_entries(&entries) {
+            Ok(()) => {
+                debug_log!("Imported {} history entries from {}", entries.len(), yaml_path.display());
+                let _ = fs::rename(&yaml_path, yaml_path.with_extension("yml.bak"));
+            }
+            Err(e) => {
+                debug_log!("Failed to import {}: {}", yaml_path.display(), e);
             }
         }
+    }
 
-        Self::new()
+    /// Records one visit per entry in a single transaction. Entries are expected newest first,
+    /// as they were stored in `history.yml`.
+    fn import_entries(&mut self, entries: &[HistoryEntry]) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        for entry in entries.iter().rev() {
+            Self::insert_visit(&tx, &entry.url, &entry.title, entry.timestamp, false)?;
+        }
+        tx.commit()
     }
 
-    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let history_dir = Self::get_history_dir();
-        fs::create_dir_all(&history_dir)?;
+    /// Records visits imported from another browser with their original times, oldest first so
+    /// the newest title wins. Visits already in history are skipped, so importing the same file
+    /// twice adds nothing. Returns the number of visits added.
+    pub fn import_visits(&mut self, visits: &[HistoryEntry]) -> rusqlite::Result<usize> {
+        let mut visits: Vec<&HistoryEntry> = visits.iter().collect();
+        visits.sort_by_key(|visit| visit.timestamp);
+
+        let tx = self.conn.transaction()?;
+        let mut added = 0;
+        for visit in visits {
+            let known = tx
+                .prepare_cached(
+                    "SELECT 1 FROM visits JOIN urls ON urls.id = visits.url_id
+                     WHERE urls.url = ?1 AND visits.timestamp = ?2",
+                )?
+                .exists(params![visit.url, visit.timestamp])?;
+            if !known {
+                Self::insert_visit(&tx, &visit.url, &visit.title, visit.timestamp, false)?;
+                added += 1;
+            }
+        }
+        tx.commit()?;
+        Ok(added)
+    }
 
-        let history_path = Self::get_history_path();
-        let yaml = serde_yaml::to_string(self)?;
-        fs::write(history_path, yaml)?;
+    /// Adds a visit to `url`, updating its title unless the new one is empty. `typed` marks
+    /// visits the user typed the address for, rather than followed a link to.
+    fn insert_visit(
+        conn: &Connection,
+        url: &str,
+        title: &str,
+        timestamp: i64,
+        typed: bool,
+    ) -> rusqlite::Result<()> {
+        conn.prepare_cached(
+            "INSERT INTO urls (url, title, visit_count, last_visit) VALUES (?1, ?2, 1, ?3)
+             ON CONFLICT (url) DO UPDATE SET
+                 title = CASE WHEN excluded.title = '' THEN urls.title ELSE excluded.title END,
+                 visit_count = urls.visit_count + 1,
+                 last_visit = MAX(urls.last_visit, excluded.last_visit)",
+        )?
+        .execute(params![url, title, timestamp])?;
+        conn.prepare_cached(
+            "INSERT INTO visits (url_id, timestamp, typed) SELECT id, ?2, ?3 FROM urls WHERE url = ?1",
+        )?
+        .execute(params![url, timestamp, typed])?;
         Ok(())
     }
 
-    pub fn add_entry(&mut self, url: String, title: String) {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+    pub fn add_entry(&mut self, url: String, title: String, typed: bool) {
+        let timestamp = unix_now();
 
-        self.entries.insert(
-            0,
-            HistoryEntry {
-                url,
-                title,
-                timestamp,
-            },
-        );
+        let result = self
+            .conn
+            .transaction()
+            .and_then(|tx| Self::insert_visit(&tx, &url, &title, timestamp, typed).and_then(|_| tx.commit()));
+        if let Err(e) = result {
+            debug_log!("Failed to record visit to {}: {}", url, e);
+        }
+    }
 
-        while self.entries.len() > 10000 {
-            self.entries.pop();
+    pub fn clear(&mut self) {
+        if let Err(e) = self.conn.execute_batch("DELETE FROM visits; DELETE FROM urls;") {
+            debug_log!("Failed to clear history: {}", e);
         }
+    }
 
-        let _ = self.save();
+    /// Removes the given visits. URLs left without any visit are dropped from history, the others
+    /// get their visit count and last visit time updated. Returns the number of visits removed.
+    pub fn remove_entries(&mut self, visit_ids: &[i64]) -> usize {
+        let result = self.conn.transaction().and_then(|tx| {
+            let mut url_ids = Vec::new();
+            let mut removed = 0;
+            for &visit_id in visit_ids {
+                let url_id: Option<i64> = tx
+                    .prepare_cached("DELETE FROM visits WHERE id = ?1 RETURNING url_id")?
+                    .query_row(params![visit_id], |row| row.get(0))
+                    .optional()?;
+                if let Some(url_id) = url_id {
+                    removed += 1;
+                    if !url_ids.contains(&url_id) {
+                        url_ids.push(url_id);
+                    }
+                }
+            }
+            for url_id in url_ids {
+                Self::refresh_url_stats(&tx, url_id)?;
+            }
+            tx.commit()?;
+            Ok(removed)
+        });
+
+        result.unwrap_or_else(|e| {
+            debug_log!("Failed to remove history entries: {}", e);
+            0
+        })
     }
 
-    pub fn clear(&mut self) {
-        self.entries.clear();
-        let _ = self.save();
+    /// Removes every visit to `host` and its subdomains. Returns the number of URLs removed.
+    pub fn remove_host(&mut self, host: &str) -> usize {
+        let host = host.trim().to_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        if host.is_empty() {
+            return 0;
+        }
+
+        let result = self.conn.transaction().and_then(|tx| {
+            let url_ids: Vec<i64> = {
+                let mut stmt = tx.prepare("SELECT id, url FROM urls")?;
+                let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+                let mut url_ids = Vec::new();
+                for row in rows {
+                    let (id, url) = row?;
+                    if host_matches(url_host(&url.to_lowercase()), host) {
+                        url_ids.push(id);
+                    }
+                }
+                url_ids
+            };
+            for &url_id in &url_ids {
+                tx.prepare_cached("DELETE FROM urls WHERE id = ?1")?
+                    .execute(params![url_id])?;
+            }
+            tx.commit()?;
+            Ok(url_ids.len())
+        });
+
+        result.unwrap_or_else(|e| {
+            debug_log!("Failed to forget {}: {}", host, e);
+            0
+        })
+    }
+
+    /// Removes visits older than `max_age_days`. Does nothing when the age is 0. Returns the
+    /// number of visits removed.
+    pub fn prune_expired(&mut self, max_age_days: u64) -> usize {
+        let Some(cutoff) = expiry_cutoff(max_age_days, unix_now()) else {
+            return 0;
+        };
+
+        let result = self.conn.transaction().and_then(|tx| {
+            let removed = Self::delete_visits_before(&tx, cutoff)?;
+            tx.commit()?;
+            Ok(removed)
+        });
+
+        result.unwrap_or_else(|e| {
+            debug_log!("Failed to prune expired history: {}", e);
+            0
+        })
+    }
+
+    /// Returns the number of visits that `rules` would remove or rewrite if applied to the
+    /// existing history.
+    pub fn count_rule_matches(&self, rules: &HistorySettings) -> usize {
+        let cutoff = expiry_cutoff(rules.max_age_days, unix_now()).unwrap_or(i64::MIN);
+
+        let result = (|| {
+            let mut count: usize = self
+                .conn
+                .query_row("SELECT COUNT(*) FROM visits WHERE timestamp < ?1", params![cutoff], |row| row.get(0))?;
+            let matches = Self::rule_matches(&self.conn, rules)?;
+            let url_ids = matches.excluded.into_iter().chain(matches.with_query.into_iter().map(|(url_id, _)| url_id));
+            for url_id in url_ids {
+                count += self
+                    .conn
+                    .prepare_cached("SELECT COUNT(*) FROM visits WHERE url_id = ?1 AND timestamp >= ?2")?
+                    .query_row(params![url_id, cutoff], |row| row.get::<_, usize>(0))?;
+            }
+            Ok::<_, rusqlite::Error>(count)
+        })();
+
+        result.unwrap_or_else(|e| {
+            debug_log!("Failed to count history matching the rules: {}", e);
+            0
+        })
+    }
+
+    /// Applies `rules` to the existing history: expired visits and excluded hosts are removed,
+    /// and URLs with a query string are folded into the URL without it when query strings are
+    /// stripped. Returns the number of visits removed or rewritten.
+    pub fn apply_rules(&mut self, rules: &HistorySettings) -> usize {
+        let cutoff = expiry_cutoff(rules.max_age_days, unix_now());
+
+        let result = self.conn.transaction().and_then(|tx| {
+            let mut affected = match cutoff {
+                Some(cutoff) => Self::delete_visits_before(&tx, cutoff)?,
+                None => 0,
+            };
+            let matches = Self::rule_matches(&tx, rules)?;
+            for url_id in matches.excluded {
+                affected += tx
+                    .prepare_cached("DELETE FROM visits WHERE url_id = ?1")?
+                    .execute(params![url_id])?;
+                tx.prepare_cached("DELETE FROM urls WHERE id = ?1")?
+                    .execute(params![url_id])?;
+            }
+            for (url_id, stripped) in matches.with_query {
+                affected += Self::merge_url(&tx, url_id, &stripped)?;
+            }
+            tx.commit()?;
+            Ok(affected)
+        });
+
+        result.unwrap_or_else(|e| {
+            debug_log!("Failed to apply history rules: {}", e);
+            0
+        })
+    }
+
+    /// Deletes the visits made before `cutoff` and updates the URLs they belonged to.
+    fn delete_visits_before(conn: &Connection, cutoff: i64) -> rusqlite::Result<usize> {
+        let mut url_ids: Vec<i64> = {
+            let mut stmt = conn.prepare("DELETE FROM visits WHERE timestamp < ?1 RETURNING url_id")?;
+            let rows = stmt.query_map(params![cutoff], |row| row.get(0))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        let removed = url_ids.len();
+        url_ids.sort_unstable();
+        url_ids.dedup();
+        for url_id in url_ids {
+            Self::refresh_url_stats(conn, url_id)?;
+        }
+        Ok(removed)
+    }
+
+    /// Finds the URLs of the history that `rules` exclude or strip of their query string.
+    fn rule_matches(conn: &Connection, rules: &HistorySettings) -> rusqlite::Result<RuleMatches> {
+        let mut matches = RuleMatches::default();
+        let mut stmt = conn.prepare("SELECT id, url FROM urls")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+        for row in rows {
+            let (id, url) = row?;
+            if url_matches_hosts(&url, &rules.excluded_hosts) {
+                matches.excluded.push(id);
+            } else if rules.strip_query_strings {
+                let stripped = strip_query(&url);
+                if stripped != url {
+                    matches.with_query.push((id, stripped.to_string()));
+                }
+            }
+        }
+        Ok(matches)
     }
 
+    /// Moves the visits of URL `url_id` to `target`, creating it if needed, and removes the
+    /// original URL. Returns the number of visits moved.
+    fn merge_url(conn: &Connection, url_id: i64, target: &str) -> rusqlite::Result<usize> {
+        conn.prepare_cached(
+            "INSERT INTO urls (url, title, visit_count, last_visit)
+             SELECT ?1, title, 0, 0 FROM urls WHERE id = ?2
+             ON CONFLICT (url) DO NOTHING",
+        )?
+        .execute(params![target, url_id])?;
+        let target_id: i64 = conn
+            .prepare_cached("SELECT id FROM urls WHERE url = ?1")?
+            .query_row(params![target], |row| row.get(0))?;
+        let moved = conn
+            .prepare_cached("UPDATE visits SET url_id = ?1 WHERE url_id = ?2")?
+            .execute(params![target_id, url_id])?;
+        conn.prepare_cached("DELETE FROM urls WHERE id = ?1")?
+            .execute(params![url_id])?;
+        Self::refresh_url_stats(conn, target_id)?;
+        Ok(moved)
+    }
+
+    /// Recomputes the visit count and last visit of a URL from its visits, removing it once it
+    /// has none left.
+    fn refresh_url_stats(conn: &Connection, url_id: i64) -> rusqlite::Result<()> {
+        conn.prepare_cached(
+            "UPDATE urls SET
+                 visit_count = (SELECT COUNT(*) FROM visits WHERE url_id = urls.id),
+                 last_visit = COALESCE((SELECT MAX(timestamp) FROM visits WHERE url_id = urls.id), 0)
+             WHERE id = ?1",
+        )?
+        .execute(params![url_id])?;
+        conn.prepare_cached("DELETE FROM urls WHERE id = ?1 AND visit_count = 0")?
+            .execute(params![url_id])?;
+        Ok(())
+    }
+
+    /// Returns the visits made from `start` up to but excluding `end`, newest first, keeping only
+    /// those whose address or title contains `query` when it is not empty.
+    pub fn entries_between(&self, start: i64, end: i64, query: &str, limit: usize) -> Vec<HistoryVisit> {
+        let pattern = format!("%{}%", escape_like_pattern(query.trim()));
+        let result = self.conn.prepare_cached(
+            "SELECT visits.id, urls.url, urls.title, visits.timestamp FROM visits
+             JOIN urls ON urls.id = visits.url_id
+             WHERE visits.timestamp >= ?1 AND visits.timestamp < ?2
+               AND (urls.url LIKE ?3 ESCAPE '\\' OR urls.title LIKE ?3 ESCAPE '\\')
+             ORDER BY visits.timestamp DESC, visits.id DESC LIMIT ?4",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(params![start, end, pattern, limit as i64], |row| {
+                Ok(HistoryVisit {
+                    id: row.get(0)?,
+                    url: row.get(1)?,
+                    title: row.get(2)?,
+                    timestamp: row.get(3)?,
+                })
+            })?
+            .collect()
+        });
+
+        result.unwrap_or_else(|e| {
+            debug_log!("Failed to read history between {} and {}: {}", start, end, e);
+            Vec::new()
+        })
+    }
+
+    /// Returns the URLs whose address or title contains `query`, ignoring case, best first.
     pub fn search(&self, query: &str, limit: usize) -> Vec<HistoryEntry> {
-        let query_lower = query.to_lowercase();
-        self.entries
-            .iter()
-            .filter(|entry| {
-                entry.url.to_lowercase().contains(&query_lower)
-                    || entry.title.to_lowercase().contains(&query_lower)
-            })
-            .take(limit)
-            .cloned()
+        self.search_scored(query, limit)
+            .into_iter()
+            .map(|(entry, _)| entry)
             .collect()
     }
 
+    /// Returns the URLs matching `query` with their score: their frecency weighted by how well
+    /// the query matches them.
+    pub fn search_scored(&self, query: &str, limit: usize) -> Vec<(HistoryEntry, f64)> {
+        let query = query.trim();
+        let now = unix_now();
+        let mut scored: Vec<(HistoryEntry, f64)> = self
+            .find_matches(query, SEARCH_CANDIDATES)
+            .into_iter()
+            .filter_map(|entry| {
+                let quality = match_quality(&entry.url, &entry.title, query)?;
+                let score = frecency(entry.visit_count, &self.sample_visits(&entry.url), now) * quality;
+                Some((entry, score))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(limit);
+        scored
+    }
+
+    /// Returns the frecency of a URL, or 0 if it was never visited.
+    pub fn url_frecency(&self, url: &str) -> f64 {
+        let visit_count = self
+            .conn
+            .prepare_cached("SELECT visit_count FROM urls WHERE url = ?1")
+            .and_then(|mut stmt| stmt.query_row(params![url], |row| row.get(0)))
+            .unwrap_or(0);
+        frecency(visit_count, &self.sample_visits(url), unix_now())
+    }
+
+    /// Returns the time and typed flag of the latest visits to a URL.
+    fn sample_visits(&self, url: &str) -> Vec<(i64, bool)> {
+        let result = self
+            .conn
+            .prepare_cached(
+                "SELECT visits.timestamp, visits.typed FROM visits
+                 JOIN urls ON urls.id = visits.url_id
+                 WHERE urls.url = ?1
+                 ORDER BY visits.timestamp DESC LIMIT ?2",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map(params![url, FRECENCY_SAMPLE_VISITS as i64], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?
+                .collect()
+            });
+        result.unwrap_or_default()
+    }
+
+    /// Returns the most recently visited URLs whose address or title contains `query`.
+    fn find_matches(&self, query: &str, limit: usize) -> Vec<HistoryEntry> {
+        let result = if query.chars().count() < MIN_INDEXED_QUERY_LENGTH {
+            let pattern = format!("%{}%", escape_like_pattern(query));
+            self.query_entries(
+                "SELECT url, title, last_visit, visit_count FROM urls
+                 WHERE url LIKE ?1 ESCAPE '\\' OR title LIKE ?1 ESCAPE '\\'
+                 ORDER BY last_visit DESC LIMIT ?2",
+                params![pattern, limit as i64],
+            )
+        } else {
+            let phrase = format!("\"{}\"", query.replace('"', "\"\""));
+            self.query_entries(
+                "SELECT urls.url, urls.title, urls.last_visit, urls.visit_count
+                 FROM urls_fts JOIN urls ON urls.id = urls_fts.rowid
+                 WHERE urls_fts MATCH ?1
+                 ORDER BY urls.last_visit DESC LIMIT ?2",
+                params![phrase, limit as i64],
+            )
+        };
+
+        result.unwrap_or_else(|e| {
+            debug_log!("History search for {:?} failed: {}", query, e);
+            Vec::new()
+        })
+    }
+
+    /// Returns the most recently visited URLs, each listed once.
     pub fn get_recent(&self, limit: usize) -> Vec<HistoryEntry> {
-        self.entries.iter().take(limit).cloned().collect()
+        self.query_entries(
+            "SELECT url, title, last_visit, visit_count FROM urls ORDER BY last_visit DESC LIMIT ?1",
+            params![limit as i64],
+        )
+        .unwrap_or_else(|e| {
+            debug_log!("Failed to read recent history: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn query_entries(&self, sql: &str, params: impl Params) -> rusqlite::Result<Vec<HistoryEntry>> {
+        let mut stmt = self.conn.prepare_cached(sql)?;
+        let rows = stmt.query_map(params, |row| {
+            Ok(HistoryEntry {
+                url: row.get(0)?,
+                title: row.get(1)?,
+                timestamp: row.get(2)?,
+                visit_count: row.get(3)?,
+            })
+        })?;
+        rows.collect()
     }
 
     fn get_history_dir() -> PathBuf {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        PathBuf::from(home).join(".calm")
+        crate::paths::data_dir()
     }
 
     fn get_history_path() -> PathBuf {
+        Self::get_history_dir().join("history.db")
+    }
+
+    fn get_legacy_history_path() -> PathBuf {
         Self::get_history_dir().join("history.yml")
     }
 }
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// Points a single visit adds to a URL's frecency. Recent visits are worth more, and typing an
+/// address counts double compared to following a link.
+fn visit_points(age_secs: i64, typed: bool) -> f64 {
+    let recency = match age_secs.max(0) / 86_400 {
+        0..=4 => 100.0,
+        5..=14 => 70.0,
+        15..=31 => 50.0,
+        32..=90 => 30.0,
+        _ => 10.0,
+    };
+    if typed {
+        recency * 2.0
+    } else {
+        recency
+    }
+}
+
+/// Scores a URL the way Firefox's frecency does: the average points of its latest visits,
+/// scaled by how many times it was visited in total.
+pub fn frecency(visit_count: u32, sampled_visits: &[(i64, bool)], now: i64) -> f64 {
+    if sampled_visits.is_empty() {
+        return 0.0;
+    }
+    let points: f64 = sampled_visits
+        .iter()
+        .map(|&(timestamp, typed)| visit_points(now - timestamp, typed))
+        .sum();
+    visit_count as f64 * points / sampled_visits.len() as f64
+}
+
+/// Returns how well `query` matches a page, or `None` if it occurs in neither its URL nor its
+/// title. Matching the start of the host is best, then matching at the start of a word.
+pub fn match_quality(url: &str, title: &str, query: &str) -> Option<f64> {
+    let query = query.trim().to_lowercase();
+    let url = url.to_lowercase();
+    let title = title.to_lowercase();
+
+    let host = url_host(&url);
+    if host.starts_with(&query) || host.strip_prefix("www.").is_some_and(|host| host.starts_with(&query)) {
+        Some(4.0)
+    } else if starts_word(&url, &query) || starts_word(&title, &query) {
+        Some(2.0)
+    } else if url.contains(&query) || title.contains(&query) {
+        Some(1.0)
+    } else {
+        None
+    }
+}
+
+/// Returns the URL to record for a visit to `url` under `rules`, or `None` when its host is
+/// excluded from history.
+pub fn recorded_url(url: &str, rules: &HistorySettings) -> Option<String> {
+    if url_matches_hosts(url, &rules.excluded_hosts) {
+        return None;
+    }
+    let url = if rules.strip_query_strings { strip_query(url) } else { url };
+    Some(url.to_string())
+}
+
+/// Returns whether the host of `url` matches one of `patterns`. A plain host also covers its
+/// subdomains, and `*` matches any run of characters.
+pub fn url_matches_hosts(url: &str, patterns: &[String]) -> bool {
+    if patterns.is_empty() {
+        return false;
+    }
+    let url = url.to_lowercase();
+    let host = url_host(&url);
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let host = host.split(':').next().unwrap_or(host);
+    let host = host.strip_prefix("www.").unwrap_or(host);
+
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.trim().to_lowercase();
+        let pattern = pattern.strip_prefix("www.").unwrap_or(&pattern);
+        if pattern.is_empty() {
+            false
+        } else if pattern.contains('*') {
+            wildcard_matches(pattern, host)
+        } else {
+            host_matches(host, pattern)
+        }
+    })
+}
+
+/// Returns whether `text` matches `pattern`, where `*` stands for any run of characters.
+fn wildcard_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let last = parts.pop().unwrap_or("");
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Returns `url` without its query string and fragment.
+fn strip_query(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+/// Returns the timestamp before which visits expire, or `None` when history is kept forever.
+fn expiry_cutoff(max_age_days: u64, now: i64) -> Option<i64> {
+    if max_age_days == 0 {
+        return None;
+    }
+    let max_age_secs = i64::try_from(max_age_days).unwrap_or(i64::MAX).saturating_mul(24 * 60 * 60);
+    Some(now.saturating_sub(max_age_secs))
+}
+
+/// Returns the host of a URL, or the text up to its first path separator if it has no scheme.
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split(['/', '?', '#']).next().unwrap_or(rest)
+}
+
+/// Returns whether `url_host` is `host` or one of its subdomains.
+fn host_matches(url_host: &str, host: &str) -> bool {
+    url_host == host
+        || url_host
+            .strip_suffix(host)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// Returns whether `query` occurs in `text` right after a non-alphanumeric character or at its start.
+fn starts_word(text: &str, query: &str) -> bool {
+    text.match_indices(query)
+        .any(|(index, _)| !matches!(text[..index].chars().next_back(), Some(c) if c.is_alphanumeric()))
+}
+
+/// Escapes the `LIKE` wildcards in `text` so it is matched literally.
+fn escape_like_pattern(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visit(history: &mut History, url: &str, title: &str, timestamp: i64) {
+        History::insert_visit(&history.conn, url, title, timestamp, false).unwrap();
+    }
+
+    #[test]
+    fn test_visits_are_deduplicated_and_counted() {
+        let mut history = History::in_memory();
+        visit(&mut history, "https://a.example", "A", 1);
+        visit(&mut history, "https://b.example", "B", 2);
+        visit(&mut history, "https://a.example", "", 3);
+
+        let recent = history.get_recent(10);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].url, "https://a.example");
+        assert_eq!(recent[0].title, "A");
+        assert_eq!(recent[0].visit_count, 2);
+        assert_eq!(recent[0].timestamp, 3);
+
+        let visits: i64 = history
+            .conn
+            .query_row("SELECT COUNT(*) FROM visits", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(visits, 3);
+    }
+
+    #[test]
+    fn test_search_short_and_indexed_queries() {
+        let mut history = History::in_memory();
+        visit(&mut history, "https://docs.rs/serde", "serde - Rust", 1);
+        visit(&mut history, "https://example.com/100%_done", "Example", 2);
+        visit(&mut history, "https://github.com", "GitHub", 3);
+
+        let urls = |entries: Vec<HistoryEntry>| entries.into_iter().map(|e| e.url).collect::<Vec<_>>();
+        assert_eq!(urls(history.search("SERDE", 10)), vec!["https://docs.rs/serde"]);
+        assert_eq!(urls(history.search("hub", 10)), vec!["https://github.com"]);
+        assert_eq!(urls(history.search("%_", 10)), vec!["https://example.com/100%_done"]);
+        assert_eq!(history.search("rs", 10).len(), 1);
+        assert_eq!(history.search("https", 2).len(), 2);
+        assert!(history.search("\"", 10).is_empty());
+    }
+
+    #[test]
+    fn test_import_keeps_newest_first_order() {
+        let mut history = History::in_memory();
+        let entry = |url: &str, timestamp| HistoryEntry {
+            url: url.to_string(),
+            title: String::new(),
+            timestamp,
+            visit_count: 0,
+        };
+        history
+            .import_entries(&[entry("https://new.example", 20), entry("https://old.example", 10)])
+            .unwrap();
+
+        let recent = history.get_recent(10);
+        assert_eq!(recent[0].url, "https://new.example");
+        assert_eq!(recent[1].url, "https://old.example");
+    }
+
+    #[test]
+    fn test_import_visits_skips_known_visits() {
+        let mut history = History::in_memory();
+        let entry = |url: &str, title: &str, timestamp| HistoryEntry {
+            url: url.to_string(),
+            title: title.to_string(),
+            timestamp,
+            visit_count: 0,
+        };
+        let visits = [
+            entry("https://a.example", "New title", 20),
+            entry("https://a.example", "Old title", 10),
+            entry("https://b.example", "B", 15),
+        ];
+
+        assert_eq!(history.import_visits(&visits).unwrap(), 3);
+        assert_eq!(history.import_visits(&visits).unwrap(), 0);
+
+        let recent = history.get_recent(10);
+        assert_eq!(recent[0].url, "https://a.example");
+        assert_eq!(recent[0].title, "New title");
+        assert_eq!(recent[0].visit_count, 2);
+        assert_eq!(recent[1].timestamp, 15);
+    }
+
+    #[test]
+    fn test_frecency_prefers_recent_and_typed_visits() {
+        let now = 100 * 86_400;
+        let recent = frecency(1, &[(now - 3600, false)], now);
+        let old = frecency(1, &[(now - 60 * 86_400, false)], now);
+        let typed = frecency(1, &[(now - 3600, true)], now);
+        assert!(recent > old);
+        assert!(typed > recent);
+        assert_eq!(frecency(3, &[(now, false)], now), 300.0);
+        assert_eq!(frecency(0, &[], now), 0.0);
+    }
+
+    #[test]
+    fn test_match_quality() {
+        let quality = |url, title, query| match_quality(url, title, query);
+        assert_eq!(quality("https://www.github.com/rust", "", "git"), Some(4.0));
+        assert_eq!(quality("https://docs.rs/serde", "", "serde"), Some(2.0));
+        assert_eq!(quality("https://example.com", "Learn Rust", "rust"), Some(2.0));
+        assert_eq!(quality("https://example.com/digit", "", "git"), Some(1.0));
+        assert_eq!(quality("https://example.com", "", "rust"), None);
+    }
+
+    #[test]
+    fn test_search_ranks_by_frecency_and_match() {
+        let mut history = History::in_memory();
+        let now = unix_now();
+        visit(&mut history, "https://example.com/git-guide", "Guide", now - 40 * 86_400);
+        History::insert_visit(&history.conn, "https://gitlab.com", "GitLab", now, true).unwrap();
+
+        let results = history.search_scored("git", 10);
+        assert_eq!(results[0].0.url, "https://gitlab.com");
+        assert!(results[0].1 > results[1].1);
+        assert!(history.url_frecency("https://gitlab.com") > 0.0);
+        assert_eq!(history.url_frecency("https://never.example"), 0.0);
+    }
+
+    #[test]
+    fn test_entries_between() {
+        let mut history = History::in_memory();
+        visit(&mut history, "https://a.example", "Alpha", 10);
+        visit(&mut history, "https://b.example", "Beta", 20);
+        visit(&mut history, "https://a.example", "Alpha", 30);
+
+        let timestamps = |visits: Vec<HistoryVisit>| visits.into_iter().map(|v| v.timestamp).collect::<Vec<_>>();
+        assert_eq!(timestamps(history.entries_between(0, 100, "", 10)), vec![30, 20, 10]);
+        assert_eq!(timestamps(history.entries_between(10, 30, "", 10)), vec![20, 10]);
+        assert_eq!(timestamps(history.entries_between(0, 100, "alpha", 10)), vec![30, 10]);
+        assert_eq!(timestamps(history.entries_between(0, 100, "", 1)), vec![30]);
+    }
+
+    #[test]
+    fn test_remove_entries_updates_counts() {
+        let mut history = History::in_memory();
+        visit(&mut history, "https://a.example", "A", 10);
+        visit(&mut history, "https://a.example", "A", 20);
+        visit(&mut history, "https://b.example", "B", 30);
+
+        let visits = history.entries_between(0, 100, "", 10);
+        let newest_a = visits.iter().find(|v| v.timestamp == 20).unwrap().id;
+        let b = visits.iter().find(|v| v.timestamp == 30).unwrap().id;
+        assert_eq!(history.remove_entries(&[newest_a, b, 999]), 2);
+
+        let recent = history.get_recent(10);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].url, "https://a.example");
+        assert_eq!(recent[0].visit_count, 1);
+        assert_eq!(recent[0].timestamp, 10);
+        assert!(history.search("b.example", 10).is_empty());
+    }
+
+    #[test]
+    fn test_remove_host_includes_subdomains() {
+        let mut history = History::in_memory();
+        visit(&mut history, "https://www.example.com/a", "", 1);
+        visit(&mut history, "https://docs.example.com", "", 2);
+        visit(&mut history, "https://notexample.com", "", 3);
+
+        assert_eq!(history.remove_host("example.com"), 2);
+        let recent = history.get_recent(10);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].url, "https://notexample.com");
+        assert!(history.entries_between(0, 10, "", 10).iter().all(|v| v.url == "https://notexample.com"));
+    }
+
+    #[test]
+    fn test_clear_empties_search_index() {
+        let mut history = History::in_memory();
+        visit(&mut history, "https://example.com", "Example", 1);
+        history.clear();
+
+        assert!(history.get_recent(10).is_empty());
+        assert!(history.search("example", 10).is_empty());
+    }
+
+    #[test]
+    fn test_recorded_url_applies_rules() {
+        let rules = HistorySettings {
+            excluded_hosts: vec!["bank.com".to_string(), "intranet.*".to_string()],
+            max_age_days: 0,
+            strip_query_strings: true,
+        };
+
+        assert_eq!(recorded_url("https://www.bank.com/login", &rules), None);
+        assert_eq!(recorded_url("https://online.bank.com/", &rules), None);
+        assert_eq!(recorded_url("http://intranet.corp:8080/wiki", &rules), None);
+        assert_eq!(
+            recorded_url("https://example.com/search?q=rust#top", &rules),
+            Some("https://example.com/search".to_string())
+        );
+        assert_eq!(
+            recorded_url("https://notbank.com/?a=1", &HistorySettings::default()),
+            Some("https://notbank.com/?a=1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_prune_expired_keeps_recent_visits() {
+        let mut history = History::in_memory();
+        let now = unix_now();
+        visit(&mut history, "https://old.com", "Old", 1);
+        visit(&mut history, "https://example.com", "Example", 2);
+        visit(&mut history, "https://example.com", "Example", now);
+
+        assert_eq!(history.prune_expired(0), 0);
+        assert_eq!(history.prune_expired(90), 2);
+
+        let recent = history.get_recent(10);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].url, "https://example.com");
+        assert_eq!(recent[0].visit_count, 1);
+    }
+
+    #[test]
+    fn test_apply_rules_removes_excluded_hosts_and_strips_queries() {
+        let mut history = History::in_memory();
+        let now = unix_now();
+        visit(&mut history, "https://bank.com/account", "Bank", now);
+        visit(&mut history, "https://example.com/page?session=1", "Page", now - 2);
+        visit(&mut history, "https://example.com/page?session=2", "Page", now - 1);
+        visit(&mut history, "https://example.com/page", "Page", now);
+        let rules = HistorySettings {
+            excluded_hosts: vec!["bank.com".to_string()],
+            max_age_days: 0,
+            strip_query_strings: true,
+        };
+
+        assert_eq!(history.count_rule_matches(&rules), 3);
+        assert_eq!(history.apply_rules(&rules), 3);
+        assert_eq!(history.count_rule_matches(&rules), 0);
+
+        let recent = history.get_recent(10);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].url, "https://example.com/page");
+        assert_eq!(recent[0].visit_count, 3);
+        assert!(history.search("session", 10).is_empty());
+    }
+}
@@ -0,0 +1,889 @@
+use crate::bookmarks::{self, BookmarkNode};
+use crate::history::{History, HistoryEntry};
+use regex::Regex;
+use rusqlite::{Connection, OptionalExtension};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Seconds between 1601-01-01, where Chromium timestamps start, and the Unix epoch.
+const WEBKIT_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+/// Number in the name of the next directory browser databases are copied to.
+static NEXT_IMPORT_DIR: AtomicUsize = AtomicUsize::new(1);
+
+/// First bytes of every SQLite database.
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// First bytes of Firefox's compressed `.jsonlz4` bookmark backups.
+const MOZLZ4_HEADER: &[u8] = b"mozLz40\0";
+
+/// GUID of the Firefox folder whose subfolders are tag names rather than bookmark folders.
+const FIREFOX_TAGS_GUID: &str = "tagsfolder__";
+
+/// Bookmark and history files Calm can import.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    /// Bookmark HTML exported by every major browser.
+    NetscapeHtml,
+    /// Firefox bookmark backup in JSON.
+    FirefoxJson,
+    /// Firefox `places.sqlite`, holding both bookmarks and history.
+    FirefoxPlaces,
+    /// Chromium's `Bookmarks` JSON file.
+    ChromiumBookmarks,
+    /// Chromium's `History` SQLite database.
+    ChromiumHistory,
+    /// JSON written by `export_bookmarks`.
+    CalmJson,
+}
+
+impl ImportFormat {
+    fn name(self) -> &'static str {
+        match self {
+            ImportFormat::NetscapeHtml => "bookmark HTML file",
+            ImportFormat::FirefoxJson => "Firefox bookmark backup",
+            ImportFormat::FirefoxPlaces => "Firefox places.sqlite",
+            ImportFormat::ChromiumBookmarks => "Chromium bookmarks",
+            ImportFormat::ChromiumHistory => "Chromium history",
+            ImportFormat::CalmJson => "Calm bookmarks",
+        }
+    }
+
+    /// Name of the top-level folder imported bookmarks are placed in.
+    fn folder_name(self) -> &'static str {
+        match self {
+            ImportFormat::FirefoxJson | ImportFormat::FirefoxPlaces => "Imported from Firefox",
+            ImportFormat::ChromiumBookmarks | ImportFormat::ChromiumHistory => "Imported from Chromium",
+            ImportFormat::NetscapeHtml | ImportFormat::CalmJson => "Imported bookmarks",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ImportSummary {
+    pub format: ImportFormat,
+    pub folders: usize,
+    pub bookmarks: usize,
+    pub visits: usize,
+}
+
+impl fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Imported {} bookmarks in {} folders and {} history visits from {}",
+            self.bookmarks,
+            self.folders,
+            self.visits,
+            self.format.name()
+        )
+    }
+}
+
+/// Bookmarks and visits read from a file before they are added to Calm's stores.
+#[derive(Debug, Default)]
+struct ImportData {
+    bookmarks: Vec<BookmarkNode>,
+    visits: Vec<HistoryEntry>,
+}
+
+/// Imports the bookmarks and history in `path`, detecting its format from its contents.
+/// Bookmarks go into a new top-level folder named after the source browser and visits keep
+/// their original times. Entries Calm already has are skipped.
+pub fn import_file(path: &Path, history: &mut History) -> Result<ImportSummary, Box<dyn Error>> {
+    let (format, data) = read_import_file(path)?;
+    let (folders, bookmarks) =
+        bookmarks::update_bookmarks(|store| store.import(format.folder_name(), data.bookmarks));
+    let visits = history.import_visits(&data.visits)?;
+
+    Ok(ImportSummary {
+        format,
+        folders,
+        bookmarks,
+        visits,
+    })
+}
+
+/// Writes all bookmarks to `path`: a Netscape bookmark file when it ends in `.html` or `.htm`,
+/// JSON that Calm can import again otherwise. Returns the number of bookmarks written.
+pub fn export_bookmarks(path: &Path) -> Result<usize, Box<dyn Error>> {
+    let tree = bookmarks::with_bookmarks(|store| store.to_tree());
+    let is_html = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"));
+
+    let contents = if is_html {
+        to_netscape_html(&tree)
+    } else {
+        serde_json::to_string_pretty(&serde_json::json!({ "version": 1, "bookmarks": tree }))?
+    };
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(count_bookmarks(&tree))
+}
+
+/// Expands a leading `~/` to the home directory, as paths typed on the settings page often have one.
+pub fn expand_home(path: &str) -> PathBuf {
+    let path = path.trim();
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn read_import_file(path: &Path) -> Result<(ImportFormat, ImportData), Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+    if bytes.starts_with(SQLITE_HEADER) {
+        return read_database_copy(path);
+    }
+    if bytes.starts_with(MOZLZ4_HEADER) {
+        return Err("compressed Firefox backups (.jsonlz4) are not supported, import places.sqlite or a JSON backup instead".into());
+    }
+
+    let text = String::from_utf8_lossy(&bytes);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with('{') {
+        let json: Value = serde_json::from_str(text)?;
+        return parse_bookmarks_json(&json);
+    }
+    if text.to_ascii_lowercase().contains("<dl") {
+        let data = ImportData {
+            bookmarks: parse_netscape_html(text),
+            visits: Vec::new(),
+        };
+        return Ok((ImportFormat::NetscapeHtml, data));
+    }
+    Err(format!("{} is not a bookmark or history file Calm can import", path.display()).into())
+}
+
+fn parse_bookmarks_json(json: &Value) -> Result<(ImportFormat, ImportData), Box<dyn Error>> {
+    let (format, bookmarks) = if let Some(roots) = json.get("roots").and_then(Value::as_object) {
+        let roots = roots.values().filter_map(chromium_node).collect();
+        (ImportFormat::ChromiumBookmarks, drop_empty_folders(roots))
+    } else if json.get("type").and_then(Value::as_str) == Some("text/x-moz-place-container") {
+        // The backup's root holds the menu, toolbar, other and mobile roots, plus the tags root.
+        let roots = json["children"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|root| root["guid"].as_str() != Some(FIREFOX_TAGS_GUID))
+            .filter_map(firefox_node)
+            .collect();
+        (ImportFormat::FirefoxJson, drop_empty_folders(roots))
+    } else if let Some(nodes) = json.get("bookmarks") {
+        (ImportFormat::CalmJson, serde_json::from_value(nodes.clone())?)
+    } else {
+        return Err("unrecognized bookmark JSON".into());
+    };
+
+    let data = ImportData {
+        bookmarks,
+        visits: Vec::new(),
+    };
+    Ok((format, data))
+}
+
+/// Converts a node of Chromium's `Bookmarks` file. Its timestamps are microseconds since 1601.
+fn chromium_node(node: &Value) -> Option<BookmarkNode> {
+    let name = node["name"].as_str().unwrap_or_default().to_string();
+    match node.get("type")?.as_str()? {
+        "folder" => Some(BookmarkNode::Folder {
+            name,
+            children: node["children"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(chromium_node)
+                .collect(),
+        }),
+        "url" => {
+            let url = node["url"].as_str().filter(|url| is_importable_url(url))?;
+            let added_at = node["date_added"]
+                .as_str()
+                .and_then(|date| date.parse::<i64>().ok())
+                .map_or(0, webkit_to_unix);
+            Some(BookmarkNode::Bookmark {
+                url: url.to_string(),
+                title: name,
+                tags: Vec::new(),
+                notes: String::new(),
+                keyword: None,
+                added_at,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Converts a node of a Firefox JSON backup. Its timestamps are microseconds since the Unix epoch.
+fn firefox_node(node: &Value) -> Option<BookmarkNode> {
+    let guid = node["guid"].as_str().unwrap_or_default();
+    let title = node["title"].as_str().unwrap_or_default();
+    match node.get("type")?.as_str()? {
+        "text/x-moz-place-container" => Some(BookmarkNode::Folder {
+            name: firefox_root_name(guid).unwrap_or(title).to_string(),
+            children: node["children"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(firefox_node)
+                .collect(),
+        }),
+        "text/x-moz-place" => {
+            let url = node["uri"].as_str().filter(|url| is_importable_url(url))?;
+            let notes = node["annos"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|anno| anno["name"] == "bookmarkProperties/description")
+                .and_then(|anno| anno["value"].as_str())
+                .unwrap_or_default();
+            Some(BookmarkNode::Bookmark {
+                url: url.to_string(),
+                title: title.to_string(),
+                tags: split_tags(node["tags"].as_str().unwrap_or_default()),
+                notes: notes.to_string(),
+                keyword: node["keyword"].as_str().map(str::to_string),
+                added_at: node["dateAdded"].as_i64().unwrap_or(0) / 1_000_000,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Names the built-in Firefox folders, which have no title in newer profiles.
+fn firefox_root_name(guid: &str) -> Option<&'static str> {
+    match guid {
+        "menu________" => Some("Bookmarks Menu"),
+        "toolbar_____" => Some("Bookmarks Toolbar"),
+        "unfiled_____" => Some("Other Bookmarks"),
+        "mobile______" => Some("Mobile Bookmarks"),
+        _ => None,
+    }
+}
+
+/// Drops browser root folders that hold nothing, like an unused mobile bookmarks folder.
+fn drop_empty_folders(roots: Vec<BookmarkNode>) -> Vec<BookmarkNode> {
+    roots
+        .into_iter()
+        .filter(|root| !matches!(root, BookmarkNode::Folder { children, .. } if children.is_empty()))
+        .collect()
+}
+
+/// Reads a Netscape bookmark file, where each `<DL>` lists the contents of the folder named by
+/// the `<H3>` before it and a `<DD>` after a link holds its description.
+fn parse_netscape_html(html: &str) -> Vec<BookmarkNode> {
+    let tag_pattern = Regex::new(r"(?is)<(/?)(dl|h3|a|dd)\b([^>]*)>").unwrap();
+    let attribute_pattern = Regex::new(r#"(?is)([a-z_]+)\s*=\s*"([^"]*)""#).unwrap();
+
+    let mut lists: Vec<(Option<String>, Vec<BookmarkNode>)> = vec![(None, Vec::new())];
+    let mut folder_name: Option<String> = None;
+    for captures in tag_pattern.captures_iter(html) {
+        let end = captures.get(0).map_or(0, |tag| tag.end());
+        let rest = &html[end..];
+        let text = decode_entities(rest[..rest.find('<').unwrap_or(rest.len())].trim());
+        let closing = !captures[1].is_empty();
+
+        match (captures[2].to_ascii_lowercase().as_str(), closing) {
+            ("dl", false) => lists.push((folder_name.take(), Vec::new())),
+            ("dl", true) => close_list(&mut lists),
+            ("h3", false) => folder_name = Some(text),
+            ("a", false) => {
+                let attributes: HashMap<String, String> = attribute_pattern
+                    .captures_iter(&captures[3])
+                    .map(|attribute| (attribute[1].to_ascii_lowercase(), decode_entities(&attribute[2])))
+                    .collect();
+                let Some(url) = attributes.get("href").filter(|url| is_importable_url(url)) else {
+                    continue;
+                };
+                let bookmark = BookmarkNode::Bookmark {
+                    url: url.clone(),
+                    title: text,
+                    tags: split_tags(attributes.get("tags").map_or("", String::as_str)),
+                    notes: String::new(),
+                    keyword: attributes.get("shortcuturl").cloned(),
+                    added_at: attributes
+                        .get("add_date")
+                        .and_then(|date| date.parse().ok())
+                        .unwrap_or(0),
+                };
+                if let Some((_, nodes)) = lists.last_mut() {
+                    nodes.push(bookmark);
+                }
+            }
+            ("dd", false) => {
+                if let Some(BookmarkNode::Bookmark { notes, .. }) = lists.last_mut().and_then(|(_, nodes)| nodes.last_mut()) {
+                    if notes.is_empty() {
+                        *notes = text;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Lists left open by a truncated file still keep their bookmarks.
+    while lists.len() > 1 {
+        close_list(&mut lists);
+    }
+    lists.pop().map(|(_, nodes)| nodes).unwrap_or_default()
+}
+
+/// Ends the innermost open list, adding it to its parent as a folder, or merging it into the
+/// parent when it is the file's unnamed top-level list.
+fn close_list(lists: &mut Vec<(Option<String>, Vec<BookmarkNode>)>) {
+    if lists.len() < 2 {
+        return;
+    }
+    let Some((name, children)) = lists.pop() else {
+        return;
+    };
+    if let Some((_, parent)) = lists.last_mut() {
+        match name {
+            Some(name) => parent.push(BookmarkNode::Folder { name, children }),
+            None => parent.extend(children),
+        }
+    }
+}
+
+fn to_netscape_html(tree: &[BookmarkNode]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n\
+         <!-- This is an automatically generated file.\n     It will be read and overwritten.\n     DO NOT EDIT! -->\n\
+         <META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n\
+         <TITLE>Bookmarks</TITLE>\n\
+         <H1>Bookmarks</H1>\n\
+         <DL><p>\n",
+    );
+    write_netscape_nodes(&mut html, tree, 1);
+    html.push_str("</DL><p>\n");
+    html
+}
+
+fn write_netscape_nodes(html: &mut String, nodes: &[BookmarkNode], depth: usize) {
+    let indent = "    ".repeat(depth);
+    for node in nodes {
+        match node {
+            BookmarkNode::Folder { name, children } => {
+                html.push_str(&format!("{}<DT><H3>{}</H3>\n", indent, escape_html(name)));
+                html.push_str(&format!("{}<DL><p>\n", indent));
+                write_netscape_nodes(html, children, depth + 1);
+                html.push_str(&format!("{}</DL><p>\n", indent));
+            }
+            BookmarkNode::Bookmark {
+                url,
+                title,
+                tags,
+                notes,
+                keyword,
+                added_at,
+            } => {
+                let mut attributes = format!("HREF=\"{}\" ADD_DATE=\"{}\"", escape_html(url), added_at);
+                if !tags.is_empty() {
+                    attributes.push_str(&format!(" TAGS=\"{}\"", escape_html(&tags.join(","))));
+                }
+                if let Some(keyword) = keyword {
+                    attributes.push_str(&format!(" SHORTCUTURL=\"{}\"", escape_html(keyword)));
+                }
+                html.push_str(&format!("{}<DT><A {}>{}</A>\n", indent, attributes, escape_html(title)));
+                if !notes.is_empty() {
+                    html.push_str(&format!("{}<DD>{}\n", indent, escape_html(notes)));
+                }
+            }
+        }
+    }
+}
+
+/// Reads a browser database from a copy, as browsers keep theirs locked while running and hold
+/// recent changes in a write-ahead log next to it.
+fn read_database_copy(path: &Path) -> Result<(ImportFormat, ImportData), Box<dyn Error>> {
+    let dir = create_private_dir()?;
+    let copy = dir.join("import.sqlite");
+    let result = fs::copy(path, &copy)
+        .map_err(Box::<dyn Error>::from)
+        .and_then(|_| {
+            let wal = with_suffix(path, "-wal");
+            if wal.exists() {
+                let _ = fs::copy(&wal, with_suffix(&copy, "-wal"));
+            }
+            Connection::open(&copy).map_err(Box::<dyn Error>::from)
+        })
+        .and_then(|conn| read_database(&conn));
+
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+/// Creates a new directory in the temporary directory that only the current user can enter.
+/// Creating it fails rather than reusing whatever is already at its path, so other users can't
+/// redirect the copies made in it.
+fn create_private_dir() -> io::Result<PathBuf> {
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    loop {
+        let dir = std::env::temp_dir().join(format!(
+            "calm-import-{}-{}",
+            std::process::id(),
+            NEXT_IMPORT_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+        match builder.create(&dir) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            result => return result.map(|()| dir),
+        }
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn read_database(conn: &Connection) -> Result<(ImportFormat, ImportData), Box<dyn Error>> {
+    let has_table = |name: &str| {
+        conn.query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [name],
+            |_| Ok(()),
+        )
+        .optional()
+        .map(|table| table.is_some())
+    };
+
+    if has_table("moz_places")? {
+        let data = ImportData {
+            bookmarks: read_firefox_bookmarks(conn)?,
+            visits: read_visits(
+                conn,
+                "SELECT p.url, p.title, v.visit_date / 1000000
+                 FROM moz_historyvisits v JOIN moz_places p ON p.id = v.place_id",
+            )?,
+        };
+        Ok((ImportFormat::FirefoxPlaces, data))
+    } else if has_table("urls")? && has_table("visits")? {
+        let data = ImportData {
+            bookmarks: Vec::new(),
+            visits: read_visits(
+                conn,
+                &format!(
+                    "SELECT u.url, u.title, v.visit_time / 1000000 - {}
+                     FROM visits v JOIN urls u ON u.id = v.url",
+                    WEBKIT_EPOCH_OFFSET
+                ),
+            )?,
+        };
+        Ok((ImportFormat::ChromiumHistory, data))
+    } else {
+        Err("unrecognized browser database".into())
+    }
+}
+
+/// Runs a query returning the URL, title and Unix time of every visit.
+fn read_visits(conn: &Connection, sql: &str) -> rusqlite::Result<Vec<HistoryEntry>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(HistoryEntry {
+            url: row.get(0)?,
+            title: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            timestamp: row.get(2)?,
+            visit_count: 0,
+        })
+    })?;
+
+    let mut visits = Vec::new();
+    for visit in rows {
+        let visit = visit?;
+        if is_importable_url(&visit.url) {
+            visits.push(visit);
+        }
+    }
+    Ok(visits)
+}
+
+/// A row of Firefox's `moz_bookmarks`, which keeps bookmarks, folders and separators in one
+/// table. Tags are folders under the tags root holding one bookmark per tagged URL.
+struct FirefoxItem {
+    id: i64,
+    kind: i64,
+    parent: i64,
+    title: String,
+    guid: String,
+    url: Option<String>,
+    keyword: Option<String>,
+    added_at: i64,
+}
+
+const FIREFOX_BOOKMARK: i64 = 1;
+const FIREFOX_FOLDER: i64 = 2;
+
+fn read_firefox_bookmarks(conn: &Connection) -> rusqlite::Result<Vec<BookmarkNode>> {
+    let has_keywords = conn
+        .query_row("SELECT 1 FROM sqlite_master WHERE name = 'moz_keywords'", [], |_| Ok(()))
+        .optional()?
+        .is_some();
+    let keyword_column = if has_keywords {
+        "(SELECT keyword FROM moz_keywords k WHERE k.place_id = b.fk LIMIT 1)"
+    } else {
+        "NULL"
+    };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT b.id, b.type, b.parent, b.title, b.guid, p.url, {}, b.dateAdded / 1000000
+         FROM moz_bookmarks b LEFT JOIN moz_places p ON p.id = b.fk
+         ORDER BY b.parent, b.position",
+        keyword_column
+    ))?;
+    let items = stmt
+        .query_map([], |row| {
+            Ok(FirefoxItem {
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                parent: row.get(2)?,
+                title: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                guid: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                url: row.get(5)?,
+                keyword: row.get(6)?,
+                added_at: row.get::<_, Option<i64>>(7)?.unwrap_or(0),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut children: HashMap<i64, Vec<&FirefoxItem>> = HashMap::new();
+    for item in &items {
+        children.entry(item.parent).or_default().push(item);
+    }
+
+    let tags_root = items.iter().find(|item| item.guid == FIREFOX_TAGS_GUID).map(|item| item.id);
+    let mut tags: HashMap<&str, Vec<String>> = HashMap::new();
+    for tag in tags_root.and_then(|id| children.get(&id)).into_iter().flatten() {
+        for tagged in children.get(&tag.id).into_iter().flatten() {
+            if let Some(url) = tagged.url.as_deref() {
+                tags.entry(url).or_default().push(tag.title.clone());
+            }
+        }
+    }
+
+    let Some(root) = items.iter().find(|item| item.parent == 0 && item.kind == FIREFOX_FOLDER) else {
+        return Ok(Vec::new());
+    };
+    let roots = children
+        .get(&root.id)
+        .into_iter()
+        .flatten()
+        .filter(|item| Some(item.id) != tags_root)
+        .filter_map(|item| firefox_item(item, &children, &tags))
+        .collect();
+    Ok(drop_empty_folders(roots))
+}
+
+fn firefox_item(
+    item: &FirefoxItem,
+    children: &HashMap<i64, Vec<&FirefoxItem>>,
+    tags: &HashMap<&str, Vec<String>>,
+) -> Option<BookmarkNode> {
+    match item.kind {
+        FIREFOX_FOLDER => Some(BookmarkNode::Folder {
+            name: firefox_root_name(&item.guid).unwrap_or(&item.title).to_string(),
+            children: children
+                .get(&item.id)
+                .into_iter()
+                .flatten()
+                .filter_map(|child| firefox_item(child, children, tags))
+                .collect(),
+        }),
+        FIREFOX_BOOKMARK => {
+            let url = item.url.as_deref().filter(|url| is_importable_url(url))?;
+            Some(BookmarkNode::Bookmark {
+                url: url.to_string(),
+                title: item.title.clone(),
+                tags: tags.get(url).cloned().unwrap_or_default(),
+                notes: String::new(),
+                keyword: item.keyword.clone(),
+                added_at: item.added_at,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether a URL is a page Calm can open. Browser-internal URLs such as Firefox's
+/// `place:` queries and bookmarklets are left out.
+fn is_importable_url(url: &str) -> bool {
+    ["http://", "https://", "file://", "ftp://"]
+        .iter()
+        .any(|scheme| {
+            url.len() > scheme.len()
+                && url
+                    .get(..scheme.len())
+                    .is_some_and(|start| start.eq_ignore_ascii_case(scheme))
+        })
+}
+
+fn webkit_to_unix(microseconds: i64) -> i64 {
+    (microseconds / 1_000_000 - WEBKIT_EPOCH_OFFSET).max(0)
+}
+
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn count_bookmarks(nodes: &[BookmarkNode]) -> usize {
+    nodes
+        .iter()
+        .map(|node| match node {
+            BookmarkNode::Folder { children, .. } => count_bookmarks(children),
+            BookmarkNode::Bookmark { .. } => 1,
+        })
+        .sum()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(url: &str, title: &str) -> BookmarkNode {
+        BookmarkNode::Bookmark {
+            url: url.to_string(),
+            title: title.to_string(),
+            tags: Vec::new(),
+            notes: String::new(),
+            keyword: None,
+            added_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_netscape_html_round_trip() {
+        let tree = vec![
+            BookmarkNode::Folder {
+                name: "Dev & Docs".to_string(),
+                children: vec![BookmarkNode::Bookmark {
+                    url: "https://github.com/search?q=%s&type=code".to_string(),
+                    title: "Search <code>".to_string(),
+                    tags: vec!["dev".to_string(), "search".to_string()],
+                    notes: "Finds code".to_string(),
+                    keyword: Some("gh".to_string()),
+                    added_at: 1_700_000_000,
+                }],
+            },
+            bookmark("https://example.com", "Example"),
+        ];
+
+        assert_eq!(parse_netscape_html(&to_netscape_html(&tree)), tree);
+    }
+
+    #[test]
+    fn test_netscape_html_from_other_browsers() {
+        let html = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><H3 ADD_DATE="1" PERSONAL_TOOLBAR_FOLDER="true">Bookmarks bar</H3>
+    <DL><p>
+        <DT><A HREF="https://rust-lang.org/" ADD_DATE="1600000000" ICON="data:image/png;base64,AAA">Rust</A>
+        <DT><A HREF="javascript:alert(1)">Bookmarklet</A>
+    </DL><p>
+    <DT><A href="place:sort=8">Recent</A>
+    <DT><a href="https://news.ycombinator.com/">HN</a>
+"#;
+        let tree = parse_netscape_html(html);
+        assert_eq!(tree.len(), 2);
+        assert!(matches!(&tree[0], BookmarkNode::Folder { name, children } if name == "Bookmarks bar" && children.len() == 1));
+        assert!(matches!(&tree[1], BookmarkNode::Bookmark { url, title, .. } if url == "https://news.ycombinator.com/" && title == "HN"));
+    }
+
+    #[test]
+    fn test_importable_urls() {
+        assert!(is_importable_url("HTTPS://example.com/"));
+        assert!(is_importable_url("file:///home/me/notes.html"));
+        assert!(!is_importable_url("https://"));
+        assert!(!is_importable_url("place:sort=8"));
+        // Multi-byte characters where a scheme would end don't split the string.
+        assert!(!is_importable_url("abcde€fghij"));
+        assert!(!is_importable_url("http:/€/example.com"));
+    }
+
+    #[test]
+    fn test_chromium_and_firefox_json() {
+        let chromium = serde_json::json!({
+            "roots": {
+                "bookmark_bar": {
+                    "type": "folder",
+                    "name": "Bookmarks bar",
+                    "children": [
+                        { "type": "url", "name": "Rust", "url": "https://rust-lang.org/", "date_added": "13300000000000000" }
+                    ]
+                },
+                "synced": { "type": "folder", "name": "Mobile bookmarks", "children": [] }
+            },
+            "version": 1
+        });
+        let (format, data) = parse_bookmarks_json(&chromium).unwrap();
+        assert_eq!(format, ImportFormat::ChromiumBookmarks);
+        assert_eq!(data.bookmarks.len(), 1);
+        let BookmarkNode::Folder { children, .. } = &data.bookmarks[0] else {
+            panic!("expected the bookmarks bar");
+        };
+        assert!(matches!(&children[0], BookmarkNode::Bookmark { added_at: 1_655_526_400, .. }));
+
+        let firefox = serde_json::json!({
+            "guid": "root________",
+            "type": "text/x-moz-place-container",
+            "children": [
+                {
+                    "guid": "toolbar_____",
+                    "title": "toolbar",
+                    "type": "text/x-moz-place-container",
+                    "children": [{
+                        "type": "text/x-moz-place",
+                        "title": "MDN",
+                        "uri": "https://developer.mozilla.org/search?q=%s",
+                        "tags": "docs,web",
+                        "keyword": "mdn",
+                        "dateAdded": 1_600_000_000_000_000i64
+                    }]
+                },
+                { "guid": "tagsfolder__", "type": "text/x-moz-place-container", "children": [
+                    { "type": "text/x-moz-place-container", "title": "docs", "children": [] }
+                ]}
+            ]
+        });
+        let (format, data) = parse_bookmarks_json(&firefox).unwrap();
+        assert_eq!(format, ImportFormat::FirefoxJson);
+        assert_eq!(
+            data.bookmarks,
+            vec![BookmarkNode::Folder {
+                name: "Bookmarks Toolbar".to_string(),
+                children: vec![BookmarkNode::Bookmark {
+                    url: "https://developer.mozilla.org/search?q=%s".to_string(),
+                    title: "MDN".to_string(),
+                    tags: vec!["docs".to_string(), "web".to_string()],
+                    notes: String::new(),
+                    keyword: Some("mdn".to_string()),
+                    added_at: 1_600_000_000,
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_firefox_places_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+             CREATE TABLE moz_historyvisits (id INTEGER PRIMARY KEY, place_id INTEGER, visit_date INTEGER);
+             CREATE TABLE moz_keywords (id INTEGER PRIMARY KEY, keyword TEXT, place_id INTEGER);
+             CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER, parent INTEGER,
+                 position INTEGER, title TEXT, dateAdded INTEGER, guid TEXT);
+             INSERT INTO moz_places VALUES (1, 'https://rust-lang.org/', 'Rust'), (2, 'place:sort=8', NULL);
+             INSERT INTO moz_historyvisits VALUES (1, 1, 1600000000000000), (2, 2, 1600000000000000);
+             INSERT INTO moz_keywords VALUES (1, 'rs', 1);
+             INSERT INTO moz_bookmarks VALUES
+                 (1, 2, NULL, 0, 0, '', 0, 'root________'),
+                 (2, 2, NULL, 1, 0, 'menu', 0, 'menu________'),
+                 (3, 2, NULL, 1, 1, 'tags', 0, 'tagsfolder__'),
+                 (4, 2, NULL, 1, 2, 'mobile', 0, 'mobile______'),
+                 (5, 1, 1, 2, 0, 'Rust lang', 1600000000000000, 'a'),
+                 (6, 1, 2, 2, 1, 'Recent', 0, 'b'),
+                 (7, 2, NULL, 3, 0, 'lang', 0, 'c'),
+                 (8, 1, 1, 7, 0, NULL, 0, 'd');",
+        )
+        .unwrap();
+
+        let (format, data) = read_database(&conn).unwrap();
+        assert_eq!(format, ImportFormat::FirefoxPlaces);
+        assert_eq!(data.visits.len(), 1);
+        assert_eq!(data.visits[0].timestamp, 1_600_000_000);
+        assert_eq!(
+            data.bookmarks,
+            vec![BookmarkNode::Folder {
+                name: "Bookmarks Menu".to_string(),
+                children: vec![BookmarkNode::Bookmark {
+                    url: "https://rust-lang.org/".to_string(),
+                    title: "Rust lang".to_string(),
+                    tags: vec!["lang".to_string()],
+                    notes: String::new(),
+                    keyword: Some("rs".to_string()),
+                    added_at: 1_600_000_000,
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_database_copies_get_their_own_directory() {
+        let first = create_private_dir().unwrap();
+        let second = create_private_dir().unwrap();
+        assert_ne!(first, second);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&first).unwrap().permissions().mode() & 0o777, 0o700);
+        }
+
+        let database = first.join("History");
+        Connection::open(&database)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE urls (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+                 CREATE TABLE visits (id INTEGER PRIMARY KEY, url INTEGER, visit_time INTEGER);
+                 INSERT INTO urls VALUES (1, 'https://example.com/', 'Example');
+                 INSERT INTO visits VALUES (1, 1, 13300000000000000);",
+            )
+            .unwrap();
+        let (format, data) = read_database_copy(&database).unwrap();
+        assert_eq!(format, ImportFormat::ChromiumHistory);
+        assert_eq!(data.visits.len(), 1);
+
+        let _ = fs::remove_dir_all(first);
+        let _ = fs::remove_dir_all(second);
+    }
+
+    #[test]
+    fn test_chromium_history_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE urls (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+             CREATE TABLE visits (id INTEGER PRIMARY KEY, url INTEGER, visit_time INTEGER);
+             INSERT INTO urls VALUES (1, 'https://example.com/', 'Example'), (2, 'chrome://settings/', 'Settings');
+             INSERT INTO visits VALUES (1, 1, 13300000000000000), (2, 1, 13300000060000000), (3, 2, 13300000000000000);",
+        )
+        .unwrap();
+
+        let (format, data) = read_database(&conn).unwrap();
+        assert_eq!(format, ImportFormat::ChromiumHistory);
+        let timestamps: Vec<i64> = data.visits.iter().map(|visit| visit.timestamp).collect();
+        assert_eq!(timestamps, vec![1_655_526_400, 1_655_526_460]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::privacy::block_log::LogFilter;
 use serde::{Deserialize, Serialize};
 
 /// Messages that can be sent between browser components via IPC.
@@ -19,6 +20,139 @@ pub enum BrowserMessage {
     #[serde(rename = "new_tab")]
     NewTab,
 
+    #[serde(rename = "move_tab")]
+    MoveTab {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+        #[serde(rename = "toIndex")]
+        to_index: usize,
+    },
+
+    #[serde(rename = "create_tab_group")]
+    CreateTabGroup {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+        name: Option<String>,
+    },
+
+    #[serde(rename = "add_tab_to_group")]
+    AddTabToGroup {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+        #[serde(rename = "groupId")]
+        group_id: usize,
+    },
+
+    #[serde(rename = "remove_tab_from_group")]
+    RemoveTabFromGroup {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+    },
+
+    #[serde(rename = "rename_tab_group")]
+    RenameTabGroup {
+        #[serde(rename = "groupId")]
+        group_id: usize,
+        name: String,
+    },
+
+    #[serde(rename = "set_tab_group_color")]
+    SetTabGroupColor {
+        #[serde(rename = "groupId")]
+        group_id: usize,
+        color: String,
+    },
+
+    #[serde(rename = "set_tab_group_collapsed")]
+    SetTabGroupCollapsed {
+        #[serde(rename = "groupId")]
+        group_id: usize,
+        collapsed: bool,
+    },
+
+    #[serde(rename = "close_tab_group")]
+    CloseTabGroup {
+        #[serde(rename = "groupId")]
+        group_id: usize,
+    },
+
+    #[serde(rename = "move_tab_group_to_new_window")]
+    MoveTabGroupToNewWindow {
+        #[serde(rename = "groupId")]
+        group_id: usize,
+    },
+
+    #[serde(rename = "save_tab_group")]
+    SaveTabGroup {
+        #[serde(rename = "groupId")]
+        group_id: usize,
+    },
+
+    #[serde(rename = "switch_tab_group")]
+    SwitchTabGroup {
+        #[serde(rename = "groupId")]
+        group_id: usize,
+    },
+
+    #[serde(rename = "open_saved_tab_group")]
+    OpenSavedTabGroup {
+        #[serde(rename = "savedId")]
+        saved_id: u64,
+    },
+
+    #[serde(rename = "move_tab_to_window")]
+    MoveTabToWindow {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+        #[serde(rename = "fromWindow")]
+        from_window: Option<usize>,
+        #[serde(rename = "toWindow")]
+        to_window: Option<usize>,
+    },
+
+    #[serde(rename = "move_active_tab_to_window")]
+    MoveActiveTabToWindow {
+        #[serde(rename = "toWindow")]
+        to_window: Option<usize>,
+    },
+
+    #[serde(rename = "pin_tab")]
+    PinTab {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+        pinned: bool,
+    },
+
+    #[serde(rename = "set_tab_container")]
+    SetTabContainer {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+        container: Option<String>,
+    },
+
+    #[serde(rename = "add_container_rule")]
+    AddContainerRule {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+    },
+
+    #[serde(rename = "show_shields")]
+    ShowShields,
+
+    #[serde(rename = "set_site_shield")]
+    SetSiteShield {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+        name: String,
+        enabled: bool,
+    },
+
+    #[serde(rename = "reset_site_shields")]
+    ResetSiteShields {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+    },
+
     #[serde(rename = "navigate")]
     Navigate {
         #[serde(rename = "tabId")]
@@ -55,11 +189,200 @@ pub enum BrowserMessage {
     #[serde(rename = "toggle_split_view")]
     ToggleSplitView,
 
+    #[serde(rename = "add_split_pane")]
+    AddSplitPane,
+
+    #[serde(rename = "set_split_layout")]
+    SetSplitLayout { layout: String },
+
+    #[serde(rename = "split_divider_drag")]
+    SplitDividerDrag {
+        divider: usize,
+        phase: String,
+        x: f64,
+        y: f64,
+    },
+
+    #[serde(rename = "focus_split_pane")]
+    FocusSplitPane {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+        direction: String,
+    },
+
     #[serde(rename = "inspect_element")]
     InspectElement,
 
     #[serde(rename = "close_window")]
     CloseWindow,
+
+    #[serde(rename = "reopen_closed_tab")]
+    ReopenClosedTab,
+
+    #[serde(rename = "blocked")]
+    Blocked { items: Vec<BlockedItem> },
+
+    #[serde(rename = "privacy_log_query")]
+    PrivacyLogQuery { filter: LogFilter },
+
+    #[serde(rename = "privacy_log_clear")]
+    PrivacyLogClear,
+
+    #[serde(rename = "privacy_log_export")]
+    PrivacyLogExport {
+        path: String,
+        filter: LogFilter,
+    },
+
+    #[serde(rename = "history_page_query")]
+    HistoryPageQuery {
+        query: String,
+        before: Option<i64>,
+    },
+
+    #[serde(rename = "history_page_delete")]
+    HistoryPageDelete { ids: Vec<i64> },
+
+    #[serde(rename = "history_forget_site")]
+    HistoryForgetSite { host: String },
+
+    #[serde(rename = "apply_history_rules")]
+    ApplyHistoryRules,
+
+    #[serde(rename = "search_history")]
+    SearchHistory { query: String },
+
+    #[serde(rename = "switch_to_open_tab")]
+    SwitchToOpenTab {
+        #[serde(rename = "tabId")]
+        tab_id: usize,
+    },
+
+    #[serde(rename = "reopen_closed_entry")]
+    ReopenClosedEntry {
+        #[serde(rename = "entryId")]
+        entry_id: u64,
+    },
+
+    #[serde(rename = "add_bookmark")]
+    AddBookmark {
+        url: String,
+        title: Option<String>,
+        #[serde(rename = "folderId")]
+        folder_id: Option<u64>,
+    },
+
+    #[serde(rename = "update_bookmark")]
+    UpdateBookmark {
+        id: u64,
+        url: Option<String>,
+        title: Option<String>,
+        #[serde(rename = "folderId")]
+        folder_id: Option<u64>,
+        tags: Option<Vec<String>>,
+        notes: Option<String>,
+        keyword: Option<String>,
+    },
+
+    #[serde(rename = "remove_bookmark")]
+    RemoveBookmark { id: u64 },
+
+    #[serde(rename = "add_bookmark_folder")]
+    AddBookmarkFolder {
+        name: String,
+        #[serde(rename = "parentId")]
+        parent_id: Option<u64>,
+    },
+
+    #[serde(rename = "rename_bookmark_folder")]
+    RenameBookmarkFolder {
+        #[serde(rename = "folderId")]
+        folder_id: u64,
+        name: String,
+    },
+
+    #[serde(rename = "move_bookmark_folder")]
+    MoveBookmarkFolder {
+        #[serde(rename = "folderId")]
+        folder_id: u64,
+        #[serde(rename = "parentId")]
+        parent_id: Option<u64>,
+    },
+
+    #[serde(rename = "remove_bookmark_folder")]
+    RemoveBookmarkFolder {
+        #[serde(rename = "folderId")]
+        folder_id: u64,
+    },
+
+    #[serde(rename = "open_bookmark")]
+    OpenBookmark {
+        url: String,
+        #[serde(rename = "newTab", default)]
+        new_tab: bool,
+    },
+
+    #[serde(rename = "close_bookmarks_panel")]
+    CloseBookmarksPanel,
+
+    #[serde(rename = "import_browser_data")]
+    ImportBrowserData { path: String },
+
+    #[serde(rename = "export_bookmarks")]
+    ExportBookmarks { path: String },
+
+    #[serde(rename = "save_to_reading_list")]
+    SaveToReadingList {
+        title: String,
+        html: Option<String>,
+    },
+
+    #[serde(rename = "open_reading_item")]
+    OpenReadingItem {
+        id: u64,
+        #[serde(default)]
+        original: bool,
+        #[serde(rename = "newTab", default)]
+        new_tab: bool,
+    },
+
+    #[serde(rename = "set_reading_item_read")]
+    SetReadingItemRead { id: u64, read: bool },
+
+    #[serde(rename = "remove_reading_item")]
+    RemoveReadingItem { id: u64 },
+
+    #[serde(rename = "save_page_for_later")]
+    SavePageForLater,
+
+    #[serde(rename = "open_reading_list")]
+    OpenReadingList,
+
+    #[serde(rename = "new_private_window")]
+    NewPrivateWindow,
+
+    #[serde(rename = "open_profile_picker")]
+    OpenProfilePicker,
+
+    #[serde(rename = "open_profile")]
+    OpenProfile { name: String },
+
+    #[serde(rename = "create_profile")]
+    CreateProfile { name: String },
+}
+
+/// A request a privacy defense stopped on a page, as reported by the page's scripts.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockedItem {
+    pub url: String,
+    pub defense: String,
+    #[serde(rename = "resourceType")]
+    pub resource_type: String,
+    pub rule: Option<String>,
+    /// Set for failed loads, which only count when a filter list rule blocks them.
+    #[serde(default)]
+    pub check: bool,
+    pub timestamp: Option<i64>,
 }
 
 /// Information about a browser tab for IPC communication.
@@ -1,12 +1,17 @@
+mod bookmarks;
 mod config;
 mod debug;
 mod downloads;
 mod errors;
 mod history;
+mod import_export;
 mod ipc;
+mod memory;
+mod paths;
 #[allow(dead_code)]
 mod performance;
 mod privacy;
+mod reading_list;
 #[allow(dead_code)]
 mod session;
 mod shortcuts;
@@ -21,19 +26,39 @@ mod vimium_hints;
 mod window;
 
 use muda::{Menu, MenuItem, PredefinedMenuItem, Submenu};
-use std::{cell::RefCell, collections::HashMap, rc::Rc, time::Instant};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 use tao::{
     event::{ElementState, Event, MouseButton, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
+    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
     keyboard::ModifiersState,
     window::WindowId,
 };
 
 use config::Config;
-use window::{create_browser_window, BrowserWindowComponents};
+use window::{
+    create_browser_window, create_session_recovery_window, restore_browser_window,
+    BrowserWindowComponents, WindowRequest, WindowSummary,
+};
 
 const DOWNLOAD_SIDEBAR_WIDTH: i32 = 360;
 
+/// How often background tabs are checked for suspension.
+const TAB_SUSPENSION_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often visits older than the configured history age are pruned.
+const HISTORY_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often subscribed filter lists are checked for being due an update.
+const FILTER_UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// How often the memory of web processes is sampled.
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
 /// Converts file:// URLs to calmfile://localhost URLs for custom protocol handling.
 ///
 /// # Arguments
@@ -52,7 +77,21 @@ pub fn convert_file_url(url: &str) -> String {
 }
 
 fn main() -> wry::Result<()> {
-    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+
+    // The profile decides where every file lives, so it is picked before anything is loaded.
+    if let Some(index) = args.iter().position(|arg| arg == "--profile") {
+        let Some(name) = args.get(index + 1).cloned() else {
+            eprintln!("Usage: calm --profile <name>");
+            std::process::exit(2);
+        };
+        if !paths::is_valid_profile_name(&name) {
+            eprintln!("Invalid profile name: {} (use letters, digits, - and _)", name);
+            std::process::exit(2);
+        }
+        args.drain(index..=index + 1);
+        paths::set_profile(&name);
+    }
 
     if !args.is_empty() {
         let first_arg = &args[0];
@@ -68,18 +107,26 @@ fn main() -> wry::Result<()> {
             println!("    calm                    Open default URL (configured in ~/.calm.yml)");
             println!("    calm <url>              Open specific URL");
             println!("    calm <search terms>     Search using configured search engine");
+            println!("    calm --import <file>    Import bookmarks and history from another browser");
             println!();
             println!("OPTIONS:");
             println!("    -h, --help              Print this help information");
             println!("    -v, --version           Print version information");
+            println!("    --profile <name>        Use profile <name>, created on first use");
+            println!("    --profiles              List profiles");
             println!();
             println!("CONFIGURATION:");
             println!("    Edit ~/.calm.yml to configure default URL and search engine");
+            println!("    Named profiles keep their config and data in ~/.calm/profiles/<name>");
             println!();
             println!("KEYBOARD SHORTCUTS:");
             println!("    Cmd+N                   Open new window");
+            println!("    Cmd+Shift+N             Open new private window");
             println!("    Cmd+T                   Open new tab");
             println!("    Cmd+W                   Close tab/window");
+            println!("    Cmd+Shift+T             Reopen closed tab/window");
+            println!("    Cmd+D                   Bookmark page");
+            println!("    Cmd+Shift+B             Toggle bookmarks");
             println!("    Cmd+L                   Focus URL bar");
             println!("    Cmd+R                   Reload page");
             println!();
@@ -87,6 +134,27 @@ fn main() -> wry::Result<()> {
             println!("    calm");
             println!("    calm https://example.com");
             println!("    calm rust programming");
+            println!("    calm --import ~/Downloads/bookmarks.html");
+            println!("    calm --profile work https://example.com");
+            return Ok(());
+        } else if first_arg == "--profiles" {
+            for name in paths::list_profiles() {
+                println!("{}", name);
+            }
+            return Ok(());
+        } else if first_arg == "--import" {
+            let Some(path) = args.get(1) else {
+                eprintln!("Usage: calm --import <file>");
+                std::process::exit(2);
+            };
+            let mut browsing_history = history::History::load();
+            match import_export::import_file(&import_export::expand_home(path), &mut browsing_history) {
+                Ok(summary) => println!("{}", summary),
+                Err(e) => {
+                    eprintln!("Failed to import {}: {}", path, e);
+                    std::process::exit(1);
+                }
+            }
             return Ok(());
         }
     }
@@ -111,10 +179,23 @@ fn main() -> wry::Result<()> {
     debug_log!("  focus_sidebar: {}", config.ui.shortcuts.focus_sidebar);
     debug_log!("  find: {}", config.ui.shortcuts.find);
     debug_log!("  new_window: {}", config.ui.shortcuts.new_window);
+    debug_log!(
+        "  new_private_window: {}",
+        config.ui.shortcuts.new_private_window
+    );
     debug_log!(
         "  toggle_split_view: {}",
         config.ui.shortcuts.toggle_split_view
     );
+    debug_log!(
+        "  reopen_closed_tab: {}",
+        config.ui.shortcuts.reopen_closed_tab
+    );
+    debug_log!("  bookmark_page: {}", config.ui.shortcuts.bookmark_page);
+    debug_log!(
+        "  toggle_bookmarks: {}",
+        config.ui.shortcuts.toggle_bookmarks
+    );
 
     if !single_instance::SingleInstance::is_single() {
         let url_to_send = if args.is_empty() {
@@ -147,6 +228,24 @@ fn main() -> wry::Result<()> {
 
     debug_log!("Starting new Calm instance");
 
+    let session_restore_enabled = config.performance.session_restore;
+    let session_save_interval =
+        Duration::from_secs(config.performance.session_save_interval_seconds.max(5));
+    let saved_session = if session_restore_enabled {
+        session::BrowserSession::load()
+    } else {
+        None
+    };
+
+    let previous_run_crashed = session::BrowserSession::previous_run_crashed();
+    if previous_run_crashed {
+        debug_log!("Previous run did not exit cleanly");
+    }
+    if let Err(e) = session::BrowserSession::mark_running() {
+        debug_log!("Failed to create session run marker: {}", e);
+    }
+    tabs::private_data::remove_leftover_data();
+
     let event_loop = EventLoop::new();
 
     #[cfg(target_os = "macos")]
@@ -164,18 +263,32 @@ fn main() -> wry::Result<()> {
             true,
             shortcuts::parse_shortcut(&config.ui.shortcuts.new_window),
         );
+        let new_private_window_item = MenuItem::new(
+            "New Private Window",
+            true,
+            shortcuts::parse_shortcut(&config.ui.shortcuts.new_private_window),
+        );
         let close_tab_item = MenuItem::new(
             "Close Tab",
             true,
             shortcuts::parse_shortcut(&config.ui.shortcuts.close_tab),
         );
+        let reopen_closed_tab_item = MenuItem::new(
+            "Reopen Closed Tab",
+            true,
+            shortcuts::parse_shortcut(&config.ui.shortcuts.reopen_closed_tab),
+        );
+        let switch_profile_item = MenuItem::new("Switch Profile...", true, None);
 
         file_menu
             .append_items(&[
                 &new_tab_item,
                 &new_window_item,
+                &new_private_window_item,
+                &switch_profile_item,
                 &PredefinedMenuItem::separator(),
                 &close_tab_item,
+                &reopen_closed_tab_item,
                 &PredefinedMenuItem::separator(),
                 &PredefinedMenuItem::quit(None),
             ])
@@ -208,12 +321,18 @@ fn main() -> wry::Result<()> {
             true,
             shortcuts::parse_shortcut(&config.ui.shortcuts.toggle_split_view),
         );
+        let toggle_bookmarks_item = MenuItem::new(
+            "Toggle Bookmarks",
+            true,
+            shortcuts::parse_shortcut(&config.ui.shortcuts.toggle_bookmarks),
+        );
 
         view_menu
             .append_items(&[
                 &reload_item,
                 &PredefinedMenuItem::separator(),
                 &toggle_downloads_item,
+                &toggle_bookmarks_item,
                 &toggle_split_view_item,
             ])
             .expect("Failed to append View menu items");
@@ -244,6 +363,17 @@ fn main() -> wry::Result<()> {
             ])
             .expect("Failed to append Navigate menu items");
 
+        let bookmarks_menu = Submenu::new("Bookmarks", true);
+        let bookmark_page_item = MenuItem::new(
+            "Bookmark This Page",
+            true,
+            shortcuts::parse_shortcut(&config.ui.shortcuts.bookmark_page),
+        );
+
+        bookmarks_menu
+            .append_items(&[&bookmark_page_item])
+            .expect("Failed to append Bookmarks menu items");
+
         menu_bar
             .append(&file_menu)
             .expect("Failed to append File menu");
@@ -256,6 +386,9 @@ fn main() -> wry::Result<()> {
         menu_bar
             .append(&navigate_menu)
             .expect("Failed to append Navigate menu");
+        menu_bar
+            .append(&bookmarks_menu)
+            .expect("Failed to append Bookmarks menu");
         menu_bar.init_for_nsapp();
 
         (
@@ -268,6 +401,11 @@ fn main() -> wry::Result<()> {
             focus_url_item,
             focus_sidebar_item,
             find_item,
+            reopen_closed_tab_item,
+            bookmark_page_item,
+            toggle_bookmarks_item,
+            new_private_window_item,
+            switch_profile_item,
         )
     };
 
@@ -295,16 +433,57 @@ fn main() -> wry::Result<()> {
     let mut windows: HashMap<WindowId, BrowserWindowComponents> = HashMap::new();
     let focused_window_id = Rc::new(RefCell::new(None::<WindowId>));
 
-    let first_window = create_browser_window(
-        &event_loop,
-        Rc::clone(&config),
-        initial_url.clone(),
-        use_welcome_html,
-    )?;
+    // After a crash, let the user pick what to reopen instead of restoring a page that may
+    // have caused it. Autosave stays off until they decide so the old session is kept.
+    let mut session_recovery_pending = false;
 
-    let first_window_id = first_window.window.id();
-    *focused_window_id.borrow_mut() = Some(first_window_id);
-    windows.insert(first_window_id, first_window);
+    match saved_session {
+        Some(saved_session) if previous_run_crashed && !saved_session.windows.is_empty() => {
+            let recovery_window =
+                create_session_recovery_window(&event_loop, Rc::clone(&config), &saved_session)?;
+            let recovery_window_id = recovery_window.window.id();
+            *focused_window_id.borrow_mut() = Some(recovery_window_id);
+            windows.insert(recovery_window_id, recovery_window);
+            session_recovery_pending = true;
+        }
+        Some(saved_session) => {
+            for window_state in &saved_session.windows {
+                match restore_browser_window(&event_loop, Rc::clone(&config), window_state, false) {
+                    Ok(components) => {
+                        let window_id = components.window.id();
+                        if focused_window_id.borrow().is_none() {
+                            *focused_window_id.borrow_mut() = Some(window_id);
+                        }
+                        windows.insert(window_id, components);
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to restore window from session: {}", e);
+                    }
+                }
+            }
+            debug_log!("Restored {} windows from session", windows.len());
+        }
+        None => {}
+    }
+
+    if windows.is_empty() {
+        let first_window = create_browser_window(
+            &event_loop,
+            Rc::clone(&config),
+            initial_url.clone(),
+            use_welcome_html,
+            false,
+        )?;
+
+        let first_window_id = first_window.window.id();
+        *focused_window_id.borrow_mut() = Some(first_window_id);
+        windows.insert(first_window_id, first_window);
+    } else if !args.is_empty() && !session_recovery_pending {
+        let focused_id = *focused_window_id.borrow();
+        if let Some(components) = focused_id.and_then(|id| windows.get(&id)) {
+            open_url_in_new_tab(components, &initial_url);
+        }
+    }
 
     let windows_ref = Rc::new(RefCell::new(windows));
     let last_g_key_time = Rc::new(RefCell::new(None::<Instant>));
@@ -322,9 +501,29 @@ fn main() -> wry::Result<()> {
         }
     };
 
+    let mut last_session_save = Instant::now();
+    let mut last_suspension_check = Instant::now();
+    let mut last_history_prune: Option<Instant> = None;
+    let mut last_filter_update_check: Option<Instant> = None;
+    let min_available_memory_mb = config.borrow().performance.min_available_memory_mb;
+    let memory_snapshot = memory::start_sampler(MEMORY_SAMPLE_INTERVAL);
+    let mut known_window_count = 0;
+    let mut window_list_stale = true;
+
     event_loop.run(move |event, event_loop_target, control_flow| {
         *control_flow = ControlFlow::Poll;
 
+        if let Event::LoopDestroyed = event {
+            if session_restore_enabled
+                && !session_recovery_pending
+                && !windows_ref.borrow().is_empty()
+            {
+                save_browser_session(&windows_ref.borrow());
+            }
+            session::BrowserSession::clear_run_marker();
+            return;
+        }
+
         std::thread::sleep(std::time::Duration::from_millis(10));
 
         #[cfg(target_os = "macos")]
@@ -424,6 +623,48 @@ fn main() -> wry::Result<()> {
                             &focused_window_id,
                             control_flow,
                         );
+                    } else if menu_event.id() == menu_items.9.id() {
+                        shortcut_manager.handle_shortcut(
+                            shortcuts::Shortcut::ReopenClosedTab,
+                            components,
+                            &config,
+                            event_loop_target,
+                            &windows_ref,
+                            &focused_window_id,
+                            control_flow,
+                        );
+                    } else if menu_event.id() == menu_items.10.id() {
+                        shortcut_manager.handle_shortcut(
+                            shortcuts::Shortcut::BookmarkPage,
+                            components,
+                            &config,
+                            event_loop_target,
+                            &windows_ref,
+                            &focused_window_id,
+                            control_flow,
+                        );
+                    } else if menu_event.id() == menu_items.11.id() {
+                        shortcut_manager.handle_shortcut(
+                            shortcuts::Shortcut::ToggleBookmarks,
+                            components,
+                            &config,
+                            event_loop_target,
+                            &windows_ref,
+                            &focused_window_id,
+                            control_flow,
+                        );
+                    } else if menu_event.id() == menu_items.12.id() {
+                        shortcut_manager.handle_shortcut(
+                            shortcuts::Shortcut::NewPrivateWindow,
+                            components,
+                            &config,
+                            event_loop_target,
+                            &windows_ref,
+                            &focused_window_id,
+                            control_flow,
+                        );
+                    } else if menu_event.id() == menu_items.13.id() {
+                        window::push_window_request(WindowRequest::OpenProfilePicker);
                     }
                 }
             }
@@ -436,24 +677,100 @@ fn main() -> wry::Result<()> {
                 if let Some(focused_id) = *focused_window_id.borrow() {
                     let windows = windows_ref.borrow();
                     if let Some(components) = windows.get(&focused_id) {
-                        match components.tab_manager.borrow_mut().create_tab(&components.window, &url) {
-                            Ok(tab_id) => {
-                                debug_log!("Created new tab {} for URL: {}", tab_id, url);
-                                components.tab_manager.borrow_mut().switch_to_tab(tab_id);
-
-                                let escaped_url = serde_json::to_string(&url).unwrap_or_else(|_| "\"\"".to_string());
-                                let script = format!(
-                                    "window.addTab({}, {}); window.setActiveTab({}); window.updateUrlBar({});",
-                                    tab_id, escaped_url, tab_id, escaped_url
-                                );
-                                let _ = components.tab_bar_webview.evaluate_script(&script);
+                        open_url_in_new_tab(components, &url);
+                    }
+                }
+            }
+        }
+
+        for request in window::take_window_requests() {
+            match request {
+                WindowRequest::RestoreSession(browser_session) => {
+                    session_recovery_pending = false;
+                    for window_state in &browser_session.windows {
+                        match restore_browser_window(event_loop_target, Rc::clone(&config), window_state, false) {
+                            Ok(components) => {
+                                let window_id = components.window.id();
+                                windows_ref.borrow_mut().insert(window_id, components);
                             }
                             Err(e) => {
-                                debug_log!("Failed to create tab for URL: {:?}", e);
+                                eprintln!("Failed to restore window from session: {}", e);
                             }
                         }
                     }
                 }
+                WindowRequest::DismissSessionRecovery => {
+                    session_recovery_pending = false;
+                }
+                WindowRequest::ReopenClosedWindow(state) | WindowRequest::OpenWindow { state, private: false } => {
+                    match restore_browser_window(event_loop_target, Rc::clone(&config), &state, false) {
+                        Ok(components) => {
+                            let window_id = components.window.id();
+                            windows_ref.borrow_mut().insert(window_id, components);
+                            *focused_window_id.borrow_mut() = Some(window_id);
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to open window: {}", e);
+                        }
+                    }
+                }
+                WindowRequest::OpenWindow { state, private: true } => {
+                    match restore_browser_window(event_loop_target, Rc::clone(&config), &state, true) {
+                        Ok(components) => {
+                            let window_id = components.window.id();
+                            windows_ref.borrow_mut().insert(window_id, components);
+                            *focused_window_id.borrow_mut() = Some(window_id);
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to open private window: {}", e);
+                        }
+                    }
+                }
+                WindowRequest::NewPrivateWindow => {
+                    let default_url = convert_file_url(&config.borrow().default_url);
+                    match create_browser_window(event_loop_target, Rc::clone(&config), default_url, false, true) {
+                        Ok(components) => {
+                            let window_id = components.window.id();
+                            windows_ref.borrow_mut().insert(window_id, components);
+                            *focused_window_id.borrow_mut() = Some(window_id);
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to open private window: {}", e);
+                        }
+                    }
+                }
+                WindowRequest::OpenProfilePicker => {
+                    match create_browser_window(event_loop_target, Rc::clone(&config), "calm://profiles".to_string(), false, false) {
+                        Ok(components) => {
+                            let window_id = components.window.id();
+                            windows_ref.borrow_mut().insert(window_id, components);
+                            *focused_window_id.borrow_mut() = Some(window_id);
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to open profile picker: {}", e);
+                        }
+                    }
+                }
+                WindowRequest::MoveTab {
+                    from_window,
+                    tab_id,
+                    to_window,
+                } => {
+                    let new_window = move_tab_between_windows(
+                        event_loop_target,
+                        &config,
+                        &windows_ref.borrow(),
+                        from_window,
+                        tab_id,
+                        to_window,
+                    );
+                    if let Some(components) = new_window {
+                        let window_id = components.window.id();
+                        windows_ref.borrow_mut().insert(window_id, components);
+                        *focused_window_id.borrow_mut() = Some(window_id);
+                    }
+                    window_list_stale = true;
+                }
             }
         }
 
@@ -464,9 +781,21 @@ fn main() -> wry::Result<()> {
             }
         }
 
+        if session_restore_enabled
+            && !session_recovery_pending
+            && !windows_to_close.is_empty()
+            && windows_to_close.len() == windows_ref.borrow().len()
+        {
+            save_browser_session(&windows_ref.borrow());
+        }
+
+        let closing_all_windows = windows_to_close.len() == windows_ref.borrow().len();
         for window_id in windows_to_close {
             debug_log!("Closing window: {:?}", window_id);
-            windows_ref.borrow_mut().remove(&window_id);
+            let closed_window = windows_ref.borrow_mut().remove(&window_id);
+            if let Some(components) = closed_window.filter(|_| !closing_all_windows) {
+                remember_closed_window(&components);
+            }
         }
 
         if windows_ref.borrow().is_empty() {
@@ -474,6 +803,55 @@ fn main() -> wry::Result<()> {
             return;
         }
 
+        if window_list_stale || windows_ref.borrow().len() != known_window_count {
+            broadcast_window_list(&windows_ref.borrow());
+            known_window_count = windows_ref.borrow().len();
+            window_list_stale = false;
+        }
+
+        if session_restore_enabled
+            && !session_recovery_pending
+            && last_session_save.elapsed() >= session_save_interval
+        {
+            save_browser_session(&windows_ref.borrow());
+            last_session_save = Instant::now();
+        }
+
+        if last_suspension_check.elapsed() >= TAB_SUSPENSION_CHECK_INTERVAL {
+            for components in windows_ref.borrow().values() {
+                let suspended = components.tab_manager.borrow_mut().suspend_inactive_tabs();
+                if suspended > 0 {
+                    debug_log!("Suspended {} idle tabs", suspended);
+                }
+            }
+            last_suspension_check = Instant::now();
+        }
+
+        if !matches!(last_history_prune, Some(pruned) if pruned.elapsed() < HISTORY_PRUNE_INTERVAL) {
+            // Every window shares the same history database, so pruning it once is enough.
+            if let Some(components) = windows_ref.borrow().values().next() {
+                let pruned = components.tab_manager.borrow().prune_history();
+                if pruned > 0 {
+                    debug_log!("Pruned {} expired history visits", pruned);
+                }
+                last_history_prune = Some(Instant::now());
+            }
+        }
+
+        if !matches!(last_filter_update_check, Some(checked) if checked.elapsed() < FILTER_UPDATE_CHECK_INTERVAL) {
+            privacy::filters::subscriptions::update_due(&config.borrow().privacy);
+            last_filter_update_check = Some(Instant::now());
+        }
+        if privacy::filters::subscriptions::take_updated() {
+            debug_log!("Filter lists updated, new tabs get the new rules");
+            privacy::filters::reload();
+        }
+
+        let snapshot = memory_snapshot.lock().ok().and_then(|mut latest| latest.take());
+        if let Some(snapshot) = snapshot {
+            enforce_memory_budget(&windows_ref.borrow(), &snapshot, min_available_memory_mb);
+        }
+
         match event {
             Event::WindowEvent {
                 window_id,
@@ -481,7 +859,17 @@ fn main() -> wry::Result<()> {
                 ..
             } => {
                 debug_log!("Window close requested: {:?}", window_id);
-                windows_ref.borrow_mut().remove(&window_id);
+                let closing_last_window = {
+                    let windows = windows_ref.borrow();
+                    windows.len() == 1 && windows.contains_key(&window_id)
+                };
+                if session_restore_enabled && !session_recovery_pending && closing_last_window {
+                    save_browser_session(&windows_ref.borrow());
+                }
+                let closed_window = windows_ref.borrow_mut().remove(&window_id);
+                if let Some(components) = closed_window.filter(|_| !closing_last_window) {
+                    remember_closed_window(&components);
+                }
                 if windows_ref.borrow().is_empty() {
                     *control_flow = ControlFlow::Exit;
                 }
@@ -494,6 +882,7 @@ fn main() -> wry::Result<()> {
                 if focused {
                     debug_log!("Window focused: {:?}", window_id);
                     *focused_window_id.borrow_mut() = Some(window_id);
+                    window_list_stale = true;
                 }
             }
             Event::WindowEvent {
@@ -599,6 +988,231 @@ fn main() -> wry::Result<()> {
     });
 }
 
+/// Opens a URL in a new tab of the given window and makes it the active tab.
+///
+/// # Arguments
+///
+/// * `components` - Browser window components to open the tab in
+/// * `url` - The URL to load
+fn open_url_in_new_tab(components: &BrowserWindowComponents, url: &str) {
+    let tab_result = components
+        .tab_manager
+        .borrow_mut()
+        .create_tab(&components.window, url);
+    match tab_result {
+        Ok(tab_id) => {
+            debug_log!("Created new tab {} for URL: {}", tab_id, url);
+            components.tab_manager.borrow_mut().switch_to_tab(tab_id);
+
+            let escaped_url = serde_json::to_string(&url).unwrap_or_else(|_| "\"\"".to_string());
+            let script = format!(
+                "window.addTab({}, {}); window.setActiveTab({}); window.updateUrlBar({});",
+                tab_id, escaped_url, tab_id, escaped_url
+            );
+            let _ = components.tab_bar_webview.evaluate_script(&script);
+        }
+        Err(e) => {
+            debug_log!("Failed to create tab for URL: {:?}", e);
+        }
+    }
+}
+
+/// Moves a tab to another window, or to a new window when `to_window` is `None`.
+///
+/// # Arguments
+///
+/// * `event_loop` - The event loop window target
+/// * `config` - Application configuration
+/// * `windows` - All open browser windows
+/// * `from_window` - Number of the window the tab is in
+/// * `tab_id` - The tab to move
+/// * `to_window` - Number of the window to move the tab to
+///
+/// # Returns
+///
+/// The window created for the tab, if any
+fn move_tab_between_windows<T>(
+    event_loop: &EventLoopWindowTarget<T>,
+    config: &Rc<RefCell<Config>>,
+    windows: &HashMap<WindowId, BrowserWindowComponents>,
+    from_window: usize,
+    tab_id: usize,
+    to_window: Option<usize>,
+) -> Option<BrowserWindowComponents> {
+    let find_window = |number: usize| windows.values().find(|components| components.number == number);
+    let source = find_window(from_window)?;
+    let target = match to_window {
+        Some(number) if number == from_window => return None,
+        // Tabs stay on their side of private browsing, so private pages never end up in a window
+        // that is saved to the session.
+        Some(number) => Some(find_window(number).filter(|target| target.private == source.private)?),
+        // A window's only tab already is in a window of its own.
+        None if source.tab_manager.borrow().get_tab_count() <= 1 => return None,
+        None => None,
+    };
+
+    let detached = source.tab_manager.borrow_mut().detach_tab(tab_id)?;
+    let _ = source
+        .tab_bar_webview
+        .evaluate_script(&format!("window.removeTab({});", tab_id));
+    if source.tab_manager.borrow().get_tab_count() == 0 {
+        *source.should_quit.borrow_mut() = true;
+    }
+
+    match target {
+        Some(target) => {
+            let new_tab_id = target.tab_manager.borrow_mut().attach_tab(detached);
+            debug_log!("Moved tab {} to window {} as tab {}", tab_id, target.number, new_tab_id);
+            target.window.set_focus();
+            None
+        }
+        None => match window::create_window_with_tab(event_loop, Rc::clone(config), detached, source.private) {
+            Ok(components) => {
+                debug_log!("Moved tab {} to new window {}", tab_id, components.number);
+                Some(components)
+            }
+            Err(e) => {
+                eprintln!("Failed to open window for moved tab: {}", e);
+                None
+            }
+        },
+    }
+}
+
+/// Publishes the open windows as tab move targets and sends each tab bar the windows it can
+/// move tabs to.
+///
+/// # Arguments
+///
+/// * `windows` - All open browser windows
+fn broadcast_window_list(windows: &HashMap<WindowId, BrowserWindowComponents>) {
+    let mut summaries: Vec<WindowSummary> = windows
+        .values()
+        .map(|components| {
+            let tab_manager = components.tab_manager.borrow();
+            WindowSummary {
+                number: components.number,
+                title: tab_manager.get_active_tab_title().unwrap_or_default(),
+                tab_count: tab_manager.get_tab_count(),
+                private: components.private,
+            }
+        })
+        .collect();
+    summaries.sort_by_key(|summary| summary.number);
+    window::set_open_windows(summaries);
+
+    for components in windows.values() {
+        let script = format!(
+            "if (window.setWindowList) {{ window.setWindowList({}, {}); }}",
+            components.number,
+            window::other_windows_json(components.number)
+        );
+        let _ = components.tab_bar_webview.evaluate_script(&script);
+    }
+}
+
+/// Pushes a window that was closed while others stay open onto the recently closed stack.
+/// Private windows are not remembered.
+///
+/// # Arguments
+///
+/// * `components` - Browser window components of the closed window
+fn remember_closed_window(components: &BrowserWindowComponents) {
+    if components.private {
+        return;
+    }
+    let window_state = components.tab_manager.borrow().get_window_state();
+    if !window_state.tabs.is_empty() {
+        tabs::recently_closed::update_recently_closed(|store| store.push_window(window_state));
+    }
+}
+
+/// Records per-tab memory usage and discards the least recently used background tab when a tab
+/// is over its memory budget or the system is running low on memory.
+///
+/// # Arguments
+///
+/// * `windows` - All open browser windows
+/// * `snapshot` - Latest web process memory sample
+/// * `min_available_memory_mb` - Available system memory below which tabs are discarded
+fn enforce_memory_budget(
+    windows: &HashMap<WindowId, BrowserWindowComponents>,
+    snapshot: &memory::MemorySnapshot,
+    min_available_memory_mb: usize,
+) {
+    let mut webview_ages = Vec::new();
+    for (window_id, components) in windows {
+        for (tab_id, age) in components.tab_manager.borrow().webview_ages() {
+            webview_ages.push(((*window_id, tab_id), age));
+        }
+    }
+    let tab_memory = memory::assign_processes(&webview_ages, &snapshot.processes);
+
+    let mut over_budget = false;
+    for (window_id, components) in windows {
+        let window_tab_memory: HashMap<usize, usize> = tab_memory
+            .iter()
+            .filter(|((id, _), _)| id == window_id)
+            .map(|((_, tab_id), memory_mb)| (*tab_id, *memory_mb))
+            .collect();
+        over_budget |= components
+            .tab_manager
+            .borrow_mut()
+            .record_memory_usage(&window_tab_memory, snapshot);
+    }
+
+    let low_memory = snapshot
+        .available_mb
+        .is_some_and(|available_mb| available_mb < min_available_memory_mb);
+    if !over_budget && !low_memory {
+        return;
+    }
+
+    let candidate = windows
+        .values()
+        .filter_map(|components| {
+            let (tab_id, last_active) = components.tab_manager.borrow().discard_candidate()?;
+            Some((components, tab_id, last_active))
+        })
+        .min_by_key(|(_, _, last_active)| *last_active);
+
+    if let Some((components, tab_id, _)) = candidate {
+        if components.tab_manager.borrow_mut().suspend_tab(tab_id) {
+            debug_log!(
+                "Discarded tab {} under memory pressure (available: {:?} MB)",
+                tab_id,
+                snapshot.available_mb
+            );
+        }
+    }
+}
+
+/// Snapshots every open window except private ones into a browser session and writes it to disk.
+///
+/// # Arguments
+///
+/// * `windows` - All open browser windows
+fn save_browser_session(windows: &HashMap<WindowId, BrowserWindowComponents>) {
+    // With only private windows open there is nothing to save, so the session left by the last
+    // regular window is kept.
+    if windows.values().all(|components| components.private) {
+        return;
+    }
+
+    let mut browser_session = session::BrowserSession::new();
+    for components in windows.values().filter(|components| !components.private) {
+        let window_state = components.tab_manager.borrow().get_window_state();
+        if !window_state.tabs.is_empty() {
+            browser_session.add_window(window_state);
+        }
+    }
+
+    match browser_session.save() {
+        Ok(_) => debug_log!("Session saved with {} windows", browser_session.windows.len()),
+        Err(e) => debug_log!("Failed to save session: {}", e),
+    }
+}
+
 /// Handles window resize events by updating bounds of all UI components.
 ///
 /// # Arguments
@@ -633,6 +1247,7 @@ fn handle_window_resize(components: &BrowserWindowComponents) {
         .into(),
     };
     let _ = components.download_overlay.set_bounds(sidebar_bounds);
+    let _ = components.bookmarks_panel.set_bounds(sidebar_bounds);
 
     if *components.command_prompt_visible.borrow() {
         if let Some(ref overlay) = *components.command_prompt_overlay.borrow() {
@@ -648,7 +1263,8 @@ fn handle_window_resize(components: &BrowserWindowComponents) {
         }
     }
 
-    let is_visible = *components.sidebar_visible.borrow();
+    let is_visible =
+        *components.sidebar_visible.borrow() || *components.bookmarks_visible.borrow();
     if is_visible {
         components
             .tab_manager
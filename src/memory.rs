@@ -0,0 +1,276 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Process name of WebKitGTK web content processes, as truncated by the kernel in `/proc/<pid>/comm`.
+const WEB_PROCESS_COMM: &str = "WebKitWebProces";
+
+/// How far apart a tab's webview creation and its web process spawn may be to be paired.
+const PROCESS_MATCH_TOLERANCE: Duration = Duration::from_secs(3);
+
+/// Clock ticks per second assumed when the system does not report them. This is 100 on every
+/// mainstream Linux configuration.
+const DEFAULT_CLOCK_TICKS_PER_SECOND: u64 = 100;
+
+/// A WebKit web content process spawned by this browser.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebProcess {
+    pub pid: u32,
+    /// Time since the process was started.
+    pub age: Duration,
+    pub rss_mb: usize,
+}
+
+/// Memory usage of the web processes and of the system at a point in time.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySnapshot {
+    pub processes: Vec<WebProcess>,
+    pub available_mb: Option<usize>,
+}
+
+impl MemorySnapshot {
+    pub fn total_rss_mb(&self) -> usize {
+        self.processes.iter().map(|process| process.rss_mb).sum()
+    }
+}
+
+/// Latest snapshot published by the sampler thread, taken by the event loop.
+pub type SharedSnapshot = Arc<Mutex<Option<MemorySnapshot>>>;
+
+/// Starts a background thread sampling web process memory every `interval`.
+/// Sampling relies on `/proc` and is only available on Linux; elsewhere no snapshots are produced.
+pub fn start_sampler(interval: Duration) -> SharedSnapshot {
+    let shared: SharedSnapshot = Arc::new(Mutex::new(None));
+
+    if cfg!(target_os = "linux") {
+        let shared_for_thread = Arc::clone(&shared);
+        let _ = std::thread::Builder::new()
+            .name("memory-sampler".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(interval);
+                let snapshot = take_snapshot();
+                if let Ok(mut latest) = shared_for_thread.lock() {
+                    *latest = Some(snapshot);
+                }
+            });
+    }
+
+    shared
+}
+
+/// Reads the web processes descending from this process and the available system memory.
+fn take_snapshot() -> MemorySnapshot {
+    let uptime = std::fs::read_to_string("/proc/uptime")
+        .ok()
+        .and_then(|contents| parse_uptime(&contents));
+    let available_mb = std::fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|contents| parse_mem_available_mb(&contents));
+
+    let mut parents = HashMap::new();
+    let mut web_processes = Vec::new();
+
+    if let Ok(entries) = std::fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            let Some((ppid, start_ticks)) = std::fs::read_to_string(entry.path().join("stat"))
+                .ok()
+                .and_then(|contents| parse_stat(&contents))
+            else {
+                continue;
+            };
+            parents.insert(pid, ppid);
+
+            let comm = std::fs::read_to_string(entry.path().join("comm")).unwrap_or_default();
+            if comm.trim() == WEB_PROCESS_COMM {
+                web_processes.push((pid, start_ticks));
+            }
+        }
+    }
+
+    let own_pid = std::process::id();
+    let ticks_per_second = clock_ticks_per_second();
+    let processes = web_processes
+        .into_iter()
+        .filter(|(pid, _)| is_descendant(*pid, own_pid, &parents))
+        .filter_map(|(pid, start_ticks)| {
+            let rss_mb = std::fs::read_to_string(format!("/proc/{}/status", pid))
+                .ok()
+                .and_then(|contents| parse_rss_mb(&contents))?;
+            let started = Duration::from_millis(start_ticks * 1000 / ticks_per_second);
+            let age = uptime.map(|uptime| uptime.saturating_sub(started))?;
+            Some(WebProcess { pid, age, rss_mb })
+        })
+        .collect();
+
+    MemorySnapshot {
+        processes,
+        available_mb,
+    }
+}
+
+/// Returns the clock ticks per second used by `/proc/<pid>/stat` start times.
+#[cfg(target_os = "linux")]
+fn clock_ticks_per_second() -> u64 {
+    // SAFETY: `sysconf` only reads a system configuration value.
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks > 0 {
+        ticks as u64
+    } else {
+        DEFAULT_CLOCK_TICKS_PER_SECOND
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn clock_ticks_per_second() -> u64 {
+    DEFAULT_CLOCK_TICKS_PER_SECOND
+}
+
+/// Returns whether `pid` is `ancestor` or one of its descendants. Web processes may be spawned
+/// through a sandbox helper, so the whole parent chain is walked.
+fn is_descendant(pid: u32, ancestor: u32, parents: &HashMap<u32, u32>) -> bool {
+    let mut current = pid;
+    let mut seen = HashSet::new();
+    while seen.insert(current) {
+        if current == ancestor {
+            return true;
+        }
+        match parents.get(&current) {
+            Some(&parent) if parent != 0 => current = parent,
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Pairs tabs with the web process spawned when their webview was built and returns the
+/// resident memory of each matched tab in MB. Tabs are keyed by whatever identifies them across
+/// windows.
+///
+/// WebKit does not expose which process renders which webview, so a tab is matched to the
+/// unclaimed process whose age is closest to the age of its webview. Tabs are matched oldest
+/// first, mirroring the order their processes were spawned in.
+pub fn assign_processes<K: Copy + Eq + Hash>(
+    webview_ages: &[(K, Duration)],
+    processes: &[WebProcess],
+) -> HashMap<K, usize> {
+    let mut tabs = webview_ages.to_vec();
+    tabs.sort_by_key(|(_, webview_age)| std::cmp::Reverse(*webview_age));
+
+    let mut claimed = HashSet::new();
+    let mut assignments = HashMap::new();
+
+    for (tab_id, webview_age) in tabs {
+        let best = processes
+            .iter()
+            .filter(|process| !claimed.contains(&process.pid))
+            .map(|process| (process, process.age.abs_diff(webview_age)))
+            .filter(|(_, distance)| *distance <= PROCESS_MATCH_TOLERANCE)
+            .min_by_key(|(_, distance)| *distance);
+
+        if let Some((process, _)) = best {
+            claimed.insert(process.pid);
+            assignments.insert(tab_id, process.rss_mb);
+        }
+    }
+
+    assignments
+}
+
+/// Parses the parent pid and start time (in clock ticks) out of `/proc/<pid>/stat`.
+fn parse_stat(contents: &str) -> Option<(u32, u64)> {
+    // The command name is wrapped in parentheses and may itself contain spaces or parentheses.
+    let after_comm = &contents[contents.rfind(')')? + 1..];
+    let fields: Vec<&str> = after_comm.split_whitespace().collect();
+    let ppid = fields.get(1)?.parse().ok()?;
+    let start_ticks = fields.get(19)?.parse().ok()?;
+    Some((ppid, start_ticks))
+}
+
+/// Parses the resident set size out of `/proc/<pid>/status`, in MB.
+fn parse_rss_mb(contents: &str) -> Option<usize> {
+    parse_kb_field(contents, "VmRSS:").map(|kb| kb / 1024)
+}
+
+/// Parses the memory available for new allocations out of `/proc/meminfo`, in MB.
+fn parse_mem_available_mb(contents: &str) -> Option<usize> {
+    parse_kb_field(contents, "MemAvailable:").map(|kb| kb / 1024)
+}
+
+fn parse_kb_field(contents: &str, field: &str) -> Option<usize> {
+    contents
+        .lines()
+        .find(|line| line.starts_with(field))?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+/// Parses the system uptime out of `/proc/uptime`.
+fn parse_uptime(contents: &str) -> Option<Duration> {
+    let seconds: f64 = contents.split_whitespace().next()?.parse().ok()?;
+    Some(Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat_with_spaces_in_name() {
+        let stat = "4242 (Web Content (x)) S 4000 4242 4000 0 -1 4194560 1 0 0 0 5 3 0 0 20 0 12 0 987654 1000 100";
+        assert_eq!(parse_stat(stat), Some((4000, 987654)));
+    }
+
+    #[test]
+    fn test_parse_meminfo_and_status() {
+        let meminfo = "MemTotal:       16000000 kB\nMemFree:         1000000 kB\nMemAvailable:    2048000 kB\n";
+        assert_eq!(parse_mem_available_mb(meminfo), Some(2000));
+
+        let status = "Name:\tWebKitWebProces\nVmPeak:\t  900000 kB\nVmRSS:\t  524288 kB\n";
+        assert_eq!(parse_rss_mb(status), Some(512));
+    }
+
+    #[test]
+    fn test_is_descendant_through_sandbox_helper() {
+        let parents = HashMap::from([(30, 20), (20, 10), (10, 1), (40, 1)]);
+        assert!(is_descendant(30, 10, &parents));
+        assert!(!is_descendant(40, 10, &parents));
+    }
+
+    #[test]
+    fn test_assign_processes_by_age() {
+        let processes = vec![
+            WebProcess {
+                pid: 1,
+                age: Duration::from_secs(600),
+                rss_mb: 100,
+            },
+            WebProcess {
+                pid: 2,
+                age: Duration::from_secs(300),
+                rss_mb: 700,
+            },
+            WebProcess {
+                pid: 3,
+                age: Duration::from_secs(60),
+                rss_mb: 50,
+            },
+        ];
+        let webview_ages = vec![
+            (1, Duration::from_secs(599)),
+            (2, Duration::from_secs(301)),
+            (3, Duration::from_secs(10)),
+        ];
+
+        let assignments = assign_processes(&webview_ages, &processes);
+        assert_eq!(assignments.get(&1), Some(&100));
+        assert_eq!(assignments.get(&2), Some(&700));
+        assert_eq!(assignments.get(&3), None);
+    }
+}
@@ -0,0 +1,183 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Name of the profile whose files live at the locations Calm used before profiles existed.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Longest accepted profile name.
+const MAX_PROFILE_NAME_LENGTH: usize = 64;
+
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Selects the profile this process runs as. Must be called before any path is looked up, and
+/// only the first call has an effect.
+pub fn set_profile(name: &str) {
+    let _ = PROFILE.set(name.to_string());
+}
+
+/// Returns the name of the profile this process runs as.
+pub fn profile() -> &'static str {
+    PROFILE.get().map(String::as_str).unwrap_or(DEFAULT_PROFILE)
+}
+
+/// Returns whether this process runs as the default profile.
+pub fn is_default_profile() -> bool {
+    profile() == DEFAULT_PROFILE
+}
+
+/// Returns whether `name` can be used as a profile name. Names become directory and socket
+/// names, so only letters, digits, `-` and `_` are allowed.
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_PROFILE_NAME_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Returns the user's home directory.
+pub fn home_dir() -> PathBuf {
+    PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".to_string()))
+}
+
+/// Returns the directory holding the data of named profiles (`~/.calm/profiles`).
+pub fn profiles_dir() -> PathBuf {
+    home_dir().join(".calm/profiles")
+}
+
+/// Returns the configuration file of the current profile: `~/.calm.yml` for the default
+/// profile, `~/.calm/profiles/<name>/config.yml` otherwise.
+pub fn config_file() -> PathBuf {
+    config_file_in(&home_dir(), profile())
+}
+
+/// Returns the directory holding the current profile's history, bookmarks, downloads and other
+/// stores: `~/.calm` for the default profile, `~/.calm/profiles/<name>` otherwise.
+pub fn data_dir() -> PathBuf {
+    data_dir_in(&home_dir(), profile())
+}
+
+/// Returns the path of a session file of the current profile. `name` is the file name within a
+/// named profile's directory; the default profile keeps it at `~/.calm_<name>`.
+pub fn session_file(name: &str) -> PathBuf {
+    session_file_in(&home_dir(), profile(), name)
+}
+
+/// Returns the directory holding the current profile's cookies, storage and cache, or `None` for
+/// the default profile, which keeps using the WebView's default data store.
+pub fn web_data_dir() -> Option<PathBuf> {
+    (!is_default_profile()).then(|| data_dir().join("web_data"))
+}
+
+/// Returns the socket the running instance of the current profile listens on for URLs.
+pub fn socket_path() -> PathBuf {
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR")
+        .or_else(|_| std::env::var("TMPDIR"))
+        .unwrap_or_else(|_| "/tmp".to_string());
+    PathBuf::from(runtime_dir).join(socket_name(profile()))
+}
+
+/// Returns the temporary directory holding the web data of the current profile's private
+/// windows.
+pub fn private_data_dir() -> PathBuf {
+    std::env::temp_dir().join("calm-private").join(profile())
+}
+
+/// Lists the default profile followed by the named profiles in alphabetical order.
+pub fn list_profiles() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(profiles_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| is_valid_profile_name(name) && name != DEFAULT_PROFILE)
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort_by_key(|name| name.to_lowercase());
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+/// Creates the directory of profile `name` so it shows up in [`list_profiles`].
+pub fn create_profile(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if !is_valid_profile_name(name) {
+        return Err(format!("Invalid profile name: {}", name).into());
+    }
+    fs::create_dir_all(data_dir_in(&home_dir(), name))?;
+    Ok(())
+}
+
+fn config_file_in(home: &Path, profile: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        home.join(".calm.yml")
+    } else {
+        data_dir_in(home, profile).join("config.yml")
+    }
+}
+
+fn data_dir_in(home: &Path, profile: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        home.join(".calm")
+    } else {
+        home.join(".calm/profiles").join(profile)
+    }
+}
+
+fn session_file_in(home: &Path, profile: &str, name: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        home.join(format!(".calm_{}", name))
+    } else {
+        data_dir_in(home, profile).join(name)
+    }
+}
+
+fn socket_name(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        "calm.socket".to_string()
+    } else {
+        format!("calm-{}.socket", profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_names() {
+        assert!(is_valid_profile_name("work"));
+        assert!(is_valid_profile_name("side_project-2"));
+        assert!(!is_valid_profile_name(""));
+        assert!(!is_valid_profile_name("../work"));
+        assert!(!is_valid_profile_name("my work"));
+        assert!(!is_valid_profile_name(&"a".repeat(MAX_PROFILE_NAME_LENGTH + 1)));
+    }
+
+    #[test]
+    fn test_default_profile_keeps_legacy_locations() {
+        let home = Path::new("/home/user");
+        assert_eq!(config_file_in(home, DEFAULT_PROFILE), home.join(".calm.yml"));
+        assert_eq!(data_dir_in(home, DEFAULT_PROFILE), home.join(".calm"));
+        assert_eq!(
+            session_file_in(home, DEFAULT_PROFILE, "session.json"),
+            home.join(".calm_session.json")
+        );
+        assert_eq!(socket_name(DEFAULT_PROFILE), "calm.socket");
+    }
+
+    #[test]
+    fn test_named_profile_lives_in_its_own_directory() {
+        let home = Path::new("/home/user");
+        let profile_dir = home.join(".calm/profiles/work");
+        assert_eq!(config_file_in(home, "work"), profile_dir.join("config.yml"));
+        assert_eq!(data_dir_in(home, "work"), profile_dir);
+        assert_eq!(
+            session_file_in(home, "work", "session.json"),
+            profile_dir.join("session.json")
+        );
+        assert_eq!(socket_name("work"), "calm-work.socket");
+    }
+}
@@ -8,6 +8,8 @@ pub struct PerformanceMetrics {
     pub tab_creation_times: Vec<Duration>,
     pub tab_switch_times: Vec<Duration>,
     pub memory_samples: Vec<usize>,
+    /// Latest resident memory of each loaded tab, in MB.
+    pub tab_memory: HashMap<usize, usize>,
     pub active_tabs: usize,
     pub suspended_tabs: usize,
     pub total_tabs: usize,
@@ -20,6 +22,7 @@ impl PerformanceMetrics {
             tab_creation_times: Vec::new(),
             tab_switch_times: Vec::new(),
             memory_samples: Vec::new(),
+            tab_memory: HashMap::new(),
             active_tabs: 0,
             suspended_tabs: 0,
             total_tabs: 0,
@@ -51,6 +54,14 @@ impl PerformanceMetrics {
         }
     }
 
+    pub fn record_tab_memory_sample(&mut self, tab_id: usize, memory_mb: usize) {
+        self.tab_memory.insert(tab_id, memory_mb);
+    }
+
+    pub fn remove_tab_memory(&mut self, tab_id: usize) {
+        self.tab_memory.remove(&tab_id);
+    }
+
     pub fn update_tab_counts(&mut self, active: usize, suspended: usize, total: usize) {
         self.active_tabs = active;
         self.suspended_tabs = suspended;
@@ -137,6 +148,29 @@ impl TabActivityTracker {
         }
     }
 
+    /// Returns the tracked tabs ordered from least to most recently active.
+    pub fn least_recently_active(&self) -> Vec<usize> {
+        let mut tabs: Vec<(usize, Instant)> = self
+            .last_activity
+            .iter()
+            .map(|(id, time)| (*id, *time))
+            .collect();
+        tabs.sort_by_key(|(_, time)| *time);
+        tabs.into_iter().map(|(id, _)| id).collect()
+    }
+
+    pub fn last_active(&self, tab_id: usize) -> Option<Instant> {
+        self.last_activity.get(&tab_id).copied()
+    }
+
+    /// Returns how long each tracked tab has been idle.
+    pub fn idle_times(&self) -> Vec<(usize, Duration)> {
+        self.last_activity
+            .iter()
+            .map(|(id, time)| (*id, time.elapsed()))
+            .collect()
+    }
+
     pub fn get_inactive_tabs(&self) -> Vec<usize> {
         self.last_activity
             .iter()
@@ -0,0 +1,247 @@
+use crate::privacy::shields;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, VecDeque};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// Block events kept for the privacy log. The oldest are dropped first.
+const LOG_CAPACITY: usize = 5000;
+
+/// Something a privacy defense stopped: a request, or a page reading a fingerprinting API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockEvent {
+    /// The blocked request, or the page for a fingerprinting API.
+    pub url: String,
+    /// A filter list resource type such as `script` or `xmlhttprequest`, or `api` for a
+    /// fingerprinting API.
+    pub resource_type: String,
+    /// The defense that stopped it, as a `PrivacySettings` field.
+    pub defense: String,
+    /// The filter rule, blocked domain or API that matched.
+    pub rule: Option<String>,
+    /// The filter list the rule comes from, for requests stopped by the content blocker.
+    pub list: Option<String>,
+    pub page_url: String,
+    pub tab_id: usize,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: i64,
+}
+
+/// What the privacy log page narrows the log to. Empty fields match every event.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LogFilter {
+    /// Text found in the URL, page, rule or list, ignoring case.
+    pub query: String,
+    pub defense: String,
+    pub resource_type: String,
+    pub tab_id: Option<usize>,
+}
+
+impl LogFilter {
+    pub fn matches(&self, event: &BlockEvent) -> bool {
+        let query = self.query.trim().to_lowercase();
+        let found = |text: &str| text.to_lowercase().contains(&query);
+        (self.defense.is_empty() || event.defense == self.defense)
+            && (self.resource_type.is_empty() || event.resource_type == self.resource_type)
+            && self.tab_id.is_none_or(|tab_id| event.tab_id == tab_id)
+            && (query.is_empty()
+                || found(&event.url)
+                || found(&event.page_url)
+                || event.rule.as_deref().is_some_and(found)
+                || event.list.as_deref().is_some_and(found))
+    }
+}
+
+/// A bounded ring buffer of block events.
+#[derive(Debug)]
+pub struct BlockLog {
+    events: VecDeque<BlockEvent>,
+    capacity: usize,
+}
+
+impl BlockLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Adds `event`, dropping the oldest event when the log is full.
+    pub fn push(&mut self, event: BlockEvent) {
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        if self.capacity > 0 {
+            self.events.push_back(event);
+        }
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = &BlockEvent> {
+        self.events.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+thread_local! {
+    static LOG: RefCell<BlockLog> = RefCell::new(BlockLog::new(LOG_CAPACITY));
+}
+
+/// Adds `event` to the log shared by all windows. The log lives in memory only.
+pub fn record(event: BlockEvent) {
+    LOG.with(|log| log.borrow_mut().push(event));
+}
+
+/// Returns the logged events, oldest first.
+pub fn events() -> Vec<BlockEvent> {
+    LOG.with(|log| log.borrow().events().cloned().collect())
+}
+
+/// Returns the logged events that `filter` matches, oldest first.
+pub fn query(filter: &LogFilter) -> Vec<BlockEvent> {
+    LOG.with(|log| {
+        log.borrow()
+            .events()
+            .filter(|event| filter.matches(event))
+            .cloned()
+            .collect()
+    })
+}
+
+/// Returns the tabs with events in the log, in order.
+pub fn tab_ids() -> Vec<usize> {
+    LOG.with(|log| {
+        let tab_ids: BTreeSet<usize> = log.borrow().events().map(|event| event.tab_id).collect();
+        tab_ids.into_iter().collect()
+    })
+}
+
+pub fn clear() {
+    LOG.with(|log| log.borrow_mut().clear());
+}
+
+/// Writes the logged events that `filter` matches to `path` as JSON, for attaching to bug
+/// reports. Returns the number of events written.
+pub fn export(path: &Path, filter: &LogFilter) -> Result<usize, Box<dyn Error>> {
+    let events = query(filter);
+    let contents = serde_json::to_string_pretty(&serde_json::json!({
+        "version": 1,
+        "calmVersion": env!("CARGO_PKG_VERSION"),
+        "exportedAt": now_millis(),
+        "events": events,
+    }))?;
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(events.len())
+}
+
+/// What the defenses stopped on a tab's current page.
+#[derive(Debug, Clone, Default)]
+pub struct PageBlocks {
+    /// Number of events on the page, shown in the tab bar.
+    pub count: usize,
+    /// What was stopped, listed in the shield panel.
+    pub blocked: Vec<shields::Blocked>,
+}
+
+impl PageBlocks {
+    pub fn record(&mut self, event: BlockEvent) {
+        self.count += 1;
+        shields::record_blocked(
+            &mut self.blocked,
+            shields::Blocked {
+                url: event.url,
+                defense: event.defense,
+                rule: event.rule,
+                list: event.list,
+            },
+        );
+    }
+}
+
+/// Returns the current time in milliseconds since the Unix epoch.
+pub fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(url: &str) -> BlockEvent {
+        BlockEvent {
+            url: url.to_string(),
+            resource_type: "script".to_string(),
+            defense: "adblock_enabled".to_string(),
+            rule: Some("||ads.net^".to_string()),
+            list: None,
+            page_url: "https://news.org/".to_string(),
+            tab_id: 1,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_log_drops_oldest_events() {
+        let mut log = BlockLog::new(2);
+        for url in [
+            "https://ads.net/1.js",
+            "https://ads.net/2.js",
+            "https://ads.net/3.js",
+        ] {
+            log.push(event(url));
+        }
+        let urls: Vec<&str> = log.events().map(|event| event.url.as_str()).collect();
+        assert_eq!(urls, ["https://ads.net/2.js", "https://ads.net/3.js"]);
+    }
+
+    #[test]
+    fn test_filter_matches_every_given_field() {
+        let event = event("https://ads.net/ad.js");
+        assert!(LogFilter::default().matches(&event));
+        let filter = LogFilter {
+            query: "ADS.NET".to_string(),
+            defense: "adblock_enabled".to_string(),
+            resource_type: "script".to_string(),
+            tab_id: Some(1),
+        };
+        assert!(filter.matches(&event));
+        assert!(!LogFilter {
+            tab_id: Some(2),
+            ..filter.clone()
+        }
+        .matches(&event));
+        assert!(!LogFilter {
+            query: "tracker".to_string(),
+            ..filter
+        }
+        .matches(&event));
+    }
+
+    #[test]
+    fn test_page_counts_every_event() {
+        let mut page = PageBlocks::default();
+        for _ in 0..3 {
+            page.record(event("https://ads.net/ad.js"));
+        }
+        page.record(event("https://ads.net/other.js"));
+        assert_eq!(page.count, 4);
+        assert_eq!(page.blocked.len(), 2);
+        assert_eq!(page.blocked[0].rule.as_deref(), Some("||ads.net^"));
+    }
+}
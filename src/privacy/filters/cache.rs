@@ -0,0 +1,127 @@
+use super::cosmetic::CosmeticFilter;
+use super::network::{parse_line, Line, NetworkFilter};
+use super::FilterList;
+use crate::debug_log;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Bumped whenever parsing changes, so lists compiled by an older Calm are parsed again.
+const CACHE_VERSION: u32 = 2;
+
+/// A parsed filter list as stored in the cache, with what identifies the file it came from.
+#[derive(Serialize, Deserialize)]
+struct CompiledList {
+    version: u32,
+    source: String,
+    modified: u64,
+    size: u64,
+    filters: Vec<NetworkFilter>,
+    cosmetic: Vec<CosmeticFilter>,
+    unsupported: usize,
+}
+
+/// Loads the filter list at `path`, reusing its compiled copy from the cache while the file is
+/// unchanged and compiling and caching it otherwise.
+pub fn load_list(path: &Path) -> Result<FilterList, Box<dyn Error>> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |age| age.as_secs());
+    let source = path.to_string_lossy().to_string();
+    let cache_path = cache_file(&source);
+
+    let cached = fs::read(&cache_path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<CompiledList>(&bytes).ok())
+        .filter(|list| {
+            list.version == CACHE_VERSION
+                && list.source == source
+                && list.modified == modified
+                && list.size == metadata.len()
+        });
+    if let Some(list) = cached {
+        debug_log!("Loaded {} compiled filters of {} from cache", list.filters.len(), source);
+        return Ok(FilterList {
+            source,
+            filters: list.filters,
+            cosmetic: list.cosmetic,
+            unsupported: list.unsupported,
+        });
+    }
+
+    let list = parse_list(source, &fs::read_to_string(path)?);
+    debug_log!(
+        "Compiled {} network and {} element hiding filters of {} ({} unsupported)",
+        list.filters.len(),
+        list.cosmetic.len(),
+        list.source,
+        list.unsupported
+    );
+    let compiled = CompiledList {
+        version: CACHE_VERSION,
+        source: list.source,
+        modified,
+        size: metadata.len(),
+        filters: list.filters,
+        cosmetic: list.cosmetic,
+        unsupported: list.unsupported,
+    };
+    if let Err(e) = write_cache(&cache_path, &compiled) {
+        debug_log!("Failed to cache compiled filter list {}: {}", compiled.source, e);
+    }
+    Ok(FilterList {
+        source: compiled.source,
+        filters: compiled.filters,
+        cosmetic: compiled.cosmetic,
+        unsupported: compiled.unsupported,
+    })
+}
+
+/// Parses the rules of a filter list, counting those left out because they use unsupported
+/// syntax.
+pub fn parse_list(source: String, text: &str) -> FilterList {
+    let mut list = FilterList {
+        source,
+        filters: Vec::new(),
+        cosmetic: Vec::new(),
+        unsupported: 0,
+    };
+    for line in text.lines() {
+        match parse_line(line) {
+            Line::Network(filter) => list.filters.push(filter),
+            Line::Cosmetic(filter) => list.cosmetic.push(filter),
+            Line::Unsupported => list.unsupported += 1,
+            Line::Ignored => {}
+        }
+    }
+    list
+}
+
+/// Returns the directory holding compiled filter lists.
+pub fn cache_dir() -> PathBuf {
+    crate::paths::data_dir().join("filters").join("cache")
+}
+
+fn cache_file(source: &str) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    cache_dir().join(format!("{:016x}.json", hasher.finish()))
+}
+
+/// Writes the cache through a temporary file so a crash never leaves a truncated copy behind.
+fn write_cache(path: &Path, list: &CompiledList) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temporary = path.with_extension("json.tmp");
+    fs::write(&temporary, serde_json::to_vec(list)?)?;
+    fs::rename(&temporary, path)?;
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use super::network::{NetworkFilter, ResourceType};
+use serde_json::{json, Value};
+
+/// Converts network filters to the JSON rule format of WebKit content blockers, which WebKit
+/// compiles and applies to every request of a page before it is sent.
+///
+/// WebKit lets a later `ignore-previous-rules` rule cancel earlier blocking rules, so blocking
+/// rules come first, then exceptions, then `$important` rules that exceptions must not cancel.
+pub fn to_content_rules<'a>(filters: impl IntoIterator<Item = &'a NetworkFilter>) -> Vec<Value> {
+    let mut blocking = Vec::new();
+    let mut exceptions = Vec::new();
+    let mut important = Vec::new();
+    for filter in filters {
+        let section = if filter.exception {
+            &mut exceptions
+        } else if filter.important {
+            &mut important
+        } else {
+            &mut blocking
+        };
+        section.extend(filter_rules(filter));
+    }
+    blocking.extend(exceptions);
+    blocking.extend(important);
+    blocking
+}
+
+fn filter_rules(filter: &NetworkFilter) -> Vec<Value> {
+    let Some(url_filter) = url_filter(filter) else {
+        return Vec::new();
+    };
+    // WebKit cannot match `example.*` domains, and dropping them from `$domain=` would widen
+    // the rule to every site.
+    if filter.include_domains.iter().any(|domain| is_entity(domain)) {
+        return Vec::new();
+    }
+    // A trigger takes either `if-domain` or `unless-domain`, and the only way to carve excluded
+    // sites out of a rule limited to some sites would be `ignore-previous-rules`, which would
+    // also cancel every other rule blocking the same requests there.
+    if !filter.include_domains.is_empty() && !domain_list(&filter.exclude_domains).is_empty() {
+        return Vec::new();
+    }
+    let action = if filter.exception { "ignore-previous-rules" } else { "block" };
+
+    // WebKit has a single `document` type for pages and frames, so frames are told apart by
+    // their load context to keep rules that name neither from blocking whole pages.
+    let mut triggers = Vec::new();
+    let subresource_types = webkit_types(filter.types);
+    if !subresource_types.is_empty() {
+        triggers.push(trigger(filter, &url_filter, subresource_types, None));
+    }
+    let blocks_pages = filter.types & ResourceType::Document.bit() != 0;
+    let blocks_frames = filter.types & ResourceType::Subdocument.bit() != 0;
+    if blocks_pages || blocks_frames {
+        let load_context = (!blocks_pages).then_some("child-frame");
+        triggers.push(trigger(filter, &url_filter, vec!["document"], load_context));
+    }
+
+    let mut rules: Vec<Value> = triggers
+        .into_iter()
+        .map(|trigger| json!({ "trigger": trigger, "action": { "type": action } }))
+        .collect();
+
+    // `@@||site^$document` allows everything on the site, not only the page itself.
+    if let Some(site) = filter.allowed_site() {
+        rules.push(json!({
+            "trigger": { "url-filter": ".*", "if-domain": [format!("*{}", site)] },
+            "action": { "type": "ignore-previous-rules" }
+        }));
+    }
+    rules
+}
+
+fn trigger(filter: &NetworkFilter, url_filter: &str, types: Vec<&str>, load_context: Option<&str>) -> Value {
+    let mut trigger = json!({ "url-filter": url_filter, "resource-type": types });
+    if filter.match_case {
+        trigger["url-filter-is-case-sensitive"] = json!(true);
+    }
+    match filter.third_party {
+        Some(true) => trigger["load-type"] = json!(["third-party"]),
+        Some(false) => trigger["load-type"] = json!(["first-party"]),
+        None => {}
+    }
+    let excluded_domains = domain_list(&filter.exclude_domains);
+    if !filter.include_domains.is_empty() {
+        trigger["if-domain"] = json!(domain_list(&filter.include_domains));
+    } else if !excluded_domains.is_empty() {
+        trigger["unless-domain"] = json!(excluded_domains);
+    }
+    if let Some(load_context) = load_context {
+        trigger["load-context"] = json!([load_context]);
+    }
+    trigger
+}
+
+/// Lists domains in WebKit's syntax, where a leading `*` also matches subdomains.
+fn domain_list(domains: &[String]) -> Vec<String> {
+    domains
+        .iter()
+        .filter(|domain| !is_entity(domain))
+        .map(|domain| format!("*{}", domain))
+        .collect()
+}
+
+fn is_entity(domain: &str) -> bool {
+    domain.ends_with(".*")
+}
+
+/// Returns the WebKit types of everything but documents and frames in a type bit set.
+fn webkit_types(types: u16) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    for resource_type in ResourceType::ALL {
+        if types & resource_type.bit() == 0 {
+            continue;
+        }
+        let name = match resource_type {
+            ResourceType::Document | ResourceType::Subdocument => continue,
+            ResourceType::Script => "script",
+            ResourceType::Image => "image",
+            ResourceType::Stylesheet => "style-sheet",
+            ResourceType::Font => "font",
+            ResourceType::Media => "media",
+            ResourceType::Ping => "ping",
+            ResourceType::XmlHttpRequest | ResourceType::WebSocket | ResourceType::Other => "raw",
+        };
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Translates a filter pattern to the regular expression subset WebKit accepts, which has no
+/// alternation. Returns `None` for patterns WebKit cannot match, such as non-ASCII ones.
+fn url_filter(filter: &NetworkFilter) -> Option<String> {
+    if !filter.pattern.is_ascii() {
+        return None;
+    }
+    let mut regex = String::with_capacity(filter.pattern.len() * 2);
+    if filter.host_anchor {
+        regex.push_str("^[a-z][a-z0-9+.-]*://([^/:?#]*\\.)?");
+    } else if filter.start_anchor {
+        regex.push('^');
+    }
+
+    let pattern = filter.pattern.trim_start_matches('*');
+    let last = pattern.len().saturating_sub(1);
+    for (index, c) in pattern.char_indices() {
+        match c {
+            '*' => regex.push_str(".*"),
+            // A trailing separator also matches the end of the URL.
+            '^' if index == last && !filter.end_anchor => regex.push_str("([^a-zA-Z0-9_.%-].*)?$"),
+            '^' => regex.push_str("[^a-zA-Z0-9_.%-]"),
+            '.' | '+' | '?' | '$' | '{' | '}' | '(' | ')' | '[' | ']' | '\\' | '/' | '|' => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            _ => regex.push(c),
+        }
+    }
+    if filter.end_anchor {
+        regex.push('$');
+    }
+    if regex.is_empty() {
+        regex.push_str(".*");
+    }
+    Some(regex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(lines: &[&str]) -> Vec<Value> {
+        let filters: Vec<NetworkFilter> = lines.iter().filter_map(|line| NetworkFilter::parse(line)).collect();
+        to_content_rules(&filters)
+    }
+
+    #[test]
+    fn test_host_rule_translation() {
+        let rules = rules(&["||ads.example.com^$third-party"]);
+        assert_eq!(rules.len(), 2);
+        let trigger = &rules[0]["trigger"];
+        assert_eq!(
+            trigger["url-filter"],
+            "^[a-z][a-z0-9+.-]*://([^/:?#]*\\.)?ads\\.example\\.com([^a-zA-Z0-9_.%-].*)?$"
+        );
+        assert_eq!(trigger["load-type"], json!(["third-party"]));
+        assert!(!trigger["resource-type"].as_array().unwrap().contains(&json!("document")));
+        assert_eq!(rules[1]["trigger"]["resource-type"], json!(["document"]));
+        assert_eq!(rules[1]["trigger"]["load-context"], json!(["child-frame"]));
+        assert_eq!(rules[0]["action"]["type"], "block");
+    }
+
+    #[test]
+    fn test_rule_order_and_domains() {
+        let rules = rules(&[
+            "@@||example.com/ads.js$script",
+            "||tracker.net^$script,important",
+            "/banner/*$image,domain=news.org",
+            "/promo/*$image,domain=news.org|~blog.news.org",
+            "/popup/*$image,domain=~blog.news.org",
+        ]);
+        let actions: Vec<&str> = rules.iter().map(|rule| rule["action"]["type"].as_str().unwrap()).collect();
+        assert_eq!(actions, ["block", "block", "ignore-previous-rules", "block"]);
+        assert_eq!(rules[0]["trigger"]["url-filter"], "\\/banner\\/.*");
+        assert_eq!(rules[0]["trigger"]["if-domain"], json!(["*news.org"]));
+        // Rules limited to some sites but not others are left out rather than cancelling
+        // unrelated rules on the excluded sites.
+        assert_eq!(rules[1]["trigger"]["url-filter"], "\\/popup\\/.*");
+        assert_eq!(rules[1]["trigger"]["unless-domain"], json!(["*blog.news.org"]));
+        assert_eq!(rules[2]["trigger"]["resource-type"], json!(["script"]));
+    }
+}
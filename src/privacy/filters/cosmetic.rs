@@ -0,0 +1,196 @@
+use super::network::domain_matches;
+use serde::{Deserialize, Serialize};
+
+/// Procedural operators other than `:has-text()` and `:has()`, which Calm does not evaluate.
+const UNSUPPORTED_OPERATORS: &[&str] = &[
+    ":-abp-properties(",
+    ":matches-attr(",
+    ":matches-css",
+    ":matches-media(",
+    ":matches-path(",
+    ":matches-prop(",
+    ":min-text-length(",
+    ":others(",
+    ":remove(",
+    ":remove-attr(",
+    ":remove-class(",
+    ":style(",
+    ":upward(",
+    ":watch-attr(",
+    ":xpath(",
+];
+
+/// An element hiding rule such as `example.com,~shop.example.com##.ad-banner` or the exception
+/// `example.com#@#.ad-banner`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CosmeticFilter {
+    /// The rule as written in the list.
+    pub text: String,
+    /// Whether the rule is a `#@#` exception that keeps the selector from hiding anything.
+    pub exception: bool,
+    /// Sites the rule is limited to, or none for a generic rule that applies everywhere.
+    pub include_domains: Vec<String>,
+    /// Sites the rule does not apply on.
+    pub exclude_domains: Vec<String>,
+    /// CSS selector of the elements to hide, with Adblock Plus operators renamed to uBlock
+    /// Origin's.
+    pub selector: String,
+    /// Whether the selector uses `:has-text()`, which has to be evaluated by script.
+    pub procedural: bool,
+}
+
+impl CosmeticFilter {
+    /// Parses an element hiding rule, returning `None` for scriptlets, HTML filters and
+    /// procedural operators Calm does not support. `separator` is the index of the `##`, `#@#`,
+    /// `#?#` or `#@?#` in `text`.
+    pub fn parse(text: &str, separator: usize) -> Option<Self> {
+        let (domains, rest) = text.split_at(separator);
+        let (exception, selector) = if let Some(selector) = rest.strip_prefix("#@?#") {
+            (true, selector)
+        } else if let Some(selector) = rest.strip_prefix("#@#") {
+            (true, selector)
+        } else if let Some(selector) = rest.strip_prefix("#?#") {
+            (false, selector)
+        } else {
+            (false, rest.strip_prefix("##")?)
+        };
+
+        let selector = selector
+            .trim()
+            .replace(":-abp-has(", ":has(")
+            .replace(":-abp-contains(", ":has-text(")
+            .replace(":contains(", ":has-text(");
+        if selector.is_empty() || selector.starts_with("+js(") || selector.starts_with('^') {
+            return None;
+        }
+        if UNSUPPORTED_OPERATORS.iter().any(|operator| selector.contains(operator)) {
+            return None;
+        }
+
+        let mut include_domains = Vec::new();
+        let mut exclude_domains = Vec::new();
+        for domain in domains.split(',').map(|domain| domain.trim().to_lowercase()) {
+            match domain.strip_prefix('~') {
+                Some(domain) => exclude_domains.push(domain.to_string()),
+                None if !domain.is_empty() => include_domains.push(domain),
+                None => {}
+            }
+        }
+
+        // Procedural rules are evaluated against every element, so like uBlock Origin Calm only
+        // accepts them for specific sites.
+        let procedural = selector.contains(":has-text(");
+        if procedural && include_domains.is_empty() && !exception {
+            return None;
+        }
+
+        Some(CosmeticFilter {
+            text: text.to_string(),
+            exception,
+            include_domains,
+            exclude_domains,
+            selector,
+            procedural,
+        })
+    }
+
+    /// Returns whether the rule applies on pages of `host`.
+    pub fn applies_to(&self, host: &str) -> bool {
+        (self.include_domains.is_empty() || self.include_domains.iter().any(|domain| domain_matches(host, domain)))
+            && !self.exclude_domains.iter().any(|domain| domain_matches(host, domain))
+    }
+}
+
+/// Returns the index of the element hiding separator in a filter list line, if it has one.
+pub fn separator_index(line: &str) -> Option<usize> {
+    let index = line.find('#')?;
+    let rest = &line[index..];
+    ["##", "#@#", "#?#", "#@?#"]
+        .iter()
+        .any(|separator| rest.starts_with(separator))
+        .then_some(index)
+}
+
+/// Element hiding rules that apply on one page.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct PageFilters {
+    /// Selectors hidden by the page's stylesheet.
+    pub hidden: Vec<String>,
+    /// `:has-text()` selectors evaluated by script.
+    pub procedural: Vec<String>,
+}
+
+/// Collects the rules of `filters` that apply on pages of `host`, leaving out selectors an
+/// exception allows there.
+pub fn page_filters<'a>(filters: impl Iterator<Item = &'a CosmeticFilter> + Clone, host: &str) -> PageFilters {
+    let allowed: Vec<&str> = filters
+        .clone()
+        .filter(|filter| filter.exception && filter.applies_to(host))
+        .map(|filter| filter.selector.as_str())
+        .collect();
+
+    let mut page = PageFilters::default();
+    for filter in filters.filter(|filter| !filter.exception && filter.applies_to(host)) {
+        if allowed.contains(&filter.selector.as_str()) {
+            continue;
+        }
+        let selectors = if filter.procedural { &mut page.procedural } else { &mut page.hidden };
+        if !selectors.contains(&filter.selector) {
+            selectors.push(filter.selector.clone());
+        }
+    }
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Option<CosmeticFilter> {
+        CosmeticFilter::parse(text, separator_index(text)?)
+    }
+
+    #[test]
+    fn test_parse_cosmetic_filters() {
+        let filter = parse("example.com,~shop.example.com##.ad-banner").unwrap();
+        assert_eq!(filter.include_domains, ["example.com"]);
+        assert_eq!(filter.exclude_domains, ["shop.example.com"]);
+        assert_eq!(filter.selector, ".ad-banner");
+        assert!(!filter.exception && !filter.procedural);
+
+        let filter = parse("news.org#?#div:-abp-has(> span:-abp-contains(Sponsored))").unwrap();
+        assert_eq!(filter.selector, "div:has(> span:has-text(Sponsored))");
+        assert!(filter.procedural);
+
+        assert!(parse("example.com#@#.ad-banner").unwrap().exception);
+        assert_eq!(parse("##div:has-text(Advertisement)"), None);
+        assert_eq!(parse("example.com##+js(set-constant, ads, false)"), None);
+        assert_eq!(parse("example.com##^script:has-text(ads)"), None);
+        assert_eq!(parse("example.com##.ad:style(display: none)"), None);
+        assert_eq!(separator_index("||example.com/#ad"), None);
+    }
+
+    #[test]
+    fn test_page_filters_apply_exceptions() {
+        let filters: Vec<CosmeticFilter> = [
+            "##.ad",
+            "##.sponsor",
+            "example.com##.banner",
+            "example.*##.promo",
+            "shop.example.com#@#.ad",
+            "example.com##div:has-text(/sign up/i)",
+            "other.org##.banner",
+        ]
+        .iter()
+        .filter_map(|text| parse(text))
+        .collect();
+
+        let page = page_filters(filters.iter(), "shop.example.com");
+        assert_eq!(page.hidden, [".sponsor", ".banner", ".promo"]);
+        assert_eq!(page.procedural, ["div:has-text(/sign up/i)"]);
+
+        let page = page_filters(filters.iter(), "other.org");
+        assert_eq!(page.hidden, [".ad", ".sponsor", ".banner"]);
+        assert!(page.procedural.is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+pub mod cache;
+pub mod content_blocker;
+pub mod cosmetic;
+mod native;
+pub mod network;
+pub mod subscriptions;
+
+use crate::config::PrivacySettings;
+use crate::debug_log;
+use crate::privacy::shields;
+use std::cell::{OnceCell, RefCell};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use wry::WebView;
+
+pub use cosmetic::{CosmeticFilter, PageFilters};
+pub use network::{NetworkFilter, Request};
+
+/// Element hiding rules that replace the selector lists the ad blocking script used to carry,
+/// applied whenever ad blocking is on.
+const BUILTIN_FILTERS: &str = include_str!("builtin.txt");
+
+/// The rules of one filter list file.
+#[derive(Debug, Clone)]
+pub struct FilterList {
+    /// Path of the list file.
+    pub source: String,
+    pub filters: Vec<NetworkFilter>,
+    pub cosmetic: Vec<CosmeticFilter>,
+    /// Number of rules left out because they use unsupported syntax or options.
+    pub unsupported: usize,
+}
+
+/// The rule that blocks a request, and the list it comes from.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct Match<'a> {
+    pub filter: &'a NetworkFilter,
+    pub list: &'a str,
+}
+
+/// Adblock Plus and uBlock Origin rules from the built-in list and the configured filter lists.
+pub struct FilterEngine {
+    lists: Vec<FilterList>,
+    content_rules: OnceCell<(String, u64)>,
+}
+
+impl FilterEngine {
+    pub fn new(lists: Vec<FilterList>) -> Self {
+        Self {
+            lists,
+            content_rules: OnceCell::new(),
+        }
+    }
+
+    /// Loads the built-in list and the filter list files at `paths`, skipping those that cannot
+    /// be read, with exceptions for the `allowed_sites` where ad blocking is turned off.
+    pub fn load(paths: &[String], allowed_sites: &[String]) -> Self {
+        let mut lists = vec![cache::parse_list("built-in".to_string(), BUILTIN_FILTERS)];
+        if !allowed_sites.is_empty() {
+            let exceptions: Vec<String> = allowed_sites
+                .iter()
+                .map(|site| format!("@@||{}^$document", site))
+                .collect();
+            lists.push(cache::parse_list("site settings".to_string(), &exceptions.join("\n")));
+        }
+        lists.extend(paths.iter().filter_map(|path| {
+            match cache::load_list(&crate::import_export::expand_home(path)) {
+                Ok(list) => Some(list),
+                Err(e) => {
+                    debug_log!("Failed to load filter list {}: {}", path, e);
+                    None
+                }
+            }
+        }));
+        Self::new(lists)
+    }
+
+    pub fn lists(&self) -> &[FilterList] {
+        &self.lists
+    }
+
+    /// Returns the total number of network rules in all lists.
+    pub fn rule_count(&self) -> usize {
+        self.lists.iter().map(|list| list.filters.len()).sum()
+    }
+
+    /// Returns the element hiding rules for the page at `page_url`. Sites allowed by a
+    /// `$document` exception get none.
+    pub fn page_filters(&self, page_url: &str) -> PageFilters {
+        let Some(host) = url::Url::parse(page_url).ok().and_then(|url| url.host_str().map(str::to_string)) else {
+            return PageFilters::default();
+        };
+        let allowed = self.filters().any(|(_, filter)| {
+            filter
+                .allowed_site()
+                .is_some_and(|site| network::is_same_or_subdomain(&host, site))
+        });
+        if allowed {
+            return PageFilters::default();
+        }
+        cosmetic::page_filters(self.lists.iter().flat_map(|list| list.cosmetic.iter()), &host)
+    }
+
+    /// Returns the rule blocking `request`, or `None` when no rule blocks it or an exception
+    /// lets it through. `$important` rules win over exceptions.
+    pub fn check(&self, request: &Request) -> Option<Match<'_>> {
+        let mut blocking = None;
+        for (list, filter) in self.filters().filter(|(_, filter)| !filter.exception) {
+            if filter.matches(request) {
+                let found = Match { filter, list };
+                if filter.important {
+                    return Some(found);
+                }
+                blocking.get_or_insert(found);
+            }
+        }
+        let blocking = blocking?;
+
+        let excepted = self.filters().any(|(_, filter)| {
+            filter.exception
+                && (filter.matches(request)
+                    || filter
+                        .allowed_site()
+                        .is_some_and(|site| network::is_same_or_subdomain(&request.source_host, site)))
+        });
+        (!excepted).then_some(blocking)
+    }
+
+    /// Returns the lists as WebKit content blocker JSON, with a fingerprint that changes with it.
+    pub fn content_rules(&self) -> (&str, u64) {
+        let (rules, fingerprint) = self.content_rules.get_or_init(|| {
+            let rules = content_blocker::to_content_rules(self.filters().map(|(_, filter)| filter));
+            let rules = serde_json::to_string(&rules).unwrap_or_else(|_| "[]".to_string());
+            let mut hasher = DefaultHasher::new();
+            rules.hash(&mut hasher);
+            (rules, hasher.finish())
+        });
+        (rules, *fingerprint)
+    }
+
+    fn filters(&self) -> impl Iterator<Item = (&str, &NetworkFilter)> {
+        self.lists
+            .iter()
+            .flat_map(|list| list.filters.iter().map(move |filter| (list.source.as_str(), filter)))
+    }
+}
+
+thread_local! {
+    static ENGINE: RefCell<Option<(EngineKey, Rc<FilterEngine>)>> = const { RefCell::new(None) };
+}
+
+/// Filter list files and sites without ad blocking an engine was built from.
+type EngineKey = (Vec<String>, Vec<String>);
+
+/// Returns the engine built from the filter lists and subscriptions in `settings`, shared by all
+/// windows, or `None` when ad blocking is off. Sites where ad blocking is turned off through
+/// `site_overrides` are let through.
+pub fn engine(settings: &PrivacySettings) -> Option<Rc<FilterEngine>> {
+    if !settings.adblock_enabled {
+        return None;
+    }
+    let mut lists = settings.filter_lists.clone();
+    lists.extend(subscriptions::enabled_files(settings));
+    let key = (lists, shields::sites_without(settings, "adblock_enabled"));
+    ENGINE.with(|engine| {
+        let mut engine = engine.borrow_mut();
+        if engine.as_ref().is_none_or(|(loaded_key, _)| *loaded_key != key) {
+            let loaded = FilterEngine::load(&key.0, &key.1);
+            debug_log!(
+                "Loaded {} network and {} element hiding rules from {} lists ({} unsupported)",
+                loaded.rule_count(),
+                loaded.lists().iter().map(|list| list.cosmetic.len()).sum::<usize>(),
+                loaded.lists().len(),
+                loaded.lists().iter().map(|list| list.unsupported).sum::<usize>()
+            );
+            *engine = Some((key, Rc::new(loaded)));
+        }
+        engine.as_ref().map(|(_, engine)| Rc::clone(engine))
+    })
+}
+
+/// Drops the shared engine so the next call to [`engine`] reads the lists again, for when their
+/// files changed. Webviews created before keep the rules they were given.
+pub fn reload() {
+    ENGINE.with(|engine| *engine.borrow_mut() = None);
+}
+
+/// Makes `webview` block every request the filter lists in `settings` match, before it is sent.
+/// The rules are enforced by the WebView's native content blocker, so they also cover images,
+/// scripts, frames and stylesheets that the injected scripts never see.
+pub fn install(webview: &WebView, settings: &PrivacySettings) {
+    let Some(engine) = engine(settings) else {
+        return;
+    };
+    if engine.rule_count() == 0 {
+        return;
+    }
+    let (rules, fingerprint) = engine.content_rules();
+    native::install(webview, rules, fingerprint);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine(rules: &str) -> FilterEngine {
+        FilterEngine::new(vec![cache::parse_list("test.txt".to_string(), rules)])
+    }
+
+    fn check<'a>(engine: &'a FilterEngine, url: &str, source: &str) -> Option<&'a str> {
+        let request = Request::new(url, source, network::ResourceType::Script).unwrap();
+        engine.check(&request).map(|found| found.filter.text.as_str())
+    }
+
+    #[test]
+    fn test_exceptions_and_important_rules() {
+        let engine = engine(
+            "! Comment\n||ads.net^\n@@||ads.net/allowed.js\n||tracker.org^$important\n@@||tracker.org^\n",
+        );
+        let page = "https://news.org/";
+        assert_eq!(check(&engine, "https://ads.net/ad.js", page), Some("||ads.net^"));
+        assert_eq!(check(&engine, "https://ads.net/allowed.js", page), None);
+        assert_eq!(check(&engine, "https://tracker.org/t.js", page), Some("||tracker.org^$important"));
+        assert_eq!(check(&engine, "https://news.org/app.js", page), None);
+    }
+
+    #[test]
+    fn test_document_exception_allows_whole_site() {
+        let engine = engine("||ads.net^\n@@||shop.com^$document\n");
+        assert_eq!(check(&engine, "https://ads.net/ad.js", "https://www.shop.com/"), None);
+        assert_eq!(check(&engine, "https://ads.net/ad.js", "https://news.org/"), Some("||ads.net^"));
+    }
+
+    #[test]
+    fn test_page_filters() {
+        let engine = engine("##.ad\nnews.org##.paywall\n@@||shop.com^$document\n");
+        assert_eq!(engine.page_filters("https://www.news.org/story").hidden, [".ad", ".paywall"]);
+        assert_eq!(engine.page_filters("https://shop.com/"), PageFilters::default());
+        assert_eq!(engine.page_filters("about:blank"), PageFilters::default());
+    }
+
+    #[test]
+    fn test_sites_without_ad_blocking_are_allowed() {
+        let engine = FilterEngine::load(&[], &["shop.com".to_string()]);
+        assert_eq!(engine.page_filters("https://www.shop.com/"), PageFilters::default());
+        assert!(!engine.page_filters("https://news.org/").hidden.is_empty());
+    }
+}
@@ -0,0 +1,269 @@
+// Installs compiled filter lists as the WebView's content blocker: `WKContentRuleList` on
+// macOS and `WebKitUserContentFilter` on Linux. WebKit compiles the rules once and keeps the
+// result in the profile's `filters/webkit` directory, so later launches only load it.
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub use platform::install;
+
+/// WebView2 on Windows has no content blocker, so pages there only get the injected scripts.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn install(_webview: &wry::WebView, _rules: &str, _fingerprint: u64) {}
+
+/// Identifier of the compiled rules in WebKit's store.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+const IDENTIFIER: &str = "calm-filters";
+
+/// Directory of WebKit's store of compiled rules.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn store_dir() -> std::path::PathBuf {
+    crate::paths::data_dir().join("filters").join("webkit")
+}
+
+/// Returns whether the rules in WebKit's store were compiled from rules with `fingerprint`.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn is_stored(fingerprint: u64) -> bool {
+    std::fs::read_to_string(store_dir().join("fingerprint"))
+        .is_ok_and(|stored| stored.trim() == format!("{:016x}", fingerprint))
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn set_stored(fingerprint: u64) {
+    let directory = store_dir();
+    let written = std::fs::create_dir_all(&directory)
+        .and_then(|_| std::fs::write(directory.join("fingerprint"), format!("{:016x}", fingerprint)));
+    if let Err(e) = written {
+        crate::debug_log!("Failed to record compiled filter lists: {}", e);
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{is_stored, set_stored, store_dir, IDENTIFIER};
+    use crate::debug_log;
+    use std::cell::RefCell;
+    use webkit2gtk::prelude::*;
+    use webkit2gtk::{gio, glib, UserContentFilter, UserContentFilterStore, UserContentManager};
+    use wry::WebViewExtUnix;
+
+    /// Compiled rules, or the content managers of webviews waiting for them to compile.
+    enum State {
+        Compiling(Vec<UserContentManager>),
+        Ready(UserContentFilter),
+        Failed,
+    }
+
+    thread_local! {
+        static FILTER: RefCell<Option<(u64, State)>> = const { RefCell::new(None) };
+    }
+
+    /// Adds the rules to `webview`, compiling them first when they changed.
+    pub fn install(webview: &wry::WebView, rules: &str, fingerprint: u64) {
+        let Some(manager) = webview.webview().user_content_manager() else {
+            return;
+        };
+        let needs_compiling = FILTER.with(|filter| {
+            let mut filter = filter.borrow_mut();
+            match filter.as_mut() {
+                Some((current, state)) if *current == fingerprint => {
+                    match state {
+                        State::Compiling(managers) => managers.push(manager),
+                        State::Ready(compiled) => manager.add_filter(compiled),
+                        State::Failed => {}
+                    }
+                    false
+                }
+                _ => {
+                    *filter = Some((fingerprint, State::Compiling(vec![manager])));
+                    true
+                }
+            }
+        });
+        if needs_compiling {
+            compile(rules, fingerprint);
+        }
+    }
+
+    fn compile(rules: &str, fingerprint: u64) {
+        let store = UserContentFilterStore::new(&store_dir().to_string_lossy());
+        if !is_stored(fingerprint) {
+            save(&store, rules.to_string(), fingerprint);
+            return;
+        }
+        let rules = rules.to_string();
+        let store_for_save = store.clone();
+        store.load(IDENTIFIER, None::<&gio::Cancellable>, move |result| match result {
+            Ok(compiled) => finish(fingerprint, Some(compiled)),
+            Err(_) => save(&store_for_save, rules, fingerprint),
+        });
+    }
+
+    fn save(store: &UserContentFilterStore, rules: String, fingerprint: u64) {
+        let source = glib::Bytes::from_owned(rules.into_bytes());
+        store.save(IDENTIFIER, &source, None::<&gio::Cancellable>, move |result| match result {
+            Ok(compiled) => {
+                set_stored(fingerprint);
+                finish(fingerprint, Some(compiled));
+            }
+            Err(e) => {
+                debug_log!("Failed to compile filter lists: {}", e);
+                finish(fingerprint, None);
+            }
+        });
+    }
+
+    fn finish(fingerprint: u64, compiled: Option<UserContentFilter>) {
+        FILTER.with(|filter| {
+            let mut filter = filter.borrow_mut();
+            let Some((current, state)) = filter.as_mut().filter(|(current, _)| *current == fingerprint) else {
+                return;
+            };
+            debug_log!("Filter lists {:016x} compiled: {}", current, compiled.is_some());
+            if let (State::Compiling(managers), Some(compiled)) = (&*state, &compiled) {
+                for manager in managers {
+                    manager.add_filter(compiled);
+                }
+            }
+            *state = compiled.map_or(State::Failed, State::Ready);
+        });
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{is_stored, set_stored, store_dir, IDENTIFIER};
+    use crate::debug_log;
+    use block2::RcBlock;
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::{class, msg_send};
+    use std::cell::RefCell;
+    use std::ffi::CString;
+    use wry::WebViewExtMacOS;
+
+    /// Compiled rules, or the user content controllers of webviews waiting for them to compile.
+    enum State {
+        Compiling(Vec<Retained<AnyObject>>),
+        Ready(Retained<AnyObject>),
+        Failed,
+    }
+
+    thread_local! {
+        static RULE_LIST: RefCell<Option<(u64, State)>> = const { RefCell::new(None) };
+    }
+
+    /// Adds the rules to `webview`, compiling them first when they changed.
+    pub fn install(webview: &wry::WebView, rules: &str, fingerprint: u64) {
+        let Some(controller) = (unsafe { user_content_controller(webview) }) else {
+            return;
+        };
+        let needs_compiling = RULE_LIST.with(|rule_list| {
+            let mut rule_list = rule_list.borrow_mut();
+            match rule_list.as_mut() {
+                Some((current, state)) if *current == fingerprint => {
+                    match state {
+                        State::Compiling(controllers) => controllers.push(controller),
+                        State::Ready(list) => unsafe { add_rule_list(&controller, list) },
+                        State::Failed => {}
+                    }
+                    false
+                }
+                _ => {
+                    *rule_list = Some((fingerprint, State::Compiling(vec![controller])));
+                    true
+                }
+            }
+        });
+        if needs_compiling {
+            unsafe { compile(rules, fingerprint) };
+        }
+    }
+
+    unsafe fn user_content_controller(webview: &wry::WebView) -> Option<Retained<AnyObject>> {
+        let wk_webview = webview.webview();
+        let wk_webview = &*wk_webview as *const _ as *const AnyObject;
+        let configuration: Option<Retained<AnyObject>> = msg_send![wk_webview, configuration];
+        msg_send![&*configuration?, userContentController]
+    }
+
+    unsafe fn add_rule_list(controller: &AnyObject, list: &AnyObject) {
+        let _: () = msg_send![controller, addContentRuleList: list];
+    }
+
+    unsafe fn ns_string(text: &str) -> Option<Retained<AnyObject>> {
+        let text = CString::new(text).ok()?;
+        msg_send![class!(NSString), stringWithUTF8String: text.as_ptr()]
+    }
+
+    unsafe fn rule_list_store() -> Option<Retained<AnyObject>> {
+        let directory = store_dir();
+        std::fs::create_dir_all(&directory).ok()?;
+        let path = ns_string(&directory.to_string_lossy())?;
+        let url: Option<Retained<AnyObject>> = msg_send![class!(NSURL), fileURLWithPath: &*path, isDirectory: true];
+        msg_send![class!(WKContentRuleListStore), storeWithURL: &*url?]
+    }
+
+    unsafe fn compile(rules: &str, fingerprint: u64) {
+        let (Some(store), Some(identifier)) = (rule_list_store(), ns_string(IDENTIFIER)) else {
+            finish(fingerprint, None);
+            return;
+        };
+        if !is_stored(fingerprint) {
+            compile_rules(&store, rules, fingerprint);
+            return;
+        }
+        let rules = rules.to_string();
+        let store_for_compile = store.clone();
+        let handler = RcBlock::new(move |list: *mut AnyObject, _error: *mut AnyObject| {
+            match unsafe { Retained::retain(list) } {
+                Some(list) => finish(fingerprint, Some(list)),
+                None => unsafe { compile_rules(&store_for_compile, &rules, fingerprint) },
+            }
+        });
+        let _: () = msg_send![
+            &*store,
+            lookUpContentRuleListForIdentifier: &*identifier,
+            completionHandler: &*handler
+        ];
+    }
+
+    unsafe fn compile_rules(store: &AnyObject, rules: &str, fingerprint: u64) {
+        let (Some(identifier), Some(source)) = (ns_string(IDENTIFIER), ns_string(rules)) else {
+            finish(fingerprint, None);
+            return;
+        };
+        let handler = RcBlock::new(move |list: *mut AnyObject, error: *mut AnyObject| {
+            match unsafe { Retained::retain(list) } {
+                Some(list) => {
+                    set_stored(fingerprint);
+                    finish(fingerprint, Some(list));
+                }
+                None => {
+                    debug_log!("Failed to compile filter lists (error: {})", !error.is_null());
+                    finish(fingerprint, None);
+                }
+            }
+        });
+        let _: () = msg_send![
+            store,
+            compileContentRuleListForIdentifier: &*identifier,
+            encodedContentRuleList: &*source,
+            completionHandler: &*handler
+        ];
+    }
+
+    fn finish(fingerprint: u64, compiled: Option<Retained<AnyObject>>) {
+        RULE_LIST.with(|rule_list| {
+            let mut rule_list = rule_list.borrow_mut();
+            let Some((current, state)) = rule_list.as_mut().filter(|(current, _)| *current == fingerprint) else {
+                return;
+            };
+            debug_log!("Filter lists {:016x} compiled: {}", current, compiled.is_some());
+            if let (State::Compiling(controllers), Some(compiled)) = (&*state, &compiled) {
+                for controller in controllers {
+                    unsafe { add_rule_list(controller, compiled) };
+                }
+            }
+            *state = compiled.map_or(State::Failed, State::Ready);
+        });
+    }
+}
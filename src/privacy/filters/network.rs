@@ -0,0 +1,479 @@
+use super::cosmetic::{separator_index, CosmeticFilter};
+use serde::{Deserialize, Serialize};
+
+/// Kind of resource a request loads, named as in filter list options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceType {
+    Document,
+    Subdocument,
+    Script,
+    Image,
+    Stylesheet,
+    Font,
+    Media,
+    XmlHttpRequest,
+    WebSocket,
+    Ping,
+    Other,
+}
+
+impl ResourceType {
+    pub const ALL: [ResourceType; 11] = [
+        ResourceType::Document,
+        ResourceType::Subdocument,
+        ResourceType::Script,
+        ResourceType::Image,
+        ResourceType::Stylesheet,
+        ResourceType::Font,
+        ResourceType::Media,
+        ResourceType::XmlHttpRequest,
+        ResourceType::WebSocket,
+        ResourceType::Ping,
+        ResourceType::Other,
+    ];
+
+    /// Returns the type named by a filter option, accepting uBlock Origin's aliases.
+    fn from_option(name: &str) -> Option<Self> {
+        match name {
+            "document" | "doc" => Some(ResourceType::Document),
+            "subdocument" | "frame" => Some(ResourceType::Subdocument),
+            "script" => Some(ResourceType::Script),
+            "image" => Some(ResourceType::Image),
+            "stylesheet" | "css" => Some(ResourceType::Stylesheet),
+            "font" => Some(ResourceType::Font),
+            "media" => Some(ResourceType::Media),
+            "xmlhttprequest" | "xhr" => Some(ResourceType::XmlHttpRequest),
+            "websocket" => Some(ResourceType::WebSocket),
+            "ping" | "beacon" => Some(ResourceType::Ping),
+            "other" | "object" | "object-subrequest" => Some(ResourceType::Other),
+            _ => None,
+        }
+    }
+
+    pub const fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+/// Types a network filter applies to when it names none: everything but top-level documents.
+const DEFAULT_TYPES: u16 = ALL_TYPES & !ResourceType::Document.bit();
+
+const ALL_TYPES: u16 = (1 << ResourceType::ALL.len()) - 1;
+
+/// A network rule of an Adblock Plus or uBlock Origin filter list, such as
+/// `||ads.example.com^$third-party,script` or the exception `@@||example.com/ads.js`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkFilter {
+    /// The rule as written in the list.
+    pub text: String,
+    /// Whether the rule is an `@@` exception that lets matching requests through.
+    pub exception: bool,
+    /// Whether the pattern starts with `||` and matches at the start of the host or a subdomain.
+    pub host_anchor: bool,
+    /// Whether the pattern starts with `|` and matches at the start of the URL.
+    pub start_anchor: bool,
+    /// Whether the pattern ends with `|` and matches at the end of the URL.
+    pub end_anchor: bool,
+    /// Pattern without anchors, where `*` matches anything and `^` a separator. Lowercase unless
+    /// `match_case` is set, and starting with `*` when the rule is not anchored.
+    pub pattern: String,
+    /// Bit set of the [`ResourceType`]s the rule applies to.
+    pub types: u16,
+    /// `Some(true)` for `$third-party` rules, `Some(false)` for `$~third-party` rules.
+    pub third_party: Option<bool>,
+    /// Sites from `$domain=` the rule is limited to.
+    pub include_domains: Vec<String>,
+    /// Sites from `$domain=~` the rule does not apply on.
+    pub exclude_domains: Vec<String>,
+    /// Whether `$important` makes the rule win over exceptions.
+    pub important: bool,
+    pub match_case: bool,
+}
+
+/// What a line of a filter list holds.
+#[derive(Debug, PartialEq)]
+pub enum Line {
+    /// A network rule Calm can enforce.
+    Network(NetworkFilter),
+    /// An element hiding rule.
+    Cosmetic(CosmeticFilter),
+    /// A rule using syntax or options Calm does not support, which is left out.
+    Unsupported,
+    /// A comment, header or blank line.
+    Ignored,
+}
+
+/// Parses one line of a filter list.
+pub fn parse_line(line: &str) -> Line {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('!') || line.starts_with('[') {
+        return Line::Ignored;
+    }
+    // Snippets and AdGuard's scriptlet, CSS injection and HTML filtering rules.
+    if ["#$#", "#@$#", "#%#", "#@%#", "$$"].iter().any(|marker| line.contains(marker)) {
+        return Line::Unsupported;
+    }
+    if let Some(separator) = separator_index(line) {
+        return CosmeticFilter::parse(line, separator).map_or(Line::Unsupported, Line::Cosmetic);
+    }
+    if let Some(host) = hosts_file_entry(line) {
+        return NetworkFilter::parse(&format!("||{}^", host)).map_or(Line::Unsupported, |mut filter| {
+            filter.text = line.to_string();
+            Line::Network(filter)
+        });
+    }
+    NetworkFilter::parse(line).map_or(Line::Unsupported, Line::Network)
+}
+
+/// Returns the host blocked by a hosts file line such as `0.0.0.0 ads.example.com`, which some
+/// uBlock Origin lists are written as.
+fn hosts_file_entry(line: &str) -> Option<&str> {
+    let mut parts = line.split_whitespace();
+    let address = parts.next()?;
+    let host = parts.next()?;
+    let is_blackhole = matches!(address, "0.0.0.0" | "127.0.0.1" | "::" | "::1");
+    (is_blackhole && host != "localhost" && host.contains('.') && !host.contains('/')).then_some(host)
+}
+
+impl NetworkFilter {
+    /// Parses a network rule, returning `None` when it uses regular expressions or options that
+    /// cannot be enforced.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (exception, rule) = match text.strip_prefix("@@") {
+            Some(rule) => (true, rule),
+            None => (false, text),
+        };
+
+        let (pattern, options) = match rule.rfind('$') {
+            Some(index) if !is_regex(rule) => (&rule[..index], Some(&rule[index + 1..])),
+            _ => (rule, None),
+        };
+        if is_regex(pattern) {
+            return None;
+        }
+
+        let mut filter = NetworkFilter {
+            text: text.to_string(),
+            exception,
+            host_anchor: false,
+            start_anchor: false,
+            end_anchor: false,
+            pattern: String::new(),
+            types: DEFAULT_TYPES,
+            third_party: None,
+            include_domains: Vec::new(),
+            exclude_domains: Vec::new(),
+            important: false,
+            match_case: false,
+        };
+        if let Some(options) = options {
+            filter.apply_options(options)?;
+        }
+
+        let mut pattern = pattern;
+        if let Some(rest) = pattern.strip_prefix("||") {
+            filter.host_anchor = true;
+            pattern = rest;
+        } else if let Some(rest) = pattern.strip_prefix('|') {
+            filter.start_anchor = true;
+            pattern = rest;
+        }
+        if let Some(rest) = pattern.strip_suffix('|') {
+            filter.end_anchor = true;
+            pattern = rest;
+        }
+
+        let mut normalized = String::with_capacity(pattern.len() + 1);
+        if !filter.host_anchor && !filter.start_anchor {
+            normalized.push('*');
+        }
+        for c in pattern.chars() {
+            if c == '*' && normalized.ends_with('*') {
+                continue;
+            }
+            normalized.push(if filter.match_case { c } else { c.to_ascii_lowercase() });
+        }
+        if normalized.is_empty() {
+            normalized.push('*');
+        }
+        filter.pattern = normalized;
+        Some(filter)
+    }
+
+    fn apply_options(&mut self, options: &str) -> Option<()> {
+        let mut included = 0u16;
+        let mut excluded = 0u16;
+        for option in options.split(',').map(str::trim).filter(|option| !option.is_empty()) {
+            let (negated, name) = match option.strip_prefix('~') {
+                Some(name) => (true, name),
+                None => (false, option),
+            };
+            if let Some(domains) = name.strip_prefix("domain=").or_else(|| name.strip_prefix("from=")) {
+                for domain in domains.split('|').map(|domain| domain.trim().to_lowercase()) {
+                    match domain.strip_prefix('~') {
+                        Some(domain) => self.exclude_domains.push(domain.to_string()),
+                        None if !domain.is_empty() => self.include_domains.push(domain),
+                        None => {}
+                    }
+                }
+                continue;
+            }
+            match name {
+                "third-party" | "3p" => self.third_party = Some(!negated),
+                "first-party" | "1p" => self.third_party = Some(negated),
+                "important" => self.important = true,
+                "match-case" => self.match_case = true,
+                "all" => included |= ALL_TYPES,
+                _ => {
+                    let resource_type = ResourceType::from_option(name)?;
+                    if negated {
+                        excluded |= resource_type.bit();
+                    } else {
+                        included |= resource_type.bit();
+                    }
+                }
+            }
+        }
+        let types = if included != 0 { included } else { DEFAULT_TYPES };
+        self.types = types & !excluded;
+        (self.types != 0).then_some(())
+    }
+
+    /// Returns whether the rule applies to `request`, ignoring whether it is an exception.
+    pub fn matches(&self, request: &Request) -> bool {
+        self.types & request.resource_type.bit() != 0
+            && self.third_party.is_none_or(|third_party| third_party == request.third_party)
+            && self.matches_source(&request.source_host)
+            && self.matches_url(request)
+    }
+
+    /// Returns the site a `$document` exception such as `@@||example.com^$document` allows
+    /// everything on.
+    pub fn allowed_site(&self) -> Option<&str> {
+        if !self.exception || !self.host_anchor || self.types & ResourceType::Document.bit() == 0 {
+            return None;
+        }
+        let site = self.pattern.strip_suffix('^').unwrap_or(&self.pattern);
+        let is_host = !site.is_empty() && site.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+        is_host.then_some(site)
+    }
+
+    fn matches_source(&self, source_host: &str) -> bool {
+        (self.include_domains.is_empty()
+            || self.include_domains.iter().any(|domain| domain_matches(source_host, domain)))
+            && !self.exclude_domains.iter().any(|domain| domain_matches(source_host, domain))
+    }
+
+    fn matches_url(&self, request: &Request) -> bool {
+        let url = if self.match_case { &request.url } else { &request.url_lower };
+        let pattern = self.pattern.as_bytes();
+        if self.host_anchor {
+            let host = &request.url_lower[request.host_start..request.host_start + request.host.len()];
+            std::iter::once(0)
+                .chain(host.match_indices('.').map(|(index, _)| index + 1))
+                .any(|offset| glob_match(pattern, &url.as_bytes()[request.host_start + offset..], self.end_anchor))
+        } else {
+            glob_match(pattern, url.as_bytes(), self.end_anchor)
+        }
+    }
+}
+
+/// A subresource request checked against filter lists.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub url: String,
+    url_lower: String,
+    pub host: String,
+    host_start: usize,
+    /// Host of the page that makes the request.
+    pub source_host: String,
+    pub third_party: bool,
+    pub resource_type: ResourceType,
+}
+
+impl Request {
+    /// Describes a request for `url` made by the page at `source_url`. Returns `None` for URLs
+    /// without a host, which filter lists do not apply to.
+    pub fn new(url: &str, source_url: &str, resource_type: ResourceType) -> Option<Self> {
+        let parsed = url::Url::parse(url).ok()?;
+        let host = parsed.host_str()?.to_string();
+        let url = parsed.as_str().to_string();
+        let host_start = url.find("://").map(|index| index + 3)?;
+        let host_start = host_start + url[host_start..].find(host.as_str())?;
+        let source_host = url::Url::parse(source_url)
+            .ok()
+            .and_then(|source| source.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let third_party = !source_host.is_empty() && base_domain(&host) != base_domain(&source_host);
+        Some(Request {
+            url_lower: url.to_lowercase(),
+            url,
+            host,
+            host_start,
+            source_host,
+            third_party,
+            resource_type,
+        })
+    }
+}
+
+/// Returns the registrable part of `host`, e.g. `example.co.uk` for `ads.example.co.uk`. Without
+/// the public suffix list, two-letter country codes under a short second-level label such as
+/// `co.uk` or `com.au` are treated as suffixes.
+pub fn base_domain(host: &str) -> &str {
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return host;
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let keep = match labels.as_slice() {
+        [.., second, top] if top.len() == 2 && second.len() <= 3 && labels.len() > 2 => 3,
+        _ => 2,
+    };
+    if labels.len() <= keep {
+        return host;
+    }
+    let skipped: usize = labels[..labels.len() - keep].iter().map(|label| label.len() + 1).sum();
+    &host[skipped..]
+}
+
+/// Returns whether `host` is covered by a `domain` of a rule's options: the domain itself or one
+/// of its subdomains. A domain ending in `.*`, such as `example.*`, covers every top-level domain.
+pub fn domain_matches(host: &str, domain: &str) -> bool {
+    match domain.strip_suffix(".*") {
+        Some(name) => base_domain(host)
+            .strip_prefix(name)
+            .is_some_and(|suffix| suffix.starts_with('.')),
+        None => is_same_or_subdomain(host, domain),
+    }
+}
+
+/// Returns whether `host` is `domain` or one of its subdomains.
+pub fn is_same_or_subdomain(host: &str, domain: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+fn is_regex(pattern: &str) -> bool {
+    pattern.len() > 1 && pattern.starts_with('/') && pattern.ends_with('/')
+}
+
+/// Returns whether a separator (`^`) matches `c`: anything but a letter, digit, `_`, `-`, `.`
+/// or `%`. It also matches the end of the URL.
+fn is_separator(c: u8) -> bool {
+    !(c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-' | b'.' | b'%'))
+}
+
+/// Matches `pattern` against the start of `text`, or against all of it when `to_end` is set.
+fn glob_match(pattern: &[u8], text: &[u8], to_end: bool) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    loop {
+        if p == pattern.len() {
+            if !to_end || t == text.len() {
+                return true;
+            }
+        } else if pattern[p] == b'*' {
+            backtrack = Some((p, t));
+            p += 1;
+            continue;
+        } else if t < text.len() && (pattern[p] == text[t] || (pattern[p] == b'^' && is_separator(text[t]))) {
+            p += 1;
+            t += 1;
+            continue;
+        } else if t == text.len() && pattern[p] == b'^' {
+            p += 1;
+            continue;
+        }
+
+        match backtrack {
+            Some((star, matched)) if matched < text.len() => {
+                backtrack = Some((star, matched + 1));
+                p = star + 1;
+                t = matched + 1;
+            }
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str, source: &str, resource_type: ResourceType) -> Request {
+        Request::new(url, source, resource_type).unwrap()
+    }
+
+    #[test]
+    fn test_host_anchor_matches_domain_and_subdomains() {
+        let filter = NetworkFilter::parse("||ads.example.com^").unwrap();
+        let page = "https://news.org/";
+        assert!(filter.matches(&request("https://ads.example.com/x.js", page, ResourceType::Script)));
+        assert!(filter.matches(&request("https://cdn.ads.example.com/", page, ResourceType::Image)));
+        assert!(!filter.matches(&request("https://badads.example.com/", page, ResourceType::Image)));
+        assert!(!filter.matches(&request("https://ads.example.community/", page, ResourceType::Image)));
+        assert!(!filter.matches(&request("https://ads.example.com/", page, ResourceType::Document)));
+    }
+
+    #[test]
+    fn test_wildcards_separators_and_anchors() {
+        let page = "https://news.org/";
+        let filter = NetworkFilter::parse("/banner/*/ad_^").unwrap();
+        assert!(filter.matches(&request("https://news.org/banner/top/ad_?id=1", page, ResourceType::Image)));
+        assert!(filter.matches(&request("https://news.org/banner/top/ad_", page, ResourceType::Image)));
+        assert!(!filter.matches(&request("https://news.org/banner/top/ad_x", page, ResourceType::Image)));
+
+        let filter = NetworkFilter::parse("|http://*.swf|").unwrap();
+        assert!(filter.matches(&request("http://a.org/movie.swf", page, ResourceType::Media)));
+        assert!(!filter.matches(&request("https://a.org/movie.swf", page, ResourceType::Media)));
+        assert!(!filter.matches(&request("http://a.org/movie.swf?x", page, ResourceType::Media)));
+    }
+
+    #[test]
+    fn test_options() {
+        let filter = NetworkFilter::parse("||tracker.net^$third-party,script,domain=news.org|~blog.news.org").unwrap();
+        let url = "https://tracker.net/t.js";
+        assert!(filter.matches(&request(url, "https://www.news.org/", ResourceType::Script)));
+        assert!(!filter.matches(&request(url, "https://blog.news.org/", ResourceType::Script)));
+        assert!(!filter.matches(&request(url, "https://other.org/", ResourceType::Script)));
+        assert!(!filter.matches(&request(url, "https://www.news.org/", ResourceType::Image)));
+        assert!(!filter.matches(&request("https://a.tracker.net/t.js", "https://tracker.net/", ResourceType::Script)));
+
+        let filter = NetworkFilter::parse("@@||cdn.org^$~image,match-case").unwrap();
+        assert!(filter.exception && filter.match_case);
+        assert_eq!(filter.types & ResourceType::Image.bit(), 0);
+
+        assert_eq!(NetworkFilter::parse("||popups.net^$popup"), None);
+        assert_eq!(NetworkFilter::parse("/ads?[0-9]+/"), None);
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(parse_line("! Title: EasyList"), Line::Ignored);
+        assert_eq!(parse_line("[Adblock Plus 2.0]"), Line::Ignored);
+        assert!(matches!(parse_line("example.com##.ad-banner"), Line::Cosmetic(_)));
+        assert_eq!(parse_line("example.com#$#abort-on-property-read ads"), Line::Unsupported);
+        assert_eq!(parse_line("||x.com^$csp=script-src 'none'"), Line::Unsupported);
+        match parse_line("0.0.0.0 ads.example.com") {
+            Line::Network(filter) => {
+                assert!(filter.host_anchor);
+                assert_eq!(filter.pattern, "ads.example.com^");
+                assert_eq!(filter.text, "0.0.0.0 ads.example.com");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_base_domain() {
+        assert_eq!(base_domain("a.b.example.com"), "example.com");
+        assert_eq!(base_domain("example.com"), "example.com");
+        assert_eq!(base_domain("ads.example.co.uk"), "example.co.uk");
+        assert_eq!(base_domain("192.168.1.1"), "192.168.1.1");
+        assert!(domain_matches("www.google.co.uk", "google.*"));
+        assert!(!domain_matches("google.example.com", "google.*"));
+    }
+}
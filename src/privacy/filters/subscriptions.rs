@@ -0,0 +1,371 @@
+use super::cache;
+use crate::config::{FilterSubscription, PrivacySettings};
+use crate::debug_log;
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Wait before trying again to update a list whose last update failed.
+const RETRY_INTERVAL_SECS: i64 = 60 * 60;
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Largest list accepted, well above EasyList's few megabytes.
+const MAX_LIST_BYTES: usize = 32 * 1024 * 1024;
+
+/// Set while a background update runs, so only one runs at a time.
+static UPDATING: AtomicBool = AtomicBool::new(false);
+
+/// Set when an update changed a list, until the event loop takes it to reload the filters.
+static UPDATED: AtomicBool = AtomicBool::new(false);
+
+/// What Calm knows about a subscribed list from its past updates.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListState {
+    /// When the stored copy was downloaded.
+    #[serde(default)]
+    pub last_updated: Option<i64>,
+    /// When Calm last asked the server for a newer copy.
+    #[serde(default)]
+    pub last_checked: Option<i64>,
+    /// ETag of the stored copy, sent back so an unchanged list is not downloaded again.
+    #[serde(default)]
+    pub etag: Option<String>,
+    /// Number of rules in the stored copy that Calm enforces.
+    #[serde(default)]
+    pub rule_count: usize,
+    /// Why the last update failed. The stored copy, if any, stays in use.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl ListState {
+    /// Returns whether the list should be updated at `now`, given the hours between updates.
+    pub fn is_due(&self, now: i64, interval_hours: u64) -> bool {
+        let Some(last_checked) = self.last_checked else {
+            return true;
+        };
+        let interval = if self.error.is_some() {
+            RETRY_INTERVAL_SECS
+        } else {
+            (interval_hours.max(1) * 3600) as i64
+        };
+        now - last_checked >= interval
+    }
+}
+
+/// Update state of the subscribed lists, keyed by URL and stored in
+/// `~/.calm/filters/subscriptions.json`. The lists themselves are kept in
+/// `~/.calm/filters/lists/`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubscriptionStore {
+    #[serde(default)]
+    lists: HashMap<String, ListState>,
+}
+
+impl SubscriptionStore {
+    pub fn load() -> Self {
+        fs::read(Self::get_path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        write_atomically(&Self::get_path(), &serde_json::to_vec_pretty(self)?)
+    }
+
+    pub fn get(&self, url: &str) -> ListState {
+        self.lists.get(url).cloned().unwrap_or_default()
+    }
+
+    fn get_path() -> PathBuf {
+        crate::paths::data_dir().join("filters").join("subscriptions.json")
+    }
+}
+
+/// Returns the file holding the last good copy of the list at `url`.
+pub fn list_file(url: &str) -> PathBuf {
+    crate::paths::data_dir()
+        .join("filters")
+        .join("lists")
+        .join(list_file_name(url))
+}
+
+/// Names a list's file after a 64-bit FNV-1a digest of its URL, which unlike the standard
+/// library's hashers stays the same across Rust releases.
+fn list_file_name(url: &str) -> String {
+    let digest = url.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}.txt", digest)
+}
+
+/// Returns the files of the enabled subscriptions that have been downloaded.
+pub fn enabled_files(settings: &PrivacySettings) -> Vec<String> {
+    settings
+        .filter_subscriptions
+        .iter()
+        .filter(|subscription| subscription.enabled)
+        .map(|subscription| list_file(&subscription.url))
+        .filter(|path| path.exists())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Returns how to show the list `source` of a filter engine: the URL of the subscription it
+/// was downloaded from, or `source` itself for local and built-in lists.
+pub fn source_name(settings: &PrivacySettings, source: &str) -> String {
+    settings
+        .filter_subscriptions
+        .iter()
+        .find(|subscription| list_file(&subscription.url).to_string_lossy() == source)
+        .map_or_else(|| source.to_string(), |subscription| subscription.url.clone())
+}
+
+/// Checks that a downloaded list is a filter list, returning the number of rules Calm enforces.
+pub fn validate(text: &str) -> Result<usize, Box<dyn Error>> {
+    let start = text.trim_start().to_lowercase();
+    if start.starts_with("<!doctype") || start.starts_with("<html") {
+        return Err("received a web page instead of a filter list".into());
+    }
+    let list = cache::parse_list(String::new(), text);
+    match list.filters.len() + list.cosmetic.len() {
+        0 => Err("the list has no supported rules".into()),
+        count => Ok(count),
+    }
+}
+
+/// Updates the enabled subscriptions in `settings` that are due, in the background.
+pub fn update_due(settings: &PrivacySettings) {
+    if !settings.adblock_enabled {
+        return;
+    }
+    let store = SubscriptionStore::load();
+    let now = unix_now();
+    let due: Vec<String> = settings
+        .filter_subscriptions
+        .iter()
+        .filter(|subscription| subscription.enabled)
+        .filter(|subscription| {
+            !list_file(&subscription.url).exists()
+                || store.get(&subscription.url).is_due(now, settings.filter_update_interval_hours)
+        })
+        .map(|subscription| subscription.url.clone())
+        .collect();
+    if due.is_empty() || UPDATING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("filter-updater".to_string())
+        .spawn(move || {
+            update_lists(&due);
+            UPDATING.store(false, Ordering::SeqCst);
+        });
+    if spawned.is_err() {
+        UPDATING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Returns whether a list changed since the last call.
+pub fn take_updated() -> bool {
+    UPDATED.swap(false, Ordering::SeqCst)
+}
+
+fn update_lists(urls: &[String]) {
+    let client = match reqwest::blocking::Client::builder()
+        .user_agent(format!("Calm Browser {}", env!("CARGO_PKG_VERSION")))
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            debug_log!("Failed to create the filter list client: {}", e);
+            return;
+        }
+    };
+
+    let mut store = SubscriptionStore::load();
+    for url in urls {
+        let state = store.lists.entry(url.clone()).or_default();
+        match update_list(&client, url, state) {
+            Ok(changed) => {
+                debug_log!("Filter list {} checked (changed: {})", url, changed);
+                state.error = None;
+                if changed {
+                    UPDATED.store(true, Ordering::SeqCst);
+                }
+            }
+            Err(e) => {
+                debug_log!("Failed to update filter list {}: {}", url, e);
+                state.error = Some(e.to_string());
+            }
+        }
+        state.last_checked = Some(unix_now());
+        if let Err(e) = store.save() {
+            debug_log!("Failed to save filter list state: {}", e);
+        }
+    }
+}
+
+/// Downloads a newer copy of the list at `url` and stores it in place of the previous one once
+/// it is known to be good. Returns whether the stored copy changed.
+fn update_list(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    state: &mut ListState,
+) -> Result<bool, Box<dyn Error>> {
+    let path = list_file(url);
+    let mut request = client.get(url);
+    if let Some(etag) = state.etag.as_ref().filter(|_| path.exists()) {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    let response = request.send()?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(false);
+    }
+    let response = response.error_for_status()?;
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_LIST_BYTES as u64)
+    {
+        return Err("the list is too large".into());
+    }
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .map(str::to_string);
+    let text = response.text()?;
+    if text.len() > MAX_LIST_BYTES {
+        return Err("the list is too large".into());
+    }
+
+    let rule_count = validate(&text)?;
+    write_atomically(&path, text.as_bytes())?;
+    state.rule_count = rule_count;
+    state.etag = etag;
+    state.last_updated = Some(unix_now());
+    Ok(true)
+}
+
+/// Enables or disables the subscriptions to `url`.
+pub fn set_enabled(subscriptions: &mut [FilterSubscription], url: &str, enabled: bool) {
+    for subscription in subscriptions.iter_mut().filter(|subscription| subscription.url == url) {
+        subscription.enabled = enabled;
+    }
+}
+
+/// How a filter list is doing, as shown in the settings page.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListStatus {
+    /// URL of a subscription, or path of a local list file.
+    pub source: String,
+    pub subscribed: bool,
+    pub enabled: bool,
+    /// Whether a copy of the list is available to enforce.
+    pub available: bool,
+    pub rule_count: usize,
+    pub last_updated: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// Returns the status of the local filter lists and subscriptions in `settings`.
+pub fn statuses(settings: &PrivacySettings) -> Vec<ListStatus> {
+    let local = settings.filter_lists.iter().map(|path| {
+        let list = cache::load_list(&crate::import_export::expand_home(path));
+        ListStatus {
+            source: path.clone(),
+            subscribed: false,
+            enabled: true,
+            available: list.is_ok(),
+            rule_count: list
+                .as_ref()
+                .map_or(0, |list| list.filters.len() + list.cosmetic.len()),
+            last_updated: None,
+            error: list.err().map(|e| e.to_string()),
+        }
+    });
+    let store = SubscriptionStore::load();
+    let subscribed = settings.filter_subscriptions.iter().map(|subscription| {
+        let state = store.get(&subscription.url);
+        ListStatus {
+            source: subscription.url.clone(),
+            subscribed: true,
+            enabled: subscription.enabled,
+            available: list_file(&subscription.url).exists(),
+            rule_count: state.rule_count,
+            last_updated: state.last_updated,
+            error: state.error,
+        }
+    });
+    local.chain(subscribed).collect()
+}
+
+/// Writes through a temporary file so a crash never leaves a truncated copy behind.
+fn write_atomically(path: &Path, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temporary = path.with_extension("tmp");
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_are_due_after_interval_or_retry_delay() {
+        let hour = 3600;
+        let now = 100 * hour;
+        assert!(ListState::default().is_due(now, 24));
+
+        let checked = ListState {
+            last_checked: Some(now - 2 * hour),
+            ..ListState::default()
+        };
+        assert!(!checked.is_due(now, 24));
+        assert!(checked.is_due(now, 2));
+
+        let failed = ListState {
+            error: Some("timed out".to_string()),
+            ..checked
+        };
+        assert!(failed.is_due(now, 24));
+    }
+
+    #[test]
+    fn test_list_file_names_are_stable() {
+        assert_eq!(list_file_name(""), "cbf29ce484222325.txt");
+        assert_eq!(list_file_name("a"), "af63dc4c8601ec8c.txt");
+        assert_ne!(
+            list_file_name("https://easylist.to/easylist/easylist.txt"),
+            list_file_name("https://easylist.to/easylist/easyprivacy.txt")
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate("[Adblock Plus 2.0]\n||ads.net^\n##.ad\n").unwrap(), 2);
+        assert!(validate("<!DOCTYPE html>\n<html><body>Not found</body></html>").is_err());
+        assert!(validate("! Only comments\n").is_err());
+    }
+}
@@ -1,4 +1,7 @@
+pub mod block_log;
+pub mod filters;
 pub mod scripts;
+pub mod shields;
 
 use crate::config::PrivacySettings;
 
@@ -137,44 +137,6 @@ pub fn get_adblock_script() -> &'static str {
         /\/affiliate/i, /\/conversion/i, /\/impressions?/i, /\/clicks?/i
     ];
 
-    const YOUTUBE_AD_SELECTORS = [
-        '.video-ads', '.ytp-ad-module', '.ytp-ad-overlay-container',
-        '.ytp-ad-text-overlay', '.ytp-ad-player-overlay',
-        '.ytp-ad-progress-list', '.ytp-ad-image-overlay',
-        '.ytp-ad-skip-button-container', '.ad-container',
-        '.ad-showing', '.ytp-ad-persistent-progress-bar-container',
-        'ytd-promoted-sparkles-web-renderer', 'ytd-compact-promoted-video-renderer',
-        'ytd-promoted-video-renderer', 'ytd-ad-slot-renderer',
-        'ytd-banner-promo-renderer', 'ytd-display-ad-renderer',
-        'ytd-statement-banner-renderer', 'ytd-in-feed-ad-layout-renderer',
-        'ytd-player-legacy-desktop-watch-ads-renderer',
-        '#masthead-ad', '.ytd-action-companion-ad-renderer',
-        'ytd-merch-shelf-renderer', 'tp-yt-paper-dialog:has(#feedback)',
-        '[id^="player-ads"]', '.ytd-promoted-sparkles-text-search-renderer',
-        'ytd-ad-slot-renderer', 'yt-mealbar-promo-renderer',
-        '#player-ads', 'ytd-engagement-panel-section-list-renderer[target-id="engagement-panel-ads"]',
-        '.ytd-compact-promoted-item-renderer', '.ytd-promoted-video-inline-renderer',
-        'ytd-video-masthead-ad-v3-renderer', 'ytd-primetime-promo-renderer',
-        '#merch-shelf', 'ytd-companion-slot-renderer',
-        'ytd-action-companion-ad-renderer', 'ytd-watch-next-secondary-results-renderer > #items > ytd-compact-promoted-video-renderer'
-    ];
-
-    const GENERIC_AD_SELECTORS = [
-        '[id*="ad-"]', '[class*="ad-"]', '[id*="ads-"]', '[class*="ads-"]',
-        '[id*="banner"]', '[class*="banner"]', '[id*="sponsor"]', '[class*="sponsor"]',
-        '[id*="promo"]', '[class*="promo"]', '[aria-label*="Advertisement"]',
-        '[data-ad]', '[data-ads]', 'iframe[src*="doubleclick"]',
-        'iframe[src*="googlesyndication"]', 'iframe[src*="/ads/"]',
-        'div[data-google-query-id]', 'ins.adsbygoogle',
-        '.advertisement', '.advertising', '#ad', '#ads',
-        '.ad-container', '.ads-container', '.ad-wrapper', '.ads-wrapper',
-        '[id*="google_ads"]', '[class*="google_ads"]',
-        '[id*="taboola"]', '[class*="taboola"]',
-        '[id*="outbrain"]', '[class*="outbrain"]',
-        '.native-ad', '.sponsored-content', '.promoted-content',
-        '[data-ad-slot]', '[data-ad-unit]', '[data-dfp-id]'
-    ];
-
     const ANTI_ADBLOCK_SELECTORS = [
         '[id*="adblock"]', '[class*="adblock"]',
         '[id*="ad-block"]', '[class*="ad-block"]',
@@ -184,33 +146,54 @@ pub fn get_adblock_script() -> &'static str {
         'tp-yt-paper-dialog', 'ytd-popup-container'
     ];
 
-    function isAdUrl(url) {
+    // Returns the ad domain or URL pattern `url` matches, or null.
+    function adRule(url) {
         try {
             const urlObj = new URL(url, window.location.href);
             const hostname = urlObj.hostname.toLowerCase();
             const pathname = urlObj.pathname.toLowerCase();
             const fullUrl = urlObj.href.toLowerCase();
 
-            if (AD_DOMAINS.has(hostname)) return true;
+            if (AD_DOMAINS.has(hostname)) return hostname;
 
             for (const domain of AD_DOMAINS) {
                 if (hostname.endsWith('.' + domain) || hostname === domain) {
-                    return true;
+                    return domain;
                 }
             }
 
             for (const pattern of AD_PATTERNS) {
                 if (pattern.test(pathname) || pattern.test(fullUrl)) {
-                    return true;
+                    return String(pattern);
                 }
             }
 
-            return false;
+            return null;
         } catch (e) {
-            return false;
+            return null;
         }
     }
 
+    function blocksAd(url, resourceType) {
+        const rule = adRule(url);
+        if (!rule) return false;
+        window.__calmReportBlocked?.(String(url), 'adblock_enabled', { resourceType: resourceType, rule: rule });
+        return true;
+    }
+
+    const ELEMENT_TYPES = { script: 'script', iframe: 'subdocument', img: 'image', link: 'stylesheet' };
+
+    // Loads the content blocker stopped fail in the page; Calm keeps those a filter list rule
+    // blocks.
+    window.addEventListener('error', (event) => {
+        const element = event.target;
+        const resourceType = element && element.tagName && ELEMENT_TYPES[element.tagName.toLowerCase()];
+        const url = resourceType && (element.currentSrc || element.src || element.href);
+        if (url) {
+            window.__calmReportBlocked?.(url, 'adblock_enabled', { resourceType: resourceType, check: true });
+        }
+    }, true);
+
     function pruneAdData(obj) {
         if (!obj || typeof obj !== 'object') return obj;
 
@@ -242,7 +225,7 @@ pub fn get_adblock_script() -> &'static str {
         const url = args[0];
         const urlString = typeof url === 'string' ? url : url?.url || '';
 
-        if (isAdUrl(urlString)) {
+        if (blocksAd(urlString, 'xmlhttprequest')) {
             return Promise.reject(new Error('Blocked by Calm'));
         }
 
@@ -286,7 +269,7 @@ pub fn get_adblock_script() -> &'static str {
         xhr.open = function(method, url, ...rest) {
             this._url = url;
 
-            if (isAdUrl(url)) {
+            if (blocksAd(url, 'xmlhttprequest')) {
                 return;
             }
             return originalOpen.call(this, method, url, ...rest);
@@ -362,7 +345,7 @@ pub fn get_adblock_script() -> &'static str {
                     Object.defineProperty(elem, 'src', {
                         get: srcDescriptor.get,
                         set: function(value) {
-                            if (isAdUrl(value)) {
+                            if (blocksAd(value, ELEMENT_TYPES[tagName.toLowerCase()])) {
                                 return;
                             }
                             srcDescriptor.set.call(this, value);
@@ -375,7 +358,7 @@ pub fn get_adblock_script() -> &'static str {
                     Object.defineProperty(elem, 'href', {
                         get: hrefDescriptor.get,
                         set: function(value) {
-                            if (isAdUrl(value)) {
+                            if (blocksAd(value, ELEMENT_TYPES[tagName.toLowerCase()])) {
                                 return;
                             }
                             hrefDescriptor.set.call(this, value);
@@ -434,57 +417,144 @@ pub fn get_adblock_script() -> &'static str {
         } catch (e) {}
     }
 
-    function processShadowRoots(root) {
-        try {
-            const walker = document.createTreeWalker(
-                root,
-                NodeFilter.SHOW_ELEMENT,
-                null,
-                false
-            );
-
-            let node;
-            while (node = walker.nextNode()) {
-                if (node.shadowRoot) {
-                    hideAdsInTree(node.shadowRoot);
-                    processShadowRoots(node.shadowRoot);
+    // Element hiding rules from the filter lists, requested for this page below.
+    let proceduralSelectors = [];
+
+    function hideSelectors(selectors) {
+        const root = document.head || document.documentElement;
+        if (!root) {
+            document.addEventListener('DOMContentLoaded', () => hideSelectors(selectors), { once: true });
+            return;
+        }
+        let style = document.getElementById('calm-cosmetic-filters');
+        if (!style) {
+            style = document.createElement('style');
+            style.id = 'calm-cosmetic-filters';
+            root.appendChild(style);
+        }
+        const sheet = style.sheet;
+        if (!sheet) return;
+
+        for (let i = 0; i < selectors.length; i += 100) {
+            const chunk = selectors.slice(i, i + 100);
+            try {
+                sheet.insertRule(chunk.join(',\n') + ' { display: none !important; }', sheet.cssRules.length);
+            } catch (e) {
+                // One selector the engine does not know drops the whole rule, so retry one by one.
+                for (const selector of chunk) {
+                    try {
+                        sheet.insertRule(selector + ' { display: none !important; }', sheet.cssRules.length);
+                    } catch (e) {}
                 }
             }
-        } catch (e) {}
+        }
     }
 
-    function hideAdsInTree(root) {
-        const allSelectors = [...YOUTUBE_AD_SELECTORS, ...GENERIC_AD_SELECTORS];
-
-        for (const selector of allSelectors) {
-            try {
-                const elements = root.querySelectorAll(selector);
-                for (const el of elements) {
-                    if (el && el.parentNode && !el.hasAttribute('data-calm-hidden')) {
-                        el.style.cssText = 'display: none !important; visibility: hidden !important;';
-                        el.setAttribute('data-calm-hidden', 'true');
+    function closingParen(selector, open) {
+        let depth = 0;
+        for (let i = open; i < selector.length; i++) {
+            if (selector[i] === '(') depth++;
+            else if (selector[i] === ')' && --depth === 0) return i;
+        }
+        return -1;
+    }
 
-                        requestAnimationFrame(() => {
-                            if (el.parentNode) {
-                                el.remove();
-                            }
-                        });
+    // Finds the first `:has-text()`, or `:has()` containing one, outside of brackets and quotes.
+    function findProceduralOperator(selector) {
+        let depth = 0;
+        let quote = null;
+        for (let i = 0; i < selector.length; i++) {
+            const c = selector[i];
+            if (quote) {
+                if (c === '\\') i++;
+                else if (c === quote) quote = null;
+            } else if (c === '"' || c === "'") {
+                quote = c;
+            } else if (c === '(' || c === '[') {
+                depth++;
+            } else if (c === ')' || c === ']') {
+                depth--;
+            } else if (c === ':' && depth === 0) {
+                for (const name of [':has-text(', ':has(']) {
+                    if (!selector.startsWith(name, i)) continue;
+                    const end = closingParen(selector, i + name.length - 1);
+                    if (end < 0) return null;
+                    const arg = selector.slice(i + name.length, end);
+                    if (name === ':has-text(' || arg.includes(':has-text(')) {
+                        return { name, start: i, arg, end };
                     }
                 }
+            }
+        }
+        return null;
+    }
+
+    function textMatcher(arg) {
+        const regex = arg.match(/^\/(.*)\/([imsu]*)$/s);
+        if (regex) {
+            const re = new RegExp(regex[1], regex[2]);
+            return text => re.test(text);
+        }
+        return text => text.includes(arg);
+    }
+
+    // Returns the elements under `root` matching a selector with `:has-text()`, evaluating the
+    // procedural part by script and leaving the rest to querySelectorAll.
+    function selectProcedural(selector, root) {
+        const operator = findProceduralOperator(selector);
+        if (!operator) return Array.from(root.querySelectorAll(selector));
+
+        let base = selector.slice(0, operator.start);
+        if (/^\s*$|[\s>+~]$/.test(base)) base += '*';
+        const candidates = Array.from(root.querySelectorAll(base));
+        let matches;
+        if (operator.name === ':has-text(') {
+            const matchesText = textMatcher(operator.arg);
+            matches = candidates.filter(el => matchesText(el.textContent || ''));
+        } else {
+            matches = candidates.filter(el => selectProcedural(':scope ' + operator.arg, el).length > 0);
+        }
+
+        const rest = selector.slice(operator.end + 1);
+        if (!rest.trim() || matches.length === 0) return matches;
+        const mark = String(Math.random()).slice(2);
+        matches.forEach(el => el.setAttribute('data-calm-p', mark));
+        try {
+            return selectProcedural('[data-calm-p="' + mark + '"]' + rest, root);
+        } finally {
+            matches.forEach(el => el.removeAttribute('data-calm-p'));
+        }
+    }
+
+    function hideProcedural() {
+        for (const selector of proceduralSelectors) {
+            try {
+                for (const el of selectProcedural(selector, document)) {
+                    el.style.setProperty('display', 'none', 'important');
+                }
             } catch (e) {}
         }
     }
 
+    window.__calmApplyCosmeticFilters = function(filters) {
+        hideSelectors(filters.hidden || []);
+        proceduralSelectors = filters.procedural || [];
+        if (document.readyState !== 'loading') {
+            hideProcedural();
+        }
+    };
+
+    if (window.ipc) {
+        window.ipc.postMessage(JSON.stringify({ action: 'cosmetic_filters' }));
+    }
+
     function hideAds() {
         const isYouTube = window.location.hostname.includes('youtube.com');
 
         if (isYouTube) {
             hideYouTubeAds();
-            hideAdsInTree(document);
-            processShadowRoots(document.body || document.documentElement);
-        } else {
-            hideAdsInTree(document);
         }
+        hideProcedural();
 
         for (const selector of ANTI_ADBLOCK_SELECTORS) {
             try {
@@ -508,35 +578,6 @@ pub fn get_adblock_script() -> &'static str {
         const style = document.createElement('style');
         style.id = 'calm-adblock-styles';
         style.textContent = `
-            .video-ads, .ytp-ad-module, .ytp-ad-overlay-container,
-            .ytp-ad-text-overlay, .ytp-ad-player-overlay,
-            ytd-promoted-sparkles-web-renderer, ytd-compact-promoted-video-renderer,
-            ytd-promoted-video-renderer, ytd-ad-slot-renderer,
-            ytd-display-ad-renderer, ytd-player-legacy-desktop-watch-ads-renderer,
-            ytd-engagement-panel-section-list-renderer[target-id="engagement-panel-ads"],
-            ytd-companion-slot-renderer, ytd-action-companion-ad-renderer,
-            [id*="google_ads"], [class*="google_ads"],
-            ins.adsbygoogle, .adsbygoogle,
-            iframe[src*="doubleclick"], iframe[src*="googlesyndication"],
-            iframe[src*="/ads/"], iframe[src*="advertising"],
-            [id*="taboola"], [class*="taboola"],
-            [id*="outbrain"], [class*="outbrain"],
-            .native-ad, .sponsored-content, .promoted-content,
-            [data-ad-slot], [data-ad-unit], [data-dfp-id],
-            tp-yt-paper-dialog:has(yt-mealbar-promo-renderer),
-            ytd-popup-container:has([style*="adblock"]) {
-                display: none !important;
-                visibility: hidden !important;
-                opacity: 0 !important;
-                width: 0 !important;
-                height: 0 !important;
-                position: absolute !important;
-                top: -9999px !important;
-                left: -9999px !important;
-                pointer-events: none !important;
-                z-index: -1 !important;
-            }
-
             .html5-video-player.ad-showing video,
             .html5-video-player.ad-interrupting video {
                 display: block !important;
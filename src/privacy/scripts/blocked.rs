@@ -0,0 +1,43 @@
+/// Reports what the page's defenses stop to the browser, which counts it in the tab bar, lists
+/// it in the shield panel and logs it for `calm://privacy-log`. `details` gives the resource
+/// type and the rule that matched. Reports are sent in batches, once per page for each URL and
+/// rule, and loads marked `check` are only kept when a filter list rule blocks them.
+pub fn get_blocked_reporter_script() -> &'static str {
+    r#"
+        (function() {
+            if (window.top !== window || window.__calmReportBlocked) return;
+
+            const MAX_REPORTS = 1000;
+            const reported = new Set();
+            let pending = [];
+            let timer = null;
+
+            function flush() {
+                timer = null;
+                if (window.ipc && pending.length) {
+                    window.ipc.postMessage(JSON.stringify({
+                        action: 'blocked',
+                        items: pending
+                    }));
+                }
+                pending = [];
+            }
+
+            window.__calmReportBlocked = function(url, defense, details) {
+                details = details || {};
+                const key = [defense, details.rule || '', url].join(' ');
+                if (!url || reported.has(key) || reported.size >= MAX_REPORTS) return;
+                reported.add(key);
+                pending.push({
+                    url: url,
+                    defense: defense,
+                    resourceType: details.resourceType || 'other',
+                    rule: details.rule || null,
+                    check: !!details.check,
+                    timestamp: Date.now()
+                });
+                if (!timer) timer = setTimeout(flush, 500);
+            };
+        })();
+    "#
+}
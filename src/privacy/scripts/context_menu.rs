@@ -2,7 +2,31 @@ pub fn get_script() -> &'static str {
     r#"
         (function() {
             console.log('[Context Menu] Script loading...');
-            
+
+            // Sends the page's main content to the browser, which keeps a readable snapshot of it.
+            window.__calmSaveForLater = function() {
+                const root = document.querySelector('article') ||
+                    document.querySelector('main') ||
+                    document.querySelector('[role="main"]') ||
+                    document.body;
+                if (!window.ipc || !root) return;
+
+                const clone = root.cloneNode(true);
+                clone.querySelectorAll('script, style, noscript, iframe, nav, aside, footer, form, button').forEach(el => el.remove());
+                clone.querySelectorAll('a[href]').forEach(a => a.setAttribute('href', a.href));
+                clone.querySelectorAll('img').forEach(img => {
+                    const src = img.currentSrc || img.src || img.dataset.src;
+                    if (src) img.setAttribute('src', new URL(src, location.href).href);
+                    img.removeAttribute('srcset');
+                });
+
+                window.ipc.postMessage(JSON.stringify({
+                    action: 'save_to_reading_list',
+                    title: document.title,
+                    html: clone.innerHTML
+                }));
+            };
+
             function initContextMenu() {
                 console.log('[Context Menu] initContextMenu() called');
                 try {
@@ -285,6 +309,11 @@ pub fn get_script() -> &'static str {
                         window.location.reload();
                         hideContextMenu();
                     }, icons.reload));
+
+                    items.push(createMenuItem('Save to Reading List', () => {
+                        window.__calmSaveForLater();
+                        hideContextMenu();
+                    }, icons.save));
                 }
 
                 items.push(createMenuItem('Inspect Element', () => {
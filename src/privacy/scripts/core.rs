@@ -1,12 +1,15 @@
-use crate::config::PrivacySettings;
+use crate::privacy::shields::SiteGuards;
 
-pub fn get_privacy_script(settings: &PrivacySettings) -> String {
+pub fn get_privacy_script(guards: &SiteGuards) -> String {
     let mut script = String::from("(function() { 'use strict';\n");
     script.push_str("const config = { configurable: false, enumerable: true };\n");
+    script.push_str(
+        "const report = (defense, api) => window.__calmReportBlocked?.(location.href, defense, { resourceType: 'api', rule: api });\n",
+    );
 
-    if settings.hardware_spoofing {
-        script.push_str(
-            r#"
+    script.push_str(&guards.guard(
+        "hardware_spoofing",
+        r#"
         Object.defineProperty(navigator, 'webdriver', { ...config, get: () => false });
         Object.defineProperty(navigator, 'plugins', { ...config, get: () => [] });
         Object.defineProperty(navigator, 'languages', { ...config, get: () => ['en-US', 'en'] });
@@ -16,12 +19,11 @@ pub fn get_privacy_script(settings: &PrivacySettings) -> String {
         Object.defineProperty(navigator, 'maxTouchPoints', { ...config, get: () => 0 });
         Object.defineProperty(navigator, 'vendor', { ...config, get: () => 'Apple Computer, Inc.' });
         "#,
-        );
-    }
+    ));
 
-    if settings.network_info_spoofing {
-        script.push_str(
-            r#"
+    script.push_str(&guards.guard(
+        "network_info_spoofing",
+        r#"
         if (navigator.connection) {
             Object.defineProperty(navigator, 'connection', {
                 ...config,
@@ -35,24 +37,28 @@ pub fn get_privacy_script(settings: &PrivacySettings) -> String {
             });
         }
         "#,
-        );
-    }
+    ));
 
-    if settings.battery_blocking {
-        script.push_str(
-            r#"
+    script.push_str(&guards.guard(
+        "battery_blocking",
+        r#"
         if (navigator.getBattery) {
-            navigator.getBattery = () => Promise.reject(new Error('Battery API disabled'));
+            navigator.getBattery = () => {
+                report('battery_blocking', 'navigator.getBattery');
+                return Promise.reject(new Error('Battery API disabled'));
+            };
         }
         "#,
-        );
-    }
+    ));
 
-    if settings.webrtc_blocking {
-        script.push_str(
-            r#"
+    script.push_str(&guards.guard(
+        "webrtc_blocking",
+        r#"
         if (window.RTCPeerConnection) {
-            const BlockedRTC = function() { throw new Error('WebRTC disabled'); };
+            const BlockedRTC = function() {
+                report('webrtc_blocking', 'RTCPeerConnection');
+                throw new Error('WebRTC disabled');
+            };
             window.RTCPeerConnection = BlockedRTC;
             window.webkitRTCPeerConnection = BlockedRTC;
             window.mozRTCPeerConnection = BlockedRTC;
@@ -61,55 +67,69 @@ pub fn get_privacy_script(settings: &PrivacySettings) -> String {
             window.RTCIceCandidate = BlockedRTC;
         }
         "#,
-        );
-    }
+    ));
 
-    if settings.media_device_blocking {
-        script.push_str(r#"
+    script.push_str(&guards.guard(
+        "media_device_blocking",
+        r#"
         if (navigator.mediaDevices) {
-            navigator.mediaDevices.enumerateDevices = () => Promise.resolve([]);
-            navigator.mediaDevices.getUserMedia = () => Promise.reject(new Error('Media access disabled'));
-            navigator.mediaDevices.getDisplayMedia = () => Promise.reject(new Error('Screen capture disabled'));
+            navigator.mediaDevices.enumerateDevices = () => {
+                report('media_device_blocking', 'MediaDevices.enumerateDevices');
+                return Promise.resolve([]);
+            };
+            navigator.mediaDevices.getUserMedia = () => {
+                report('media_device_blocking', 'MediaDevices.getUserMedia');
+                return Promise.reject(new Error('Media access disabled'));
+            };
+            navigator.mediaDevices.getDisplayMedia = () => {
+                report('media_device_blocking', 'MediaDevices.getDisplayMedia');
+                return Promise.reject(new Error('Screen capture disabled'));
+            };
         }
-        "#);
-    }
+        "#,
+    ));
 
-    if settings.geolocation_blocking {
-        script.push_str(
-            r#"
+    script.push_str(&guards.guard(
+        "geolocation_blocking",
+        r#"
         if (navigator.geolocation) {
-            const blockGeo = () => { throw new Error('Geolocation disabled'); };
+            const blockGeo = () => {
+                report('geolocation_blocking', 'Geolocation');
+                throw new Error('Geolocation disabled');
+            };
             navigator.geolocation.getCurrentPosition = blockGeo;
             navigator.geolocation.watchPosition = blockGeo;
             navigator.geolocation.clearWatch = () => {};
         }
         "#,
-        );
-    }
+    ));
 
-    if settings.credentials_blocking {
-        script.push_str(
-            r#"
+    script.push_str(&guards.guard(
+        "credentials_blocking",
+        r#"
         if (navigator.credentials) {
-            navigator.credentials.get = () => Promise.resolve(null);
+            navigator.credentials.get = () => {
+                report('credentials_blocking', 'CredentialsContainer.get');
+                return Promise.resolve(null);
+            };
             navigator.credentials.store = () => Promise.resolve();
             navigator.credentials.create = () => Promise.resolve(null);
         }
         "#,
-        );
-    }
+    ));
 
-    if settings.storage_quota_spoofing {
-        script.push_str(r#"
+    script.push_str(&guards.guard(
+        "storage_quota_spoofing",
+        r#"
         if (navigator.storage?.estimate) {
             navigator.storage.estimate = () => Promise.resolve({ quota: 8589934592, usage: 1073741824 });
         }
-        "#);
-    }
+        "#,
+    ));
 
-    if settings.permissions_hardening {
-        script.push_str(
-            r#"
+    script.push_str(&guards.guard(
+        "permissions_hardening",
+        r#"
         if (navigator.permissions) {
             const originalQuery = navigator.permissions.query;
             navigator.permissions.query = function(descriptor) {
@@ -121,12 +141,11 @@ pub fn get_privacy_script(settings: &PrivacySettings) -> String {
             };
         }
         "#,
-        );
-    }
+    ));
 
-    if settings.screen_normalization {
-        script.push_str(
-            r#"
+    script.push_str(&guards.guard(
+        "screen_normalization",
+        r#"
         Object.defineProperty(screen, 'width', { ...config, get: () => 1920 });
         Object.defineProperty(screen, 'height', { ...config, get: () => 1080 });
         Object.defineProperty(screen, 'availWidth', { ...config, get: () => 1920 });
@@ -134,16 +153,14 @@ pub fn get_privacy_script(settings: &PrivacySettings) -> String {
         Object.defineProperty(screen, 'colorDepth', { ...config, get: () => 24 });
         Object.defineProperty(screen, 'pixelDepth', { ...config, get: () => 24 });
         "#,
-        );
-    }
+    ));
 
-    if settings.timezone_normalization {
-        script.push_str(
-            r#"
+    script.push_str(&guards.guard(
+        "timezone_normalization",
+        r#"
         Date.prototype.getTimezoneOffset = function() { return 0; };
         "#,
-        );
-    }
+    ));
 
     script.push_str("\nwindow.__calm_privacy_enabled = true;\n})();\n");
     script
@@ -7,6 +7,7 @@ pub fn get_canvas_fingerprint_protection() -> &'static str {
         const originalGetImageData = CanvasRenderingContext2D.prototype.getImageData;
 
         const noise = () => Math.floor(Math.random() * 3) - 1;
+        const report = (api) => window.__calmReportBlocked?.(location.href, 'canvas_fingerprint_protection', { resourceType: 'api', rule: api });
 
         const addNoise = (data) => {
             for (let i = 0; i < data.length; i += 4) {
@@ -17,6 +18,7 @@ pub fn get_canvas_fingerprint_protection() -> &'static str {
         };
 
         HTMLCanvasElement.prototype.toDataURL = function() {
+            report('HTMLCanvasElement.toDataURL');
             const context = this.getContext('2d');
             if (context && this.width > 0 && this.height > 0) {
                 const imageData = context.getImageData(0, 0, this.width, this.height);
@@ -27,6 +29,7 @@ pub fn get_canvas_fingerprint_protection() -> &'static str {
         };
 
         CanvasRenderingContext2D.prototype.getImageData = function() {
+            report('CanvasRenderingContext2D.getImageData');
             const imageData = originalGetImageData.apply(this, arguments);
             addNoise(imageData.data);
             return imageData;
@@ -40,11 +43,26 @@ pub fn get_webgl_fingerprint_protection() -> &'static str {
     (function() {
         'use strict';
 
+        const SPOOFED_PARAMETERS = {
+            37445: 'UNMASKED_VENDOR_WEBGL',
+            37446: 'UNMASKED_RENDERER_WEBGL',
+            7936: 'VENDOR',
+            7937: 'RENDERER',
+            35724: 'SHADING_LANGUAGE_VERSION'
+        };
+
         const getParameterProxyHandler = {
             apply: function(target, thisArg, args) {
                 const param = args[0];
                 const noise = () => Math.random() * 0.0001;
 
+                if (SPOOFED_PARAMETERS[param]) {
+                    window.__calmReportBlocked?.(location.href, 'webgl_fingerprint_protection', {
+                        resourceType: 'api',
+                        rule: `WebGLRenderingContext.getParameter(${SPOOFED_PARAMETERS[param]})`
+                    });
+                }
+
                 if (param === 37445) {
                     return 'Intel Inc.';
                 }
@@ -91,9 +109,11 @@ pub fn get_audio_fingerprint_protection() -> &'static str {
         'use strict';
 
         const audioContext = window.AudioContext || window.webkitAudioContext;
+        const report = (api) => window.__calmReportBlocked?.(location.href, 'audio_fingerprint_protection', { resourceType: 'api', rule: api });
         if (audioContext) {
             const originalGetChannelData = AudioBuffer.prototype.getChannelData;
             AudioBuffer.prototype.getChannelData = function() {
+                report('AudioBuffer.getChannelData');
                 const channelData = originalGetChannelData.apply(this, arguments);
                 for (let i = 0; i < channelData.length; i++) {
                     channelData[i] = channelData[i] + Math.random() * 0.0001 - 0.00005;
@@ -105,6 +125,7 @@ pub fn get_audio_fingerprint_protection() -> &'static str {
             if (OriginalAnalyser) {
                 const originalGetFloatFrequencyData = OriginalAnalyser.prototype.getFloatFrequencyData;
                 OriginalAnalyser.prototype.getFloatFrequencyData = function(array) {
+                    report('AnalyserNode.getFloatFrequencyData');
                     originalGetFloatFrequencyData.apply(this, arguments);
                     for (let i = 0; i < array.length; i++) {
                         array[i] = array[i] + Math.random() * 0.1 - 0.05;
@@ -132,6 +153,7 @@ pub fn get_font_fingerprint_protection() -> &'static str {
             document.fonts.check = function(font) {
                 const fontFamily = font.match(/['"](.*?)['"]/);
                 if (fontFamily && !standardFonts.includes(fontFamily[1])) {
+                    window.__calmReportBlocked?.(location.href, 'font_enumeration_restriction', { resourceType: 'api', rule: 'FontFaceSet.check' });
                     return false;
                 }
                 return originalCheck.apply(this, arguments);
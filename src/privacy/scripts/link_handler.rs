@@ -32,6 +32,10 @@ pub fn get_link_handler_script() -> &'static str {
                 return false;
             }
 
+            function opensInBackground(e) {
+                return e.button === 1 || ((e.ctrlKey || e.metaKey) && e.button === 0);
+            }
+
             function handleLinkClick(e) {
                 const link = findLinkElement(e.target);
 
@@ -47,7 +51,8 @@ pub fn get_link_handler_script() -> &'static str {
                             console.log('[LINK HANDLER] Opening link in new tab:', absoluteUrl);
                             window.ipc.postMessage(JSON.stringify({
                                 action: 'open_url_new_tab',
-                                url: absoluteUrl
+                                url: absoluteUrl,
+                                background: opensInBackground(e)
                             }));
                         } catch (err) {
                             console.error('[LINK HANDLER] Failed to parse URL:', err);
@@ -1,4 +1,5 @@
 pub mod adblock;
+pub mod blocked;
 pub mod context_menu;
 pub mod core;
 pub mod download_intercept;
@@ -8,8 +9,10 @@ pub mod title_tracker;
 pub mod tracking;
 
 use crate::config::PrivacySettings;
+use crate::privacy::shields::SiteGuards;
 
 pub use adblock::get_adblock_script;
+pub use blocked::get_blocked_reporter_script;
 pub use context_menu::get_script as get_context_menu_script;
 pub use core::{get_dark_mode_preference, get_privacy_script};
 pub use download_intercept::get_script as get_download_interceptor;
@@ -27,6 +30,7 @@ pub fn get_content_renderer() -> &'static str {
 }
 
 /// Aggregates all enabled privacy protection scripts into a single string based on configuration.
+/// Protections turned on or off for particular sites are decided in the page when it loads.
 ///
 /// # Arguments
 ///
@@ -36,39 +40,37 @@ pub fn get_content_renderer() -> &'static str {
 ///
 /// Combined JavaScript code for all enabled privacy features
 pub fn get_all_privacy_scripts_with_config(settings: &PrivacySettings) -> String {
-    let mut scripts = Vec::new();
-
-    scripts.push(get_privacy_script(settings).to_string());
-
-    if settings.adblock_enabled {
-        scripts.push(get_adblock_script().to_string());
-    }
-
-    if settings.tracking_domain_blocking {
-        scripts.push(get_tracking_blocker().to_string());
-    }
-
-    if settings.canvas_fingerprint_protection {
-        scripts.push(get_canvas_fingerprint_protection().to_string());
-    }
-
-    if settings.webgl_fingerprint_protection {
-        scripts.push(get_webgl_fingerprint_protection().to_string());
-    }
-
-    if settings.audio_fingerprint_protection {
-        scripts.push(get_audio_fingerprint_protection().to_string());
-    }
-
-    if settings.font_enumeration_restriction {
-        scripts.push(get_font_fingerprint_protection().to_string());
-    }
-
-    scripts.push(get_dark_mode_preference().to_string());
-    scripts.push(get_title_tracker_script().to_string());
-    scripts.push(get_download_interceptor().to_string());
-    scripts.push(get_context_menu_script().to_string());
-    scripts.push(get_link_handler_script().to_string());
+    let guards = SiteGuards::new(settings);
+    let scripts = [
+        "{".to_string(),
+        guards.prelude(),
+        get_blocked_reporter_script().to_string(),
+        get_privacy_script(&guards),
+        guards.guard("adblock_enabled", get_adblock_script()),
+        guards.guard("tracking_domain_blocking", get_tracking_blocker()),
+        guards.guard(
+            "canvas_fingerprint_protection",
+            get_canvas_fingerprint_protection(),
+        ),
+        guards.guard(
+            "webgl_fingerprint_protection",
+            get_webgl_fingerprint_protection(),
+        ),
+        guards.guard(
+            "audio_fingerprint_protection",
+            get_audio_fingerprint_protection(),
+        ),
+        guards.guard(
+            "font_enumeration_restriction",
+            get_font_fingerprint_protection(),
+        ),
+        "}".to_string(),
+        get_dark_mode_preference().to_string(),
+        get_title_tracker_script().to_string(),
+        get_download_interceptor().to_string(),
+        get_context_menu_script().to_string(),
+        get_link_handler_script().to_string(),
+    ];
 
     scripts.join("\n")
 }
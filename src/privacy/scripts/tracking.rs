@@ -31,12 +31,18 @@ pub fn get_tracking_blocker() -> &'static str {
             'launchdarkly.com', 'vwo.com', 'ab-tasty.com'
         ];
 
-        const isTracking = (url) => trackingDomains.some(domain => url.includes(domain));
+        const trackingDomain = (url) => trackingDomains.find(domain => url.includes(domain));
+        const blocksTracker = (url, resourceType) => {
+            const domain = trackingDomain(String(url));
+            if (!domain) return false;
+            window.__calmReportBlocked?.(String(url), 'tracking_domain_blocking', { resourceType: resourceType, rule: domain });
+            return true;
+        };
 
         const originalFetch = window.fetch;
         window.fetch = function(url, options = {}) {
             const urlStr = typeof url === 'string' ? url : url.url;
-            if (isTracking(urlStr)) return Promise.reject(new Error('Blocked'));
+            if (blocksTracker(urlStr, 'xmlhttprequest')) return Promise.reject(new Error('Blocked'));
 
             const headers = new Headers(options.headers || {});
             headers.set('DNT', '1');
@@ -51,7 +57,7 @@ pub fn get_tracking_blocker() -> &'static str {
 
         const originalOpen = XMLHttpRequest.prototype.open;
         XMLHttpRequest.prototype.open = function(method, url) {
-            if (isTracking(url)) return;
+            if (blocksTracker(url, 'xmlhttprequest')) return;
             return originalOpen.apply(this, arguments);
         };
 
@@ -67,7 +73,7 @@ pub fn get_tracking_blocker() -> &'static str {
         const blockTrackerElement = (child) => {
             if (child.tagName === 'SCRIPT' || child.tagName === 'IFRAME') {
                 const src = child.src || '';
-                if (isTracking(src)) return true;
+                if (blocksTracker(src, child.tagName === 'SCRIPT' ? 'script' : 'subdocument')) return true;
             }
             return false;
         };
@@ -0,0 +1,403 @@
+use crate::config::PrivacySettings;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+/// Defenses that can be turned on or off for a site, by `PrivacySettings` field, with their
+/// label in the shield panel.
+pub const DEFENSES: &[(&str, &str)] = &[
+    ("adblock_enabled", "Ads"),
+    ("tracking_domain_blocking", "Trackers"),
+    ("canvas_fingerprint_protection", "Canvas fingerprinting"),
+    ("webgl_fingerprint_protection", "WebGL fingerprinting"),
+    ("audio_fingerprint_protection", "Audio fingerprinting"),
+    ("font_enumeration_restriction", "Font fingerprinting"),
+    ("hardware_spoofing", "Hardware details"),
+    ("screen_normalization", "Screen size"),
+    ("timezone_normalization", "Time zone"),
+    ("network_info_spoofing", "Network information"),
+    ("storage_quota_spoofing", "Storage quota"),
+    ("battery_blocking", "Battery status"),
+    ("webrtc_blocking", "WebRTC"),
+    ("media_device_blocking", "Cameras and microphones"),
+    ("geolocation_blocking", "Location"),
+    ("permissions_hardening", "Permission queries"),
+    ("credentials_blocking", "Saved credentials"),
+];
+
+/// Most requests remembered per tab for the shield panel.
+const MAX_BLOCKED_PER_TAB: usize = 200;
+
+/// Generates `defense` and `defense_mut` from one list of the `PrivacySettings` fields in
+/// `DEFENSES`, so the two cannot disagree.
+macro_rules! defense_fields {
+    ($($field:ident),* $(,)?) => {
+        /// Returns the state of the defense `name` in `settings`, or `None` when `name` is not a
+        /// defense.
+        fn defense(settings: &PrivacySettings, name: &str) -> Option<bool> {
+            match name {
+                $(stringify!($field) => Some(settings.$field),)*
+                _ => None,
+            }
+        }
+
+        fn defense_mut<'a>(settings: &'a mut PrivacySettings, name: &str) -> Option<&'a mut bool> {
+            match name {
+                $(stringify!($field) => Some(&mut settings.$field),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+defense_fields!(
+    adblock_enabled,
+    tracking_domain_blocking,
+    canvas_fingerprint_protection,
+    webgl_fingerprint_protection,
+    audio_fingerprint_protection,
+    font_enumeration_restriction,
+    hardware_spoofing,
+    screen_normalization,
+    timezone_normalization,
+    network_info_spoofing,
+    storage_quota_spoofing,
+    battery_blocking,
+    webrtc_blocking,
+    media_device_blocking,
+    geolocation_blocking,
+    permissions_hardening,
+    credentials_blocking,
+);
+
+/// Returns whether the defense `name` is on in `settings`.
+pub fn is_enabled(settings: &PrivacySettings, name: &str) -> bool {
+    defense(settings, name).unwrap_or(false)
+}
+
+/// Returns the site of a page, the key its overrides are stored under: the host of `url`
+/// without `www.`.
+pub fn site_of(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.to_lowercase();
+    Some(host.strip_prefix("www.").unwrap_or(&host).to_string())
+}
+
+/// Returns whether overrides stored for `site` apply on `host`, which they do on the site and
+/// its subdomains.
+pub fn covers(site: &str, host: &str) -> bool {
+    host == site
+        || host
+            .strip_suffix(site)
+            .is_some_and(|rest| rest.ends_with('.'))
+}
+
+/// Returns the settings in effect on `host`: `settings` with the overrides of every site
+/// covering it applied, the most specific site last.
+pub fn site_settings(settings: &PrivacySettings, host: &str) -> PrivacySettings {
+    let host = host.to_lowercase();
+    let mut sites: Vec<&String> = settings
+        .site_overrides
+        .keys()
+        .filter(|site| covers(site, &host))
+        .collect();
+    sites.sort_by_key(|site| site.len());
+
+    let mut effective = settings.clone();
+    for site in sites {
+        for (name, enabled) in &settings.site_overrides[site] {
+            if let Some(defense) = defense_mut(&mut effective, name) {
+                *defense = *enabled;
+            }
+        }
+    }
+    effective
+}
+
+/// Turns the defense `name` on or off for `site`, dropping the override when it matches what
+/// the site would get anyway. Returns whether `name` is a defense.
+pub fn set_site_defense(
+    settings: &mut PrivacySettings,
+    site: &str,
+    name: &str,
+    enabled: bool,
+) -> bool {
+    if defense(settings, name).is_none() {
+        return false;
+    }
+    let overrides = settings.site_overrides.entry(site.to_string()).or_default();
+    overrides.remove(name);
+    if is_enabled(&site_settings(settings, site), name) != enabled {
+        settings
+            .site_overrides
+            .entry(site.to_string())
+            .or_default()
+            .insert(name.to_string(), enabled);
+    }
+    settings
+        .site_overrides
+        .retain(|_, overrides| !overrides.is_empty());
+    true
+}
+
+/// Returns the sites on which the defense `name` is off although it is on by default.
+pub fn sites_without(settings: &PrivacySettings, name: &str) -> Vec<String> {
+    if !is_enabled(settings, name) {
+        return Vec::new();
+    }
+    settings
+        .site_overrides
+        .keys()
+        .filter(|site| !is_enabled(&site_settings(settings, site), name))
+        .cloned()
+        .collect()
+}
+
+/// A defense as shown in the shield panel for a site.
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteDefense {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub enabled: bool,
+    /// Whether the site has its own override for the defense, which resetting the site removes.
+    /// Overrides inherited from a parent site do not count.
+    pub overridden: bool,
+}
+
+/// Returns the defenses in effect on `site`.
+pub fn site_defenses(settings: &PrivacySettings, site: &str) -> Vec<SiteDefense> {
+    let effective = site_settings(settings, site);
+    let own = settings.site_overrides.get(site);
+    DEFENSES
+        .iter()
+        .map(|&(name, label)| SiteDefense {
+            name,
+            label,
+            enabled: is_enabled(&effective, name),
+            overridden: own.is_some_and(|overrides| overrides.contains_key(name)),
+        })
+        .collect()
+}
+
+/// A request a defense stopped on a page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Blocked {
+    pub url: String,
+    /// The defense that stopped it, as a `PrivacySettings` field.
+    pub defense: String,
+    /// The filter rule, blocked domain or API that matched.
+    pub rule: Option<String>,
+    /// The filter list the rule comes from.
+    pub list: Option<String>,
+}
+
+/// Records `blocked` among the requests stopped on a page, once per URL and up to a limit.
+pub fn record_blocked(page: &mut Vec<Blocked>, blocked: Blocked) {
+    if page.len() < MAX_BLOCKED_PER_TAB && !page.contains(&blocked) {
+        page.push(blocked);
+    }
+}
+
+/// Decides in page scripts which defenses run. The script carries the settings of every site
+/// with overrides and picks the one covering the page when it starts loading, so a tab that
+/// navigates to another site switches to that site's settings.
+pub struct SiteGuards {
+    default: PrivacySettings,
+    sites: Vec<(String, PrivacySettings)>,
+}
+
+impl SiteGuards {
+    pub fn new(settings: &PrivacySettings) -> Self {
+        Self {
+            default: settings.clone(),
+            sites: settings
+                .site_overrides
+                .keys()
+                .map(|site| (site.clone(), site_settings(settings, site)))
+                .collect(),
+        }
+    }
+
+    /// Returns the script deciding the settings of the page, which must come before the guarded
+    /// scripts in the same block.
+    pub fn prelude(&self) -> String {
+        if self.sites.is_empty() {
+            return String::new();
+        }
+        let sites: Map<String, Value> = self
+            .sites
+            .iter()
+            .map(|(site, settings)| (site.clone(), defenses_json(settings)))
+            .collect();
+        format!(
+            r#"
+const __calmShields = (function() {{
+    const sites = {};
+    const host = location.hostname.toLowerCase();
+    let match = null;
+    for (const site of Object.keys(sites)) {{
+        if ((host === site || host.endsWith('.' + site)) && (!match || site.length > match.length)) {{
+            match = site;
+        }}
+    }}
+    return match ? sites[match] : {};
+}})();
+"#,
+            Value::Object(sites),
+            defenses_json(&self.default)
+        )
+    }
+
+    /// Returns `script` so that it only runs on pages where the defense `name` is on: as is when
+    /// it is on everywhere, nothing when it is on nowhere, and behind a check otherwise.
+    pub fn guard(&self, name: &str, script: &str) -> String {
+        let mut states = std::iter::once(&self.default)
+            .chain(self.sites.iter().map(|(_, settings)| settings))
+            .map(|settings| is_enabled(settings, name));
+        let first = states.next().unwrap_or(false);
+        if states.all(|enabled| enabled == first) {
+            return if first {
+                script.to_string()
+            } else {
+                String::new()
+            };
+        }
+        format!("if (__calmShields.{}) {{\n{}\n}}\n", name, script)
+    }
+}
+
+fn defenses_json(settings: &PrivacySettings) -> Value {
+    let defenses: Map<String, Value> = DEFENSES
+        .iter()
+        .map(|&(name, _)| (name.to_string(), json!(is_enabled(settings, name))))
+        .collect();
+    Value::Object(defenses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings_with(overrides: &[(&str, &str, bool)]) -> PrivacySettings {
+        let mut settings = PrivacySettings::default();
+        for &(site, name, enabled) in overrides {
+            settings
+                .site_overrides
+                .entry(site.to_string())
+                .or_default()
+                .insert(name.to_string(), enabled);
+        }
+        settings
+    }
+
+    #[test]
+    fn test_most_specific_site_wins() {
+        let settings = settings_with(&[
+            ("example.com", "webrtc_blocking", false),
+            ("example.com", "webgl_fingerprint_protection", false),
+            ("maps.example.com", "webgl_fingerprint_protection", true),
+        ]);
+        let maps = site_settings(&settings, "eu.maps.example.com");
+        assert!(!maps.webrtc_blocking && maps.webgl_fingerprint_protection);
+        assert!(!site_settings(&settings, "www.example.com").webgl_fingerprint_protection);
+        assert!(site_settings(&settings, "notexample.com").webrtc_blocking);
+        assert_eq!(
+            site_of("https://www.Example.com/page"),
+            Some("example.com".to_string())
+        );
+        assert_eq!(site_of("about:blank"), None);
+    }
+
+    #[test]
+    fn test_set_site_defense_drops_needless_overrides() {
+        let mut settings = settings_with(&[("example.com", "webrtc_blocking", false)]);
+        assert!(set_site_defense(
+            &mut settings,
+            "maps.example.com",
+            "webrtc_blocking",
+            false
+        ));
+        assert!(!settings.site_overrides.contains_key("maps.example.com"));
+
+        assert!(set_site_defense(
+            &mut settings,
+            "example.com",
+            "adblock_enabled",
+            false
+        ));
+        assert_eq!(sites_without(&settings, "adblock_enabled"), ["example.com"]);
+        set_site_defense(&mut settings, "example.com", "webrtc_blocking", true);
+        set_site_defense(&mut settings, "example.com", "adblock_enabled", true);
+        assert!(settings.site_overrides.is_empty());
+        assert!(!set_site_defense(
+            &mut settings,
+            "example.com",
+            "site_overrides",
+            false
+        ));
+    }
+
+    #[test]
+    fn test_every_defense_is_a_setting() {
+        let settings = PrivacySettings::default();
+        for &(name, _) in DEFENSES {
+            assert!(defense(&settings, name).is_some(), "{}", name);
+        }
+        assert_eq!(defense(&settings, "site_overrides"), None);
+    }
+
+    #[test]
+    fn test_inherited_defenses_are_not_overridden() {
+        let settings = settings_with(&[
+            ("example.com", "webrtc_blocking", false),
+            ("maps.example.com", "battery_blocking", false),
+        ]);
+        let defense = |site, name| {
+            site_defenses(&settings, site)
+                .into_iter()
+                .find(|defense| defense.name == name)
+                .unwrap()
+        };
+        let webrtc = defense("maps.example.com", "webrtc_blocking");
+        assert!(!webrtc.enabled && !webrtc.overridden);
+        let battery = defense("maps.example.com", "battery_blocking");
+        assert!(!battery.enabled && battery.overridden);
+        assert!(defense("example.com", "webrtc_blocking").overridden);
+        assert!(!defense("example.com", "adblock_enabled").overridden);
+    }
+
+    #[test]
+    fn test_blocked_requests_are_recorded_once_up_to_a_limit() {
+        let blocked = |i: usize| Blocked {
+            url: format!("https://ads.net/{}.js", i),
+            defense: "adblock_enabled".to_string(),
+            rule: Some("||ads.net^".to_string()),
+            list: None,
+        };
+        let mut page = Vec::new();
+        record_blocked(&mut page, blocked(0));
+        record_blocked(&mut page, blocked(0));
+        assert_eq!(page.len(), 1);
+        for i in 1..MAX_BLOCKED_PER_TAB + 5 {
+            record_blocked(&mut page, blocked(i));
+        }
+        assert_eq!(page.len(), MAX_BLOCKED_PER_TAB);
+    }
+
+    #[test]
+    fn test_guards() {
+        let guards = SiteGuards::new(&settings_with(&[("example.com", "webrtc_blocking", false)]));
+        assert!(guards.prelude().contains(r#"{"example.com":{"#));
+        assert_eq!(guards.guard("battery_blocking", "B"), "B");
+        assert_eq!(
+            guards.guard("webrtc_blocking", "W"),
+            "if (__calmShields.webrtc_blocking) {\nW\n}\n"
+        );
+
+        let guards = SiteGuards::new(&PrivacySettings::default());
+        assert_eq!(guards.prelude(), "");
+        assert_eq!(guards.guard("webrtc_blocking", "W"), "W");
+    }
+}
@@ -0,0 +1,552 @@
+use crate::debug_log;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Content Security Policy for snapshots: no scripts, frames or plugins, only inline styles
+/// and images.
+pub const SNAPSHOT_CSP: &str =
+    "default-src 'none'; img-src https: http: data:; style-src 'unsafe-inline'";
+
+/// Elements removed from snapshots together with everything inside them.
+const DROPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "frame", "frameset", "object", "embed",
+    "applet", "form", "button", "select", "textarea", "svg", "math", "canvas", "video", "audio",
+    "nav", "aside", "footer", "dialog",
+];
+
+/// Elements without content that snapshots never keep.
+const DROPPED_VOID_ELEMENTS: &[&str] =
+    &["link", "meta", "base", "input", "source", "track", "param"];
+
+/// Attributes kept on snapshot elements; every other one is dropped.
+const ALLOWED_ATTRIBUTES: &[&str] = &[
+    "href", "src", "alt", "title", "width", "height", "colspan", "rowspan", "datetime", "cite",
+    "start", "lang", "dir",
+];
+
+/// Number of characters of article text shown in the reading list.
+const EXCERPT_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadingItem {
+    pub id: u64,
+    pub url: String,
+    pub title: String,
+    #[serde(default)]
+    pub excerpt: String,
+    #[serde(rename = "addedAt")]
+    pub added_at: i64,
+    #[serde(default)]
+    pub read: bool,
+}
+
+/// Articles saved for later, shared by all windows and stored in `~/.calm/reading_list.yml`.
+/// Each article's readable snapshot is kept next to it in `~/.calm/reading_list/<id>.html`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadingList {
+    #[serde(default)]
+    items: Vec<ReadingItem>,
+    #[serde(default)]
+    next_id: u64,
+}
+
+thread_local! {
+    static READING_LIST: RefCell<ReadingList> = RefCell::new(ReadingList::load());
+}
+
+/// Runs `f` against the reading list shared by all windows.
+pub fn with_reading_list<R>(f: impl FnOnce(&ReadingList) -> R) -> R {
+    READING_LIST.with(|list| f(&list.borrow()))
+}
+
+/// Runs `f` to change the reading list shared by all windows, then saves it.
+pub fn update_reading_list<R>(f: impl FnOnce(&mut ReadingList) -> R) -> R {
+    READING_LIST.with(|list| {
+        let mut list = list.borrow_mut();
+        let result = f(&mut list);
+        if let Err(e) = list.save() {
+            debug_log!("Failed to save reading list: {}", e);
+        }
+        result
+    })
+}
+
+/// Saves `url` to the shared reading list with a snapshot built from `article_html`, the page's
+/// main content. Returns the id of the item and whether it was not in the list yet.
+pub fn save_article(url: &str, title: &str, article_html: Option<&str>) -> (u64, bool) {
+    let (id, added, title) = update_reading_list(|list| {
+        let added = !list.contains(url);
+        let id = list.add(url, title, article_html);
+        (id, added, list.items[0].title.clone())
+    });
+    if let Some(article_html) = article_html {
+        write_snapshot(id, &build_snapshot(article_html, &title, url, unix_now()));
+    }
+    (id, added)
+}
+
+/// Removes an item from the shared reading list together with its snapshot. Returns whether it
+/// existed.
+pub fn remove_article(id: u64) -> bool {
+    let removed = update_reading_list(|list| list.remove(id));
+    if removed {
+        let _ = fs::remove_file(snapshot_path(id));
+    }
+    removed
+}
+
+impl ReadingList {
+    pub fn load() -> Self {
+        let path = Self::get_path();
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => serde_yaml::from_str::<ReadingList>(&contents).unwrap_or_else(|e| {
+                debug_log!("Failed to parse {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::get_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let yaml = serde_yaml::to_string(self)?;
+        fs::write(path, yaml)?;
+        Ok(())
+    }
+
+    /// Adds `url` with an excerpt of `article_html`, the page's main content. Adding a page that
+    /// is already in the list refreshes it and marks it unread.
+    /// Returns the id of the item.
+    pub fn add(&mut self, url: &str, title: &str, article_html: Option<&str>) -> u64 {
+        let title = title.trim();
+        let title = if title.is_empty() { url } else { title };
+        let excerpt = article_html.map(excerpt).unwrap_or_default();
+
+        let id = match self.items.iter().position(|item| item.url == url) {
+            Some(index) => {
+                let mut item = self.items.remove(index);
+                item.title = title.to_string();
+                item.read = false;
+                if !excerpt.is_empty() {
+                    item.excerpt = excerpt;
+                }
+                let id = item.id;
+                self.items.insert(0, item);
+                id
+            }
+            None => {
+                self.next_id += 1;
+                self.items.insert(
+                    0,
+                    ReadingItem {
+                        id: self.next_id,
+                        url: url.to_string(),
+                        title: title.to_string(),
+                        excerpt,
+                        added_at: unix_now(),
+                        read: false,
+                    },
+                );
+                self.next_id
+            }
+        };
+
+        id
+    }
+
+    pub fn contains(&self, url: &str) -> bool {
+        self.items.iter().any(|item| item.url == url)
+    }
+
+    pub fn set_read(&mut self, id: u64, read: bool) -> bool {
+        match self.items.iter_mut().find(|item| item.id == id) {
+            Some(item) => {
+                item.read = read;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes an item. Returns whether it existed.
+    pub fn remove(&mut self, id: u64) -> bool {
+        let count = self.items.len();
+        self.items.retain(|item| item.id != id);
+        self.items.len() != count
+    }
+
+    /// Marks an item read and returns the address to open it at: its snapshot, unless
+    /// `original` asks for the live page or no snapshot was saved.
+    pub fn open(&mut self, id: u64, original: bool) -> Option<String> {
+        let url = self.items.iter().find(|item| item.id == id)?.url.clone();
+        self.set_read(id, true);
+        if !original && snapshot_path(id).exists() {
+            Some(snapshot_url(id))
+        } else {
+            Some(url)
+        }
+    }
+
+    /// Returns the items, newest first, as JSON for the reading list panel.
+    pub fn to_json(&self) -> String {
+        let items: Vec<serde_json::Value> = self
+            .items
+            .iter()
+            .map(|item| {
+                serde_json::json!({
+                    "id": item.id,
+                    "url": item.url,
+                    "title": item.title,
+                    "excerpt": item.excerpt,
+                    "addedAt": item.added_at,
+                    "read": item.read,
+                    "offline": snapshot_path(item.id).exists(),
+                })
+            })
+            .collect();
+        serde_json::Value::Array(items).to_string()
+    }
+
+    fn get_path() -> PathBuf {
+        crate::paths::data_dir().join("reading_list.yml")
+    }
+}
+
+/// Returns where the snapshot of item `id` is stored.
+pub fn snapshot_path(id: u64) -> PathBuf {
+    crate::paths::data_dir().join(format!("reading_list/{}.html", id))
+}
+
+/// Returns the `calm://` address the snapshot of item `id` is served at.
+pub fn snapshot_url(id: u64) -> String {
+    format!("calm://reading/{}", id)
+}
+
+/// Reads the snapshot served at `calm://reading/<id>`, given the path of that address.
+pub fn read_snapshot(path: &str) -> Option<String> {
+    let id: u64 = path.trim_matches('/').parse().ok()?;
+    fs::read_to_string(snapshot_path(id)).ok()
+}
+
+fn write_snapshot(id: u64, snapshot: &str) {
+    let path = snapshot_path(id);
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, snapshot));
+    if let Err(e) = result {
+        debug_log!(
+            "Failed to write reading list snapshot {}: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+/// Downloads a link saved without being opened and writes its snapshot in the background.
+pub fn fetch_snapshot(id: u64, url: String, title: String) {
+    std::thread::spawn(move || {
+        let page = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(20))
+            .build()
+            .and_then(|client| client.get(&url).send())
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.text());
+        match page {
+            Ok(page) => {
+                let title = if title.trim().is_empty() {
+                    page_title(&page)
+                } else {
+                    title
+                };
+                write_snapshot(
+                    id,
+                    &build_snapshot(extract_article(&page), &title, &url, unix_now()),
+                );
+            }
+            Err(e) => debug_log!("Failed to fetch {} for the reading list: {}", url, e),
+        }
+    });
+}
+
+/// Returns the main content of a full page: its `<article>`, `<main>` or `<body>`.
+fn extract_article(page: &str) -> &str {
+    ["article", "main", "body"]
+        .iter()
+        .find_map(|tag| {
+            let pattern = Regex::new(&format!(r"(?is)<{}\b[^>]*>(.*)</{}\s*>", tag, tag)).ok()?;
+            pattern.captures(page).and_then(|captures| captures.get(1))
+        })
+        .map_or(page, |content| content.as_str())
+}
+
+fn page_title(page: &str) -> String {
+    Regex::new(r"(?is)<title[^>]*>(.*?)</title>")
+        .ok()
+        .and_then(|pattern| {
+            pattern
+                .captures(page)
+                .map(|captures| decode_entities(captures[1].trim()))
+        })
+        .unwrap_or_default()
+}
+
+/// Wraps sanitized article HTML in a standalone page with the title and a link to the original.
+fn build_snapshot(article_html: &str, title: &str, url: &str, saved_at: i64) -> String {
+    let saved = chrono::DateTime::from_timestamp(saved_at, 0)
+        .map(|date| date.format("%B %-d, %Y").to_string())
+        .unwrap_or_default();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <meta name="referrer" content="no-referrer">
+    <title>{title}</title>
+    <style>
+        html {{ background: #101010; }}
+        body {{
+            max-width: 680px;
+            margin: 0 auto;
+            padding: 80px 24px;
+            color: #e0e0e0;
+            font-family: Georgia, 'Times New Roman', serif;
+            font-size: 19px;
+            line-height: 1.7;
+        }}
+        .snapshot-header {{
+            font-family: 'gohu', monospace;
+            font-size: 13px;
+            color: #888888;
+            border-bottom: 1px solid #2a2a2a;
+            padding-bottom: 16px;
+            margin-bottom: 32px;
+        }}
+        .snapshot-header h1 {{
+            font-family: Georgia, 'Times New Roman', serif;
+            font-size: 34px;
+            line-height: 1.25;
+            color: #ffffff;
+            margin: 0 0 12px 0;
+        }}
+        a {{ color: #9ecbff; }}
+        img {{ max-width: 100%; height: auto; }}
+        pre, code {{ font-family: 'gohu', monospace; font-size: 14px; background: #1a1a1a; }}
+        pre {{ padding: 12px; overflow-x: auto; }}
+        blockquote {{ border-left: 3px solid #444444; margin-left: 0; padding-left: 16px; color: #bbbbbb; }}
+        table {{ border-collapse: collapse; }}
+        td, th {{ border: 1px solid #333333; padding: 4px 8px; }}
+    </style>
+</head>
+<body>
+    <div class="snapshot-header">
+        <h1>{title}</h1>
+        <a href="{url}">{url}</a> &middot; saved {saved}
+    </div>
+    <article>
+{content}
+    </article>
+</body>
+</html>"#,
+        title = escape_html(title),
+        url = escape_html(url),
+        saved = saved,
+        content = sanitize_html(article_html, url)
+    )
+}
+
+/// Reduces page HTML to readable markup: scripts, styles, embedded content and forms are
+/// removed, and only plain attributes with `http(s)`, `mailto` or inline image URLs are kept.
+/// Relative URLs are resolved against `base_url`.
+pub fn sanitize_html(html: &str, base_url: &str) -> String {
+    let base = url::Url::parse(base_url).ok();
+
+    let mut html = Regex::new(r"(?s)<!--.*?-->")
+        .map(|comments| comments.replace_all(html, "").into_owned())
+        .unwrap_or_default();
+    for tag in DROPPED_ELEMENTS {
+        if let Ok(element) = Regex::new(&format!(r"(?is)<{}\b[^>]*>.*?</{}\s*>", tag, tag)) {
+            html = element.replace_all(&html, "").into_owned();
+        }
+    }
+    for tag in DROPPED_ELEMENTS.iter().chain(DROPPED_VOID_ELEMENTS) {
+        if let Ok(stray) = Regex::new(&format!(r"(?is)</?{}\b[^>]*>", tag)) {
+            html = stray.replace_all(&html, "").into_owned();
+        }
+    }
+
+    let Ok(tag) = Regex::new(r"<([a-zA-Z][a-zA-Z0-9-]*)([^>]*)>") else {
+        return String::new();
+    };
+    tag.replace_all(&html, |captures: &Captures| {
+        let self_closing = captures[2].trim_end().ends_with('/');
+        format!(
+            "<{}{}{}>",
+            &captures[1],
+            clean_attributes(&captures[1], &captures[2], base.as_ref()),
+            if self_closing { " /" } else { "" }
+        )
+    })
+    .into_owned()
+}
+
+fn clean_attributes(tag: &str, attributes: &str, base: Option<&url::Url>) -> String {
+    let Ok(attribute) =
+        Regex::new(r#"(?s)([a-zA-Z][a-zA-Z0-9:_-]*)\s*=\s*("[^"]*"|'[^']*'|[^\s"'>]+)"#)
+    else {
+        return String::new();
+    };
+
+    let mut cleaned = String::new();
+    for captures in attribute.captures_iter(attributes) {
+        let name = captures[1].to_ascii_lowercase();
+        if !ALLOWED_ATTRIBUTES.contains(&name.as_str()) {
+            continue;
+        }
+        let value = decode_entities(captures[2].trim_matches(|c| c == '"' || c == '\''));
+        let value = if matches!(name.as_str(), "href" | "src" | "cite") {
+            match safe_url(
+                &value,
+                base,
+                tag.eq_ignore_ascii_case("img") && name == "src",
+            ) {
+                Some(url) => url,
+                None => continue,
+            }
+        } else {
+            value
+        };
+        cleaned.push_str(&format!(" {}=\"{}\"", name, escape_html(&value)));
+    }
+    cleaned
+}
+
+/// Resolves a URL found in a snapshot, or returns `None` if it could run code. Inline
+/// `data:image` URLs are only allowed for images.
+fn safe_url(value: &str, base: Option<&url::Url>, is_image: bool) -> Option<String> {
+    let value = value.trim();
+    if value.starts_with('#') {
+        return Some(value.to_string());
+    }
+    if is_image && value.to_ascii_lowercase().starts_with("data:image/") {
+        return Some(value.to_string());
+    }
+
+    let url = match base {
+        Some(base) => base.join(value).ok()?,
+        None => url::Url::parse(value).ok()?,
+    };
+    matches!(url.scheme(), "http" | "https" | "mailto").then(|| url.to_string())
+}
+
+/// Returns the start of an article's text for the reading list.
+fn excerpt(article_html: &str) -> String {
+    let text = sanitize_html(article_html, "about:blank");
+    let text = Regex::new(r"<[^>]*>")
+        .map(|tags| tags.replace_all(&text, " ").into_owned())
+        .unwrap_or(text);
+    let text = decode_entities(&text.split_whitespace().collect::<Vec<_>>().join(" "));
+    if text.chars().count() > EXCERPT_LENGTH {
+        let cut: String = text.chars().take(EXCERPT_LENGTH).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        text
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_removes_active_content() {
+        let html = r#"<h2 class="x" onclick="steal()">Title</h2>
+<script>alert(1)</script><SCRIPT src="evil.js"></SCRIPT>
+<p style="color: red">Text <a href="javascript:alert(1)">bad</a> <a href="/docs?a=1&amp;b=2">docs</a></p>
+<iframe src="https://ads.example"></iframe><!-- <script>hidden</script> -->
+<img src="data:image/png;base64,AAAA" onerror="x()"><a href="data:text/html,hi">data</a>
+<meta http-equiv="refresh" content="0;url=https://evil.example">"#;
+        let clean = sanitize_html(html, "https://example.com/posts/1");
+
+        assert!(!clean.to_lowercase().contains("script"));
+        assert!(
+            !clean.contains("onclick") && !clean.contains("onerror") && !clean.contains("style=")
+        );
+        assert!(!clean.contains("javascript:") && !clean.contains("data:text"));
+        assert!(!clean.contains("iframe") && !clean.contains("refresh"));
+        assert!(clean.contains(r#"<h2>Title</h2>"#));
+        assert!(clean.contains(r#"<a href="https://example.com/docs?a=1&amp;b=2">docs</a>"#));
+        assert!(clean.contains(r#"<img src="data:image/png;base64,AAAA">"#));
+        assert!(clean.contains("<a>bad</a>"));
+    }
+
+    #[test]
+    fn test_extract_article_and_excerpt() {
+        let page = "<html><head><title>Post &amp; more</title></head><body><nav>Menu</nav>\
+                    <article><p>Hello   <b>world</b></p></article></body></html>";
+        assert_eq!(extract_article(page), "<p>Hello   <b>world</b></p>");
+        assert_eq!(page_title(page), "Post & more");
+        assert_eq!(excerpt(extract_article(page)), "Hello world");
+        assert_eq!(
+            excerpt(&"word ".repeat(100)).chars().count(),
+            EXCERPT_LENGTH
+        );
+    }
+
+    #[test]
+    fn test_add_existing_url_moves_it_to_the_top_unread() {
+        let mut list = ReadingList::default();
+        let first = list.add("https://a.example", "A", Some("<p>First</p>"));
+        let second = list.add("https://b.example", "", None);
+        assert!(list.set_read(first, true));
+
+        assert!(list.contains("https://a.example"));
+        assert_eq!(list.add("https://a.example", "A again", None), first);
+        assert_eq!(list.items[0].id, first);
+        assert_eq!(list.items[0].title, "A again");
+        assert_eq!(list.items[0].excerpt, "First");
+        assert!(!list.items[0].read);
+        assert_eq!(list.items[1].title, "https://b.example");
+
+        assert_eq!(
+            list.open(second, false).as_deref(),
+            Some("https://b.example")
+        );
+        assert!(list.items[1].read);
+        assert!(list.remove(second));
+        assert!(!list.remove(second));
+    }
+}
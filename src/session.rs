@@ -1,3 +1,4 @@
+use crate::debug_log;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -9,12 +10,63 @@ pub struct TabState {
     pub url: String,
     pub title: Option<String>,
     pub is_active: bool,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+}
+
+/// A split group, referencing its panes by index into `WindowState::tabs`.
+/// `orientation` and `split_ratio` describe the outermost split; `tree` holds the full tiling
+/// layout and is missing from sessions saved before groups could hold more than two panes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitGroupState {
+    pub tab_indices: Vec<usize>,
+    pub orientation: String,
+    #[serde(default = "default_split_ratio")]
+    pub split_ratio: f32,
+    #[serde(default)]
+    pub layout: String,
+    #[serde(default)]
+    pub tree: Option<SplitNodeState>,
+}
+
+/// A node of a split group's tiling tree, referencing tabs by index into `WindowState::tabs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SplitNodeState {
+    Pane {
+        tab_index: usize,
+    },
+    Split {
+        orientation: String,
+        ratios: Vec<f32>,
+        children: Vec<SplitNodeState>,
+    },
+}
+
+fn default_split_ratio() -> f32 {
+    0.5
+}
+
+/// A named tab group, referencing its tabs by index into `WindowState::tabs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TabGroupState {
+    pub name: String,
+    pub color: String,
+    #[serde(default)]
+    pub collapsed: bool,
+    pub tab_indices: Vec<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowState {
     pub tabs: Vec<TabState>,
     pub active_tab_index: Option<usize>,
+    #[serde(default)]
+    pub split_groups: Vec<SplitGroupState>,
+    #[serde(default)]
+    pub tab_groups: Vec<TabGroupState>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,13 +99,19 @@ impl BrowserSession {
             fs::create_dir_all(parent)?;
         }
 
+        // Only rotate the previous session into the backup if it is readable,
+        // so a corrupt primary file never overwrites the last good backup.
         let backup_path = Self::get_session_backup_path();
-        if session_path.exists() {
-            let _ = fs::copy(&session_path, &backup_path);
+        if let Ok(contents) = fs::read_to_string(&session_path) {
+            if serde_json::from_str::<BrowserSession>(&contents).is_ok() {
+                let _ = fs::write(&backup_path, contents);
+            }
         }
 
         let json = serde_json::to_string_pretty(self)?;
-        fs::write(session_path, json)?;
+        let temp_path = session_path.with_extension("json.tmp");
+        fs::write(&temp_path, json)?;
+        fs::rename(temp_path, session_path)?;
         Ok(())
     }
 
@@ -61,8 +119,9 @@ impl BrowserSession {
         let session_path = Self::get_session_path();
 
         if let Ok(contents) = fs::read_to_string(&session_path) {
-            if let Ok(session) = serde_json::from_str(&contents) {
-                return Some(session);
+            match serde_json::from_str(&contents) {
+                Ok(session) => return Some(session),
+                Err(e) => debug_log!("Session file is unreadable ({}), trying backup", e),
             }
         }
 
@@ -84,14 +143,31 @@ impl BrowserSession {
         Ok(())
     }
 
+    /// Creates the run marker, which stays on disk until the browser exits cleanly.
+    pub fn mark_running() -> std::io::Result<()> {
+        fs::write(Self::get_run_marker_path(), std::process::id().to_string())
+    }
+
+    /// Returns whether the previous run left its marker behind, i.e. did not exit cleanly.
+    pub fn previous_run_crashed() -> bool {
+        Self::get_run_marker_path().exists()
+    }
+
+    /// Removes the run marker on orderly exit.
+    pub fn clear_run_marker() {
+        let _ = fs::remove_file(Self::get_run_marker_path());
+    }
+
     fn get_session_path() -> PathBuf {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        PathBuf::from(home).join(".calm_session.json")
+        crate::paths::session_file("session.json")
     }
 
     fn get_session_backup_path() -> PathBuf {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        PathBuf::from(home).join(".calm_session.json.bak")
+        crate::paths::session_file("session.json.bak")
+    }
+
+    fn get_run_marker_path() -> PathBuf {
+        crate::paths::session_file("session.running")
     }
 }
 
@@ -10,6 +10,7 @@ use crate::{config::Config, debug_log, window::BrowserWindowComponents};
 pub enum Shortcut {
     NewTab,
     NewWindow,
+    NewPrivateWindow,
     CloseTab,
     Reload,
     FocusUrlBar,
@@ -17,6 +18,9 @@ pub enum Shortcut {
     ToggleSplitView,
     FocusSidebar,
     Find,
+    ReopenClosedTab,
+    BookmarkPage,
+    ToggleBookmarks,
 }
 
 pub struct ShortcutManager;
@@ -43,8 +47,8 @@ impl ShortcutManager {
                     "window.ipc.postMessage(JSON.stringify({action: 'keyboard_shortcut', shortcut: 'new_tab'}));"
                 );
             }
-            Shortcut::NewWindow => {
-                debug_log!("Shortcut: NewWindow");
+            Shortcut::NewWindow | Shortcut::NewPrivateWindow => {
+                debug_log!("Shortcut: {:?}", shortcut);
                 let config = components.config.borrow();
                 let default_url = crate::convert_file_url(&config.default_url);
                 drop(config);
@@ -54,6 +58,7 @@ impl ShortcutManager {
                     Rc::clone(&components.config),
                     default_url,
                     false,
+                    shortcut == Shortcut::NewPrivateWindow,
                 ) {
                     Ok(new_components) => {
                         let new_window_id = new_components.window.id();
@@ -70,8 +75,14 @@ impl ShortcutManager {
             Shortcut::CloseTab => {
                 debug_log!("Shortcut: CloseTab (Cmd+W)");
                 
-                // Always close the current tab
-                if let Some(tab_id) = components.tab_manager.borrow().get_active_tab_id() {
+                // Pinned tabs can only be closed explicitly from the tab bar
+                let active_tab_id = components.tab_manager.borrow().get_active_tab_id();
+                if let Some(tab_id) = active_tab_id {
+                    if components.tab_manager.borrow().is_tab_pinned(tab_id) {
+                        debug_log!("Tab {} is pinned, not closing", tab_id);
+                        return;
+                    }
+
                     debug_log!("Closing tab {}", tab_id);
                     components.tab_manager.borrow_mut().close_tab(tab_id);
                     
@@ -117,6 +128,10 @@ impl ShortcutManager {
                     };
 
                     if should_show {
+                        crate::window::hide_bookmarks_panel(
+                            &components.bookmarks_panel,
+                            &components.bookmarks_visible,
+                        );
                         let _ = components.download_overlay.set_visible(true);
                         std::thread::sleep(std::time::Duration::from_millis(10));
                         components
@@ -185,6 +200,28 @@ impl ShortcutManager {
                     let _ = active_webview.evaluate_script("window.calmStartSearch();");
                 }
             }
+            Shortcut::ReopenClosedTab => {
+                debug_log!("Shortcut: ReopenClosedTab");
+                if !components
+                    .tab_manager
+                    .borrow_mut()
+                    .reopen_closed(&components.window, None)
+                {
+                    debug_log!("Nothing to reopen");
+                }
+            }
+            Shortcut::BookmarkPage => {
+                debug_log!("Shortcut: BookmarkPage");
+                let _ = components.tab_bar_webview.evaluate_script(
+                    "window.ipc.postMessage(JSON.stringify({action: 'keyboard_shortcut', shortcut: 'bookmark_page'}));"
+                );
+            }
+            Shortcut::ToggleBookmarks => {
+                debug_log!("Shortcut: ToggleBookmarks");
+                let _ = components.tab_bar_webview.evaluate_script(
+                    "window.ipc.postMessage(JSON.stringify({action: 'keyboard_shortcut', shortcut: 'toggle_bookmarks'}));"
+                );
+            }
         }
     }
 }
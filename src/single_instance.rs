@@ -1,6 +1,7 @@
 use std::io::{Read, Write};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::PathBuf;
+use std::process::Command;
 use std::sync::mpsc::{channel, Receiver};
 use std::thread;
 
@@ -8,7 +9,7 @@ pub struct SingleInstance;
 
 impl SingleInstance {
     pub fn is_single() -> bool {
-        let socket_path = match Self::get_socket_path("calm") {
+        let socket_path = match Self::get_socket_path() {
             Ok(path) => path,
             Err(_) => return true,
         };
@@ -27,7 +28,7 @@ impl SingleInstance {
     }
 
     pub fn send_to_existing(url: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let socket_path = Self::get_socket_path("calm")?;
+        let socket_path = Self::get_socket_path()?;
         let mut stream = UnixStream::connect(&socket_path)?;
         stream.write_all(url.as_bytes())?;
         stream.write_all(b"\n")?;
@@ -36,7 +37,7 @@ impl SingleInstance {
     }
 
     pub fn start_listener() -> Result<Receiver<String>, Box<dyn std::error::Error>> {
-        let socket_path = Self::get_socket_path("calm")?;
+        let socket_path = Self::get_socket_path()?;
 
         let listener = match UnixListener::bind(&socket_path) {
             Ok(l) => l,
@@ -71,11 +72,24 @@ impl SingleInstance {
         Ok(rx)
     }
 
-    fn get_socket_path(app_name: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
-        let runtime_dir = std::env::var("XDG_RUNTIME_DIR")
-            .or_else(|_| std::env::var("TMPDIR"))
-            .unwrap_or_else(|_| "/tmp".to_string());
+    /// Starts Calm as profile `name` in a new process. If that profile is already running, the new
+    /// process hands its start page to the running instance and exits.
+    pub fn launch_profile(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if !crate::paths::is_valid_profile_name(name) {
+            return Err(format!("Invalid profile name: {}", name).into());
+        }
+        let mut child = Command::new(std::env::current_exe()?)
+            .arg("--profile")
+            .arg(name)
+            .spawn()?;
+        thread::spawn(move || {
+            let _ = child.wait();
+        });
+        Ok(())
+    }
 
-        Ok(PathBuf::from(runtime_dir).join(format!("{}.socket", app_name)))
+    /// Each profile runs its own instance, so each has its own socket.
+    fn get_socket_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+        Ok(crate::paths::socket_path())
     }
 }
@@ -0,0 +1,141 @@
+use crate::config::{ContainerDefinition, ContainerRule};
+use crate::debug_log;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::rc::Rc;
+use wry::WebContext;
+
+thread_local! {
+    static CONTAINER_CONTEXTS: RefCell<HashMap<String, Rc<RefCell<WebContext>>>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the web context of container `name`, shared by all of its tabs in every window.
+/// Its cookies, storage and cache live in the profile's `containers/<name>` directory.
+pub fn container_web_context(name: &str) -> Rc<RefCell<WebContext>> {
+    let directory = directory_name(name);
+    CONTAINER_CONTEXTS.with(|contexts| {
+        let mut contexts = contexts.borrow_mut();
+        let context = contexts.entry(directory.clone()).or_insert_with(|| {
+            Rc::new(RefCell::new(WebContext::new(Some(container_data_dir(&directory)))))
+        });
+        Rc::clone(context)
+    })
+}
+
+/// Returns the container a rule assigns to `url`, if that container exists.
+pub fn container_for_url(
+    url: &str,
+    rules: &[ContainerRule],
+    containers: &[ContainerDefinition],
+) -> Option<String> {
+    rules
+        .iter()
+        .filter(|rule| find_container(&rule.container, containers).is_some())
+        .find(|rule| crate::history::url_matches_hosts(url, std::slice::from_ref(&rule.host)))
+        .map(|rule| rule.container.clone())
+}
+
+/// Returns the container called `name`.
+pub fn find_container<'a>(name: &str, containers: &'a [ContainerDefinition]) -> Option<&'a ContainerDefinition> {
+    containers.iter().find(|container| container.name == name)
+}
+
+/// Drops containers whose name turns into the same directory as an earlier container's, such as
+/// "Work" after "work", so that no two containers share cookies and site data.
+pub fn remove_colliding(containers: &mut Vec<ContainerDefinition>) {
+    let mut directories = HashSet::new();
+    containers.retain(|container| {
+        let unique = directories.insert(directory_name(&container.name));
+        if !unique {
+            debug_log!("Ignoring container {:?}, which shares its data directory with another container", container.name);
+        }
+        unique
+    });
+}
+
+/// Returns the host a rule created for `url` should match, without a leading `www.`.
+pub fn rule_host(url: &str) -> Option<String> {
+    let host = url::Url::parse(url).ok()?.host_str()?.to_lowercase();
+    Some(host.strip_prefix("www.").map(str::to_string).unwrap_or(host))
+}
+
+fn container_data_dir(directory: &str) -> PathBuf {
+    crate::paths::data_dir().join("containers").join(directory)
+}
+
+/// Turns a container name into a directory name, keeping letters and digits.
+fn directory_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn containers() -> Vec<ContainerDefinition> {
+        ["Work", "Personal"]
+            .into_iter()
+            .map(|name| ContainerDefinition {
+                name: name.to_string(),
+                color: "blue".to_string(),
+            })
+            .collect()
+    }
+
+    fn rule(host: &str, container: &str) -> ContainerRule {
+        ContainerRule {
+            host: host.to_string(),
+            container: container.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_container_for_url_uses_first_matching_rule() {
+        let rules = vec![rule("github.com", "Work"), rule("example.*", "Personal")];
+        assert_eq!(
+            container_for_url("https://gist.github.com/x", &rules, &containers()),
+            Some("Work".to_string())
+        );
+        assert_eq!(
+            container_for_url("https://www.example.org/", &rules, &containers()),
+            Some("Personal".to_string())
+        );
+        assert_eq!(container_for_url("https://rust-lang.org/", &rules, &containers()), None);
+    }
+
+    #[test]
+    fn test_container_for_url_ignores_missing_containers() {
+        let rules = vec![rule("github.com", "Deleted"), rule("github.com", "Personal")];
+        assert_eq!(
+            container_for_url("https://github.com/", &rules, &containers()),
+            Some("Personal".to_string())
+        );
+    }
+
+    #[test]
+    fn test_containers_sharing_a_directory_are_dropped() {
+        let names = ["Work", "work", "Side Project", "Side-Project", "Personal"];
+        let mut containers: Vec<ContainerDefinition> = names
+            .into_iter()
+            .map(|name| ContainerDefinition {
+                name: name.to_string(),
+                color: "blue".to_string(),
+            })
+            .collect();
+        remove_colliding(&mut containers);
+        let kept: Vec<&str> = containers.iter().map(|container| container.name.as_str()).collect();
+        assert_eq!(kept, ["Work", "Side Project", "Personal"]);
+    }
+
+    #[test]
+    fn test_rule_host_and_directory_name() {
+        assert_eq!(rule_host("https://www.GitHub.com/login"), Some("github.com".to_string()));
+        assert_eq!(rule_host("about:blank"), None);
+        assert_eq!(directory_name("Side Project"), "side-project");
+        assert_eq!(directory_name("../Work"), "---work");
+    }
+}
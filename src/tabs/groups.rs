@@ -0,0 +1,303 @@
+use crate::session::TabState;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Colors a tab group can take, in the order new groups cycle through them.
+pub const GROUP_COLORS: &[&str] = &[
+    "blue", "red", "yellow", "green", "pink", "purple", "cyan", "orange", "grey",
+];
+
+/// A named, colored collection of tabs shown as a collapsible section in the tab bar.
+#[derive(Debug, Clone, Serialize)]
+pub struct TabGroup {
+    pub id: usize,
+    pub name: String,
+    pub color: String,
+    pub collapsed: bool,
+}
+
+/// Tracks the tab groups of a window and which tab belongs to which group.
+pub struct TabGroupManager {
+    groups: HashMap<usize, TabGroup>,
+    next_group_id: usize,
+    tab_to_group: HashMap<usize, usize>,
+}
+
+impl TabGroupManager {
+    pub fn new() -> Self {
+        Self {
+            groups: HashMap::new(),
+            next_group_id: 1,
+            tab_to_group: HashMap::new(),
+        }
+    }
+
+    /// Creates an empty group. A missing name or color is filled in from the group number.
+    pub fn create_group(&mut self, name: Option<&str>, color: Option<&str>) -> usize {
+        let group_id = self.next_group_id;
+        self.next_group_id += 1;
+
+        let name = name
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Group {}", group_id));
+        let color = color
+            .filter(|color| GROUP_COLORS.contains(color))
+            .unwrap_or(GROUP_COLORS[(group_id - 1) % GROUP_COLORS.len()])
+            .to_string();
+
+        self.groups.insert(
+            group_id,
+            TabGroup {
+                id: group_id,
+                name,
+                color,
+                collapsed: false,
+            },
+        );
+
+        group_id
+    }
+
+    /// Removes a group and returns the tabs that belonged to it.
+    pub fn remove_group(&mut self, group_id: usize) -> Vec<usize> {
+        self.groups.remove(&group_id);
+        let tab_ids: Vec<usize> = self
+            .tab_to_group
+            .iter()
+            .filter(|(_, &id)| id == group_id)
+            .map(|(&tab_id, _)| tab_id)
+            .collect();
+        for tab_id in &tab_ids {
+            self.tab_to_group.remove(tab_id);
+        }
+        tab_ids
+    }
+
+    /// Adds a tab to a group, taking it out of any group it was in.
+    pub fn add_tab(&mut self, tab_id: usize, group_id: usize) -> bool {
+        if !self.groups.contains_key(&group_id) {
+            return false;
+        }
+        self.remove_tab(tab_id);
+        self.tab_to_group.insert(tab_id, group_id);
+        true
+    }
+
+    /// Takes a tab out of its group, dropping the group once it is empty.
+    /// Returns the group the tab was in.
+    pub fn remove_tab(&mut self, tab_id: usize) -> Option<usize> {
+        let group_id = self.tab_to_group.remove(&tab_id)?;
+        if !self.tab_to_group.values().any(|&id| id == group_id) {
+            self.groups.remove(&group_id);
+        }
+        Some(group_id)
+    }
+
+    pub fn get_group(&self, group_id: usize) -> Option<&TabGroup> {
+        self.groups.get(&group_id)
+    }
+
+    pub fn get_group_id_for_tab(&self, tab_id: usize) -> Option<usize> {
+        self.tab_to_group.get(&tab_id).copied()
+    }
+
+    pub fn rename_group(&mut self, group_id: usize, name: &str) {
+        let name = name.trim();
+        if let Some(group) = self.groups.get_mut(&group_id) {
+            if !name.is_empty() {
+                group.name = name.to_string();
+            }
+        }
+    }
+
+    pub fn set_group_color(&mut self, group_id: usize, color: &str) {
+        if let Some(group) = self.groups.get_mut(&group_id) {
+            if GROUP_COLORS.contains(&color) {
+                group.color = color.to_string();
+            }
+        }
+    }
+
+    pub fn set_collapsed(&mut self, group_id: usize, collapsed: bool) {
+        if let Some(group) = self.groups.get_mut(&group_id) {
+            group.collapsed = collapsed;
+        }
+    }
+
+    /// Returns the tabs of a group in tab bar order.
+    pub fn tabs_in_group(&self, group_id: usize, tab_order: &[usize]) -> Vec<usize> {
+        tab_order
+            .iter()
+            .copied()
+            .filter(|tab_id| self.tab_to_group.get(tab_id) == Some(&group_id))
+            .collect()
+    }
+
+    /// Returns the groups in the order they first appear in the tab bar.
+    pub fn ordered_groups(&self, tab_order: &[usize]) -> Vec<&TabGroup> {
+        let mut ordered: Vec<&TabGroup> = Vec::new();
+        for tab_id in tab_order {
+            if let Some(group) = self.get_group_id_for_tab(*tab_id).and_then(|id| self.groups.get(&id)) {
+                if !ordered.iter().any(|existing| existing.id == group.id) {
+                    ordered.push(group);
+                }
+            }
+        }
+        ordered
+    }
+
+    /// Reorders tabs so that every group's tabs sit next to each other, at the position of the
+    /// group's first tab. Tabs keep their relative order otherwise.
+    pub fn group_tab_order(&self, tab_order: &[usize]) -> Vec<usize> {
+        let mut ordered = Vec::with_capacity(tab_order.len());
+        for &tab_id in tab_order {
+            if ordered.contains(&tab_id) {
+                continue;
+            }
+            match self.get_group_id_for_tab(tab_id) {
+                Some(group_id) => ordered.extend(self.tabs_in_group(group_id, tab_order)),
+                None => ordered.push(tab_id),
+            }
+        }
+        ordered
+    }
+
+    /// Returns the groups as JSON for the tab bar, each with its tab ids in order.
+    pub fn get_tab_groups_json(&self, tab_order: &[usize]) -> String {
+        let groups_data: Vec<serde_json::Value> = self
+            .ordered_groups(tab_order)
+            .into_iter()
+            .map(|group| {
+                serde_json::json!({
+                    "id": group.id,
+                    "name": group.name,
+                    "color": group.color,
+                    "collapsed": group.collapsed,
+                    "tabIds": self.tabs_in_group(group.id, tab_order),
+                })
+            })
+            .collect();
+
+        serde_json::to_string(&groups_data).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+/// A tab group that was saved and closed so it can be reopened later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedTabGroup {
+    pub id: u64,
+    pub name: String,
+    pub color: String,
+    pub tabs: Vec<TabState>,
+    pub saved_at: i64,
+}
+
+/// Tab groups put aside by the user, shared by all windows.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedTabGroups {
+    groups: Vec<SavedTabGroup>,
+    #[serde(default)]
+    next_id: u64,
+}
+
+thread_local! {
+    static SAVED_TAB_GROUPS: RefCell<SavedTabGroups> = RefCell::new(SavedTabGroups::load());
+}
+
+/// Runs `f` against the saved tab groups shared by all windows.
+pub fn with_saved_tab_groups<R>(f: impl FnOnce(&mut SavedTabGroups) -> R) -> R {
+    SAVED_TAB_GROUPS.with(|store| f(&mut store.borrow_mut()))
+}
+
+impl SavedTabGroups {
+    pub fn load() -> Self {
+        let path = Self::get_path();
+
+        if let Ok(contents) = fs::read_to_string(&path) {
+            if let Ok(store) = serde_yaml::from_str::<SavedTabGroups>(&contents) {
+                return store;
+            }
+        }
+
+        Self::default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::get_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let yaml = serde_yaml::to_string(self)?;
+        fs::write(path, yaml)?;
+        Ok(())
+    }
+
+    pub fn add(&mut self, name: String, color: String, tabs: Vec<TabState>) {
+        let saved_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        self.next_id += 1;
+        self.groups.insert(
+            0,
+            SavedTabGroup {
+                id: self.next_id,
+                name,
+                color,
+                tabs,
+                saved_at,
+            },
+        );
+
+        let _ = self.save();
+    }
+
+    /// Removes and returns the saved group with the given id.
+    pub fn take(&mut self, id: u64) -> Option<SavedTabGroup> {
+        let position = self.groups.iter().position(|group| group.id == id)?;
+        let group = self.groups.remove(position);
+        let _ = self.save();
+        Some(group)
+    }
+
+    pub fn get_all(&self) -> &[SavedTabGroup] {
+        &self.groups
+    }
+
+    fn get_path() -> PathBuf {
+        crate::paths::data_dir().join("saved_groups.yml")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_tab_order_keeps_groups_together() {
+        let mut groups = TabGroupManager::new();
+        let group_id = groups.create_group(Some("Work"), None);
+        groups.add_tab(2, group_id);
+        groups.add_tab(5, group_id);
+
+        assert_eq!(groups.group_tab_order(&[1, 2, 3, 4, 5]), vec![1, 2, 5, 3, 4]);
+    }
+
+    #[test]
+    fn test_empty_group_is_dropped() {
+        let mut groups = TabGroupManager::new();
+        let group_id = groups.create_group(None, None);
+        groups.add_tab(1, group_id);
+
+        assert_eq!(groups.get_group(group_id).map(|g| g.name.as_str()), Some("Group 1"));
+        assert_eq!(groups.remove_tab(1), Some(group_id));
+        assert!(groups.get_group(group_id).is_none());
+    }
+}
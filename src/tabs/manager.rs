@@ -1,33 +1,66 @@
-use super::split_view::SplitViewManager;
-use super::tab::Tab;
+use super::containers;
+use super::groups::{self, TabGroupManager};
+use super::order;
+use super::private_data::PrivateData;
+use super::recently_closed::{self, ClosedItem, ClosedSplitState, ClosedTab};
+use super::split_view::{
+    neighbor_pane, PaneDirection, SplitDivider, SplitLayout, SplitNode, SplitOrientation,
+    SplitViewManager,
+};
+use super::tab::{tabs_to_suspend, SuspendCheck, Tab};
 use crate::config::Config;
 use crate::debug_log;
 use crate::downloads::DownloadManager;
-use crate::history::History;
-use crate::privacy;
+use crate::history::{self, History};
+use crate::memory::MemorySnapshot;
+use crate::performance::{PerformanceMetrics, TabActivityTracker};
+use crate::privacy::block_log::{self, BlockEvent, PageBlocks};
+use crate::privacy::{self, shields};
+use crate::session::{
+    BrowserSession, SplitGroupState, TabGroupState, TabState as SessionTabState, WindowState,
+};
+use crate::single_instance::SingleInstance;
+use crate::window::WindowRequest;
 use crate::url_cleaner;
 use crate::vimium_hints;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tao::{
     dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
     window::Window,
 };
-use wry::{Rect, WebView, WebViewBuilder};
+use wry::{Rect, WebContext, WebView, WebViewBuilder};
+
+/// Last scroll offset reported by a tab, used to put discarded tabs back where they were.
+#[derive(Debug, Clone)]
+struct ScrollPosition {
+    url: String,
+    x: f64,
+    y: f64,
+}
+
+/// A tab taken out of one window, to be attached to another.
+///
+/// Webviews cannot move between windows because their handlers are bound to the window's tab
+/// bar, so the tab is rebuilt in its new window at the same URL and scroll offset.
+pub struct DetachedTab {
+    state: SessionTabState,
+    scroll: Option<ScrollPosition>,
+}
 
 struct ThreadSafeWebView(std::rc::Rc<WebView>);
 unsafe impl Send for ThreadSafeWebView {}
 unsafe impl Sync for ThreadSafeWebView {}
 
 
-/// Maximum number of tabs that can be open simultaneously.
-const MAX_TABS: usize = 20;
-
 /// Manages all browser tabs including creation, switching, navigation, and download handling.
 pub struct TabManager {
     tabs: HashMap<usize, Tab>,
+    /// Tab ids in the order they appear in the tab bar.
+    tab_order: Vec<usize>,
     active_tab_id: Option<usize>,
     next_tab_id: usize,
     download_manager: DownloadManager,
@@ -36,9 +69,38 @@ pub struct TabManager {
     download_overlay: Option<std::rc::Rc<WebView>>,
     config: std::rc::Rc<std::cell::RefCell<Config>>,
     split_view: SplitViewManager,
+    tab_groups: TabGroupManager,
     current_urls: Arc<Mutex<HashMap<usize, String>>>,
+    current_titles: Arc<Mutex<HashMap<usize, String>>>,
+    current_favicons: Arc<Mutex<HashMap<usize, String>>>,
+    /// Tabs currently playing audio, which are never suspended.
+    audio_tabs: Arc<Mutex<HashSet<usize>>>,
     active_tab_id_shared: Arc<Mutex<Option<usize>>>,
     history: std::rc::Rc<std::cell::RefCell<History>>,
+    /// Tabs navigating to an address the user typed, so their next history visit counts as typed.
+    typed_navigations: std::rc::Rc<std::cell::RefCell<HashSet<usize>>>,
+    /// What the privacy defenses stopped on each tab's current page, counted in the tab bar and
+    /// listed in the shield panel.
+    page_blocks: std::rc::Rc<std::cell::RefCell<HashMap<usize, PageBlocks>>>,
+    /// The window tab webviews are built in, used to load unloaded and suspended tabs on demand.
+    window: Option<std::rc::Rc<Window>>,
+    activity: TabActivityTracker,
+    /// When each loaded tab's webview was built, used to pair it with its web process.
+    webview_created: HashMap<usize, Instant>,
+    scroll_positions: Arc<Mutex<HashMap<usize, ScrollPosition>>>,
+    metrics: PerformanceMetrics,
+    /// Overlays on the dividers of the visible split group, in the order of `active_dividers`.
+    split_dividers: Vec<std::rc::Rc<WebView>>,
+    active_dividers: Vec<SplitDivider>,
+    /// Divider being dragged, whose overlay is stretched over its whole split meanwhile.
+    dragging_divider: Option<usize>,
+    /// When the divider overlays were built. Panes built later would be stacked above them.
+    dividers_built_at: Option<Instant>,
+    /// Web context of a named profile, shared with the profile's other regular windows.
+    profile_context: Option<std::rc::Rc<std::cell::RefCell<WebContext>>>,
+    /// Web data of a private window. Declared last so the tabs are dropped before its data
+    /// directory is deleted.
+    private_data: Option<std::cell::RefCell<PrivateData>>,
 }
 
 /// Returns the path to the user's Downloads directory.
@@ -64,11 +126,31 @@ fn add_extension_if_needed(path: &PathBuf) -> PathBuf {
     path.clone()
 }
 
+/// Frecency given to open tabs missing from history, that of a single recent visit, so they
+/// still rank among URL bar suggestions.
+const OPEN_TAB_MIN_FRECENCY: f64 = 100.0;
+
+/// Frecency given to bookmarks that were never visited, so imported bookmarks still show up
+/// among URL bar suggestions.
+const BOOKMARK_MIN_FRECENCY: f64 = 100.0;
+
+/// Number of visits the history page loads at a time.
+const HISTORY_PAGE_SIZE: usize = 300;
+
+/// Returns whether a tab URL is worth persisting in the session.
+/// Internal pages rendered from HTML report `about:blank` and cannot be reloaded by URL, and the
+/// recovery page is gone once the session it offered was restored or dismissed.
+fn is_restorable_url(url: &str) -> bool {
+    !url.is_empty() && !url.starts_with("about:") && url != "calm://recovery"
+}
+
 impl TabManager {
     /// Creates a new TabManager instance with the specified tab sidebar width and configuration.
     pub fn new(tab_sidebar_width: u32, config: std::rc::Rc<std::cell::RefCell<Config>>) -> Self {
+        let suspension_timeout_minutes = config.borrow().performance.suspension_timeout_minutes;
         Self {
             tabs: HashMap::new(),
+            tab_order: Vec::new(),
             active_tab_id: None,
             next_tab_id: 1,
             download_manager: DownloadManager::new(),
@@ -77,12 +159,50 @@ impl TabManager {
             download_overlay: None,
             config,
             split_view: SplitViewManager::new(),
+            tab_groups: TabGroupManager::new(),
             current_urls: Arc::new(Mutex::new(HashMap::new())),
+            current_titles: Arc::new(Mutex::new(HashMap::new())),
+            current_favicons: Arc::new(Mutex::new(HashMap::new())),
+            audio_tabs: Arc::new(Mutex::new(HashSet::new())),
             active_tab_id_shared: Arc::new(Mutex::new(None)),
             history: std::rc::Rc::new(std::cell::RefCell::new(History::load())),
+            typed_navigations: std::rc::Rc::new(std::cell::RefCell::new(HashSet::new())),
+            page_blocks: std::rc::Rc::new(std::cell::RefCell::new(HashMap::new())),
+            window: None,
+            activity: TabActivityTracker::new(suspension_timeout_minutes),
+            webview_created: HashMap::new(),
+            scroll_positions: Arc::new(Mutex::new(HashMap::new())),
+            metrics: PerformanceMetrics::new(),
+            split_dividers: Vec::new(),
+            active_dividers: Vec::new(),
+            dragging_divider: None,
+            dividers_built_at: None,
+            profile_context: super::profile_data::profile_web_context(),
+            private_data: None,
+        }
+    }
+
+    /// Creates a TabManager for a private window. Its tabs get their own ephemeral web data, and
+    /// nothing they do is written to history, the recently closed stack or download history.
+    pub fn new_private(tab_sidebar_width: u32, config: std::rc::Rc<std::cell::RefCell<Config>>) -> Self {
+        Self {
+            download_manager: DownloadManager::new_private(),
+            profile_context: None,
+            private_data: Some(std::cell::RefCell::new(PrivateData::new())),
+            ..Self::new(tab_sidebar_width, config)
         }
     }
 
+    /// Returns whether this manager belongs to a private window.
+    pub fn is_private(&self) -> bool {
+        self.private_data.is_some()
+    }
+
+    /// Sets the window that tab webviews are built in.
+    pub fn set_window(&mut self, window: std::rc::Rc<Window>) {
+        self.window = Some(window);
+    }
+
     /// Sets the reference to the tab bar webview for IPC communication.
     pub fn set_tab_bar_webview(&mut self, webview: std::rc::Rc<WebView>) {
         self.tab_bar_webview = Some(webview);
@@ -97,10 +217,22 @@ impl TabManager {
         std::rc::Rc::clone(&self.history)
     }
 
-    /// Creates a new tab with the specified URL.
+    /// Creates a new tab with the specified URL, in the container a host rule assigns to it.
     /// Returns the tab ID on success.
     pub fn create_tab(&mut self, window: &Window, url: &str) -> Result<usize, wry::Error> {
-        self.create_tab_internal(window, url, None)
+        let container = self.rule_container(url);
+        self.create_tab_in_container(window, url, container)
+    }
+
+    /// Creates a new tab with the specified URL in `container`, or outside any container.
+    /// Returns the tab ID on success.
+    pub fn create_tab_in_container(
+        &mut self,
+        window: &Window,
+        url: &str,
+        container: Option<String>,
+    ) -> Result<usize, wry::Error> {
+        self.create_tab_internal(window, url, None, container)
     }
 
     /// Creates a new tab with custom HTML content.
@@ -110,7 +242,7 @@ impl TabManager {
         window: &Window,
         html: &str,
     ) -> Result<usize, wry::Error> {
-        self.create_tab_internal(window, "about:blank", Some(html))
+        self.create_tab_internal(window, "about:blank", Some(html), None)
     }
 
     /// Internal method to create a tab with URL or HTML content.
@@ -119,6 +251,7 @@ impl TabManager {
         window: &Window,
         url: &str,
         html: Option<&str>,
+        container: Option<String>,
     ) -> Result<usize, wry::Error> {
         debug_log!(
             "create_tab_internal called - url: {}, has_html: {}",
@@ -126,15 +259,289 @@ impl TabManager {
             html.is_some()
         );
 
-        if self.tabs.len() >= MAX_TABS {
-            debug_log!("Max tabs reached ({}), not creating new tab", MAX_TABS);
-            return Ok(self.active_tab_id.unwrap_or(1));
+        let tab_id = self.next_tab_id;
+        self.next_tab_id += 1;
+        debug_log!("Creating new tab with id: {}", tab_id);
+
+        let cleaned_url = self.clean_tab_url(url);
+        let container = container.filter(|_| !self.is_private());
+        let webview = self.build_tab_webview(window, tab_id, &cleaned_url, html, None, container.as_deref())?;
+
+        let mut tab = Tab::new(tab_id, cleaned_url.clone(), webview);
+        tab.mark_accessed();
+        tab.container = container;
+        self.tabs.insert(tab_id, tab);
+        self.show_tab_container(tab_id);
+        self.tab_order.push(tab_id);
+        self.activity.mark_active(tab_id);
+        self.webview_created.insert(tab_id, Instant::now());
+
+        if let Ok(mut urls) = self.current_urls.lock() {
+            urls.insert(tab_id, cleaned_url);
         }
 
+        debug_log!("Tab {} created and inserted into tabs map", tab_id);
+
+        Ok(tab_id)
+    }
+
+    /// Creates a tab without a webview, in the container a host rule assigns to it. It is built
+    /// the first time the tab is shown. Returns the tab ID.
+    pub fn create_lazy_tab(&mut self, url: &str) -> usize {
+        let container = self.rule_container(url);
+        self.create_lazy_tab_in_container(url, container)
+    }
+
+    /// Creates a tab without a webview in `container`, or outside any container.
+    /// Returns the tab ID.
+    pub fn create_lazy_tab_in_container(&mut self, url: &str, container: Option<String>) -> usize {
         let tab_id = self.next_tab_id;
         self.next_tab_id += 1;
-        debug_log!("Creating new tab with id: {}", tab_id);
+        debug_log!("Creating unloaded tab {} for {}", tab_id, url);
+
+        let cleaned_url = self.clean_tab_url(url);
+        let mut tab = Tab::new_unloaded(tab_id, cleaned_url.clone());
+        tab.container = container.filter(|_| !self.is_private());
+        self.tabs.insert(tab_id, tab);
+        self.tab_order.push(tab_id);
+        self.show_tab_container(tab_id);
+
+        if let Ok(mut urls) = self.current_urls.lock() {
+            urls.insert(tab_id, cleaned_url);
+        }
+
+        tab_id
+    }
+
+    /// Returns the container a host rule assigns to `url`. Private windows have no containers.
+    fn rule_container(&self, url: &str) -> Option<String> {
+        if self.is_private() {
+            return None;
+        }
+        let config = self.config.borrow();
+        containers::container_for_url(url, &config.container_rules, &config.containers)
+    }
+
+    /// Shows a tab's container, if any, as a colored stripe in the tab bar.
+    fn show_tab_container(&self, tab_id: usize) {
+        let Some(ref webview) = self.tab_bar_webview else {
+            return;
+        };
+        let container = self.tabs.get(&tab_id).and_then(|tab| tab.container.as_deref()).map(|name| {
+            let config = self.config.borrow();
+            let color = containers::find_container(name, &config.containers)
+                .map_or("grey", |container| container.color.as_str())
+                .to_string();
+            serde_json::json!({ "name": name, "color": color })
+        });
+        let script = format!(
+            "if (window.setTabContainer) {{ window.setTabContainer({}, {}); }}",
+            tab_id,
+            container.unwrap_or(serde_json::Value::Null)
+        );
+        let _ = webview.evaluate_script(&script);
+    }
+
+    /// Moves a tab into `container`, or out of any container when `None`, and loads `url` (its
+    /// current page by default) with the container's cookies and site data. The tab keeps its ID
+    /// and position, but its webview is rebuilt since a web context is fixed when it is created.
+    /// Returns whether the tab was moved.
+    pub fn set_tab_container(&mut self, tab_id: usize, container: Option<String>, url: Option<&str>) -> bool {
+        if self.is_private() {
+            return false;
+        }
+        if let Some(ref name) = container {
+            if containers::find_container(name, &self.config.borrow().containers).is_none() {
+                return false;
+            }
+        }
+        let Some(url) = url.map(str::to_string).or_else(|| self.current_tab_url(tab_id)) else {
+            return false;
+        };
+        if !is_restorable_url(&url) {
+            return false;
+        }
+        let Some(tab) = self.tabs.get_mut(&tab_id) else {
+            return false;
+        };
+
+        debug_log!("Reopening tab {} in container {:?}", tab_id, container);
+        tab.container = container;
+        self.show_tab_container(tab_id);
+        self.rebuild_tab(tab_id, url);
+        true
+    }
+
+    /// Replaces the webview of a tab with a new one loading `url`, for when something fixed at
+    /// webview creation, such as its web context or injected scripts, changed. Visible tabs are
+    /// loaded right away and others once they are shown.
+    fn rebuild_tab(&mut self, tab_id: usize, url: String) {
+        let Some(tab) = self.tabs.get_mut(&tab_id) else {
+            return;
+        };
+        tab.hide();
+        tab.suspend();
+        tab.set_url(url.clone());
+        self.activity.remove_tab(tab_id);
+        self.webview_created.remove(&tab_id);
+        self.metrics.remove_tab_memory(tab_id);
+        self.page_blocks.borrow_mut().remove(&tab_id);
+        if let Ok(mut positions) = self.scroll_positions.lock() {
+            positions.remove(&tab_id);
+        }
+        if let Ok(mut urls) = self.current_urls.lock() {
+            urls.insert(tab_id, url.clone());
+        }
+
+        let visible = self.active_tab_id == Some(tab_id)
+            || self
+                .active_tab_id
+                .is_some_and(|active_id| self.split_panes(active_id).contains(&tab_id));
+        if visible {
+            self.ensure_tab_loaded(tab_id);
+            if let Some(tab) = self.tabs.get(&tab_id) {
+                tab.show();
+            }
+            let in_split = self
+                .active_tab_id
+                .is_some_and(|active_id| self.split_view.is_tab_in_group(active_id));
+            if let Some(window) = self.window.clone() {
+                if in_split {
+                    self.update_split_view_layout(&window, None);
+                } else {
+                    self.resize_all_tabs(&window);
+                }
+            }
+        } else if let Some(ref webview) = self.tab_bar_webview {
+            let script = format!(
+                "if (window.setTabSuspended) {{ window.setTabSuspended({0}, true); }} if (window.updateTabBlockedCount) {{ window.updateTabBlockedCount({0}, 0); }}",
+                tab_id
+            );
+            let _ = webview.evaluate_script(&script);
+        }
+
+        if self.active_tab_id == Some(tab_id) {
+            if let Some(ref webview) = self.tab_bar_webview {
+                let escaped_url = serde_json::to_string(&url).unwrap_or_else(|_| "\"\"".to_string());
+                let _ = webview.evaluate_script(&format!("window.updateUrlBar({});", escaped_url));
+            }
+        }
+    }
 
+    /// Adds a rule that always opens the site of a container tab in its container, replacing any
+    /// rule for the same host. Returns the host the rule was added for.
+    pub fn add_container_rule_for_tab(&mut self, tab_id: usize) -> Option<String> {
+        let container = self.tabs.get(&tab_id)?.container.clone()?;
+        let host = containers::rule_host(&self.current_tab_url(tab_id)?)?;
+
+        let mut config = self.config.borrow_mut();
+        config.container_rules.retain(|rule| rule.host != host);
+        config.container_rules.push(crate::config::ContainerRule {
+            host: host.clone(),
+            container,
+        });
+        if let Err(e) = config.save() {
+            debug_log!("Failed to save container rule: {}", e);
+        }
+        Some(host)
+    }
+
+    /// Returns what the shield panel shows for a tab: its site, the defenses in effect there and
+    /// the requests they stopped on the current page. Pages that are not on the web have none.
+    fn tab_shields(&self, tab_id: usize) -> Option<serde_json::Value> {
+        let site = shields::site_of(&self.current_tab_url(tab_id)?)?;
+        let blocked = self
+            .page_blocks
+            .borrow()
+            .get(&tab_id)
+            .map(|page| page.blocked.clone())
+            .unwrap_or_default();
+        Some(serde_json::json!({
+            "tabId": tab_id,
+            "site": site,
+            "editable": !self.is_private(),
+            "defenses": shields::site_defenses(&self.config.borrow().privacy, &site),
+            "blocked": blocked,
+        }))
+    }
+
+    /// Opens the shield panel for the active tab in the tab bar, or refreshes it.
+    pub fn show_shields(&self) {
+        let Some(ref webview) = self.tab_bar_webview else {
+            return;
+        };
+        let shields = self
+            .active_tab_id
+            .and_then(|tab_id| self.tab_shields(tab_id))
+            .unwrap_or(serde_json::Value::Null);
+        let script = format!("if (window.showShields) {{ window.showShields({}); }}", shields);
+        let _ = webview.evaluate_script(&script);
+    }
+
+    /// Turns the defense `name` on or off for the site of a tab, or drops all of the site's
+    /// overrides when `name` is `None`. Private windows leave the settings alone, since they
+    /// would record the site. Returns whether the settings changed.
+    pub fn set_site_shield(&mut self, tab_id: usize, name: Option<&str>, enabled: bool) -> bool {
+        if self.is_private() {
+            return false;
+        }
+        let Some(site) = self.current_tab_url(tab_id).and_then(|url| shields::site_of(&url)) else {
+            return false;
+        };
+        {
+            let mut config = self.config.borrow_mut();
+            let changed = match name {
+                Some(name) => shields::set_site_defense(&mut config.privacy, &site, name, enabled),
+                None => config.privacy.site_overrides.remove(&site).is_some(),
+            };
+            if !changed {
+                return false;
+            }
+            if let Err(e) = config.save() {
+                debug_log!("Failed to save site shields: {}", e);
+            }
+        }
+
+        // Scripts are injected when a webview is created, so the site's open tabs are reloaded
+        // with the new settings.
+        let tab_ids: Vec<usize> = self
+            .tabs
+            .iter()
+            .filter(|(id, tab)| {
+                (**id == tab_id || tab.is_loaded())
+                    && self
+                        .current_tab_url(**id)
+                        .and_then(|url| shields::site_of(&url))
+                        .is_some_and(|host| shields::covers(&site, &host))
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for id in tab_ids {
+            if let Some(url) = self.current_tab_url(id) {
+                self.rebuild_tab(id, url);
+            }
+        }
+        self.show_shields();
+        true
+    }
+
+    /// Applies the Invidious redirect and tracking parameter cleanup to a tab URL.
+    fn clean_tab_url(&self, url: &str) -> String {
+        let redirected_url = url_cleaner::redirect_youtube_to_invidious(url, &self.config.borrow());
+        url_cleaner::clean_url(&redirected_url).unwrap_or_else(|_| redirected_url.to_string())
+    }
+
+    /// Builds the webview backing a tab along with its IPC, download and page load handlers.
+    /// `restore_scroll` scrolls the page back to a previously reported offset once it has loaded.
+    fn build_tab_webview(
+        &self,
+        window: &Window,
+        tab_id: usize,
+        url: &str,
+        html: Option<&str>,
+        restore_scroll: Option<ScrollPosition>,
+        container: Option<&str>,
+    ) -> Result<std::rc::Rc<WebView>, wry::Error> {
         let window_size = window.inner_size();
         let content_width = window_size.width.saturating_sub(self.tab_sidebar_width);
 
@@ -143,10 +550,6 @@ impl TabManager {
             size: LogicalSize::new(content_width, window_size.height).into(),
         };
 
-        let redirected_url = url_cleaner::redirect_youtube_to_invidious(url, &self.config.borrow());
-        let cleaned_url =
-            url_cleaner::clean_url(&redirected_url).unwrap_or_else(|_| redirected_url.to_string());
-
         let download_id_counter = self.download_manager.get_download_id_counter();
         let download_history = self.download_manager.get_history();
         let download_history_started = Arc::clone(&download_history);
@@ -163,23 +566,51 @@ impl TabManager {
         let download_overlay_progress = self.download_overlay.clone();
         let tab_bar_for_ipc = self.tab_bar_webview.clone();
         let tab_bar_for_page_load = self.tab_bar_webview.clone();
+        let default_url_for_protocol = crate::convert_file_url(&self.config.borrow().default_url);
         let current_urls_for_ipc = Arc::clone(&self.current_urls);
+        let current_titles_for_ipc = Arc::clone(&self.current_titles);
+        let current_favicons_for_ipc = Arc::clone(&self.current_favicons);
+        let audio_tabs_for_ipc = Arc::clone(&self.audio_tabs);
+        let scroll_positions_for_ipc = Arc::clone(&self.scroll_positions);
         let tab_id_for_ipc = tab_id;
         let tab_id_for_page_load = tab_id;
         let active_tab_id_for_ipc = Arc::clone(&self.active_tab_id_shared);
         let config_for_ipc = std::rc::Rc::clone(&self.config);
         let history_for_ipc = std::rc::Rc::clone(&self.history);
-
-        // Proxy to allow accessing the webview from within its own IPC handler
-        let webview_proxy_slot = std::rc::Rc::new(std::cell::RefCell::new(None::<std::rc::Rc<WebView>>));
+        let typed_navigations_for_ipc = std::rc::Rc::clone(&self.typed_navigations);
+        let page_blocks_for_ipc = std::rc::Rc::clone(&self.page_blocks);
+        let page_blocks_for_page_load = std::rc::Rc::clone(&self.page_blocks);
+        let private_for_ipc = self.is_private();
+
+        // Proxy to allow accessing the webview from within its own IPC handler. It is weak so
+        // the handler does not keep its own webview alive once the tab drops it.
+        let webview_proxy_slot = std::rc::Rc::new(std::cell::RefCell::new(None::<std::rc::Weak<WebView>>));
         let webview_proxy_for_ipc = webview_proxy_slot.clone();
 
-        let mut builder = WebViewBuilder::new();
+        // Private tabs share the window's web context and also run in incognito mode, which keeps
+        // WKWebView on macOS, where data directories are not supported, off the shared data store.
+        // Container tabs use their container's context, and other tabs of a named profile keep
+        // their data in the profile's directory.
+        let mut private_data = self.private_data.as_ref().map(|data| data.borrow_mut());
+        let container_context = container
+            .filter(|_| private_data.is_none())
+            .map(containers::container_web_context);
+        let mut container_context = container_context.as_ref().map(|context| context.borrow_mut());
+        let mut profile_context = self.profile_context.as_ref().map(|context| context.borrow_mut());
+        let mut builder = match (
+            private_data.as_deref_mut(),
+            container_context.as_deref_mut(),
+            profile_context.as_deref_mut(),
+        ) {
+            (Some(data), _, _) => WebViewBuilder::with_web_context(data.web_context()).with_incognito(true),
+            (None, Some(context), _) | (None, None, Some(context)) => WebViewBuilder::with_web_context(context),
+            (None, None, None) => WebViewBuilder::new(),
+        };
 
         builder = if let Some(html_content) = html {
             builder.with_html(html_content)
         } else {
-            builder.with_url(&cleaned_url)
+            builder.with_url(url)
         };
 
         let user_agent = self.config.borrow().user_agent.clone();
@@ -191,10 +622,16 @@ impl TabManager {
             .with_devtools(true)
             .with_clipboard(true)
             .with_on_page_load_handler(move |event, _url| {
+                if matches!(event, wry::PageLoadEvent::Started) {
+                    page_blocks_for_page_load.borrow_mut().remove(&tab_id_for_page_load);
+                }
                 if let Some(ref webview) = tab_bar_for_page_load {
                     let script = match event {
                         wry::PageLoadEvent::Started => {
-                            format!("if (window.updateTabLoadingState) {{ window.updateTabLoadingState({}, true); }}", tab_id_for_page_load)
+                            format!(
+                                "if (window.updateTabLoadingState) {{ window.updateTabLoadingState({0}, true); }} if (window.updateTabBlockedCount) {{ window.updateTabBlockedCount({0}, 0); }}",
+                                tab_id_for_page_load
+                            )
                         }
                         wry::PageLoadEvent::Finished => {
                             format!("if (window.updateTabLoadingState) {{ window.updateTabLoadingState({}, false); }}", tab_id_for_page_load)
@@ -240,6 +677,38 @@ impl TabManager {
                     }
                 }
             })
+            .with_asynchronous_custom_protocol("calm".into(), move |_webview_id, request, responder| {
+                let is_snapshot = request.uri().host() == Some("reading");
+                let page = match request.uri().host() {
+                    Some("history") => Some(crate::ui::get_history_page_html()),
+                    Some("privacy-log") => Some(crate::ui::get_privacy_log_page_html()),
+                    Some("settings") => Some(crate::ui::get_settings_html()),
+                    Some("recovery") => crate::window::recovery_session()
+                        .map(|session| crate::ui::get_session_recovery_html(&session, &default_url_for_protocol)),
+                    Some("profiles") => Some(crate::ui::get_profiles_page_html(
+                        &crate::paths::list_profiles(),
+                        crate::paths::profile(),
+                    )),
+                    Some("reading") => crate::reading_list::read_snapshot(request.uri().path()),
+                    _ => None,
+                };
+                match page {
+                    Some(html) => {
+                        let mut response = wry::http::Response::builder().header("Content-Type", "text/html");
+                        if is_snapshot {
+                            // Snapshots are sanitized when saved; the policy keeps any script that slipped through from running.
+                            response = response.header("Content-Security-Policy", crate::reading_list::SNAPSHOT_CSP);
+                        }
+                        responder.respond(response.body(html.into_bytes()).unwrap());
+                    }
+                    None => {
+                        responder.respond(wry::http::Response::builder()
+                            .status(404)
+                            .body(Vec::<u8>::new())
+                            .unwrap());
+                    }
+                }
+            })
             .with_initialization_script(&{
                 debug_log!("Building initialization script for tab {}", tab_id);
 
@@ -532,7 +1001,75 @@ console.log('[INIT] Console override installed');
                 );
                 drop(cfg);
 
-                let combined_script = format!("{}\n{}\n{}\n{}\n{}", console_override, link_interception, safe_privacy_script, vimium_script, settings_init_script);
+                let restore_scroll_call = match restore_scroll {
+                    Some(ref position) => format!(
+                        "restoreScroll({}, {}, {});",
+                        serde_json::to_string(&position.url).unwrap_or_else(|_| "\"\"".to_string()),
+                        position.x,
+                        position.y
+                    ),
+                    None => String::new(),
+                };
+                let scroll_script = format!(r#"
+(function() {{
+    let scrollTimeout = null;
+
+    window.addEventListener('scroll', () => {{
+        clearTimeout(scrollTimeout);
+        scrollTimeout = setTimeout(() => {{
+            window.ipc.postMessage(JSON.stringify({{
+                action: 'update_scroll_position',
+                url: window.location.href,
+                x: window.scrollX,
+                y: window.scrollY
+            }}));
+        }}, 300);
+    }}, {{ passive: true }});
+
+    function restoreScroll(url, x, y) {{
+        if (window.top !== window || window.location.href !== url) return;
+        try {{
+            if (sessionStorage.getItem('__calmScrollRestored')) return;
+            sessionStorage.setItem('__calmScrollRestored', '1');
+        }} catch (e) {{}}
+        window.addEventListener('load', () => window.scrollTo(x, y));
+    }}
+
+    {}
+}})();
+                "#, restore_scroll_call);
+
+                // Ctrl+W followed by h/j/k/l moves focus between split panes, like vim windows.
+                // It listens on the window so it runs before the keyboard passthrough swallows Ctrl+W.
+                let split_pane_script = r#"
+(function() {
+    const directions = { h: 'left', j: 'down', k: 'up', l: 'right',
+        ArrowLeft: 'left', ArrowDown: 'down', ArrowUp: 'up', ArrowRight: 'right' };
+    let prefixUntil = 0;
+
+    window.addEventListener('keydown', (e) => {
+        if (!window.calmInSplitView) return;
+
+        if (e.ctrlKey && !e.metaKey && !e.altKey && e.key.toLowerCase() === 'w') {
+            prefixUntil = Date.now() + 1000;
+        } else if (prefixUntil > Date.now() && directions[e.key]) {
+            prefixUntil = 0;
+            window.ipc.postMessage(JSON.stringify({
+                action: 'focus_split_pane',
+                direction: directions[e.key]
+            }));
+        } else {
+            if (!['Control', 'Shift'].includes(e.key)) prefixUntil = 0;
+            return;
+        }
+
+        e.preventDefault();
+        e.stopImmediatePropagation();
+    }, true);
+})();
+                "#;
+
+                let combined_script = format!("{}\n{}\n{}\n{}\n{}\n{}\n{}", console_override, scroll_script, split_pane_script, link_interception, safe_privacy_script, vimium_script, settings_init_script);
                 debug_log!("Initialization script size: {} bytes (console: ~600, privacy: ~{}, vimium: {})",
                     combined_script.len(),
                     safe_privacy_script.len(),
@@ -647,15 +1184,44 @@ console.log('[INIT] Console override installed');
             })
             .with_ipc_handler(move |request| {
                 let body = request.body();
+                // Only the history page itself may read or edit history through IPC.
+                let from_history_page = request.uri().scheme_str() == Some("calm")
+                    && request.uri().host() == Some("history");
+                // Likewise, only the profile picker may create and open profiles.
+                let from_profiles_page = request.uri().scheme_str() == Some("calm")
+                    && request.uri().host() == Some("profiles");
+                // And only the privacy log page may read, clear and export the block log.
+                let from_privacy_log_page = request.uri().scheme_str() == Some("calm")
+                    && request.uri().host() == Some("privacy-log");
+                // Only the settings page may read and write files or purge history. The URL a tab
+                // reports is not checked, since any page can report any URL through `update_url`.
+                let from_settings_page = request.uri().scheme_str() == Some("calm")
+                    && request.uri().host() == Some("settings");
+                // Only the recovery page may restore or dismiss the previous session.
+                let from_recovery_page = request.uri().scheme_str() == Some("calm")
+                    && request.uri().host() == Some("recovery");
                 if let Ok(data) = serde_json::from_str::<serde_json::Value>(body) {
                     match data["action"].as_str() {
                         Some("open_url_new_tab") => {
                             if let Some(url) = data["url"].as_str() {
                                 if let Some(ref webview) = tab_bar_for_ipc {
                                     let escaped_url = serde_json::to_string(url).unwrap_or_else(|_| "\"\"".to_string());
+                                    let background = data["background"].as_bool().unwrap_or(false);
+                                    let script = format!(
+                                        "window.ipcMessageToWindow = {{ action: 'open_url_new_tab', url: {}, background: {} }};",
+                                        escaped_url, background
+                                    );
+                                    let _ = webview.evaluate_script(&script);
+                                }
+                            }
+                        }
+                        Some("focus_split_pane") => {
+                            if let Some(direction) = data["direction"].as_str() {
+                                if let Some(ref webview) = tab_bar_for_ipc {
                                     let script = format!(
-                                        "window.ipcMessageToWindow = {{ action: 'open_url_new_tab', url: {} }};",
-                                        escaped_url
+                                        "window.ipcMessageToWindow = {{ action: 'focus_split_pane', tabId: {}, direction: {} }};",
+                                        tab_id_for_ipc,
+                                        serde_json::to_string(direction).unwrap_or_else(|_| "\"\"".to_string())
                                     );
                                     let _ = webview.evaluate_script(&script);
                                 }
@@ -676,6 +1242,9 @@ console.log('[INIT] Console override installed');
                         }
                         Some("update_title") => {
                             if let Some(title) = data["title"].as_str() {
+                                if let Ok(mut titles) = current_titles_for_ipc.lock() {
+                                    titles.insert(tab_id_for_ipc, title.to_string());
+                                }
                                 if let Some(ref webview) = tab_bar_for_ipc {
                                     let script = format!(
                                         "window.updateTabTitle({}, {});",
@@ -688,6 +1257,9 @@ console.log('[INIT] Console override installed');
                         }
                         Some("update_favicon") => {
                             if let Some(favicon) = data["favicon"].as_str() {
+                                if let Ok(mut favicons) = current_favicons_for_ipc.lock() {
+                                    favicons.insert(tab_id_for_ipc, favicon.to_string());
+                                }
                                 if let Some(ref webview) = tab_bar_for_ipc {
                                     let script = format!(
                                         "window.updateTabFavicon({}, {});",
@@ -721,7 +1293,16 @@ console.log('[INIT] Console override installed');
                         }
                         Some("add_to_history") => {
                             if let (Some(url), Some(title)) = (data["url"].as_str(), data["title"].as_str()) {
-                                history_for_ipc.borrow_mut().add_entry(url.to_string(), title.to_string());
+                                let typed = typed_navigations_for_ipc.borrow_mut().remove(&tab_id_for_ipc);
+                                // Internal pages such as calm://history and private windows are not recorded.
+                                let recorded_url = if private_for_ipc || url.starts_with("calm://") {
+                                    None
+                                } else {
+                                    history::recorded_url(url, &config_for_ipc.borrow().history)
+                                };
+                                if let Some(url) = recorded_url {
+                                    history_for_ipc.borrow_mut().add_entry(url, title.to_string(), typed);
+                                }
                             }
                         }
                         Some("update_navigation_state") => {
@@ -748,6 +1329,13 @@ console.log('[INIT] Console override installed');
                         }
                         Some("audio_state_changed") => {
                             if let Some(is_playing) = data["isPlaying"].as_bool() {
+                                if let Ok(mut audio_tabs) = audio_tabs_for_ipc.lock() {
+                                    if is_playing {
+                                        audio_tabs.insert(tab_id_for_ipc);
+                                    } else {
+                                        audio_tabs.remove(&tab_id_for_ipc);
+                                    }
+                                }
                                 if let Some(ref webview) = tab_bar_for_ipc {
                                     let script = format!(
                                         "if (window.updateTabAudioState) {{ window.updateTabAudioState({}, {}); }}",
@@ -758,11 +1346,85 @@ console.log('[INIT] Console override installed');
                                 }
                             }
                         }
+                        Some("update_scroll_position") => {
+                            if let (Some(url), Some(x), Some(y)) = (data["url"].as_str(), data["x"].as_f64(), data["y"].as_f64()) {
+                                if let Ok(mut positions) = scroll_positions_for_ipc.lock() {
+                                    positions.insert(tab_id_for_ipc, ScrollPosition { url: url.to_string(), x, y });
+                                }
+                            }
+                        }
                         Some("inspect_element") => {
-                            if let Some(ref webview) = *webview_proxy_for_ipc.borrow() {
+                            let webview = webview_proxy_for_ipc.borrow().as_ref().and_then(|webview| webview.upgrade());
+                            if let Some(webview) = webview {
                                 webview.open_devtools();
                             }
                         }
+                        Some("cosmetic_filters") => {
+                            let engine = crate::privacy::filters::engine(&config_for_ipc.borrow().privacy);
+                            let webview = webview_proxy_for_ipc.borrow().as_ref().and_then(|webview| webview.upgrade());
+                            if let (Some(engine), Some(webview)) = (engine, webview) {
+                                let filters = engine.page_filters(&request.uri().to_string());
+                                let script = format!(
+                                    "if (window.__calmApplyCosmeticFilters) {{ window.__calmApplyCosmeticFilters({}); }}",
+                                    serde_json::to_string(&filters).unwrap_or_else(|_| "{}".to_string())
+                                );
+                                let _ = webview.evaluate_script(&script);
+                            }
+                        }
+                        Some("blocked") => {
+                            let Some(items) = data["items"].as_array() else {
+                                return;
+                            };
+                            let page_url = request.uri().to_string();
+                            let privacy_settings = config_for_ipc.borrow().privacy.clone();
+                            let engine = crate::privacy::filters::engine(&privacy_settings);
+                            let mut page_blocks = page_blocks_for_ipc.borrow_mut();
+                            let page = page_blocks.entry(tab_id_for_ipc).or_default();
+                            for item in items {
+                                let (Some(url), Some(defense)) = (item["url"].as_str(), item["defense"].as_str()) else {
+                                    continue;
+                                };
+                                let resource_type = item["resourceType"].as_str().unwrap_or("other");
+                                let mut rule = item["rule"].as_str().map(str::to_string);
+                                let mut list = None;
+                                // Loads that failed in the page only count when a filter list rule
+                                // blocks them, which is then the content blocker's doing.
+                                if item["check"].as_bool() == Some(true) {
+                                    let matched = engine.as_ref().and_then(|engine| {
+                                        let resource_type = serde_json::from_value(serde_json::json!(resource_type)).ok()?;
+                                        let request = crate::privacy::filters::Request::new(url, &page_url, resource_type)?;
+                                        engine.check(&request).map(|found| (found.filter.text.clone(), found.list.to_string()))
+                                    });
+                                    let Some((matched_rule, matched_list)) = matched else {
+                                        continue;
+                                    };
+                                    rule = Some(matched_rule);
+                                    list = Some(crate::privacy::filters::subscriptions::source_name(&privacy_settings, &matched_list));
+                                }
+                                let event = BlockEvent {
+                                    url: url.to_string(),
+                                    resource_type: resource_type.to_string(),
+                                    defense: defense.to_string(),
+                                    rule,
+                                    list,
+                                    page_url: page_url.clone(),
+                                    tab_id: tab_id_for_ipc,
+                                    timestamp: item["timestamp"].as_i64().unwrap_or_else(block_log::now_millis),
+                                };
+                                // Private windows keep their events to the tab, out of the shared log.
+                                if !private_for_ipc {
+                                    block_log::record(event.clone());
+                                }
+                                page.record(event);
+                            }
+                            if let Some(ref webview) = tab_bar_for_ipc {
+                                let script = format!(
+                                    "if (window.updateTabBlockedCount) {{ window.updateTabBlockedCount({}, {}); }}",
+                                    tab_id_for_ipc, page.count
+                                );
+                                let _ = webview.evaluate_script(&script);
+                            }
+                        }
                         Some("load_settings") => {
                             debug_log!("=== load_settings IPC received from tab ===");
                             if let Some(ref webview) = tab_bar_for_ipc {
@@ -777,6 +1439,10 @@ console.log('[INIT] Console override installed');
                                     "blockTrackers": cfg.privacy.tracking_domain_blocking,
                                     "blockFingerprinting": cfg.privacy.canvas_fingerprint_protection,
                                     "blockCookies": true,
+                                    "historyExcludedHosts": cfg.history.excluded_hosts,
+                                    "historyMaxAgeDays": cfg.history.max_age_days,
+                                    "historyStripQueryStrings": cfg.history.strip_query_strings,
+                                    "filterLists": crate::privacy::filters::subscriptions::statuses(&cfg.privacy),
                                     "shortcuts": {
                                         "new_tab": cfg.ui.shortcuts.new_tab,
                                         "close_tab": cfg.ui.shortcuts.close_tab,
@@ -786,7 +1452,11 @@ console.log('[INIT] Console override installed');
                                         "focus_sidebar": cfg.ui.shortcuts.focus_sidebar,
                                         "find": cfg.ui.shortcuts.find,
                                         "new_window": cfg.ui.shortcuts.new_window,
+                                        "new_private_window": cfg.ui.shortcuts.new_private_window,
                                         "toggle_split_view": cfg.ui.shortcuts.toggle_split_view,
+                                        "reopen_closed_tab": cfg.ui.shortcuts.reopen_closed_tab,
+                                        "bookmark_page": cfg.ui.shortcuts.bookmark_page,
+                                        "toggle_bookmarks": cfg.ui.shortcuts.toggle_bookmarks,
                                     }
                                 });
                                 debug_log!("Settings to send from tab: {:?}", settings_obj);
@@ -799,6 +1469,97 @@ console.log('[INIT] Console override installed');
                                 drop(cfg);
                             }
                         }
+                        Some("history_page_query") if from_history_page => {
+                            let before = data["before"].as_i64();
+                            let query = data["query"].as_str().unwrap_or("");
+                            let visits = history_for_ipc
+                                .borrow()
+                                .entries_between(0, before.unwrap_or(i64::MAX), query, HISTORY_PAGE_SIZE);
+                            let has_more = visits.len() == HISTORY_PAGE_SIZE;
+                            let webview = webview_proxy_for_ipc.borrow().as_ref().and_then(|webview| webview.upgrade());
+                            if let Some(webview) = webview {
+                                let script = format!(
+                                    "if (window.showHistoryVisits) {{ window.showHistoryVisits({}, {}, {}); }}",
+                                    serde_json::to_string(&visits).unwrap_or_else(|_| "[]".to_string()),
+                                    before.is_some(),
+                                    has_more
+                                );
+                                let _ = webview.evaluate_script(&script);
+                            }
+                        }
+                        Some("history_page_delete") if from_history_page => {
+                            if let Some(ids) = data["ids"].as_array() {
+                                let ids: Vec<i64> = ids.iter().filter_map(|id| id.as_i64()).collect();
+                                let removed = history_for_ipc.borrow_mut().remove_entries(&ids);
+                                debug_log!("Removed {} visits from history", removed);
+                            }
+                        }
+                        Some("history_forget_site") if from_history_page => {
+                            if let Some(host) = data["host"].as_str() {
+                                let removed = history_for_ipc.borrow_mut().remove_host(host);
+                                debug_log!("Forgot {} pages from {}", removed, host);
+                            }
+                        }
+                        Some("privacy_log_query") if from_privacy_log_page => {
+                            let filter: block_log::LogFilter = serde_json::from_value(data["filter"].clone()).unwrap_or_default();
+                            let webview = webview_proxy_for_ipc.borrow().as_ref().and_then(|webview| webview.upgrade());
+                            if let Some(webview) = webview {
+                                let script = format!(
+                                    "if (window.showPrivacyLog) {{ window.showPrivacyLog({}, {}); }}",
+                                    serde_json::to_string(&block_log::query(&filter)).unwrap_or_else(|_| "[]".to_string()),
+                                    serde_json::to_string(&block_log::tab_ids()).unwrap_or_else(|_| "[]".to_string())
+                                );
+                                let _ = webview.evaluate_script(&script);
+                            }
+                        }
+                        Some("privacy_log_clear") if from_privacy_log_page => {
+                            block_log::clear();
+                            debug_log!("Cleared the privacy log");
+                        }
+                        Some("privacy_log_export") if from_privacy_log_page => {
+                            let filter: block_log::LogFilter = serde_json::from_value(data["filter"].clone()).unwrap_or_default();
+                            let path = crate::import_export::expand_home(data["path"].as_str().unwrap_or(""));
+                            let (message, ok) = match block_log::export(&path, &filter) {
+                                Ok(count) => (format!("Exported {} events to {}", count, path.display()), true),
+                                Err(e) => (format!("{}: {}", path.display(), e), false),
+                            };
+                            debug_log!("{}", message);
+
+                            let webview = webview_proxy_for_ipc.borrow().as_ref().and_then(|webview| webview.upgrade());
+                            if let Some(webview) = webview {
+                                let script = format!(
+                                    "if (window.showPrivacyLogExportResult) {{ window.showPrivacyLogExportResult({}, {}); }}",
+                                    serde_json::to_string(&message).unwrap_or_else(|_| "\"\"".to_string()),
+                                    ok
+                                );
+                                let _ = webview.evaluate_script(&script);
+                            }
+                        }
+                        Some("open_profile") | Some("create_profile") if from_profiles_page => {
+                            let name = data["name"].as_str().unwrap_or("");
+                            let result = if data["action"] == "create_profile" {
+                                crate::paths::create_profile(name)
+                                    .and_then(|()| SingleInstance::launch_profile(name))
+                            } else {
+                                SingleInstance::launch_profile(name)
+                            };
+                            let (message, is_error) = match result {
+                                Ok(()) => (format!("Opening {}", name), false),
+                                Err(e) => {
+                                    debug_log!("Failed to open profile {}: {}", name, e);
+                                    (format!("Could not open {}: {}", name, e), true)
+                                }
+                            };
+                            let webview = webview_proxy_for_ipc.borrow().as_ref().and_then(|webview| webview.upgrade());
+                            if let Some(webview) = webview {
+                                let script = format!(
+                                    "if (window.showProfileStatus) {{ window.showProfileStatus({}, {}); }}",
+                                    serde_json::to_string(&message).unwrap_or_else(|_| "''".to_string()),
+                                    is_error
+                                );
+                                let _ = webview.evaluate_script(&script);
+                            }
+                        }
                         Some("clear_history") => {
                             debug_log!("=== clear_history IPC received ===");
                             history_for_ipc.borrow_mut().clear();
@@ -844,6 +1605,36 @@ console.log('[INIT] Console override installed');
                                     cfg.privacy.audio_fingerprint_protection = block_fp;
                                 }
 
+                                let previous_history_rules = cfg.history.clone();
+                                if let Some(hosts) = settings.get("historyExcludedHosts").and_then(|v| v.as_array()) {
+                                    cfg.history.excluded_hosts = hosts
+                                        .iter()
+                                        .filter_map(|host| host.as_str())
+                                        .map(|host| host.trim().to_string())
+                                        .filter(|host| !host.is_empty())
+                                        .collect();
+                                }
+                                if let Some(max_age_days) = settings.get("historyMaxAgeDays").and_then(|v| v.as_u64()) {
+                                    debug_log!("Setting history max_age_days to: {}", max_age_days);
+                                    cfg.history.max_age_days = max_age_days;
+                                }
+                                if let Some(strip) = settings.get("historyStripQueryStrings").and_then(|v| v.as_bool()) {
+                                    debug_log!("Setting history strip_query_strings to: {}", strip);
+                                    cfg.history.strip_query_strings = strip;
+                                }
+                                if let Some(subscriptions) = settings.get("filterSubscriptions").and_then(|v| v.as_object()) {
+                                    for (url, enabled) in subscriptions {
+                                        if let Some(enabled) = enabled.as_bool() {
+                                            debug_log!("Setting filter subscription {} enabled to: {}", url, enabled);
+                                            crate::privacy::filters::subscriptions::set_enabled(
+                                                &mut cfg.privacy.filter_subscriptions,
+                                                url,
+                                                enabled,
+                                            );
+                                        }
+                                    }
+                                }
+
                                 if let Some(shortcuts) = settings.get("shortcuts").and_then(|v| v.as_object()) {
                                     debug_log!("Saving keyboard shortcuts");
                                     if let Some(new_tab) = shortcuts.get("new_tab").and_then(|v| v.as_str()) {
@@ -886,18 +1677,55 @@ console.log('[INIT] Console override installed');
                                             cfg.ui.shortcuts.new_window = new_window.to_string();
                                         }
                                     }
+                                    if let Some(new_private_window) = shortcuts.get("new_private_window").and_then(|v| v.as_str()) {
+                                        if !new_private_window.trim().is_empty() {
+                                            cfg.ui.shortcuts.new_private_window = new_private_window.to_string();
+                                        }
+                                    }
                                     if let Some(toggle_split_view) = shortcuts.get("toggle_split_view").and_then(|v| v.as_str()) {
                                         if !toggle_split_view.trim().is_empty() {
                                             cfg.ui.shortcuts.toggle_split_view = toggle_split_view.to_string();
                                         }
                                     }
+                                    if let Some(reopen_closed_tab) = shortcuts.get("reopen_closed_tab").and_then(|v| v.as_str()) {
+                                        if !reopen_closed_tab.trim().is_empty() {
+                                            cfg.ui.shortcuts.reopen_closed_tab = reopen_closed_tab.to_string();
+                                        }
+                                    }
+                                    if let Some(bookmark_page) = shortcuts.get("bookmark_page").and_then(|v| v.as_str()) {
+                                        if !bookmark_page.trim().is_empty() {
+                                            cfg.ui.shortcuts.bookmark_page = bookmark_page.to_string();
+                                        }
+                                    }
+                                    if let Some(toggle_bookmarks) = shortcuts.get("toggle_bookmarks").and_then(|v| v.as_str()) {
+                                        if !toggle_bookmarks.trim().is_empty() {
+                                            cfg.ui.shortcuts.toggle_bookmarks = toggle_bookmarks.to_string();
+                                        }
+                                    }
                                 }
 
                                 match cfg.save() {
-                                    Ok(_) => debug_log!("Settings saved successfully to ~/.calm.yml from tab"),
+                                    Ok(_) => debug_log!("Settings saved successfully to {} from tab", crate::paths::config_file().display()),
                                     Err(e) => debug_log!("ERROR: Failed to save settings from tab: {:?}", e),
                                 }
 
+                                // Offer to apply changed history rules to the visits already recorded.
+                                let matching = if cfg.history != previous_history_rules {
+                                    history_for_ipc.borrow().count_rule_matches(&cfg.history)
+                                } else {
+                                    0
+                                };
+                                if matching > 0 {
+                                    let webview = webview_proxy_for_ipc.borrow().as_ref().and_then(|webview| webview.upgrade());
+                                    if let Some(webview) = webview {
+                                        let script = format!(
+                                            "if (window.offerHistoryPurge) {{ window.offerHistoryPurge({}); }}",
+                                            matching
+                                        );
+                                        let _ = webview.evaluate_script(&script);
+                                    }
+                                }
+
                                 if let Some(ui_sounds) = settings.get("uiSounds").and_then(|v| v.as_bool()) {
                                     let script = if ui_sounds {
                                         "window.uiSoundsEnabled = true;"
@@ -925,8 +1753,74 @@ console.log('[INIT] Console override installed');
                                 }
                             }
                         }
-                        Some("check_for_updates") => {
-                            debug_log!("Checking for updates from settings page");
+                        Some("save_to_reading_list") => {
+                            // Pages may only save themselves, so one can't plant a snapshot under
+                            // another site's URL or have the browser fetch arbitrary URLs. The URL
+                            // is the request's, since a page can report any URL it likes.
+                            let url = request.uri().to_string();
+                            if !url.starts_with("http://") && !url.starts_with("https://") {
+                                debug_log!("Not saving {} to the reading list", url);
+                                return;
+                            }
+                            let title = data["title"].as_str().unwrap_or("");
+                            let article_html = data["html"].as_str();
+                            let (id, added) = crate::reading_list::save_article(&url, title, article_html);
+                            if article_html.is_none() {
+                                crate::reading_list::fetch_snapshot(id, url.clone(), title.to_string());
+                            }
+                            debug_log!("Saved {} to the reading list as {}", url, id);
+
+                            // Saving a page already in the list only refreshes it.
+                            if !added {
+                                return;
+                            }
+                            if let Some(ref webview) = tab_bar_for_ipc {
+                                let _ = webview.evaluate_script(
+                                    "window.ipc.postMessage(JSON.stringify({action: 'keyboard_shortcut', shortcut: 'open_reading_list'}));",
+                                );
+                            }
+                        }
+                        Some("import_browser_data") | Some("export_bookmarks") if from_settings_page => {
+                            let path = crate::import_export::expand_home(data["path"].as_str().unwrap_or(""));
+                            let result = if data["action"] == "import_browser_data" {
+                                crate::import_export::import_file(&path, &mut history_for_ipc.borrow_mut())
+                                    .map(|summary| summary.to_string())
+                            } else {
+                                crate::import_export::export_bookmarks(&path)
+                                    .map(|count| format!("Exported {} bookmarks to {}", count, path.display()))
+                            };
+                            let (message, ok) = match result {
+                                Ok(message) => (message, true),
+                                Err(e) => (format!("{}: {}", path.display(), e), false),
+                            };
+                            debug_log!("{}", message);
+
+                            let webview = webview_proxy_for_ipc.borrow().as_ref().and_then(|webview| webview.upgrade());
+                            if let Some(webview) = webview {
+                                let script = format!(
+                                    "if (window.showImportExportResult) {{ window.showImportExportResult({}, {}); }}",
+                                    serde_json::to_string(&message).unwrap_or_else(|_| "\"\"".to_string()),
+                                    ok
+                                );
+                                let _ = webview.evaluate_script(&script);
+                            }
+                        }
+                        Some("apply_history_rules") if from_settings_page => {
+                            let rules = config_for_ipc.borrow().history.clone();
+                            let affected = history_for_ipc.borrow_mut().apply_rules(&rules);
+                            debug_log!("Applied history rules to {} visits", affected);
+
+                            let webview = webview_proxy_for_ipc.borrow().as_ref().and_then(|webview| webview.upgrade());
+                            if let Some(webview) = webview {
+                                let script = format!(
+                                    "if (window.showHistoryPurgeResult) {{ window.showHistoryPurgeResult({}); }}",
+                                    affected
+                                );
+                                let _ = webview.evaluate_script(&script);
+                            }
+                        }
+                        Some("check_for_updates") => {
+                            debug_log!("Checking for updates from settings page");
                             let mut updater = crate::updater::Updater::new();
 
                             std::thread::spawn(move || {
@@ -946,6 +1840,31 @@ console.log('[INIT] Console override installed');
                         Some("install_update") => {
                             debug_log!("Installing update from settings page");
                         }
+                        Some("restore_session") | Some("dismiss_session_recovery") if from_recovery_page => {
+                            if crate::window::recovery_session().is_none() {
+                                debug_log!("Ignoring {} message with no session to recover", data["action"]);
+                            } else if data["action"] == "dismiss_session_recovery" {
+                                crate::window::set_recovery_session(None);
+                                crate::window::push_window_request(WindowRequest::DismissSessionRecovery);
+                            } else {
+                                match serde_json::from_value::<BrowserSession>(data["session"].clone()) {
+                                    Ok(session) => {
+                                        crate::window::set_recovery_session(None);
+                                        crate::window::push_window_request(WindowRequest::RestoreSession(session));
+                                        if let Some(ref webview) = tab_bar_for_ipc {
+                                            let script = format!(
+                                                "window.ipcMessageToWindow = {{ action: 'close_tab', tabId: {} }};",
+                                                tab_id_for_ipc
+                                            );
+                                            let _ = webview.evaluate_script(&script);
+                                        }
+                                    }
+                                    Err(e) => {
+                                        debug_log!("Invalid session in restore_session message: {}", e);
+                                    }
+                                }
+                            }
+                        }
                         _ => {}
                     }
                 }
@@ -954,20 +1873,12 @@ console.log('[INIT] Console override installed');
 
         debug_log!("Webview built successfully for tab {}", tab_id);
 
-        let webview = std::rc::Rc::new(webview);
-        *webview_proxy_slot.borrow_mut() = Some(webview.clone());
-
-        let mut tab = Tab::new(tab_id, cleaned_url.clone(), webview);
-        tab.mark_accessed();
-        self.tabs.insert(tab_id, tab);
+        privacy::filters::install(&webview, &self.config.borrow().privacy);
 
-        if let Ok(mut urls) = self.current_urls.lock() {
-            urls.insert(tab_id, cleaned_url);
-        }
-
-        debug_log!("Tab {} created and inserted into tabs map", tab_id);
+        let webview = std::rc::Rc::new(webview);
+        *webview_proxy_slot.borrow_mut() = Some(std::rc::Rc::downgrade(&webview));
 
-        Ok(tab_id)
+        Ok(webview)
     }
 
     /// Switches the active tab to the specified tab ID.
@@ -983,19 +1894,39 @@ console.log('[INIT] Console override installed');
             }
         }
 
+        if let Some(previous_id) = self.active_tab_id {
+            self.activity.mark_active(previous_id);
+            for pane_id in self.split_panes(previous_id) {
+                self.activity.mark_active(pane_id);
+            }
+        }
+
         self.active_tab_id = Some(tab_id);
         if let Ok(mut active_id) = self.active_tab_id_shared.lock() {
             *active_id = Some(tab_id);
         }
 
+        self.ensure_tab_loaded(tab_id);
+        for pane_id in self.split_panes(tab_id) {
+            self.ensure_tab_loaded(pane_id);
+        }
+
+        if let Some(group_id) = self.tab_groups.get_group_id_for_tab(tab_id) {
+            if self.tab_groups.get_group(group_id).is_some_and(|group| group.collapsed) {
+                self.tab_groups.set_collapsed(group_id, false);
+                self.sync_tab_groups();
+            }
+        }
+
         if let Some(new_tab) = self.tabs.get_mut(&tab_id) {
             new_tab.mark_accessed();
         }
 
         let new_group = self.split_view.get_group_for_tab(tab_id);
         if let Some(group) = new_group {
+            let pane_ids = group.tab_ids();
             for (t_id, tab) in &self.tabs {
-                if group.primary_tab_id == *t_id || group.secondary_tab_id == *t_id {
+                if pane_ids.contains(t_id) {
                     tab.show();
                     if let Some(webview) = tab.webview() {
                         let _ = webview
@@ -1052,19 +1983,52 @@ console.log('[INIT] Console override installed');
 
     /// Closes the tab with the specified ID and switches to another tab if needed.
     pub fn close_tab(&mut self, tab_id: usize) {
+        self.close_tab_internal(tab_id, true);
+    }
+
+    /// Closes a tab, pushing it onto the recently closed stack when `remember` is set and the
+    /// window is not private.
+    fn close_tab_internal(&mut self, tab_id: usize, remember: bool) {
+        if remember && !self.is_private() && self.tabs.contains_key(&tab_id) {
+            self.record_closed_tab(tab_id);
+        }
+
         if let Some(tab) = self.tabs.remove(&tab_id) {
             drop(tab);
 
+            let closed_index = self.tab_order.iter().position(|&id| id == tab_id);
+            self.tab_order.retain(|&id| id != tab_id);
+
             if let Ok(mut urls) = self.current_urls.lock() {
                 urls.remove(&tab_id);
             }
+            if let Ok(mut titles) = self.current_titles.lock() {
+                titles.remove(&tab_id);
+            }
+            if let Ok(mut favicons) = self.current_favicons.lock() {
+                favicons.remove(&tab_id);
+            }
+            if let Ok(mut audio_tabs) = self.audio_tabs.lock() {
+                audio_tabs.remove(&tab_id);
+            }
+            if let Ok(mut positions) = self.scroll_positions.lock() {
+                positions.remove(&tab_id);
+            }
+            self.activity.remove_tab(tab_id);
+            self.webview_created.remove(&tab_id);
+            self.typed_navigations.borrow_mut().remove(&tab_id);
+            self.page_blocks.borrow_mut().remove(&tab_id);
+            self.metrics.remove_tab_memory(tab_id);
 
             if let Some(_group_id) = self.split_view.remove_tab_from_group(tab_id) {
                 debug_log!(
-                    "Tab {} was in a split group, group has been dissolved",
+                    "Tab {} was in a split group, the group has been re-tiled or dissolved",
                     tab_id
                 );
             }
+            if self.tab_groups.remove_tab(tab_id).is_some() {
+                self.sync_tab_groups();
+            }
 
             if self.active_tab_id == Some(tab_id) {
                 self.active_tab_id = None;
@@ -1073,20 +2037,10 @@ console.log('[INIT] Console override installed');
                     *active_id = None;
                 }
 
-                let mut tab_ids: Vec<usize> = self.tabs.keys().copied().collect();
-                tab_ids.sort();
-
-                let next_tab_id = if let Some(pos) = tab_ids.iter().position(|&id| id > tab_id) {
-                    if pos > 0 {
-                        Some(tab_ids[pos - 1])
-                    } else {
-                        Some(tab_ids[pos])
-                    }
-                } else if !tab_ids.is_empty() {
-                    Some(tab_ids[tab_ids.len() - 1])
-                } else {
-                    None
-                };
+                let next_tab_id = closed_index
+                    .and_then(|index| self.tab_order.get(index.saturating_sub(1)))
+                    .or_else(|| self.tab_order.last())
+                    .copied();
 
                 if let Some(next_id) = next_tab_id {
                     self.switch_to_tab(next_id);
@@ -1095,18 +2049,659 @@ console.log('[INIT] Console override installed');
         }
     }
 
+    /// Closes a tab without remembering it so it can be attached to another window.
+    /// Returns `None` for unknown tabs and internal pages, which cannot be reloaded by URL.
+    pub fn detach_tab(&mut self, tab_id: usize) -> Option<DetachedTab> {
+        let state = self.session_tab_state(tab_id)?;
+        let scroll = self
+            .scroll_positions
+            .lock()
+            .ok()
+            .and_then(|positions| positions.get(&tab_id).cloned())
+            .filter(|position| position.url == state.url);
+
+        self.switch_away_from(&[tab_id]);
+        self.close_tab_internal(tab_id, false);
+        Some(DetachedTab { state, scroll })
+    }
+
+    /// Adds a tab detached from another window and switches to it, restoring its title,
+    /// pinned state and scroll offset. Returns the new tab ID.
+    pub fn attach_tab(&mut self, detached: DetachedTab) -> usize {
+        let DetachedTab { state, scroll } = detached;
+        let tab_id = self.create_lazy_tab_in_container(&state.url, state.container.clone());
+
+        if let Some(scroll) = scroll {
+            if let Ok(mut positions) = self.scroll_positions.lock() {
+                positions.insert(tab_id, scroll);
+            }
+        }
+
+        let escaped_url = serde_json::to_string(&state.url).unwrap_or_else(|_| "\"\"".to_string());
+        let mut script = format!("window.addTab({}, {});", tab_id, escaped_url);
+        if let Some(ref title) = state.title {
+            if let Ok(mut titles) = self.current_titles.lock() {
+                titles.insert(tab_id, title.clone());
+            }
+            script.push_str(&format!(
+                " window.updateTabTitle({}, {});",
+                tab_id,
+                serde_json::to_string(title).unwrap_or_else(|_| "\"\"".to_string())
+            ));
+        }
+        if let Some(ref webview) = self.tab_bar_webview {
+            let _ = webview.evaluate_script(&script);
+        }
+
+        if state.pinned {
+            self.set_tab_pinned(tab_id, true);
+        }
+        self.switch_to_tab(tab_id);
+        tab_id
+    }
+
+    /// Returns the title of the active tab, falling back to its URL.
+    pub fn get_active_tab_title(&self) -> Option<String> {
+        let tab_id = self.active_tab_id?;
+        self.current_titles
+            .lock()
+            .ok()
+            .and_then(|titles| titles.get(&tab_id).cloned())
+            .filter(|title| !title.is_empty())
+            .or_else(|| self.current_tab_url(tab_id))
+    }
+
+    /// Returns the URL of the active tab, unless it shows an internal page rendered from HTML.
+    pub fn get_active_tab_url(&self) -> Option<String> {
+        let tab_id = self.active_tab_id?;
+        self.current_tab_url(tab_id).filter(|url| is_restorable_url(url))
+    }
+
+    /// Returns the number of pinned tabs, which always lead the tab order.
+    fn pinned_count(&self) -> usize {
+        self.tab_order
+            .iter()
+            .filter(|id| self.tabs.get(id).is_some_and(|tab| tab.pinned))
+            .count()
+    }
+
+    /// Returns whether a tab is pinned.
+    pub fn is_tab_pinned(&self, tab_id: usize) -> bool {
+        self.tabs.get(&tab_id).is_some_and(|tab| tab.pinned)
+    }
+
+    /// Pins or unpins a tab, moving it to the end of the pinned tabs at the top of the list.
+    /// Returns whether the tab exists.
+    pub fn set_tab_pinned(&mut self, tab_id: usize, pinned: bool) -> bool {
+        let Some(tab) = self.tabs.get_mut(&tab_id) else {
+            return false;
+        };
+        tab.pinned = pinned;
+
+        if pinned && self.tab_groups.remove_tab(tab_id).is_some() {
+            self.sync_tab_groups();
+        }
+
+        let index = order::move_to_pinned_edge(&mut self.tab_order, tab_id, |id| {
+            self.tabs.get(&id).is_some_and(|tab| tab.pinned)
+        });
+
+        if let Some(ref webview) = self.tab_bar_webview {
+            let script = format!(
+                "if (window.setTabPinned) {{ window.setTabPinned({}, {}, {}); }}",
+                tab_id, pinned, index
+            );
+            let _ = webview.evaluate_script(&script);
+        }
+
+        true
+    }
+
+    /// Moves a tab to a new position in the tab list. Pinned and unpinned tabs stay in their own
+    /// sections, so the index is clamped to the tab's section.
+    /// Returns the index the tab ended up at.
+    pub fn move_tab(&mut self, tab_id: usize, to_index: usize) -> Option<usize> {
+        let pinned = self.tabs.get(&tab_id)?.pinned;
+
+        order::move_within_section(&mut self.tab_order, tab_id, to_index, pinned, |id| {
+            self.tabs.get(&id).is_some_and(|tab| tab.pinned)
+        });
+        self.tab_order = self.tab_groups.group_tab_order(&self.tab_order);
+        self.sync_tab_order();
+
+        self.tab_order.iter().position(|&id| id == tab_id)
+    }
+
+    /// Sends the tab order and the tab groups to the tab bar.
+    fn sync_tab_order(&self) {
+        if let Some(ref webview) = self.tab_bar_webview {
+            let order_json =
+                serde_json::to_string(&self.tab_order).unwrap_or_else(|_| "[]".to_string());
+            let script = format!(
+                "if (window.setTabOrder) {{ window.setTabOrder({}); }}",
+                order_json
+            );
+            let _ = webview.evaluate_script(&script);
+        }
+        self.sync_tab_groups();
+    }
+
+    /// Sends the tab groups to the tab bar, which redraws the tab list.
+    fn sync_tab_groups(&self) {
+        if let Some(ref webview) = self.tab_bar_webview {
+            let script = format!(
+                "if (window.setTabGroups) {{ window.setTabGroups({}); }}",
+                self.tab_groups.get_tab_groups_json(&self.tab_order)
+            );
+            let _ = webview.evaluate_script(&script);
+        }
+    }
+
+    /// Puts a tab right after the last tab of a group in the tab order.
+    fn move_tab_to_group_end(&mut self, tab_id: usize, group_id: usize) {
+        self.tab_order.retain(|&id| id != tab_id);
+        let index = self
+            .tab_groups
+            .tabs_in_group(group_id, &self.tab_order)
+            .last()
+            .and_then(|last_id| self.tab_order.iter().position(|id| id == last_id))
+            .map_or(self.tab_order.len(), |position| position + 1);
+        self.tab_order.insert(index, tab_id);
+    }
+
+    /// Creates a tab group holding the given tab. Pinned tabs cannot be grouped.
+    /// Returns the new group ID.
+    pub fn create_tab_group(&mut self, tab_id: usize, name: Option<&str>) -> Option<usize> {
+        if !self.tabs.contains_key(&tab_id) || self.is_tab_pinned(tab_id) {
+            return None;
+        }
+
+        let group_id = self.tab_groups.create_group(name, None);
+        self.tab_groups.add_tab(tab_id, group_id);
+        self.tab_order = self.tab_groups.group_tab_order(&self.tab_order);
+        self.sync_tab_order();
+        Some(group_id)
+    }
+
+    /// Moves a tab into an existing group, after the group's other tabs.
+    pub fn add_tab_to_group(&mut self, tab_id: usize, group_id: usize) -> bool {
+        if !self.tabs.contains_key(&tab_id) || self.is_tab_pinned(tab_id) {
+            return false;
+        }
+        if self.tab_groups.get_group(group_id).is_none() {
+            return false;
+        }
+
+        self.move_tab_to_group_end(tab_id, group_id);
+        self.tab_groups.add_tab(tab_id, group_id);
+        self.tab_order = self.tab_groups.group_tab_order(&self.tab_order);
+        self.sync_tab_order();
+        true
+    }
+
+    /// Takes a tab out of its group and places it right after the group.
+    pub fn remove_tab_from_tab_group(&mut self, tab_id: usize) -> bool {
+        let Some(group_id) = self.tab_groups.get_group_id_for_tab(tab_id) else {
+            return false;
+        };
+
+        self.move_tab_to_group_end(tab_id, group_id);
+        self.tab_groups.remove_tab(tab_id);
+        self.tab_order = self.tab_groups.group_tab_order(&self.tab_order);
+        self.sync_tab_order();
+        true
+    }
+
+    pub fn rename_tab_group(&mut self, group_id: usize, name: &str) {
+        self.tab_groups.rename_group(group_id, name);
+        self.sync_tab_groups();
+    }
+
+    pub fn set_tab_group_color(&mut self, group_id: usize, color: &str) {
+        self.tab_groups.set_group_color(group_id, color);
+        self.sync_tab_groups();
+    }
+
+    pub fn set_tab_group_collapsed(&mut self, group_id: usize, collapsed: bool) {
+        self.tab_groups.set_collapsed(group_id, collapsed);
+        self.sync_tab_groups();
+    }
+
+    /// Switches to the closest tab outside `tab_ids` if the active tab is about to be closed with
+    /// them, so closing them one by one does not load each of them in turn.
+    fn switch_away_from(&mut self, tab_ids: &[usize]) {
+        let Some(active_id) = self.active_tab_id else {
+            return;
+        };
+        if !tab_ids.contains(&active_id) {
+            return;
+        }
+
+        let first_index = self
+            .tab_order
+            .iter()
+            .position(|id| tab_ids.contains(id))
+            .unwrap_or(0);
+        let before = self.tab_order[..first_index].iter().rev();
+        let after = self.tab_order[first_index..].iter();
+        let next_id = before
+            .chain(after)
+            .copied()
+            .find(|id| !tab_ids.contains(id));
+
+        if let Some(next_id) = next_id {
+            self.switch_to_tab(next_id);
+        }
+    }
+
+    /// Closes every tab of a group. Returns the closed tab IDs.
+    pub fn close_tab_group(&mut self, group_id: usize) -> Vec<usize> {
+        let tab_ids = self.tab_groups.tabs_in_group(group_id, &self.tab_order);
+        self.switch_away_from(&tab_ids);
+        for &tab_id in &tab_ids {
+            self.close_tab(tab_id);
+        }
+        tab_ids
+    }
+
+    /// Captures a group's tabs as a window state holding only that group.
+    fn tab_group_window_state(&self, group_id: usize) -> Option<WindowState> {
+        let group = self.tab_groups.get_group(group_id)?;
+        let tab_ids = self.tab_groups.tabs_in_group(group_id, &self.tab_order);
+
+        let mut tabs = Vec::new();
+        let mut index_by_tab_id = HashMap::new();
+        for &tab_id in &tab_ids {
+            if let Some(tab_state) = self.session_tab_state(tab_id) {
+                index_by_tab_id.insert(tab_id, tabs.len());
+                tabs.push(tab_state);
+            }
+        }
+        if tabs.is_empty() {
+            return None;
+        }
+
+        Some(WindowState {
+            active_tab_index: tabs.iter().position(|tab| tab.is_active).or(Some(0)),
+            split_groups: self.split_group_states(&index_by_tab_id),
+            tab_groups: vec![TabGroupState {
+                name: group.name.clone(),
+                color: group.color.clone(),
+                collapsed: false,
+                tab_indices: (0..tabs.len()).collect(),
+            }],
+            tabs,
+        })
+    }
+
+    /// Closes a group's tabs without remembering them individually and reopens them in a new
+    /// window. Returns the closed tab IDs.
+    pub fn move_tab_group_to_new_window(&mut self, group_id: usize) -> Vec<usize> {
+        let Some(window_state) = self.tab_group_window_state(group_id) else {
+            return Vec::new();
+        };
+
+        let tab_ids = self.tab_groups.tabs_in_group(group_id, &self.tab_order);
+        self.switch_away_from(&tab_ids);
+        for &tab_id in &tab_ids {
+            self.close_tab_internal(tab_id, false);
+        }
+        crate::window::push_window_request(WindowRequest::OpenWindow {
+            state: window_state,
+            private: self.is_private(),
+        });
+        tab_ids
+    }
+
+    /// Saves a group so it can be reopened later, then closes its tabs. Private windows don't
+    /// save groups, since saved groups are written to disk.
+    /// Returns the closed tab IDs.
+    pub fn save_tab_group(&mut self, group_id: usize) -> Vec<usize> {
+        if self.is_private() {
+            return Vec::new();
+        }
+        let Some(mut window_state) = self.tab_group_window_state(group_id) else {
+            return Vec::new();
+        };
+        let Some(group_state) = window_state.tab_groups.pop() else {
+            return Vec::new();
+        };
+
+        groups::with_saved_tab_groups(|store| {
+            store.add(group_state.name, group_state.color, window_state.tabs)
+        });
+
+        let tab_ids = self.tab_groups.tabs_in_group(group_id, &self.tab_order);
+        self.switch_away_from(&tab_ids);
+        for &tab_id in &tab_ids {
+            self.close_tab_internal(tab_id, false);
+        }
+        tab_ids
+    }
+
+    /// Reopens a saved group in this window and switches to its first tab.
+    /// Returns whether the group was found.
+    pub fn open_saved_tab_group(&mut self, window: &Window, saved_id: u64) -> bool {
+        let Some(saved) = groups::with_saved_tab_groups(|store| store.take(saved_id)) else {
+            return false;
+        };
+
+        let group_id = self.tab_groups.create_group(Some(&saved.name), Some(&saved.color));
+        let mut first_tab_id = None;
+        for saved_tab in &saved.tabs {
+            let tab_id = if first_tab_id.is_none() {
+                match self.create_tab(window, &saved_tab.url) {
+                    Ok(tab_id) => tab_id,
+                    Err(e) => {
+                        debug_log!("Failed to open saved tab {}: {:?}", saved_tab.url, e);
+                        continue;
+                    }
+                }
+            } else {
+                self.create_lazy_tab(&saved_tab.url)
+            };
+
+            let escaped_url =
+                serde_json::to_string(&saved_tab.url).unwrap_or_else(|_| "\"\"".to_string());
+            let mut script = format!("window.addTab({}, {});", tab_id, escaped_url);
+            if first_tab_id.is_some() {
+                script.push_str(&format!(" window.setTabSuspended({}, true);", tab_id));
+            }
+            if let Some(ref title) = saved_tab.title {
+                if let Ok(mut titles) = self.current_titles.lock() {
+                    titles.insert(tab_id, title.clone());
+                }
+                script.push_str(&format!(
+                    " window.updateTabTitle({}, {});",
+                    tab_id,
+                    serde_json::to_string(title).unwrap_or_else(|_| "\"\"".to_string())
+                ));
+            }
+            if let Some(ref webview) = self.tab_bar_webview {
+                let _ = webview.evaluate_script(&script);
+            }
+
+            self.tab_groups.add_tab(tab_id, group_id);
+            first_tab_id.get_or_insert(tab_id);
+        }
+
+        self.tab_order = self.tab_groups.group_tab_order(&self.tab_order);
+        self.sync_tab_order();
+
+        if let Some(tab_id) = first_tab_id {
+            self.switch_to_tab(tab_id);
+            self.update_split_view_layout(window, None);
+        }
+        true
+    }
+
+    /// Expands a group and switches to its most recently used tab.
+    /// Returns the tab switched to.
+    pub fn switch_to_tab_group(&mut self, window: &Window, group_id: usize) -> Option<usize> {
+        let tab_ids = self.tab_groups.tabs_in_group(group_id, &self.tab_order);
+        let tab_id = tab_ids
+            .iter()
+            .copied()
+            .max_by_key(|id| self.tabs.get(id).map(|tab| tab.last_accessed))?;
+
+        self.tab_groups.set_collapsed(group_id, false);
+        self.sync_tab_groups();
+        self.switch_to_tab(tab_id);
+        self.update_split_view_layout(window, None);
+        Some(tab_id)
+    }
+
+    /// Returns the open groups of this window and the saved groups as JSON for the command prompt.
+    pub fn get_workspaces_json(&self) -> String {
+        let open: Vec<serde_json::Value> = self
+            .tab_groups
+            .ordered_groups(&self.tab_order)
+            .into_iter()
+            .map(|group| {
+                serde_json::json!({
+                    "id": group.id,
+                    "name": group.name,
+                    "color": group.color,
+                    "tabCount": self.tab_groups.tabs_in_group(group.id, &self.tab_order).len(),
+                })
+            })
+            .collect();
+        let saved: Vec<serde_json::Value> = groups::with_saved_tab_groups(|store| {
+            store
+                .get_all()
+                .iter()
+                .map(|group| {
+                    serde_json::json!({
+                        "id": group.id,
+                        "name": group.name,
+                        "color": group.color,
+                        "tabCount": group.tabs.len(),
+                    })
+                })
+                .collect()
+        });
+
+        serde_json::json!({ "open": open, "saved": saved }).to_string()
+    }
+
+    /// Returns the other panes of the split group the tab belongs to.
+    fn split_panes(&self, tab_id: usize) -> Vec<usize> {
+        self.split_view
+            .get_group_for_tab(tab_id)
+            .map(|group| {
+                group
+                    .tab_ids()
+                    .into_iter()
+                    .filter(|&id| id != tab_id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Builds the webview of an unloaded or suspended tab so it can be shown.
+    fn ensure_tab_loaded(&mut self, tab_id: usize) {
+        let Some(window) = self.window.clone() else {
+            return;
+        };
+        let (url, container) = match self.tabs.get(&tab_id) {
+            Some(tab) if !tab.is_loaded() => (tab.get_url().to_string(), tab.container.clone()),
+            _ => return,
+        };
+
+        let restore_scroll = self
+            .scroll_positions
+            .lock()
+            .ok()
+            .and_then(|positions| positions.get(&tab_id).cloned());
+
+        debug_log!("Loading tab {} ({})", tab_id, url);
+        match self.build_tab_webview(&window, tab_id, &url, None, restore_scroll, container.as_deref()) {
+            Ok(webview) => {
+                if let Some(tab) = self.tabs.get_mut(&tab_id) {
+                    tab.load(webview);
+                }
+                self.activity.mark_active(tab_id);
+                self.webview_created.insert(tab_id, Instant::now());
+                if let Some(ref webview) = self.tab_bar_webview {
+                    let script = format!(
+                        "if (window.setTabSuspended) {{ window.setTabSuspended({}, false); }}",
+                        tab_id
+                    );
+                    let _ = webview.evaluate_script(&script);
+                }
+            }
+            Err(e) => {
+                debug_log!("Failed to load tab {}: {:?}", tab_id, e);
+            }
+        }
+    }
+
+    /// Returns the URL a tab is currently showing, falling back to the one it was created with.
+    fn current_tab_url(&self, tab_id: usize) -> Option<String> {
+        self.current_urls
+            .lock()
+            .ok()
+            .and_then(|urls| urls.get(&tab_id).cloned())
+            .or_else(|| self.tabs.get(&tab_id).map(|tab| tab.get_url().to_string()))
+    }
+
+    /// Returns whether a tab's webview can be destroyed. The visible tabs, internal pages and
+    /// tabs playing audio are left alone.
+    fn suspend_check(&self, tab_id: usize) -> SuspendCheck {
+        let visible = self.active_tab_id == Some(tab_id)
+            || self
+                .active_tab_id
+                .is_some_and(|active_id| self.split_panes(active_id).contains(&tab_id));
+        let playing_audio = self
+            .audio_tabs
+            .lock()
+            .map(|audio_tabs| audio_tabs.contains(&tab_id))
+            .unwrap_or(false);
+        let (loaded, pinned) = self
+            .tabs
+            .get(&tab_id)
+            .map_or((false, false), |tab| (tab.is_loaded(), tab.pinned));
+        let restorable = self
+            .current_tab_url(tab_id)
+            .is_some_and(|url| is_restorable_url(&url));
+
+        SuspendCheck {
+            loaded,
+            restorable,
+            visible,
+            playing_audio,
+            pinned,
+        }
+    }
+
+    fn can_suspend(&self, tab_id: usize) -> bool {
+        self.suspend_check(tab_id).allows()
+    }
+
+    /// Destroys the webview of a background tab, keeping its URL, title and scroll position so it
+    /// can be rebuilt. Returns whether the tab was suspended.
+    pub fn suspend_tab(&mut self, tab_id: usize) -> bool {
+        if !self.can_suspend(tab_id) {
+            return false;
+        }
+
+        let url = self.current_tab_url(tab_id).unwrap_or_default();
+        let title = self
+            .current_titles
+            .lock()
+            .ok()
+            .and_then(|titles| titles.get(&tab_id).cloned());
+
+        let Some(tab) = self.tabs.get_mut(&tab_id) else {
+            return false;
+        };
+
+        debug_log!("Suspending tab {} ({})", tab_id, url);
+        tab.set_url(url);
+        if let Some(title) = title {
+            tab.set_title(title);
+        }
+        tab.hide();
+        tab.suspend();
+        self.activity.remove_tab(tab_id);
+        self.webview_created.remove(&tab_id);
+        self.metrics.remove_tab_memory(tab_id);
+
+        if let Some(ref webview) = self.tab_bar_webview {
+            let script = format!(
+                "if (window.setTabSuspended) {{ window.setTabSuspended({}, true); }}",
+                tab_id
+            );
+            let _ = webview.evaluate_script(&script);
+        }
+
+        true
+    }
+
+    /// Removes the visits older than the configured history age. Returns the number removed.
+    pub fn prune_history(&self) -> usize {
+        let max_age_days = self.config.borrow().history.max_age_days;
+        self.history.borrow_mut().prune_expired(max_age_days)
+    }
+
+    /// Suspends background tabs that have been idle longer than the configured timeout.
+    /// Returns the number of tabs suspended.
+    pub fn suspend_inactive_tabs(&mut self) -> usize {
+        let (enabled, timeout_minutes) = {
+            let config = self.config.borrow();
+            (
+                config.performance.tab_suspension,
+                config.performance.suspension_timeout_minutes,
+            )
+        };
+        if !enabled {
+            return 0;
+        }
+
+        let timeout = Duration::from_secs(timeout_minutes * 60);
+        tabs_to_suspend(&self.activity.idle_times(), timeout, |tab_id| {
+            self.suspend_check(tab_id)
+        })
+        .into_iter()
+        .filter(|&tab_id| self.suspend_tab(tab_id))
+        .count()
+    }
+
+    /// Returns how long ago each loaded tab's webview was built.
+    pub fn webview_ages(&self) -> Vec<(usize, Duration)> {
+        self.webview_created
+            .iter()
+            .map(|(tab_id, created)| (*tab_id, created.elapsed()))
+            .collect()
+    }
+
+    /// Records a memory sample for this window's tabs.
+    /// Returns whether any tab is over the configured per-tab memory budget.
+    pub fn record_memory_usage(
+        &mut self,
+        tab_memory: &HashMap<usize, usize>,
+        snapshot: &MemorySnapshot,
+    ) -> bool {
+        let max_memory_per_tab_mb = self.config.borrow().performance.max_memory_per_tab_mb;
+        let mut over_budget = false;
+
+        for (&tab_id, &memory_mb) in tab_memory {
+            self.metrics.record_tab_memory_sample(tab_id, memory_mb);
+            if memory_mb > max_memory_per_tab_mb {
+                debug_log!(
+                    "Tab {} is using {} MB (budget {} MB)",
+                    tab_id,
+                    memory_mb,
+                    max_memory_per_tab_mb
+                );
+                over_budget = true;
+            }
+        }
+
+        self.metrics.record_memory_sample(snapshot.total_rss_mb());
+        let loaded = self.tabs.values().filter(|tab| tab.is_loaded()).count();
+        self.metrics
+            .update_tab_counts(loaded, self.tabs.len() - loaded, self.tabs.len());
+
+        over_budget
+    }
+
+    /// Returns the least recently used tab that can be discarded, with when it was last active.
+    pub fn discard_candidate(&self) -> Option<(usize, Instant)> {
+        self.activity
+            .least_recently_active()
+            .into_iter()
+            .find(|&tab_id| self.can_suspend(tab_id))
+            .and_then(|tab_id| Some((tab_id, self.activity.last_active(tab_id)?)))
+    }
+
     /// Reloads the currently active tab.
     pub fn reload_active_tab(&self) {
         if let Some(tab_id) = self.active_tab_id {
             if let Some(tab) = self.tabs.get(&tab_id) {
                 if let Some(webview) = tab.webview() {
-                    if tab.get_url() == "calm://settings" {
-                        let settings_html = crate::ui::get_settings_html();
-                        let _ = webview.load_html(&settings_html);
-                    } else {
-                        let script = "window.location.reload();";
-                        let _ = webview.evaluate_script(script);
-                    }
+                    let script = "window.location.reload();";
+                    let _ = webview.evaluate_script(script);
                 }
             }
         }
@@ -1191,14 +2786,26 @@ console.log('[INIT] Console override installed');
         }
     }
 
-    /// Navigates the specified tab to a new URL.
+    /// Navigates the specified tab to a new URL. When a host rule assigns the URL to another
+    /// container, the tab is reopened in that container instead.
     pub fn navigate_to(&mut self, tab_id: usize, url: &str) {
+        self.mark_typed_navigation(tab_id);
+        let rule_container = self.rule_container(url);
+        let tab_container = self.tabs.get(&tab_id).and_then(|tab| tab.container.clone());
+        if rule_container.is_some() && rule_container != tab_container {
+            let cleaned_url = self.clean_tab_url(url);
+            self.set_tab_container(tab_id, rule_container, Some(&cleaned_url));
+            return;
+        }
         if let Some(tab) = self.tabs.get_mut(&tab_id) {
             let redirected_url =
                 url_cleaner::redirect_youtube_to_invidious(url, &self.config.borrow());
             let cleaned_url = url_cleaner::clean_url(&redirected_url)
                 .unwrap_or_else(|_| redirected_url.to_string());
             tab.set_url(cleaned_url.clone());
+            if let Ok(mut urls) = self.current_urls.lock() {
+                urls.insert(tab_id, cleaned_url.clone());
+            }
             if let Some(webview) = tab.webview() {
                 let escaped_url =
                     serde_json::to_string(&cleaned_url).unwrap_or_else(|_| "\"\"".to_string());
@@ -1213,6 +2820,85 @@ console.log('[INIT] Console override installed');
         }
     }
 
+    /// Records that a tab is loading an address the user typed, which ranks it higher in
+    /// URL bar suggestions than pages reached through links.
+    pub fn mark_typed_navigation(&self, tab_id: usize) {
+        self.typed_navigations.borrow_mut().insert(tab_id);
+    }
+
+    /// Returns the open tabs, bookmarks and history entries matching `query` for the command
+    /// prompt, best first. A page is only listed once, as an open tab before a bookmark.
+    pub fn get_suggestions_json(&self, query: &str, limit: usize) -> String {
+        let titles = self
+            .current_titles
+            .lock()
+            .map(|titles| titles.clone())
+            .unwrap_or_default();
+        let browsing_history = self.history.borrow();
+
+        let mut open_urls = HashSet::new();
+        let mut suggestions: Vec<(f64, serde_json::Value)> = Vec::new();
+        for &tab_id in &self.tab_order {
+            let Some(url) = self.current_tab_url(tab_id) else {
+                continue;
+            };
+            if self.active_tab_id == Some(tab_id) || !is_restorable_url(&url) {
+                continue;
+            }
+            let title = titles.get(&tab_id).cloned().unwrap_or_default();
+            open_urls.insert(url.clone());
+
+            if let Some(quality) = history::match_quality(&url, &title, query) {
+                let score = quality * browsing_history.url_frecency(&url).max(OPEN_TAB_MIN_FRECENCY);
+                suggestions.push((
+                    score,
+                    serde_json::json!({ "type": "tab", "tabId": tab_id, "url": url, "title": title }),
+                ));
+            }
+        }
+
+        let mut bookmarked_urls = HashSet::new();
+        for (bookmark, quality) in crate::bookmarks::with_bookmarks(|store| store.search(query)) {
+            if open_urls.contains(&bookmark.url) {
+                continue;
+            }
+            let score = quality * browsing_history.url_frecency(&bookmark.url).max(BOOKMARK_MIN_FRECENCY);
+            bookmarked_urls.insert(bookmark.url.clone());
+            suggestions.push((
+                score,
+                serde_json::json!({
+                    "type": "bookmark",
+                    "url": bookmark.url,
+                    "title": bookmark.title,
+                    "tags": bookmark.tags
+                }),
+            ));
+        }
+
+        for (entry, score) in browsing_history.search_scored(query, limit + open_urls.len() + bookmarked_urls.len()) {
+            if open_urls.contains(&entry.url) || bookmarked_urls.contains(&entry.url) {
+                continue;
+            }
+            suggestions.push((
+                score,
+                serde_json::json!({
+                    "type": "history",
+                    "url": entry.url,
+                    "title": entry.title,
+                    "visitCount": entry.visit_count
+                }),
+            ));
+        }
+
+        suggestions.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let suggestions: Vec<serde_json::Value> = suggestions
+            .into_iter()
+            .take(limit)
+            .map(|(_, suggestion)| suggestion)
+            .collect();
+        serde_json::to_string(&suggestions).unwrap_or_else(|_| "[]".to_string())
+    }
+
     /// Returns the ID of the currently active tab, if any.
     pub fn get_active_tab_id(&self) -> Option<usize> {
         self.active_tab_id
@@ -1238,6 +2924,8 @@ console.log('[INIT] Console override installed');
             }
         }
 
+        self.leave_split_view();
+
         let window_size = window.inner_size();
         let scale_factor = window.scale_factor();
         let sidebar_width_physical = (self.tab_sidebar_width as f64 * scale_factor) as u32;
@@ -1264,6 +2952,8 @@ console.log('[INIT] Console override installed');
             }
         }
 
+        self.leave_split_view();
+
         let window_size = window.inner_size();
         let scale_factor = window.scale_factor();
         let sidebar_width_physical = (self.tab_sidebar_width as f64 * scale_factor) as u32;
@@ -1295,7 +2985,7 @@ console.log('[INIT] Console override installed');
 
         if let Some(group_id) = self.split_view.get_group_id_for_tab(active_tab_id) {
             self.split_view.remove_group(group_id);
-            self.resize_all_tabs(window);
+            self.update_split_view_layout(window, None);
             false
         } else {
             let all_tab_ids: Vec<usize> = self.tabs.keys().copied().collect();
@@ -1328,6 +3018,415 @@ console.log('[INIT] Console override installed');
             .calculate_ui_state(self.active_tab_id, &all_tab_ids)
     }
 
+    /// Returns the script that refreshes the tab bar's split view controls and groups.
+    pub fn get_split_ui_script(&self) -> String {
+        let ui_state = self.get_split_ui_state();
+        let orientation_str = ui_state
+            .active_group_orientation
+            .as_deref()
+            .unwrap_or("vertical");
+        format!(
+            "if (window.updateSplitUIState) {{ window.updateSplitUIState({}, {}, '{}'); }} if (window.setSplitGroups) {{ window.setSplitGroups({}); }}",
+            ui_state.active_tab_in_split,
+            ui_state.can_create_split,
+            orientation_str,
+            self.get_split_groups_json()
+        )
+    }
+
+    /// Captures the open tabs, active tab and split groups of this window for the session file.
+    pub fn get_window_state(&self) -> WindowState {
+        let mut tabs = Vec::new();
+        let mut index_by_tab_id = HashMap::new();
+        for &tab_id in &self.tab_order {
+            if let Some(tab_state) = self.session_tab_state(tab_id) {
+                index_by_tab_id.insert(tab_id, tabs.len());
+                tabs.push(tab_state);
+            }
+        }
+
+        let tab_groups = self
+            .tab_groups
+            .ordered_groups(&self.tab_order)
+            .into_iter()
+            .filter_map(|group| {
+                let tab_indices: Vec<usize> = self
+                    .tab_groups
+                    .tabs_in_group(group.id, &self.tab_order)
+                    .iter()
+                    .filter_map(|tab_id| index_by_tab_id.get(tab_id).copied())
+                    .collect();
+                if tab_indices.is_empty() {
+                    return None;
+                }
+                Some(TabGroupState {
+                    name: group.name.clone(),
+                    color: group.color.clone(),
+                    collapsed: group.collapsed,
+                    tab_indices,
+                })
+            })
+            .collect();
+
+        WindowState {
+            active_tab_index: tabs.iter().position(|tab| tab.is_active),
+            split_groups: self.split_group_states(&index_by_tab_id),
+            tab_groups,
+            tabs,
+        }
+    }
+
+    /// Returns the session entry for a tab, or `None` for internal pages that cannot be restored.
+    fn session_tab_state(&self, tab_id: usize) -> Option<SessionTabState> {
+        let tab = self.tabs.get(&tab_id)?;
+        let url = self
+            .current_urls
+            .lock()
+            .ok()
+            .and_then(|urls| urls.get(&tab_id).cloned())
+            .unwrap_or_else(|| tab.get_url().to_string());
+        if !is_restorable_url(&url) {
+            return None;
+        }
+
+        let title = self
+            .current_titles
+            .lock()
+            .ok()
+            .and_then(|titles| titles.get(&tab_id).cloned())
+            .or_else(|| tab.title.clone());
+
+        Some(SessionTabState {
+            id: tab_id,
+            url,
+            title,
+            is_active: self.active_tab_id == Some(tab_id),
+            pinned: tab.pinned,
+            container: tab.container.clone(),
+        })
+    }
+
+    /// Returns the split groups whose panes are all in `index_by_tab_id`, referencing them by index.
+    fn split_group_states(&self, index_by_tab_id: &HashMap<usize, usize>) -> Vec<SplitGroupState> {
+        self.split_view
+            .groups()
+            .filter_map(|group| {
+                let tab_indices = group
+                    .tab_ids()
+                    .iter()
+                    .map(|tab_id| index_by_tab_id.get(tab_id).copied())
+                    .collect::<Option<Vec<_>>>()?;
+                Some(SplitGroupState {
+                    tab_indices,
+                    orientation: group.orientation().as_str().to_string(),
+                    split_ratio: group.split_ratio(),
+                    layout: group.layout.as_str().to_string(),
+                    tree: group.root.to_state(index_by_tab_id),
+                })
+            })
+            .collect()
+    }
+
+    /// Recreates the tabs, split groups and active tab described by a saved window state.
+    /// With lazy tab loading, only the active tab gets a webview right away.
+    /// Returns the number of tabs that were restored.
+    pub fn restore_window_state(&mut self, window: &Window, state: &WindowState) -> usize {
+        let mut restored_ids: Vec<Option<usize>> = Vec::with_capacity(state.tabs.len());
+        let lazy_tab_loading = self.config.borrow().performance.lazy_tab_loading;
+        let active_index = state.active_tab_index.unwrap_or(0);
+
+        for (index, saved_tab) in state.tabs.iter().enumerate() {
+            let load_now = !lazy_tab_loading || index == active_index;
+            let tab_result = if load_now {
+                self.create_tab_in_container(window, &saved_tab.url, saved_tab.container.clone())
+            } else {
+                Ok(self.create_lazy_tab_in_container(&saved_tab.url, saved_tab.container.clone()))
+            };
+
+            match tab_result {
+                Ok(tab_id) => {
+                    let escaped_url =
+                        serde_json::to_string(&saved_tab.url).unwrap_or_else(|_| "\"\"".to_string());
+                    let mut script = format!("window.addTab({}, {});", tab_id, escaped_url);
+                    if !load_now {
+                        script.push_str(&format!(" window.setTabSuspended({}, true);", tab_id));
+                    }
+
+                    if let Some(ref title) = saved_tab.title {
+                        if let Ok(mut titles) = self.current_titles.lock() {
+                            titles.insert(tab_id, title.clone());
+                        }
+                        script.push_str(&format!(
+                            " window.updateTabTitle({}, {});",
+                            tab_id,
+                            serde_json::to_string(title).unwrap_or_else(|_| "\"\"".to_string())
+                        ));
+                    }
+
+                    if let Some(ref webview) = self.tab_bar_webview {
+                        let _ = webview.evaluate_script(&script);
+                    }
+                    if saved_tab.pinned {
+                        self.set_tab_pinned(tab_id, true);
+                    }
+                    restored_ids.push(Some(tab_id));
+                }
+                Err(e) => {
+                    debug_log!("Failed to restore tab {}: {:?}", saved_tab.url, e);
+                    restored_ids.push(None);
+                }
+            }
+        }
+
+        for saved_group in &state.split_groups {
+            let group_tabs: Vec<usize> = saved_group
+                .tab_indices
+                .iter()
+                .filter_map(|&index| restored_ids.get(index).copied().flatten())
+                .collect();
+            if group_tabs.len() < 2
+                || group_tabs
+                    .iter()
+                    .any(|&tab_id| self.split_view.is_tab_in_group(tab_id))
+            {
+                continue;
+            }
+
+            let restored_tree = saved_group
+                .tree
+                .as_ref()
+                .and_then(|tree| SplitNode::from_state(tree, &restored_ids))
+                .and_then(|root| {
+                    self.split_view
+                        .restore_group(root, SplitLayout::from_name(&saved_group.layout))
+                });
+            if restored_tree.is_some() {
+                continue;
+            }
+
+            let group_id = self.split_view.create_group(
+                group_tabs[0],
+                group_tabs[1],
+                SplitOrientation::from_name(&saved_group.orientation),
+            );
+            self.split_view
+                .set_group_split_ratio(group_id, saved_group.split_ratio);
+        }
+
+        for saved_group in &state.tab_groups {
+            let group_tabs: Vec<usize> = saved_group
+                .tab_indices
+                .iter()
+                .filter_map(|&index| restored_ids.get(index).copied().flatten())
+                .filter(|&tab_id| !self.is_tab_pinned(tab_id))
+                .collect();
+            if group_tabs.is_empty() {
+                continue;
+            }
+
+            let group_id = self
+                .tab_groups
+                .create_group(Some(&saved_group.name), Some(&saved_group.color));
+            for tab_id in group_tabs {
+                self.tab_groups.add_tab(tab_id, group_id);
+            }
+            self.tab_groups.set_collapsed(group_id, saved_group.collapsed);
+        }
+        if !state.tab_groups.is_empty() {
+            self.tab_order = self.tab_groups.group_tab_order(&self.tab_order);
+            self.sync_tab_order();
+        }
+
+        let active_tab_id = state
+            .active_tab_index
+            .and_then(|index| restored_ids.get(index).copied().flatten())
+            .or_else(|| restored_ids.iter().flatten().next().copied());
+
+        if let Some(tab_id) = active_tab_id {
+            self.switch_to_tab(tab_id);
+            self.update_split_view_layout(window, None);
+        }
+
+        if let Some(ref webview) = self.tab_bar_webview {
+            let _ = webview.evaluate_script(&self.get_split_ui_script());
+            let _ = webview.evaluate_script("window.refreshTabs();");
+        }
+
+        restored_ids.iter().flatten().count()
+    }
+
+    /// Pushes a tab that is about to be closed onto the recently closed stack.
+    fn record_closed_tab(&self, tab_id: usize) {
+        let Some(tab) = self.tabs.get(&tab_id) else {
+            return;
+        };
+
+        let urls = self
+            .current_urls
+            .lock()
+            .map(|urls| urls.clone())
+            .unwrap_or_default();
+        let url = urls
+            .get(&tab_id)
+            .cloned()
+            .unwrap_or_else(|| tab.get_url().to_string());
+        if !is_restorable_url(&url) {
+            return;
+        }
+
+        let split = self.split_view.get_group_for_tab(tab_id).and_then(|group| {
+            let pane_ids = group.tab_ids();
+            let was_primary = pane_ids.first() == Some(&tab_id);
+            let partner_tab_id = pane_ids.into_iter().find(|&id| id != tab_id)?;
+            Some(ClosedSplitState {
+                partner_tab_id,
+                partner_url: urls.get(&partner_tab_id).cloned().unwrap_or_default(),
+                was_primary,
+                orientation: group.orientation().as_str().to_string(),
+                split_ratio: group.split_ratio(),
+            })
+        });
+
+        let closed_tab = ClosedTab {
+            url,
+            title: self
+                .current_titles
+                .lock()
+                .ok()
+                .and_then(|titles| titles.get(&tab_id).cloned()),
+            favicon: self
+                .current_favicons
+                .lock()
+                .ok()
+                .and_then(|favicons| favicons.get(&tab_id).cloned()),
+            index: self
+                .tab_order
+                .iter()
+                .position(|&id| id == tab_id)
+                .unwrap_or(self.tab_order.len()),
+            split,
+            pinned: tab.pinned,
+            container: tab.container.clone(),
+        };
+
+        recently_closed::update_recently_closed(|store| store.push_tab(closed_tab));
+    }
+
+    /// Reopens an entry from the recently closed stack, or the most recent one when `entry_id` is `None`.
+    /// Closed windows are handed to the event loop to be rebuilt.
+    /// Returns whether there was anything to reopen.
+    pub fn reopen_closed(&mut self, window: &Window, entry_id: Option<u64>) -> bool {
+        let entry = recently_closed::update_recently_closed(|store| match entry_id {
+            Some(id) => store.take(id),
+            None => store.pop_latest(),
+        });
+
+        match entry.map(|entry| entry.item) {
+            Some(ClosedItem::Tab(closed_tab)) => self.reopen_closed_tab(window, &closed_tab).is_some(),
+            Some(ClosedItem::Window(window_state)) => {
+                crate::window::push_window_request(WindowRequest::ReopenClosedWindow(window_state));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Recreates a closed tab at its original position, rejoining its split group if the
+    /// other pane is still open and unpaired. Returns the new tab ID.
+    pub fn reopen_closed_tab(&mut self, window: &Window, closed_tab: &ClosedTab) -> Option<usize> {
+        let tab_id = match self.create_tab_in_container(window, &closed_tab.url, closed_tab.container.clone()) {
+            Ok(tab_id) => tab_id,
+            Err(e) => {
+                debug_log!("Failed to reopen closed tab {}: {:?}", closed_tab.url, e);
+                return None;
+            }
+        };
+
+        self.tab_order.retain(|&id| id != tab_id);
+        let pinned_count = self.pinned_count();
+        let index = if closed_tab.pinned {
+            closed_tab.index.min(pinned_count)
+        } else {
+            closed_tab.index.clamp(pinned_count, self.tab_order.len())
+        };
+        self.tab_order.insert(index, tab_id);
+        if let Some(tab) = self.tabs.get_mut(&tab_id) {
+            tab.pinned = closed_tab.pinned;
+        }
+
+        let escaped_url =
+            serde_json::to_string(&closed_tab.url).unwrap_or_else(|_| "\"\"".to_string());
+        let mut script = format!("window.addTab({}, {}, {});", tab_id, escaped_url, index);
+
+        if let Some(ref title) = closed_tab.title {
+            if let Ok(mut titles) = self.current_titles.lock() {
+                titles.insert(tab_id, title.clone());
+            }
+            script.push_str(&format!(
+                " window.updateTabTitle({}, {});",
+                tab_id,
+                serde_json::to_string(title).unwrap_or_else(|_| "\"\"".to_string())
+            ));
+        }
+        if let Some(ref favicon) = closed_tab.favicon {
+            if let Ok(mut favicons) = self.current_favicons.lock() {
+                favicons.insert(tab_id, favicon.clone());
+            }
+            script.push_str(&format!(
+                " window.updateTabFavicon({}, {});",
+                tab_id,
+                serde_json::to_string(favicon).unwrap_or_else(|_| "\"\"".to_string())
+            ));
+        }
+
+        if closed_tab.pinned {
+            script.push_str(&format!(" window.setTabPinned({}, true, {});", tab_id, index));
+        }
+
+        if let Some(ref webview) = self.tab_bar_webview {
+            let _ = webview.evaluate_script(&script);
+        }
+
+        if let Some(ref split) = closed_tab.split {
+            let urls = self
+                .current_urls
+                .lock()
+                .map(|urls| urls.clone())
+                .unwrap_or_default();
+            let is_partner = |id: &usize| *id != tab_id && urls.get(id) == Some(&split.partner_url);
+
+            let partner_tab_id = Some(split.partner_tab_id)
+                .filter(is_partner)
+                .or_else(|| self.tab_order.iter().copied().find(is_partner))
+                .filter(|&id| !self.split_view.is_tab_in_group(id));
+
+            if let Some(partner_tab_id) = partner_tab_id {
+                let (primary, secondary) = if split.was_primary {
+                    (tab_id, partner_tab_id)
+                } else {
+                    (partner_tab_id, tab_id)
+                };
+                let group_id = self.split_view.create_group(
+                    primary,
+                    secondary,
+                    SplitOrientation::from_name(&split.orientation),
+                );
+                self.split_view
+                    .set_group_split_ratio(group_id, split.split_ratio);
+            }
+        }
+
+        self.switch_to_tab(tab_id);
+        self.update_split_view_layout(window, None);
+
+        if let Some(ref webview) = self.tab_bar_webview {
+            let _ = webview.evaluate_script(&self.get_split_ui_script());
+            let _ = webview.evaluate_script("window.refreshTabs();");
+        }
+
+        Some(tab_id)
+    }
+
     /// Returns JSON representation of all split groups.
     pub fn get_split_groups_json(&self) -> String {
         self.split_view.get_split_groups_json()
@@ -1343,11 +3442,12 @@ console.log('[INIT] Console override installed');
     pub fn get_split_view_state(&self) -> (bool, Option<usize>, Option<usize>, String) {
         if let Some(active_id) = self.active_tab_id {
             if let Some(group) = self.split_view.get_group_for_tab(active_id) {
+                let pane_ids = group.tab_ids();
                 return (
                     true,
-                    Some(group.primary_tab_id),
-                    Some(group.secondary_tab_id),
-                    group.orientation.as_str().to_string(),
+                    pane_ids.first().copied(),
+                    pane_ids.get(1).copied(),
+                    group.orientation().as_str().to_string(),
                 );
             }
         }
@@ -1364,7 +3464,8 @@ console.log('[INIT] Console override installed');
         }
     }
 
-    /// Swaps the primary and secondary panes in the active tab's split group.
+    /// Reverses the order of the panes in the active tab's split group.
+    /// With two panes this swaps them.
     pub fn swap_split_panes(&mut self, window: &Window) {
         if let Some(active_id) = self.active_tab_id {
             if let Some(group_id) = self.split_view.get_group_id_for_tab(active_id) {
@@ -1374,6 +3475,93 @@ console.log('[INIT] Console override installed');
         }
     }
 
+    /// Adds the next tab that is not in a split to the active tab's split group, creating one
+    /// if the active tab is not split yet. Returns false if there is no tab to add or the
+    /// group already has the maximum number of panes.
+    pub fn add_split_pane(&mut self, window: &Window) -> bool {
+        let Some(active_tab_id) = self.active_tab_id else {
+            return false;
+        };
+        let Some(group_id) = self.split_view.get_group_id_for_tab(active_tab_id) else {
+            return self.toggle_split_view(window);
+        };
+
+        let non_grouped = self.split_view.get_non_grouped_tabs(&self.tab_order);
+        let Some(&tab_id) = non_grouped.first() else {
+            return false;
+        };
+        if !self.split_view.add_tab_to_group(group_id, tab_id) {
+            return false;
+        }
+
+        self.ensure_tab_loaded(tab_id);
+        self.update_split_view_layout(window, None);
+        true
+    }
+
+    /// Re-tiles the active tab's split group with a layout.
+    pub fn set_split_layout(&mut self, window: &Window, layout: SplitLayout) {
+        if let Some(active_id) = self.active_tab_id {
+            if let Some(group_id) = self.split_view.get_group_id_for_tab(active_id) {
+                self.split_view.set_group_layout(group_id, layout);
+                self.update_split_view_layout(window, None);
+            }
+        }
+    }
+
+    /// Moves focus from a split pane to its neighbour in `direction`, making it the active tab.
+    /// Returns whether focus moved.
+    pub fn focus_split_pane(
+        &mut self,
+        window: &Window,
+        from_tab_id: usize,
+        direction: PaneDirection,
+    ) -> bool {
+        let Some(group) = self.split_view.get_group_for_tab(from_tab_id) else {
+            return false;
+        };
+        let bounds = group.calculate_bounds(window, self.tab_sidebar_width, None);
+        let Some(tab_id) = neighbor_pane(&bounds.panes, from_tab_id, direction) else {
+            return false;
+        };
+
+        self.switch_to_tab(tab_id);
+        if let Some(webview) = self.tabs.get(&tab_id).and_then(|tab| tab.webview()) {
+            let _ = webview.focus();
+        }
+        true
+    }
+
+    /// Handles a drag on one of the split divider overlays. While a drag is in progress the
+    /// overlay covers the divider's whole split, so `x` and `y` are fractions of the split area.
+    pub fn drag_split_divider(&mut self, window: &Window, divider_index: usize, phase: &str, x: f64, y: f64) {
+        let Some(divider) = self.active_dividers.get(divider_index).cloned() else {
+            return;
+        };
+        let Some(group_id) = self
+            .active_tab_id
+            .and_then(|active_id| self.split_view.get_group_id_for_tab(active_id))
+        else {
+            return;
+        };
+
+        match phase {
+            "start" => self.dragging_divider = Some(divider_index),
+            "move" if self.dragging_divider == Some(divider_index) => {
+                let fraction = match divider.orientation {
+                    SplitOrientation::Vertical => x,
+                    SplitOrientation::Horizontal => y,
+                };
+                self.split_view
+                    .move_group_divider(group_id, &divider.path, divider.index, fraction as f32);
+            }
+            "end" => self.dragging_divider = None,
+            _ => return,
+        }
+
+        self.update_split_view_layout(window, None);
+    }
+
     /// Updates the layout and bounds of split view panes.
     /// Only shows the active tab or its split group, hiding all other tabs.
     pub fn update_split_view_layout(
@@ -1386,24 +3574,24 @@ console.log('[INIT] Console override installed');
         };
 
         if let Some(group) = self.split_view.get_group_for_tab(active_tab_id) {
-            let (primary_bounds, secondary_bounds) =
+            let bounds =
                 group.calculate_bounds(window, self.tab_sidebar_width, download_sidebar_width);
 
             for (tab_id, tab) in &self.tabs {
-                if group.primary_tab_id == *tab_id {
-                    if let Some(webview) = tab.webview() {
-                        let _ = webview.set_bounds(primary_bounds);
-                    }
-                    tab.show();
-                } else if group.secondary_tab_id == *tab_id {
-                    if let Some(webview) = tab.webview() {
-                        let _ = webview.set_bounds(secondary_bounds);
+                match bounds.panes.iter().find(|(pane_id, _)| pane_id == tab_id) {
+                    Some((_, rect)) => {
+                        if let Some(webview) = tab.webview() {
+                            let _ = webview.set_bounds(rect.to_wry_rect());
+                            let _ = webview.evaluate_script("window.calmInSplitView = true;");
+                        }
+                        tab.show();
                     }
-                    tab.show();
-                } else {
-                    tab.hide();
+                    None => tab.hide(),
                 }
             }
+
+            let pane_ids: Vec<usize> = bounds.panes.iter().map(|(tab_id, _)| *tab_id).collect();
+            self.sync_split_dividers(window, &pane_ids, bounds.dividers);
         } else {
             for (tab_id, tab) in &self.tabs {
                 if *tab_id == active_tab_id {
@@ -1416,6 +3604,102 @@ console.log('[INIT] Console override installed');
         }
     }
 
+    /// Removes the divider overlays once the active tab is no longer split.
+    fn leave_split_view(&mut self) {
+        self.split_dividers.clear();
+        self.active_dividers.clear();
+        self.dragging_divider = None;
+        self.dividers_built_at = None;
+        if let Some(webview) = self.get_active_tab_webview() {
+            let _ = webview.evaluate_script("window.calmInSplitView = false;");
+        }
+    }
+
+    /// Places an overlay on each divider of the visible split group. The overlays are rebuilt
+    /// when their number changes or a pane webview was built after them, so they stay on top.
+    fn sync_split_dividers(&mut self, window: &Window, pane_ids: &[usize], dividers: Vec<SplitDivider>) {
+        let panes_built_later = self.dividers_built_at.is_some_and(|built_at| {
+            pane_ids.iter().any(|tab_id| {
+                self.webview_created
+                    .get(tab_id)
+                    .is_some_and(|created| *created > built_at)
+            })
+        });
+        if self.split_dividers.len() != dividers.len() || panes_built_later {
+            self.split_dividers.clear();
+            self.dividers_built_at = None;
+            for index in 0..dividers.len() {
+                match self.build_divider_webview(window, index) {
+                    Ok(webview) => self.split_dividers.push(webview),
+                    Err(e) => {
+                        debug_log!("Failed to build split divider: {:?}", e);
+                        self.split_dividers.clear();
+                        break;
+                    }
+                }
+            }
+            if !self.split_dividers.is_empty() {
+                self.dividers_built_at = Some(Instant::now());
+            }
+        }
+
+        if self
+            .dragging_divider
+            .is_some_and(|index| index >= self.split_dividers.len())
+        {
+            self.dragging_divider = None;
+        }
+
+        let handle_width = (6.0 * window.scale_factor()) as u32;
+        for (index, (webview, divider)) in self.split_dividers.iter().zip(&dividers).enumerate() {
+            let expanded = self.dragging_divider == Some(index);
+            let rect = if expanded {
+                divider.area
+            } else {
+                divider.handle(handle_width)
+            };
+            let _ = webview.set_bounds(rect.to_wry_rect());
+            let _ = webview.evaluate_script(&format!(
+                "if (window.setDividerOrientation) {{ window.setDividerOrientation('{}'); window.setDividerExpanded({}); }}",
+                divider.orientation.as_str(),
+                expanded
+            ));
+        }
+
+        self.active_dividers = dividers;
+    }
+
+    /// Builds a transparent overlay for the divider at `index`. Its drag messages are relayed
+    /// through the tab bar like those of the tabs.
+    fn build_divider_webview(&self, window: &Window, index: usize) -> Result<std::rc::Rc<WebView>, wry::Error> {
+        let tab_bar_for_ipc = self.tab_bar_webview.clone();
+
+        let webview = WebViewBuilder::new()
+            .with_html(crate::ui::get_split_divider_html())
+            .with_transparent(true)
+            .with_ipc_handler(move |request| {
+                let body = request.body();
+                if let Ok(data) = serde_json::from_str::<serde_json::Value>(body) {
+                    if data["action"].as_str() == Some("split_divider_drag") {
+                        if let Some(ref webview) = tab_bar_for_ipc {
+                            let phase = data["phase"].as_str().unwrap_or("");
+                            let script = format!(
+                                "window.ipcMessageToWindow = {{ action: 'split_divider_drag', divider: {}, phase: {}, x: {}, y: {} }};",
+                                index,
+                                serde_json::to_string(phase).unwrap_or_else(|_| "\"\"".to_string()),
+                                data["x"].as_f64().unwrap_or(0.0),
+                                data["y"].as_f64().unwrap_or(0.0)
+                            );
+                            let _ = webview.evaluate_script(&script);
+                        }
+                    }
+                }
+            })
+            .build_as_child(window)?;
+
+        Ok(std::rc::Rc::new(webview))
+    }
+
     pub fn get_download_history(&self) -> crate::downloads::DownloadHistory {
         self.download_manager.get_history().lock().unwrap().clone()
     }
@@ -1,4 +1,10 @@
+pub mod containers;
+pub mod groups;
 pub mod manager;
+pub mod order;
+pub mod private_data;
+pub mod profile_data;
+pub mod recently_closed;
 pub mod split_view;
 pub mod tab;
 
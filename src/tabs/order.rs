@@ -0,0 +1,81 @@
+/// Moves `tab_id` to where the pinned tabs at the top of `order` end: the last pinned tab when
+/// `tab_id` is pinned, the first unpinned tab otherwise. Returns the index it was put at.
+pub fn move_to_pinned_edge(
+    order: &mut Vec<usize>,
+    tab_id: usize,
+    is_pinned: impl Fn(usize) -> bool,
+) -> usize {
+    order.retain(|&id| id != tab_id);
+    let index = pinned_count(order, is_pinned);
+    order.insert(index, tab_id);
+    index
+}
+
+/// Moves `tab_id` to `to_index` in `order`, clamped to the tab's own section so pinned tabs stay
+/// above unpinned ones. Returns the index it was put at.
+pub fn move_within_section(
+    order: &mut Vec<usize>,
+    tab_id: usize,
+    to_index: usize,
+    pinned: bool,
+    is_pinned: impl Fn(usize) -> bool,
+) -> usize {
+    order.retain(|&id| id != tab_id);
+    let pinned_count = pinned_count(order, is_pinned);
+    let index = if pinned {
+        to_index.min(pinned_count)
+    } else {
+        to_index.clamp(pinned_count, order.len())
+    };
+    order.insert(index, tab_id);
+    index
+}
+
+fn pinned_count(order: &[usize], is_pinned: impl Fn(usize) -> bool) -> usize {
+    order.iter().filter(|&&id| is_pinned(id)).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinning_moves_tab_to_end_of_pinned_tabs() {
+        let pinned = [1, 2, 5];
+        let is_pinned = |id| pinned.contains(&id);
+        let mut order = vec![1, 2, 3, 4, 5];
+        assert_eq!(move_to_pinned_edge(&mut order, 5, is_pinned), 2);
+        assert_eq!(order, [1, 2, 5, 3, 4]);
+    }
+
+    #[test]
+    fn test_unpinning_moves_tab_to_top_of_unpinned_tabs() {
+        let pinned = [2];
+        let is_pinned = |id| pinned.contains(&id);
+        let mut order = vec![1, 2, 3, 4];
+        assert_eq!(move_to_pinned_edge(&mut order, 1, is_pinned), 1);
+        assert_eq!(order, [2, 1, 3, 4]);
+    }
+
+    #[test]
+    fn test_pinned_tab_stays_in_pinned_section() {
+        let pinned = [1, 2];
+        let is_pinned = |id| pinned.contains(&id);
+        let mut order = vec![1, 2, 3, 4];
+        assert_eq!(move_within_section(&mut order, 1, 3, true, is_pinned), 1);
+        assert_eq!(order, [2, 1, 3, 4]);
+        assert_eq!(move_within_section(&mut order, 1, 0, true, is_pinned), 0);
+        assert_eq!(order, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_unpinned_tab_stays_below_pinned_tabs() {
+        let pinned = [1, 2];
+        let is_pinned = |id| pinned.contains(&id);
+        let mut order = vec![1, 2, 3, 4];
+        assert_eq!(move_within_section(&mut order, 4, 0, false, is_pinned), 2);
+        assert_eq!(order, [1, 2, 4, 3]);
+        assert_eq!(move_within_section(&mut order, 4, 99, false, is_pinned), 3);
+        assert_eq!(order, [1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::debug_log;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wry::WebContext;
+
+static NEXT_DATA_DIRECTORY: AtomicUsize = AtomicUsize::new(1);
+
+/// Web data of a private window. Its webviews share a web context whose data directory lives in
+/// the profile's temporary private data directory and is deleted when the window is dropped.
+pub struct PrivateData {
+    // Declared first so the web context is dropped before its directory is deleted.
+    web_context: WebContext,
+    data_directory: DataDirectory,
+}
+
+impl PrivateData {
+    /// Creates a web context backed by a fresh, empty data directory.
+    pub fn new() -> Self {
+        let data_directory = DataDirectory::create_in(&crate::paths::private_data_dir());
+
+        Self {
+            web_context: WebContext::new(Some(data_directory.path.clone())),
+            data_directory,
+        }
+    }
+
+    pub fn web_context(&mut self) -> &mut WebContext {
+        &mut self.web_context
+    }
+}
+
+impl Default for PrivateData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A private window's data directory, deleted when dropped.
+struct DataDirectory {
+    path: PathBuf,
+}
+
+impl DataDirectory {
+    /// Creates a fresh, empty directory in `parent`.
+    fn create_in(parent: &Path) -> Self {
+        let path = parent.join(format!(
+            "{}-{}",
+            std::process::id(),
+            NEXT_DATA_DIRECTORY.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&path);
+        if let Err(e) = fs::create_dir_all(&path) {
+            debug_log!("Failed to create private data directory {}: {}", path.display(), e);
+        }
+        Self { path }
+    }
+}
+
+impl Drop for DataDirectory {
+    fn drop(&mut self) {
+        match fs::remove_dir_all(&self.path) {
+            Ok(()) => debug_log!("Deleted private data directory {}", self.path.display()),
+            Err(e) => debug_log!("Failed to delete private data directory {}: {}", self.path.display(), e),
+        }
+    }
+}
+
+/// Deletes the data directories private windows left behind when Calm did not exit cleanly.
+/// Only the running instance of a profile runs this, and each profile has its own directory, so
+/// no open private window can be using them.
+pub fn remove_leftover_data() {
+    remove_leftover_data_in(&crate::paths::private_data_dir());
+}
+
+fn remove_leftover_data_in(private_data_dir: &Path) {
+    let Ok(entries) = fs::read_dir(private_data_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if fs::remove_dir_all(entry.path()).is_ok() {
+            debug_log!("Deleted leftover private data directory {}", entry.path().display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("calm-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_data_directory_is_deleted_on_drop() {
+        let parent = scratch_dir("private-drop");
+        let directory = DataDirectory::create_in(&parent);
+        let path = directory.path.clone();
+        fs::create_dir_all(path.join("storage")).unwrap();
+        fs::write(path.join("storage/cookies.sqlite"), "cookies").unwrap();
+
+        drop(directory);
+        assert!(!path.exists());
+        assert!(parent.exists());
+        let _ = fs::remove_dir_all(parent);
+    }
+
+    #[test]
+    fn test_leftover_data_is_removed() {
+        let parent = scratch_dir("private-leftover");
+        fs::create_dir_all(parent.join("1234-1/storage")).unwrap();
+        fs::write(parent.join("1234-1/storage/cookies.sqlite"), "cookies").unwrap();
+        fs::create_dir_all(parent.join("1234-2")).unwrap();
+
+        remove_leftover_data_in(&parent);
+        assert_eq!(fs::read_dir(&parent).unwrap().count(), 0);
+        let _ = fs::remove_dir_all(parent);
+
+        // A profile that never opened a private window has nothing to remove.
+        remove_leftover_data_in(&std::env::temp_dir().join("calm-test-missing-private-data"));
+    }
+}
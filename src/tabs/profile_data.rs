@@ -0,0 +1,20 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wry::WebContext;
+
+thread_local! {
+    static PROFILE_CONTEXT: RefCell<Option<Rc<RefCell<WebContext>>>> = const { RefCell::new(None) };
+}
+
+/// Returns the web context shared by the regular windows of a named profile, which keeps their
+/// cookies, storage and cache in the profile's directory. The default profile keeps using the
+/// WebView's default data store and gets `None`.
+pub fn profile_web_context() -> Option<Rc<RefCell<WebContext>>> {
+    let data_directory = crate::paths::web_data_dir()?;
+    PROFILE_CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        let context = context
+            .get_or_insert_with(|| Rc::new(RefCell::new(WebContext::new(Some(data_directory)))));
+        Some(Rc::clone(context))
+    })
+}
@@ -0,0 +1,244 @@
+use crate::session::WindowState;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+
+/// Maximum number of closed tabs and windows kept on the stack.
+const MAX_ENTRIES: usize = 25;
+
+/// The split group a closed tab was part of, identified by the pane it was paired with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosedSplitState {
+    pub partner_tab_id: usize,
+    pub partner_url: String,
+    pub was_primary: bool,
+    pub orientation: String,
+    pub split_ratio: f32,
+}
+
+/// A closed tab with enough information to put it back where it was.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosedTab {
+    pub url: String,
+    pub title: Option<String>,
+    pub favicon: Option<String>,
+    /// Position of the tab in the tab bar when it was closed.
+    pub index: usize,
+    pub split: Option<ClosedSplitState>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClosedItem {
+    Tab(ClosedTab),
+    Window(WindowState),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosedEntry {
+    pub id: u64,
+    pub closed_at: i64,
+    pub item: ClosedItem,
+}
+
+/// Bounded stack of recently closed tabs and windows, newest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecentlyClosed {
+    entries: Vec<ClosedEntry>,
+    #[serde(default)]
+    next_id: u64,
+}
+
+thread_local! {
+    static RECENTLY_CLOSED: RefCell<RecentlyClosed> = RefCell::new(RecentlyClosed::load());
+}
+
+/// Runs `f` against the recently closed stack shared by all windows.
+pub fn with_recently_closed<R>(f: impl FnOnce(&RecentlyClosed) -> R) -> R {
+    RECENTLY_CLOSED.with(|store| f(&store.borrow()))
+}
+
+/// Runs `f` to change the recently closed stack shared by all windows, then saves it.
+pub fn update_recently_closed<R>(f: impl FnOnce(&mut RecentlyClosed) -> R) -> R {
+    RECENTLY_CLOSED.with(|store| {
+        let mut store = store.borrow_mut();
+        let result = f(&mut store);
+        let _ = store.save();
+        result
+    })
+}
+
+impl RecentlyClosed {
+    pub fn load() -> Self {
+        let path = Self::get_path();
+
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_yaml::from_str::<RecentlyClosed>(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::get_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let yaml = serde_yaml::to_string(self)?;
+        fs::write(path, yaml)?;
+        Ok(())
+    }
+
+    pub fn push_tab(&mut self, tab: ClosedTab) {
+        self.push(ClosedItem::Tab(tab));
+    }
+
+    pub fn push_window(&mut self, window: WindowState) {
+        self.push(ClosedItem::Window(window));
+    }
+
+    fn push(&mut self, item: ClosedItem) {
+        let closed_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        self.next_id += 1;
+        self.entries.insert(
+            0,
+            ClosedEntry {
+                id: self.next_id,
+                closed_at,
+                item,
+            },
+        );
+        self.entries.truncate(MAX_ENTRIES);
+    }
+
+    /// Removes and returns the most recently closed entry.
+    pub fn pop_latest(&mut self) -> Option<ClosedEntry> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        Some(self.entries.remove(0))
+    }
+
+    /// Removes and returns the entry with the given id.
+    pub fn take(&mut self, id: u64) -> Option<ClosedEntry> {
+        let position = self.entries.iter().position(|entry| entry.id == id)?;
+        Some(self.entries.remove(position))
+    }
+
+    pub fn get_recent(&self, limit: usize) -> Vec<ClosedEntry> {
+        self.entries.iter().take(limit).cloned().collect()
+    }
+
+    fn get_path() -> PathBuf {
+        crate::paths::data_dir().join("recently_closed.yml")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab(url: &str) -> ClosedTab {
+        ClosedTab {
+            url: url.to_string(),
+            title: None,
+            favicon: None,
+            index: 0,
+            split: None,
+            pinned: false,
+            container: None,
+        }
+    }
+
+    fn url_of(entry: &ClosedEntry) -> &str {
+        match &entry.item {
+            ClosedItem::Tab(tab) => &tab.url,
+            ClosedItem::Window(_) => "",
+        }
+    }
+
+    #[test]
+    fn test_push_keeps_the_newest_entries() {
+        let mut store = RecentlyClosed::default();
+        for i in 0..MAX_ENTRIES + 3 {
+            store.push_tab(tab(&format!("https://example.com/{}", i)));
+        }
+
+        let recent = store.get_recent(usize::MAX);
+        assert_eq!(recent.len(), MAX_ENTRIES);
+        assert_eq!(
+            url_of(&recent[0]),
+            format!("https://example.com/{}", MAX_ENTRIES + 2)
+        );
+        assert_eq!(url_of(&recent[MAX_ENTRIES - 1]), "https://example.com/3");
+    }
+
+    #[test]
+    fn test_pop_and_take_order() {
+        let mut store = RecentlyClosed::default();
+        store.push_tab(tab("https://a.example"));
+        store.push_tab(tab("https://b.example"));
+        store.push_tab(tab("https://c.example"));
+        let first_id = store.get_recent(usize::MAX)[2].id;
+
+        assert_eq!(url_of(&store.take(first_id).unwrap()), "https://a.example");
+        assert!(store.take(first_id).is_none());
+        assert_eq!(url_of(&store.pop_latest().unwrap()), "https://c.example");
+        assert_eq!(url_of(&store.pop_latest().unwrap()), "https://b.example");
+        assert!(store.pop_latest().is_none());
+    }
+
+    #[test]
+    fn test_closed_tab_keeps_split_pinned_and_container() {
+        let mut store = RecentlyClosed::default();
+        store.push_tab(ClosedTab {
+            index: 2,
+            split: Some(ClosedSplitState {
+                partner_tab_id: 7,
+                partner_url: "https://b.example".to_string(),
+                was_primary: true,
+                orientation: "horizontal".to_string(),
+                split_ratio: 0.4,
+            }),
+            pinned: true,
+            container: Some("work".to_string()),
+            ..tab("https://a.example")
+        });
+
+        let yaml = serde_yaml::to_string(&store).unwrap();
+        let mut store: RecentlyClosed = serde_yaml::from_str(&yaml).unwrap();
+        let Some(ClosedItem::Tab(restored)) = store.pop_latest().map(|entry| entry.item) else {
+            panic!("expected a closed tab");
+        };
+        assert_eq!(restored.index, 2);
+        assert!(restored.pinned);
+        assert_eq!(restored.container.as_deref(), Some("work"));
+        let split = restored.split.unwrap();
+        assert_eq!(split.partner_tab_id, 7);
+        assert_eq!(split.partner_url, "https://b.example");
+        assert!(split.was_primary);
+        assert_eq!(split.orientation, "horizontal");
+        assert_eq!(split.split_ratio, 0.4);
+    }
+
+    #[test]
+    fn test_tabs_saved_before_pinning_load_unpinned() {
+        let yaml = "entries:\n- id: 1\n  closed_at: 0\n  item:\n    kind: tab\n    url: https://a.example\n    title: null\n    favicon: null\n    index: 0\n    split: null\nnext_id: 1\n";
+        let mut store: RecentlyClosed = serde_yaml::from_str(yaml).unwrap();
+        let Some(ClosedItem::Tab(restored)) = store.pop_latest().map(|entry| entry.item) else {
+            panic!("expected a closed tab");
+        };
+        assert!(!restored.pinned);
+        assert_eq!(restored.container, None);
+    }
+}
@@ -1,6 +1,13 @@
+use crate::session::SplitNodeState;
 use std::collections::HashMap;
 use tao::window::Window;
 
+/// Maximum number of tabs shown side by side in one split group.
+pub const MAX_SPLIT_PANES: usize = 4;
+
+/// Smallest share of a split a pane can be resized to.
+const MIN_PANE_RATIO: f32 = 0.1;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SplitOrientation {
     Horizontal,
@@ -14,16 +21,379 @@ impl SplitOrientation {
             SplitOrientation::Vertical => "vertical",
         }
     }
+
+    /// Parses an orientation name as produced by `as_str`, defaulting to vertical.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "horizontal" => SplitOrientation::Horizontal,
+            _ => SplitOrientation::Vertical,
+        }
+    }
+
+    fn flipped(&self) -> Self {
+        match self {
+            SplitOrientation::Horizontal => SplitOrientation::Vertical,
+            SplitOrientation::Vertical => SplitOrientation::Horizontal,
+        }
+    }
+}
+
+/// How the panes of a split group are tiled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitLayout {
+    /// The first pane on one side, the others stacked on the other.
+    MainStack,
+    /// Panes in rows of two.
+    Grid,
+}
+
+impl SplitLayout {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SplitLayout::MainStack => "main_stack",
+            SplitLayout::Grid => "grid",
+        }
+    }
+
+    /// Parses a layout name as produced by `as_str`, defaulting to main and stack.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "grid" => SplitLayout::Grid,
+            _ => SplitLayout::MainStack,
+        }
+    }
+}
+
+/// A rectangle in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaneRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PaneRect {
+    pub fn to_wry_rect(self) -> wry::Rect {
+        wry::Rect {
+            position: tao::dpi::PhysicalPosition::new(self.x as i32, self.y as i32).into(),
+            size: tao::dpi::PhysicalSize::new(self.width, self.height).into(),
+        }
+    }
+
+    fn center(&self) -> (f32, f32) {
+        (
+            self.x as f32 + self.width as f32 / 2.0,
+            self.y as f32 + self.height as f32 / 2.0,
+        )
+    }
+
+    /// Start and length of the rectangle along the axis a split of `orientation` divides.
+    fn span(&self, orientation: SplitOrientation) -> (u32, u32) {
+        match orientation {
+            SplitOrientation::Vertical => (self.x, self.width),
+            SplitOrientation::Horizontal => (self.y, self.height),
+        }
+    }
+}
+
+/// Direction to move pane focus in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneDirection {
+    Left,
+    Down,
+    Up,
+    Right,
+}
+
+impl PaneDirection {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "left" | "h" => Some(PaneDirection::Left),
+            "down" | "j" => Some(PaneDirection::Down),
+            "up" | "k" => Some(PaneDirection::Up),
+            "right" | "l" => Some(PaneDirection::Right),
+            _ => None,
+        }
+    }
+}
+
+/// A node of a split group's tiling tree: a tab, or a split dividing its area between children.
+/// `ratios` holds each child's share of the split and sums to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SplitNode {
+    Pane(usize),
+    Split {
+        orientation: SplitOrientation,
+        ratios: Vec<f32>,
+        children: Vec<SplitNode>,
+    },
+}
+
+impl SplitNode {
+    /// Tiles tabs according to a layout. `main_ratio` is the share of the first split.
+    pub fn tiled(
+        layout: SplitLayout,
+        orientation: SplitOrientation,
+        tab_ids: &[usize],
+        main_ratio: f32,
+    ) -> Self {
+        match tab_ids {
+            [] => SplitNode::Split {
+                orientation,
+                ratios: Vec::new(),
+                children: Vec::new(),
+            },
+            [tab_id] => SplitNode::Pane(*tab_id),
+            [first, rest @ ..] if layout == SplitLayout::MainStack || tab_ids.len() == 2 => {
+                let main_ratio = main_ratio.clamp(MIN_PANE_RATIO, 1.0 - MIN_PANE_RATIO);
+                SplitNode::Split {
+                    orientation,
+                    ratios: vec![main_ratio, 1.0 - main_ratio],
+                    children: vec![
+                        SplitNode::Pane(*first),
+                        Self::even(orientation.flipped(), rest),
+                    ],
+                }
+            }
+            _ => {
+                let rows: Vec<SplitNode> = tab_ids
+                    .chunks(2)
+                    .map(|row| Self::even(orientation, row))
+                    .collect();
+                SplitNode::Split {
+                    orientation: orientation.flipped(),
+                    ratios: vec![1.0 / rows.len() as f32; rows.len()],
+                    children: rows,
+                }
+            }
+        }
+    }
+
+    /// Splits an area evenly between tabs.
+    fn even(orientation: SplitOrientation, tab_ids: &[usize]) -> Self {
+        if let [tab_id] = tab_ids {
+            return SplitNode::Pane(*tab_id);
+        }
+        SplitNode::Split {
+            orientation,
+            ratios: vec![1.0 / tab_ids.len() as f32; tab_ids.len()],
+            children: tab_ids.iter().map(|&tab_id| SplitNode::Pane(tab_id)).collect(),
+        }
+    }
+
+    /// Returns the tabs of the tree in reading order.
+    pub fn tab_ids(&self) -> Vec<usize> {
+        match self {
+            SplitNode::Pane(tab_id) => vec![*tab_id],
+            SplitNode::Split { children, .. } => {
+                children.iter().flat_map(|child| child.tab_ids()).collect()
+            }
+        }
+    }
+
+    /// Replaces the tabs of the tree, in reading order, keeping its shape.
+    fn assign_tab_ids(&mut self, tab_ids: &mut impl Iterator<Item = usize>) {
+        match self {
+            SplitNode::Pane(tab_id) => {
+                if let Some(new_id) = tab_ids.next() {
+                    *tab_id = new_id;
+                }
+            }
+            SplitNode::Split { children, .. } => {
+                for child in children {
+                    child.assign_tab_ids(tab_ids);
+                }
+            }
+        }
+    }
+
+    fn flip_orientation(&mut self) {
+        if let SplitNode::Split {
+            orientation,
+            children,
+            ..
+        } = self
+        {
+            *orientation = orientation.flipped();
+            for child in children {
+                child.flip_orientation();
+            }
+        }
+    }
+
+    fn node_at_mut(&mut self, path: &[usize]) -> Option<&mut SplitNode> {
+        match path.split_first() {
+            None => Some(self),
+            Some((&index, rest)) => match self {
+                SplitNode::Split { children, .. } => children.get_mut(index)?.node_at_mut(rest),
+                SplitNode::Pane(_) => None,
+            },
+        }
+    }
+
+    /// Lays the tree out in `area`, leaving `gap` pixels between neighbouring panes.
+    fn layout(
+        &self,
+        area: PaneRect,
+        gap: u32,
+        path: &mut Vec<usize>,
+        bounds: &mut SplitBounds,
+    ) {
+        let (orientation, ratios, children) = match self {
+            SplitNode::Pane(tab_id) => {
+                bounds.panes.push((*tab_id, area));
+                return;
+            }
+            SplitNode::Split {
+                orientation,
+                ratios,
+                children,
+            } => (*orientation, ratios, children),
+        };
+
+        let (start, length) = area.span(orientation);
+        let mut cumulative = 0.0;
+        let mut child_start = 0;
+
+        for (index, child) in children.iter().enumerate() {
+            let is_last = index + 1 == children.len();
+            cumulative += ratios.get(index).copied().unwrap_or(0.0);
+            let child_end = if is_last {
+                length
+            } else {
+                ((length as f32 * cumulative) as u32).min(length)
+            };
+            let child_length = if is_last {
+                child_end - child_start
+            } else {
+                (child_end - child_start).saturating_sub(gap)
+            };
+
+            let child_area = match orientation {
+                SplitOrientation::Vertical => PaneRect {
+                    x: start + child_start,
+                    width: child_length,
+                    ..area
+                },
+                SplitOrientation::Horizontal => PaneRect {
+                    y: start + child_start,
+                    height: child_length,
+                    ..area
+                },
+            };
+
+            path.push(index);
+            child.layout(child_area, gap, path, bounds);
+            path.pop();
+
+            if !is_last {
+                bounds.dividers.push(SplitDivider {
+                    path: path.clone(),
+                    index,
+                    orientation,
+                    area,
+                    position: start + child_end,
+                });
+            }
+            child_start = child_end;
+        }
+    }
+
+    /// Converts the tree to its session form, referencing tabs by index.
+    pub fn to_state(&self, index_by_tab_id: &HashMap<usize, usize>) -> Option<SplitNodeState> {
+        match self {
+            SplitNode::Pane(tab_id) => Some(SplitNodeState::Pane {
+                tab_index: *index_by_tab_id.get(tab_id)?,
+            }),
+            SplitNode::Split {
+                orientation,
+                ratios,
+                children,
+            } => Some(SplitNodeState::Split {
+                orientation: orientation.as_str().to_string(),
+                ratios: ratios.clone(),
+                children: children
+                    .iter()
+                    .map(|child| child.to_state(index_by_tab_id))
+                    .collect::<Option<Vec<_>>>()?,
+            }),
+        }
+    }
+
+    /// Rebuilds a tree from its session form. `tab_ids` maps saved tab indices to restored tabs.
+    pub fn from_state(state: &SplitNodeState, tab_ids: &[Option<usize>]) -> Option<Self> {
+        match state {
+            SplitNodeState::Pane { tab_index } => {
+                tab_ids.get(*tab_index).copied().flatten().map(SplitNode::Pane)
+            }
+            SplitNodeState::Split {
+                orientation,
+                ratios,
+                children,
+            } => {
+                if children.len() < 2 || ratios.len() != children.len() {
+                    return None;
+                }
+                Some(SplitNode::Split {
+                    orientation: SplitOrientation::from_name(orientation),
+                    ratios: ratios.clone(),
+                    children: children
+                        .iter()
+                        .map(|child| Self::from_state(child, tab_ids))
+                        .collect::<Option<Vec<_>>>()?,
+                })
+            }
+        }
+    }
+}
+
+/// A boundary between two neighbouring children of a split, which can be dragged to resize them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitDivider {
+    /// Child indices leading from the root to the split this divider belongs to.
+    pub path: Vec<usize>,
+    /// The divider sits between children `index` and `index + 1`.
+    pub index: usize,
+    pub orientation: SplitOrientation,
+    /// Area of the split the divider belongs to.
+    pub area: PaneRect,
+    /// Position of the divider along the split's axis, in physical pixels.
+    pub position: u32,
+}
+
+impl SplitDivider {
+    /// Returns the strip `width` pixels wide around the divider that can be grabbed to drag it.
+    pub fn handle(&self, width: u32) -> PaneRect {
+        let offset = self.position.saturating_sub(width / 2);
+        match self.orientation {
+            SplitOrientation::Vertical => PaneRect {
+                x: offset,
+                width,
+                ..self.area
+            },
+            SplitOrientation::Horizontal => PaneRect {
+                y: offset,
+                height: width,
+                ..self.area
+            },
+        }
+    }
+}
+
+/// Pane and divider placement of a split group.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SplitBounds {
+    pub panes: Vec<(usize, PaneRect)>,
+    pub dividers: Vec<SplitDivider>,
 }
 
 #[derive(Debug, Clone)]
 pub struct SplitGroup {
     #[allow(dead_code)]
     pub id: usize,
-    pub primary_tab_id: usize,
-    pub secondary_tab_id: usize,
-    pub orientation: SplitOrientation,
-    pub split_ratio: f32,
+    pub root: SplitNode,
+    pub layout: SplitLayout,
 }
 
 impl SplitGroup {
@@ -35,30 +405,92 @@ impl SplitGroup {
     ) -> Self {
         Self {
             id,
-            primary_tab_id,
-            secondary_tab_id,
-            orientation,
-            split_ratio: 0.5,
+            root: SplitNode::tiled(
+                SplitLayout::MainStack,
+                orientation,
+                &[primary_tab_id, secondary_tab_id],
+                0.5,
+            ),
+            layout: SplitLayout::MainStack,
+        }
+    }
+
+    /// Returns the tabs of the group in reading order.
+    pub fn tab_ids(&self) -> Vec<usize> {
+        self.root.tab_ids()
+    }
+
+    pub fn contains(&self, tab_id: usize) -> bool {
+        self.tab_ids().contains(&tab_id)
+    }
+
+    /// Orientation of the outermost split.
+    pub fn orientation(&self) -> SplitOrientation {
+        match &self.root {
+            SplitNode::Split { orientation, .. } => *orientation,
+            SplitNode::Pane(_) => SplitOrientation::Vertical,
         }
     }
 
+    /// Share of the first pane of the outermost split.
+    pub fn split_ratio(&self) -> f32 {
+        match &self.root {
+            SplitNode::Split { ratios, .. } => ratios.first().copied().unwrap_or(0.5),
+            SplitNode::Pane(_) => 1.0,
+        }
+    }
+
+    /// Re-tiles the group's tabs with a layout, keeping the outermost split's ratio.
+    fn retile(&mut self, layout: SplitLayout, tab_ids: &[usize]) {
+        self.layout = layout;
+        self.root = SplitNode::tiled(layout, self.orientation(), tab_ids, self.split_ratio());
+    }
+
     pub fn toggle_orientation(&mut self) {
-        self.orientation = match self.orientation {
-            SplitOrientation::Horizontal => SplitOrientation::Vertical,
-            SplitOrientation::Vertical => SplitOrientation::Horizontal,
-        };
+        self.root.flip_orientation();
     }
 
+    /// Reverses the order of the panes, keeping the layout. With two panes this swaps them.
     pub fn swap_panes(&mut self) {
-        std::mem::swap(&mut self.primary_tab_id, &mut self.secondary_tab_id);
+        let mut tab_ids = self.tab_ids();
+        tab_ids.reverse();
+        self.root.assign_tab_ids(&mut tab_ids.into_iter());
     }
 
+    /// Moves the divider between children `index` and `index + 1` of the split at `path` to
+    /// `fraction` of the split's length, keeping every pane above the minimum size.
+    pub fn move_divider(&mut self, path: &[usize], index: usize, fraction: f32) {
+        let Some(SplitNode::Split { ratios, .. }) = self.root.node_at_mut(path) else {
+            return;
+        };
+        if index + 1 >= ratios.len() {
+            return;
+        }
+
+        let before: f32 = ratios[..index].iter().sum();
+        let combined = ratios[index] + ratios[index + 1];
+        if combined < MIN_PANE_RATIO * 2.0 {
+            return;
+        }
+        let first = (fraction - before).clamp(MIN_PANE_RATIO, combined - MIN_PANE_RATIO);
+        ratios[index] = first;
+        ratios[index + 1] = combined - first;
+    }
+
+    /// Lays out the panes and dividers in `area`.
+    pub fn layout(&self, area: PaneRect, gap: u32) -> SplitBounds {
+        let mut bounds = SplitBounds::default();
+        self.root.layout(area, gap, &mut Vec::new(), &mut bounds);
+        bounds
+    }
+
+    /// Lays out the panes and dividers in the window's content area.
     pub fn calculate_bounds(
         &self,
         window: &Window,
         sidebar_width: u32,
         download_sidebar_width: Option<u32>,
-    ) -> (wry::Rect, wry::Rect) {
+    ) -> SplitBounds {
         let window_size = window.inner_size();
         let scale_factor = window.scale_factor();
         let download_width = download_sidebar_width.unwrap_or(0);
@@ -66,74 +498,46 @@ impl SplitGroup {
         let sidebar_width_physical = (sidebar_width as f64 * scale_factor) as u32;
         let download_width_physical = (download_width as f64 * scale_factor) as u32;
 
-        let available_width = window_size
-            .width
-            .saturating_sub(sidebar_width_physical + download_width_physical);
-        let available_height = window_size.height;
-
+        let area = PaneRect {
+            x: sidebar_width_physical,
+            y: 0,
+            width: window_size
+                .width
+                .saturating_sub(sidebar_width_physical + download_width_physical),
+            height: window_size.height,
+        };
         let separator_gap = (1.0 * scale_factor) as u32;
 
-        match self.orientation {
-            SplitOrientation::Vertical => {
-                let split_pos = (available_width as f32 * self.split_ratio) as u32;
-
-                let primary_bounds = wry::Rect {
-                    position: tao::dpi::PhysicalPosition::new(sidebar_width_physical as i32, 0)
-                        .into(),
-                    size: tao::dpi::PhysicalSize::new(
-                        split_pos.saturating_sub(separator_gap),
-                        available_height,
-                    )
-                    .into(),
-                };
-
-                let secondary_bounds = wry::Rect {
-                    position: tao::dpi::PhysicalPosition::new(
-                        (sidebar_width_physical + split_pos) as i32,
-                        0,
-                    )
-                    .into(),
-                    size: tao::dpi::PhysicalSize::new(
-                        available_width - split_pos,
-                        available_height,
-                    )
-                    .into(),
-                };
-
-                (primary_bounds, secondary_bounds)
-            }
-            SplitOrientation::Horizontal => {
-                let split_pos = (available_height as f32 * self.split_ratio) as u32;
-
-                let primary_bounds = wry::Rect {
-                    position: tao::dpi::PhysicalPosition::new(sidebar_width_physical as i32, 0)
-                        .into(),
-                    size: tao::dpi::PhysicalSize::new(
-                        available_width,
-                        split_pos.saturating_sub(separator_gap),
-                    )
-                    .into(),
-                };
-
-                let secondary_bounds = wry::Rect {
-                    position: tao::dpi::PhysicalPosition::new(
-                        sidebar_width_physical as i32,
-                        split_pos as i32,
-                    )
-                    .into(),
-                    size: tao::dpi::PhysicalSize::new(
-                        available_width,
-                        available_height - split_pos,
-                    )
-                    .into(),
-                };
-
-                (primary_bounds, secondary_bounds)
-            }
-        }
+        self.layout(area, separator_gap)
     }
 }
 
+/// Returns the pane closest to `from` in `direction`, preferring panes that overlap it.
+pub fn neighbor_pane(
+    panes: &[(usize, PaneRect)],
+    from: usize,
+    direction: PaneDirection,
+) -> Option<usize> {
+    let (_, current) = panes.iter().find(|(tab_id, _)| *tab_id == from)?;
+    let (cx, cy) = current.center();
+
+    panes
+        .iter()
+        .filter(|(tab_id, _)| *tab_id != from)
+        .filter_map(|(tab_id, rect)| {
+            let (x, y) = rect.center();
+            let (along, across) = match direction {
+                PaneDirection::Left => (cx - x, y - cy),
+                PaneDirection::Right => (x - cx, y - cy),
+                PaneDirection::Up => (cy - y, x - cx),
+                PaneDirection::Down => (y - cy, x - cx),
+            };
+            (along > 0.0).then_some((*tab_id, along + across.abs() * 2.0))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(tab_id, _)| tab_id)
+}
+
 #[derive(Debug)]
 pub struct SplitUIState {
     pub active_tab_in_split: bool,
@@ -174,10 +578,77 @@ impl SplitViewManager {
         group_id
     }
 
+    /// Creates a group from a saved tiling tree. Returns `None` if it has fewer than two panes.
+    pub fn restore_group(&mut self, root: SplitNode, layout: SplitLayout) -> Option<usize> {
+        let tab_ids = root.tab_ids();
+        if tab_ids.len() < 2 || tab_ids.len() > MAX_SPLIT_PANES {
+            return None;
+        }
+
+        let group_id = self.next_group_id;
+        self.next_group_id += 1;
+        for tab_id in tab_ids {
+            self.tab_to_group.insert(tab_id, group_id);
+        }
+        self.groups.insert(
+            group_id,
+            SplitGroup {
+                id: group_id,
+                root,
+                layout,
+            },
+        );
+        Some(group_id)
+    }
+
     pub fn remove_group(&mut self, group_id: usize) {
         if let Some(group) = self.groups.remove(&group_id) {
-            self.tab_to_group.remove(&group.primary_tab_id);
-            self.tab_to_group.remove(&group.secondary_tab_id);
+            for tab_id in group.tab_ids() {
+                self.tab_to_group.remove(&tab_id);
+            }
+        }
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = &SplitGroup> {
+        self.groups.values()
+    }
+
+    pub fn set_group_split_ratio(&mut self, group_id: usize, split_ratio: f32) {
+        if let Some(group) = self.groups.get_mut(&group_id) {
+            group.move_divider(&[], 0, split_ratio);
+        }
+    }
+
+    /// Adds a tab to a group, re-tiling it with its layout.
+    /// Returns false if the group is full or the tab already is in a group.
+    pub fn add_tab_to_group(&mut self, group_id: usize, tab_id: usize) -> bool {
+        if self.is_tab_in_group(tab_id) {
+            return false;
+        }
+        let Some(group) = self.groups.get_mut(&group_id) else {
+            return false;
+        };
+        let mut tab_ids = group.tab_ids();
+        if tab_ids.len() >= MAX_SPLIT_PANES {
+            return false;
+        }
+
+        tab_ids.push(tab_id);
+        group.retile(group.layout, &tab_ids);
+        self.tab_to_group.insert(tab_id, group_id);
+        true
+    }
+
+    pub fn set_group_layout(&mut self, group_id: usize, layout: SplitLayout) {
+        if let Some(group) = self.groups.get_mut(&group_id) {
+            let tab_ids = group.tab_ids();
+            group.retile(layout, &tab_ids);
+        }
+    }
+
+    pub fn move_group_divider(&mut self, group_id: usize, path: &[usize], index: usize, fraction: f32) {
+        if let Some(group) = self.groups.get_mut(&group_id) {
+            group.move_divider(path, index, fraction);
         }
     }
 
@@ -215,21 +686,23 @@ impl SplitViewManager {
         }
     }
 
+    /// Takes a tab out of its group. The remaining panes are re-tiled, and the group is
+    /// dissolved once a single pane is left. Returns the group the tab was in.
     pub fn remove_tab_from_group(&mut self, tab_id: usize) -> Option<usize> {
-        if let Some(group_id) = self.tab_to_group.remove(&tab_id) {
-            if let Some(group) = self.groups.get(&group_id) {
-                let other_tab_id = if group.primary_tab_id == tab_id {
-                    group.secondary_tab_id
-                } else {
-                    group.primary_tab_id
-                };
-                self.tab_to_group.remove(&other_tab_id);
-            }
-            self.groups.remove(&group_id);
-            Some(group_id)
+        let group_id = self.tab_to_group.remove(&tab_id)?;
+        let group = self.groups.get_mut(&group_id)?;
+
+        let remaining: Vec<usize> = group
+            .tab_ids()
+            .into_iter()
+            .filter(|&id| id != tab_id)
+            .collect();
+        if remaining.len() < 2 {
+            self.remove_group(group_id);
         } else {
-            None
+            group.retile(group.layout, &remaining);
         }
+        Some(group_id)
     }
 
     pub fn calculate_ui_state(
@@ -239,7 +712,7 @@ impl SplitViewManager {
     ) -> SplitUIState {
         let (active_tab_in_split, active_group_orientation) = if let Some(tab_id) = active_tab_id {
             if let Some(group) = self.get_group_for_tab(tab_id) {
-                (true, Some(group.orientation.as_str().to_string()))
+                (true, Some(group.orientation().as_str().to_string()))
             } else {
                 (false, None)
             }
@@ -263,9 +736,9 @@ impl SplitViewManager {
             .values()
             .map(|group| {
                 serde_json::json!({
-                    "primary": group.primary_tab_id,
-                    "secondary": group.secondary_tab_id,
-                    "orientation": group.orientation.as_str()
+                    "tabIds": group.tab_ids(),
+                    "orientation": group.orientation().as_str(),
+                    "layout": group.layout.as_str()
                 })
             })
             .collect();
@@ -273,3 +746,83 @@ impl SplitViewManager {
         serde_json::to_string(&groups_data).unwrap_or_else(|_| "[]".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: PaneRect = PaneRect {
+        x: 250,
+        y: 0,
+        width: 1000,
+        height: 800,
+    };
+
+    #[test]
+    fn test_two_pane_split_matches_ratio() {
+        let mut group = SplitGroup::new(1, 10, 20, SplitOrientation::Vertical);
+        group.move_divider(&[], 0, 0.3);
+
+        let bounds = group.layout(AREA, 1);
+        assert_eq!(
+            bounds.panes,
+            vec![
+                (10, PaneRect { x: 250, y: 0, width: 299, height: 800 }),
+                (20, PaneRect { x: 550, y: 0, width: 700, height: 800 }),
+            ]
+        );
+        assert_eq!(bounds.dividers.len(), 1);
+        assert_eq!(bounds.dividers[0].position, 550);
+        assert_eq!(
+            bounds.dividers[0].handle(6),
+            PaneRect { x: 547, y: 0, width: 6, height: 800 }
+        );
+    }
+
+    #[test]
+    fn test_main_stack_and_grid_layouts() {
+        let mut group = SplitGroup::new(1, 1, 2, SplitOrientation::Vertical);
+        group.retile(SplitLayout::MainStack, &[1, 2, 3]);
+        let panes = group.layout(AREA, 0).panes;
+        assert_eq!(panes[0].1.height, 800);
+        assert_eq!(panes[1].1, PaneRect { x: 750, y: 0, width: 500, height: 400 });
+        assert_eq!(panes[2].1, PaneRect { x: 750, y: 400, width: 500, height: 400 });
+
+        group.retile(SplitLayout::Grid, &[1, 2, 3, 4]);
+        let bounds = group.layout(AREA, 0);
+        assert_eq!(bounds.panes[3].1, PaneRect { x: 750, y: 400, width: 500, height: 400 });
+        assert_eq!(bounds.dividers.len(), 3);
+    }
+
+    #[test]
+    fn test_move_divider_keeps_minimum_size() {
+        let mut group = SplitGroup::new(1, 1, 2, SplitOrientation::Horizontal);
+        group.move_divider(&[], 0, 0.99);
+        assert!((group.split_ratio() - 0.9).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_neighbor_pane() {
+        let mut group = SplitGroup::new(1, 1, 2, SplitOrientation::Vertical);
+        group.retile(SplitLayout::Grid, &[1, 2, 3, 4]);
+        let panes = group.layout(AREA, 0).panes;
+
+        assert_eq!(neighbor_pane(&panes, 1, PaneDirection::Right), Some(2));
+        assert_eq!(neighbor_pane(&panes, 1, PaneDirection::Down), Some(3));
+        assert_eq!(neighbor_pane(&panes, 4, PaneDirection::Up), Some(2));
+        assert_eq!(neighbor_pane(&panes, 1, PaneDirection::Left), None);
+    }
+
+    #[test]
+    fn test_removing_pane_retiles_then_dissolves() {
+        let mut manager = SplitViewManager::new();
+        let group_id = manager.create_group(1, 2, SplitOrientation::Vertical);
+        assert!(manager.add_tab_to_group(group_id, 3));
+
+        assert_eq!(manager.remove_tab_from_group(2), Some(group_id));
+        assert_eq!(manager.get_group_for_tab(1).map(|g| g.tab_ids()), Some(vec![1, 3]));
+
+        assert_eq!(manager.remove_tab_from_group(3), Some(group_id));
+        assert!(!manager.is_tab_in_group(1));
+    }
+}
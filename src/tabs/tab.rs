@@ -1,6 +1,6 @@
-use std::time::Instant;
-use wry::WebView;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
+use wry::WebView;
 
 #[allow(dead_code)]
 pub enum TabState {
@@ -9,6 +9,40 @@ pub enum TabState {
     Suspended { url: String, title: Option<String> },
 }
 
+/// What decides whether a background tab may have its webview destroyed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SuspendCheck {
+    pub loaded: bool,
+    /// Whether the tab's URL can be loaded again when the tab is rebuilt.
+    pub restorable: bool,
+    /// Whether the tab is on screen, as the active tab or one of its split panes.
+    pub visible: bool,
+    pub playing_audio: bool,
+    pub pinned: bool,
+}
+
+impl SuspendCheck {
+    pub fn allows(&self) -> bool {
+        self.loaded && self.restorable && !self.visible && !self.playing_audio && !self.pinned
+    }
+}
+
+/// Returns the tabs idle for longer than `timeout` that `check` allows suspending,
+/// longest idle first.
+pub fn tabs_to_suspend(
+    idle: &[(usize, Duration)],
+    timeout: Duration,
+    check: impl Fn(usize) -> SuspendCheck,
+) -> Vec<usize> {
+    let mut candidates: Vec<(usize, Duration)> = idle
+        .iter()
+        .copied()
+        .filter(|&(tab_id, idle_for)| idle_for > timeout && check(tab_id).allows())
+        .collect();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    candidates.into_iter().map(|(tab_id, _)| tab_id).collect()
+}
+
 /// Represents a single browser tab with its webview and URL.
 pub struct Tab {
     pub state: TabState,
@@ -16,6 +50,10 @@ pub struct Tab {
     pub id: usize,
     #[allow(dead_code)]
     pub title: Option<String>,
+    /// Pinned tabs sit at the top of the tab list and are never suspended.
+    pub pinned: bool,
+    /// Container whose cookies and site data the tab uses, if any.
+    pub container: Option<String>,
     pub last_accessed: Instant,
 }
 
@@ -29,33 +67,34 @@ impl Tab {
                 webview,
             },
             title: None,
+            pinned: false,
+            container: None,
             last_accessed: Instant::now(),
         }
     }
 
     /// Creates a new unloaded tab (for lazy loading).
-    #[allow(dead_code)]
     pub fn new_unloaded(id: usize, url: String) -> Self {
         Self {
             id,
             state: TabState::Unloaded { url },
             title: None,
+            pinned: false,
+            container: None,
             last_accessed: Instant::now(),
         }
     }
 
-    /// Loads the tab by creating its webview.
-    #[allow(dead_code)]
+    /// Loads an unloaded or suspended tab with a freshly built webview.
     pub fn load(&mut self, webview: Rc<WebView>) {
-        if let TabState::Unloaded { url } = &self.state {
-            let url = url.clone();
+        if !self.is_loaded() {
+            let url = self.get_url().to_string();
             self.state = TabState::Loaded { url, webview };
             self.last_accessed = Instant::now();
         }
     }
 
-    /// Suspends the tab to save memory.
-    #[allow(dead_code)]
+    /// Suspends the tab to save memory, dropping its webview.
     pub fn suspend(&mut self) {
         if matches!(self.state, TabState::Loaded { .. }) {
             let old_state = std::mem::replace(
@@ -76,7 +115,6 @@ impl Tab {
     }
 
     /// Checks if the tab is loaded.
-    #[allow(dead_code)]
     pub fn is_loaded(&self) -> bool {
         matches!(self.state, TabState::Loaded { .. })
     }
@@ -125,7 +163,6 @@ impl Tab {
     }
 
     /// Sets the tab's title.
-    #[allow(dead_code)]
     pub fn set_title(&mut self, title: String) {
         self.title = Some(title);
     }
@@ -210,3 +247,100 @@ impl Drop for Tab {
         self.cleanup();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    fn background() -> SuspendCheck {
+        SuspendCheck {
+            loaded: true,
+            restorable: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unloaded_tab_state() {
+        let mut tab = Tab::new_unloaded(1, "https://a.example".to_string());
+        assert!(tab.is_unloaded());
+        assert!(!tab.is_loaded());
+        assert!(tab.webview().is_none());
+
+        // Only loaded tabs have a webview to drop.
+        tab.suspend();
+        assert!(tab.is_unloaded());
+
+        tab.set_url("https://b.example".to_string());
+        assert_eq!(tab.get_url(), "https://b.example");
+    }
+
+    #[test]
+    fn test_suspended_tab_keeps_url_and_title() {
+        let mut tab = Tab::new_unloaded(1, String::new());
+        tab.state = TabState::Suspended {
+            url: "https://a.example".to_string(),
+            title: Some("A".to_string()),
+        };
+        assert!(tab.is_suspended());
+        assert!(!tab.is_loaded());
+        tab.set_url("https://b.example".to_string());
+        assert_eq!(tab.get_url(), "https://b.example");
+        assert!(
+            matches!(&tab.state, TabState::Suspended { title: Some(title), .. } if title == "A")
+        );
+    }
+
+    #[test]
+    fn test_only_idle_background_tabs_are_suspended() {
+        let idle = [
+            (1, 10 * MINUTE),
+            (2, 10 * MINUTE),
+            (3, 10 * MINUTE),
+            (4, 10 * MINUTE),
+            (5, MINUTE),
+        ];
+        let suspended = tabs_to_suspend(&idle, 5 * MINUTE, |tab_id| match tab_id {
+            2 => SuspendCheck {
+                playing_audio: true,
+                ..background()
+            },
+            3 => SuspendCheck {
+                pinned: true,
+                ..background()
+            },
+            4 => SuspendCheck {
+                visible: true,
+                ..background()
+            },
+            _ => background(),
+        });
+        assert_eq!(suspended, [1]);
+    }
+
+    #[test]
+    fn test_longest_idle_tabs_are_suspended_first() {
+        let idle = [(1, 6 * MINUTE), (2, 30 * MINUTE), (3, 12 * MINUTE)];
+        assert_eq!(
+            tabs_to_suspend(&idle, 5 * MINUTE, |_| background()),
+            [2, 3, 1]
+        );
+    }
+
+    #[test]
+    fn test_unloaded_and_unrestorable_tabs_are_not_suspended() {
+        assert!(!SuspendCheck {
+            loaded: false,
+            ..background()
+        }
+        .allows());
+        assert!(!SuspendCheck {
+            restorable: false,
+            ..background()
+        }
+        .allows());
+        assert!(background().allows());
+    }
+}
@@ -0,0 +1,53 @@
+pub fn get_html() -> &'static str {
+    r#"
+        <div class="bookmarks-panel" id="bookmarks-panel">
+            <div class="bookmarks-header">
+                <div class="panel-tabs">
+                    <button class="panel-tab active" data-view="bookmarks" onclick="showPanelView('bookmarks')">
+                        <svg width="14" height="14" viewBox="0 0 24 24" fill="currentColor" shape-rendering="crispEdges">
+                            <path d="M5 2h14v20h-2v-2h-2v-2h-2v-2h-2v2H9v2H7v2H5V2zm2 2v14h2v-2h2v-2h2v2h2v2h2V4H7z"/>
+                        </svg>
+                        Bookmarks
+                    </button>
+                    <button class="panel-tab" data-view="reading" onclick="showPanelView('reading')">
+                        <svg width="14" height="14" viewBox="0 0 24 24" fill="currentColor" shape-rendering="crispEdges">
+                            <path d="M2 4h8v2h2V4h10v16H12v-2h-2v2H2V4zm9 4v2h2V8h-2zm0 4v6h2v-6h-2zM4 6v12h5V6H4zm11 0v12h5V6h-5z"/>
+                        </svg>
+                        Reading list
+                    </button>
+                </div>
+                <button class="header-btn" onclick="closeBookmarksPanel()" title="Close">&times;</button>
+            </div>
+            <div class="panel-view" id="reading-view" style="display: none;">
+                <div class="reading-filter">
+                    <button class="active" data-filter="unread" onclick="setReadingFilter('unread')">Unread</button>
+                    <button data-filter="all" onclick="setReadingFilter('all')">All</button>
+                </div>
+                <div class="bookmarks-list" id="reading-list"></div>
+            </div>
+            <div class="panel-view" id="bookmarks-view">
+            <div class="bookmarks-search">
+                <input type="text" id="bookmarks-search" placeholder="Search title, URL or #tag" autocomplete="off">
+            </div>
+            <div class="bookmarks-list" id="bookmarks-list"></div>
+            <div class="bookmark-editor" id="bookmark-editor">
+                <div class="editor-title" id="editor-title">Edit bookmark</div>
+                <label>Name<input type="text" id="editor-name"></label>
+                <label>URL<input type="text" id="editor-url"></label>
+                <label>Folder<select id="editor-folder"></select></label>
+                <label>Tags<input type="text" id="editor-tags" placeholder="comma separated"></label>
+                <label>Keyword<input type="text" id="editor-keyword" placeholder="e.g. gh, use %s in the URL"></label>
+                <label>Notes<textarea id="editor-notes" rows="3"></textarea></label>
+                <div class="editor-actions">
+                    <button class="danger" onclick="removeEditedBookmark()">Remove</button>
+                    <button onclick="closeEditor()">Cancel</button>
+                    <button class="primary" onclick="saveEditedBookmark()">Save</button>
+                </div>
+            </div>
+            <div class="bookmarks-footer">
+                <button onclick="addFolder(null)">New Folder</button>
+            </div>
+            </div>
+        </div>
+    "#
+}
@@ -0,0 +1,400 @@
+pub fn get_script() -> &'static str {
+    r#"
+        window.bookmarkData = {folders: [], bookmarks: []};
+        window.isVisible = false;
+
+        let collapsedFolders = new Set();
+        let editingBookmarkId = null;
+        let renameNewestFolder = false;
+
+        const FOLDER_ICON = `<svg width="12" height="12" viewBox="0 0 24 24" fill="currentColor" shape-rendering="crispEdges"><path d="M4 4h8v2h10v14H2V4h2zm16 4H10V6H4v12h16V8z"/></svg>`;
+
+        function postBookmarkMessage(message) {
+            window.ipc.postMessage(JSON.stringify(message));
+        }
+
+        window.toggleVisibility = function(visible) {
+            window.isVisible = visible;
+            const panel = document.getElementById('bookmarks-panel');
+            panel.style.transform = visible ? 'translateX(0)' : 'translateX(100%)';
+            if (!visible) {
+                closeEditor();
+            }
+        };
+
+        window.setBookmarks = function(data) {
+            window.bookmarkData = data;
+            renderBookmarks();
+
+            if (renameNewestFolder) {
+                renameNewestFolder = false;
+                const newest = data.folders.reduce((latest, folder) => !latest || folder.id > latest.id ? folder : latest, null);
+                if (newest) startFolderRename(newest.id);
+            }
+            if (editingBookmarkId !== null && !data.bookmarks.some(b => b.id === editingBookmarkId)) {
+                closeEditor();
+            }
+        };
+
+        window.editBookmark = function(id, isNew) {
+            const bookmark = window.bookmarkData.bookmarks.find(b => b.id === id);
+            if (!bookmark) return;
+
+            showPanelView('bookmarks');
+
+            editingBookmarkId = id;
+            document.getElementById('editor-title').textContent = isNew ? 'Bookmark added' : 'Edit bookmark';
+            document.getElementById('editor-name').value = bookmark.title;
+            document.getElementById('editor-url').value = bookmark.url;
+            document.getElementById('editor-tags').value = (bookmark.tags || []).join(', ');
+            document.getElementById('editor-keyword').value = bookmark.keyword || '';
+            document.getElementById('editor-notes').value = bookmark.notes || '';
+
+            const select = document.getElementById('editor-folder');
+            select.innerHTML = '<option value="">No folder</option>' + folderOptions(null, 0);
+            select.value = bookmark.folderId === null || bookmark.folderId === undefined ? '' : String(bookmark.folderId);
+
+            document.getElementById('bookmark-editor').classList.add('open');
+            const nameInput = document.getElementById('editor-name');
+            nameInput.focus();
+            nameInput.select();
+        };
+
+        function folderOptions(parentId, depth) {
+            return window.bookmarkData.folders
+                .filter(folder => (folder.parentId ?? null) === parentId)
+                .map(folder => `<option value="${folder.id}">${'&nbsp;&nbsp;'.repeat(depth)}${escapeHtml(folder.name)}</option>` + folderOptions(folder.id, depth + 1))
+                .join('');
+        }
+
+        window.closeEditor = function() {
+            editingBookmarkId = null;
+            document.getElementById('bookmark-editor').classList.remove('open');
+        };
+
+        window.saveEditedBookmark = function() {
+            if (editingBookmarkId === null) return;
+            const folderValue = document.getElementById('editor-folder').value;
+            postBookmarkMessage({
+                action: 'update_bookmark',
+                id: editingBookmarkId,
+                title: document.getElementById('editor-name').value,
+                url: document.getElementById('editor-url').value,
+                folderId: folderValue === '' ? null : Number(folderValue),
+                tags: document.getElementById('editor-tags').value.split(','),
+                keyword: document.getElementById('editor-keyword').value,
+                notes: document.getElementById('editor-notes').value
+            });
+            closeEditor();
+        };
+
+        window.removeEditedBookmark = function() {
+            if (editingBookmarkId === null) return;
+            postBookmarkMessage({action: 'remove_bookmark', id: editingBookmarkId});
+            closeEditor();
+        };
+
+        window.closeBookmarksPanel = function() {
+            postBookmarkMessage({action: 'close_bookmarks_panel'});
+        };
+
+        window.addFolder = function(parentId) {
+            renameNewestFolder = true;
+            postBookmarkMessage({action: 'add_bookmark_folder', name: 'New folder', parentId: parentId});
+        };
+
+        function startFolderRename(folderId) {
+            const label = document.querySelector(`.folder-row[data-folder="${folderId}"] .folder-name`);
+            const folder = window.bookmarkData.folders.find(f => f.id === folderId);
+            if (!label || !folder) return;
+
+            const input = document.createElement('input');
+            input.type = 'text';
+            input.className = 'folder-rename';
+            input.value = folder.name;
+            label.replaceWith(input);
+            input.focus();
+            input.select();
+
+            let done = false;
+            const finish = (save) => {
+                if (done) return;
+                done = true;
+                if (save && input.value.trim() && input.value.trim() !== folder.name) {
+                    postBookmarkMessage({action: 'rename_bookmark_folder', folderId: folderId, name: input.value});
+                } else {
+                    renderBookmarks();
+                }
+            };
+            input.addEventListener('keydown', (e) => {
+                e.stopPropagation();
+                if (e.key === 'Enter') finish(true);
+                if (e.key === 'Escape') finish(false);
+            });
+            input.addEventListener('blur', () => finish(true));
+        }
+
+        function matchesSearch(bookmark, query) {
+            if (!query) return true;
+            if (query.startsWith('#')) {
+                const tag = query.slice(1);
+                return (bookmark.tags || []).some(t => t.startsWith(tag));
+            }
+            return bookmark.title.toLowerCase().includes(query) ||
+                bookmark.url.toLowerCase().includes(query) ||
+                (bookmark.notes || '').toLowerCase().includes(query) ||
+                (bookmark.keyword || '') === query ||
+                (bookmark.tags || []).some(t => t.includes(query));
+        }
+
+        function renderBookmarks() {
+            const list = document.getElementById('bookmarks-list');
+            const query = document.getElementById('bookmarks-search').value.trim().toLowerCase();
+            list.innerHTML = '';
+
+            if (query) {
+                const matches = window.bookmarkData.bookmarks.filter(b => matchesSearch(b, query));
+                matches.forEach(bookmark => list.appendChild(bookmarkRow(bookmark, 0)));
+                if (matches.length === 0) {
+                    list.innerHTML = '<div class="bookmarks-empty">No matching bookmarks</div>';
+                }
+                return;
+            }
+
+            if (window.bookmarkData.bookmarks.length === 0 && window.bookmarkData.folders.length === 0) {
+                list.innerHTML = '<div class="bookmarks-empty">No bookmarks yet. Press Cmd+D to bookmark a page.</div>';
+                return;
+            }
+            renderFolderContents(list, null, 0);
+        }
+
+        function renderFolderContents(container, folderId, depth) {
+            window.bookmarkData.folders
+                .filter(folder => (folder.parentId ?? null) === folderId)
+                .forEach(folder => {
+                    container.appendChild(folderRow(folder, depth));
+                    if (!collapsedFolders.has(folder.id)) {
+                        renderFolderContents(container, folder.id, depth + 1);
+                    }
+                });
+            window.bookmarkData.bookmarks
+                .filter(bookmark => (bookmark.folderId ?? null) === folderId)
+                .forEach(bookmark => container.appendChild(bookmarkRow(bookmark, depth)));
+        }
+
+        function folderRow(folder, depth) {
+            const row = document.createElement('div');
+            row.className = 'folder-row' + (collapsedFolders.has(folder.id) ? ' collapsed' : '');
+            row.dataset.folder = folder.id;
+            row.style.paddingLeft = `${8 + depth * 12}px`;
+            row.innerHTML = `
+                <span class="folder-caret">${collapsedFolders.has(folder.id) ? '+' : '-'}</span>
+                <span class="folder-icon">${FOLDER_ICON}</span>
+                <span class="folder-name">${escapeHtml(folder.name)}</span>
+                <span class="row-actions">
+                    <button title="New subfolder" data-action="add">+</button>
+                    <button title="Rename" data-action="rename">&#9998;</button>
+                    <button title="Delete folder and its bookmarks" data-action="remove">&times;</button>
+                </span>
+            `;
+            row.addEventListener('click', (e) => {
+                const action = e.target.closest('button')?.dataset.action;
+                e.stopPropagation();
+                if (action === 'add') {
+                    collapsedFolders.delete(folder.id);
+                    addFolder(folder.id);
+                } else if (action === 'rename') {
+                    startFolderRename(folder.id);
+                } else if (action === 'remove') {
+                    postBookmarkMessage({action: 'remove_bookmark_folder', folderId: folder.id});
+                } else if (!e.target.closest('input')) {
+                    if (collapsedFolders.has(folder.id)) {
+                        collapsedFolders.delete(folder.id);
+                    } else {
+                        collapsedFolders.add(folder.id);
+                    }
+                    renderBookmarks();
+                }
+            });
+            makeDraggable(row, {type: 'folder', id: folder.id});
+            makeDropTarget(row, folder.id);
+            row.addEventListener('dblclick', (e) => {
+                if (e.target.closest('.folder-name')) startFolderRename(folder.id);
+            });
+            return row;
+        }
+
+        function bookmarkRow(bookmark, depth) {
+            const row = document.createElement('div');
+            row.className = 'bookmark-row';
+            row.style.paddingLeft = `${8 + depth * 12}px`;
+            row.title = bookmark.notes ? `${bookmark.url}\n\n${bookmark.notes}` : bookmark.url;
+
+            const tags = (bookmark.tags || []).map(tag => `<span class="bookmark-tag">#${escapeHtml(tag)}</span>`).join('');
+            const keyword = bookmark.keyword ? `<span class="bookmark-keyword">${escapeHtml(bookmark.keyword)}</span>` : '';
+            row.innerHTML = `
+                <div class="bookmark-info">
+                    <div class="bookmark-title">${escapeHtml(bookmark.title || bookmark.url)}</div>
+                    <div class="bookmark-meta">${keyword}${tags}<span class="bookmark-url">${escapeHtml(bookmark.url)}</span></div>
+                </div>
+                <span class="row-actions">
+                    <button title="Edit" data-action="edit">&#9998;</button>
+                </span>
+            `;
+            row.addEventListener('click', (e) => {
+                if (e.target.closest('button')?.dataset.action === 'edit') {
+                    editBookmark(bookmark.id, false);
+                    return;
+                }
+                postBookmarkMessage({
+                    action: 'open_bookmark',
+                    url: bookmark.url,
+                    newTab: e.metaKey || e.ctrlKey
+                });
+            });
+            makeDraggable(row, {type: 'bookmark', id: bookmark.id});
+            return row;
+        }
+
+        let dragged = null;
+
+        function makeDraggable(row, item) {
+            row.draggable = true;
+            row.addEventListener('dragstart', (e) => {
+                dragged = item;
+                e.dataTransfer.effectAllowed = 'move';
+            });
+            row.addEventListener('dragend', () => {
+                dragged = null;
+                document.querySelectorAll('.drop-target').forEach(el => el.classList.remove('drop-target'));
+            });
+        }
+
+        function makeDropTarget(element, folderId) {
+            element.addEventListener('dragover', (e) => {
+                if (!dragged) return;
+                e.preventDefault();
+                e.stopPropagation();
+                element.classList.add('drop-target');
+            });
+            element.addEventListener('dragleave', () => element.classList.remove('drop-target'));
+            element.addEventListener('drop', (e) => {
+                e.preventDefault();
+                e.stopPropagation();
+                element.classList.remove('drop-target');
+                if (!dragged) return;
+                if (dragged.type === 'bookmark') {
+                    postBookmarkMessage({action: 'update_bookmark', id: dragged.id, folderId: folderId});
+                } else if (dragged.id !== folderId) {
+                    postBookmarkMessage({action: 'move_bookmark_folder', folderId: dragged.id, parentId: folderId});
+                }
+                dragged = null;
+            });
+        }
+
+        window.readingItems = [];
+        let readingFilter = 'unread';
+
+        window.showPanelView = function(view) {
+            document.querySelectorAll('.panel-tab').forEach(tab => {
+                tab.classList.toggle('active', tab.dataset.view === view);
+            });
+            document.getElementById('bookmarks-view').style.display = view === 'bookmarks' ? 'flex' : 'none';
+            document.getElementById('reading-view').style.display = view === 'reading' ? 'flex' : 'none';
+            if (view !== 'bookmarks') {
+                closeEditor();
+            }
+        };
+
+        window.setReadingList = function(items) {
+            window.readingItems = items;
+            renderReadingList();
+        };
+
+        window.setReadingFilter = function(filter) {
+            readingFilter = filter;
+            document.querySelectorAll('.reading-filter button').forEach(button => {
+                button.classList.toggle('active', button.dataset.filter === filter);
+            });
+            renderReadingList();
+        };
+
+        function hostOf(url) {
+            try {
+                return new URL(url).hostname.replace(/^www\./, '');
+            } catch (e) {
+                return url;
+            }
+        }
+
+        function renderReadingList() {
+            const list = document.getElementById('reading-list');
+            const items = window.readingItems.filter(item => readingFilter === 'all' || !item.read);
+            list.innerHTML = '';
+
+            if (items.length === 0) {
+                list.innerHTML = readingFilter === 'unread' && window.readingItems.length > 0
+                    ? '<div class="bookmarks-empty">Everything is read</div>'
+                    : '<div class="bookmarks-empty">Nothing saved yet. Right-click a page and choose Save to Reading List.</div>';
+                return;
+            }
+
+            items.forEach(item => {
+                const row = document.createElement('div');
+                row.className = 'reading-row' + (item.read ? ' read' : '');
+                row.title = item.url;
+                const offline = item.offline ? '<span class="reading-offline">offline</span>' : '';
+                row.innerHTML = `
+                    <div class="bookmark-info">
+                        <div class="bookmark-title">${escapeHtml(item.title || item.url)}</div>
+                        <div class="bookmark-meta">${offline}<span class="bookmark-url">${escapeHtml(hostOf(item.url))}</span></div>
+                        ${item.excerpt ? `<div class="reading-excerpt">${escapeHtml(item.excerpt)}</div>` : ''}
+                    </div>
+                    <span class="row-actions">
+                        <button title="${item.read ? 'Mark unread' : 'Mark read'}" data-action="toggle-read">${item.read ? '&#9675;' : '&#10003;'}</button>
+                        <button title="Open original page" data-action="original">&#8599;</button>
+                        <button title="Remove" data-action="remove">&times;</button>
+                    </span>
+                `;
+                row.addEventListener('click', (e) => {
+                    const action = e.target.closest('button')?.dataset.action;
+                    if (action === 'toggle-read') {
+                        postBookmarkMessage({action: 'set_reading_item_read', id: item.id, read: !item.read});
+                    } else if (action === 'remove') {
+                        postBookmarkMessage({action: 'remove_reading_item', id: item.id});
+                    } else {
+                        postBookmarkMessage({
+                            action: 'open_reading_item',
+                            id: item.id,
+                            original: action === 'original',
+                            newTab: e.metaKey || e.ctrlKey
+                        });
+                    }
+                });
+                list.appendChild(row);
+            });
+        }
+
+        function escapeHtml(text) {
+            const div = document.createElement('div');
+            div.textContent = text;
+            return div.innerHTML;
+        }
+
+        document.getElementById('bookmarks-search').addEventListener('input', renderBookmarks);
+        makeDropTarget(document.getElementById('bookmarks-list'), null);
+
+        document.addEventListener('keydown', (e) => {
+            if (e.key === 'Escape') {
+                if (editingBookmarkId !== null) {
+                    closeEditor();
+                } else {
+                    closeBookmarksPanel();
+                }
+            } else if (e.key === 'Enter' && editingBookmarkId !== null && e.target.tagName !== 'TEXTAREA') {
+                e.preventDefault();
+                saveEditedBookmark();
+            }
+        });
+    "#
+}
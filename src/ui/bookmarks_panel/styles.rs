@@ -0,0 +1,368 @@
+use crate::ui::fonts;
+
+pub fn get_styles() -> String {
+    format!(
+        r#"
+        {}
+
+        * {{
+            margin: 0;
+            padding: 0;
+            box-sizing: border-box;
+        }}
+
+        body {{
+            font-family: 'gohu', monospace;
+            background: #101010;
+            overflow: hidden;
+            width: 100%;
+            height: 100%;
+            font-size: 11px;
+            color: #ffffff;
+        }}
+
+        button, input, select, textarea {{
+            font-family: 'gohu', monospace;
+            font-size: 11px;
+        }}
+
+        .bookmarks-panel {{
+            width: 100%;
+            height: 100vh;
+            background: #101010;
+            display: flex;
+            flex-direction: column;
+            border-left: 2px solid #333333;
+            transform: translateX(100%);
+            transition: transform 0.1s linear;
+            position: fixed;
+            top: 0;
+            right: 0;
+        }}
+
+        .bookmarks-header {{
+            display: flex;
+            justify-content: space-between;
+            align-items: center;
+            padding: 16px;
+            border-bottom: 1px solid #333333;
+            flex-shrink: 0;
+        }}
+
+        .panel-tabs {{
+            display: flex;
+            gap: 4px;
+        }}
+
+        .panel-tab {{
+            display: flex;
+            align-items: center;
+            gap: 6px;
+            background: none;
+            border: 1px solid transparent;
+            color: #666666;
+            padding: 4px 8px;
+            cursor: pointer;
+        }}
+
+        .panel-tab:hover {{
+            color: #ffffff;
+        }}
+
+        .panel-tab.active {{
+            color: #ffffff;
+            border-color: #333333;
+        }}
+
+        .panel-view {{
+            display: flex;
+            flex-direction: column;
+            flex: 1;
+            min-height: 0;
+        }}
+
+        .reading-filter {{
+            display: flex;
+            gap: 4px;
+            padding: 8px;
+            border-bottom: 1px solid #333333;
+            flex-shrink: 0;
+        }}
+
+        .reading-filter button {{
+            background: none;
+            border: 1px solid #333333;
+            color: #666666;
+            padding: 4px 10px;
+            cursor: pointer;
+        }}
+
+        .reading-filter button.active {{
+            color: #101010;
+            background: #ffffff;
+            border-color: #ffffff;
+        }}
+
+        .reading-row {{
+            display: flex;
+            gap: 6px;
+            padding: 8px;
+            cursor: pointer;
+            border-left: 3px solid transparent;
+        }}
+
+        .reading-row:hover {{
+            background: #1a1a1a;
+            border-left-color: #ffffff;
+        }}
+
+        .reading-row.read .bookmark-title {{
+            color: #666666;
+        }}
+
+        .reading-row .row-actions {{
+            align-self: flex-start;
+        }}
+
+        .reading-row:hover .row-actions {{
+            display: flex;
+        }}
+
+        .reading-excerpt {{
+            color: #888888;
+            font-size: 10px;
+            margin-top: 2px;
+            display: -webkit-box;
+            -webkit-line-clamp: 2;
+            -webkit-box-orient: vertical;
+            overflow: hidden;
+        }}
+
+        .reading-offline {{
+            color: #101010;
+            background: #666666;
+            padding: 0 4px;
+            flex-shrink: 0;
+        }}
+
+        .header-btn {{
+            background: none;
+            border: none;
+            color: #666666;
+            font-size: 14px;
+            cursor: pointer;
+        }}
+
+        .header-btn:hover {{
+            color: #ffffff;
+        }}
+
+        .bookmarks-search {{
+            padding: 8px;
+            border-bottom: 1px solid #333333;
+            flex-shrink: 0;
+        }}
+
+        .bookmarks-search input, .bookmark-editor input, .bookmark-editor select, .bookmark-editor textarea, .folder-rename {{
+            width: 100%;
+            background: #1a1a1a;
+            border: 1px solid #333333;
+            color: #ffffff;
+            padding: 6px 8px;
+            outline: none;
+        }}
+
+        .bookmarks-search input:focus, .bookmark-editor input:focus, .bookmark-editor select:focus, .bookmark-editor textarea:focus {{
+            border-color: #ffffff;
+        }}
+
+        .bookmarks-list {{
+            overflow-y: auto;
+            flex: 1;
+            padding: 4px;
+        }}
+
+        .bookmarks-list::-webkit-scrollbar {{
+            width: 8px;
+        }}
+
+        .bookmarks-list::-webkit-scrollbar-track {{
+            background: #101010;
+        }}
+
+        .bookmarks-list::-webkit-scrollbar-thumb {{
+            background: #ffffff;
+        }}
+
+        .bookmarks-empty {{
+            padding: 16px 8px;
+            text-align: center;
+            color: #666666;
+        }}
+
+        .folder-row, .bookmark-row {{
+            display: flex;
+            align-items: center;
+            gap: 6px;
+            padding: 6px 8px;
+            cursor: pointer;
+            border-left: 3px solid transparent;
+        }}
+
+        .folder-row:hover, .bookmark-row:hover {{
+            background: #1a1a1a;
+            border-left-color: #ffffff;
+        }}
+
+        .folder-row.drop-target, .bookmarks-list.drop-target {{
+            background: #222222;
+            border-left-color: #ffffff;
+        }}
+
+        .folder-caret {{
+            color: #666666;
+            width: 8px;
+        }}
+
+        .folder-icon {{
+            display: flex;
+            color: #aaaaaa;
+        }}
+
+        .folder-name {{
+            flex: 1;
+            overflow: hidden;
+            text-overflow: ellipsis;
+            white-space: nowrap;
+        }}
+
+        .bookmark-info {{
+            flex: 1;
+            min-width: 0;
+        }}
+
+        .bookmark-title {{
+            overflow: hidden;
+            text-overflow: ellipsis;
+            white-space: nowrap;
+        }}
+
+        .bookmark-meta {{
+            display: flex;
+            gap: 6px;
+            color: #666666;
+            font-size: 9px;
+            overflow: hidden;
+            white-space: nowrap;
+        }}
+
+        .bookmark-url {{
+            overflow: hidden;
+            text-overflow: ellipsis;
+        }}
+
+        .bookmark-tag {{
+            color: #aaaaaa;
+            flex-shrink: 0;
+        }}
+
+        .bookmark-keyword {{
+            color: #101010;
+            background: #aaaaaa;
+            padding: 0 4px;
+            flex-shrink: 0;
+        }}
+
+        .row-actions {{
+            display: none;
+            gap: 2px;
+            flex-shrink: 0;
+        }}
+
+        .folder-row:hover .row-actions, .bookmark-row:hover .row-actions {{
+            display: flex;
+        }}
+
+        .row-actions button {{
+            background: none;
+            border: 1px solid transparent;
+            color: #aaaaaa;
+            width: 18px;
+            height: 18px;
+            cursor: pointer;
+        }}
+
+        .row-actions button:hover {{
+            border-color: #ffffff;
+            color: #ffffff;
+        }}
+
+        .bookmark-editor {{
+            display: none;
+            flex-direction: column;
+            gap: 8px;
+            padding: 12px;
+            border-top: 2px solid #333333;
+            background: #151515;
+            flex-shrink: 0;
+        }}
+
+        .bookmark-editor.open {{
+            display: flex;
+        }}
+
+        .editor-title {{
+            color: #ffffff;
+            margin-bottom: 4px;
+        }}
+
+        .bookmark-editor label {{
+            display: flex;
+            flex-direction: column;
+            gap: 4px;
+            color: #aaaaaa;
+        }}
+
+        .bookmark-editor textarea {{
+            resize: vertical;
+        }}
+
+        .editor-actions {{
+            display: flex;
+            gap: 6px;
+            justify-content: flex-end;
+        }}
+
+        .editor-actions button, .bookmarks-footer button {{
+            background: #1a1a1a;
+            border: 1px solid #333333;
+            color: #ffffff;
+            padding: 6px 10px;
+            cursor: pointer;
+        }}
+
+        .editor-actions button:hover, .bookmarks-footer button:hover {{
+            border-color: #ffffff;
+        }}
+
+        .editor-actions button.primary {{
+            background: #ffffff;
+            color: #101010;
+            border-color: #ffffff;
+        }}
+
+        .editor-actions button.danger {{
+            margin-right: auto;
+            color: #ff8a8a;
+            border-color: #5a2a2a;
+        }}
+
+        .bookmarks-footer {{
+            padding: 8px;
+            border-top: 1px solid #333333;
+            flex-shrink: 0;
+        }}
+    "#,
+        fonts::get_gohu_font_face()
+    )
+}
@@ -10,7 +10,7 @@ pub fn get_command_prompt_html_structure() -> &'static str {
                 type="text"
                 id="command-prompt-input"
                 class="command-prompt-input"
-                placeholder="Enter URL or search..."
+                placeholder="Enter URL or search, @ for workspaces, > for actions..."
                 autocomplete="off"
                 spellcheck="false"
             />
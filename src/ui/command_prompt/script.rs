@@ -1,6 +1,9 @@
 pub fn get_command_prompt_script() -> &'static str {
     r#"
         window.historyEntries = [];
+        window.recentlyClosed = [];
+        window.workspaces = {open: [], saved: []};
+        window.otherWindows = [];
         window.selectedIndex = -1;
 
         window.setHistory = function(history) {
@@ -8,6 +11,21 @@ pub fn get_command_prompt_script() -> &'static str {
             updateSuggestions();
         };
 
+        window.setRecentlyClosed = function(entries) {
+            window.recentlyClosed = entries;
+            updateSuggestions();
+        };
+
+        window.setWorkspaces = function(workspaces) {
+            window.workspaces = workspaces;
+            updateSuggestions();
+        };
+
+        window.setWindows = function(windows) {
+            window.otherWindows = windows;
+            updateSuggestions();
+        };
+
         window.hideCommandPrompt = function() {
             const input = document.getElementById('command-prompt-input');
             if (input) {
@@ -17,18 +35,67 @@ pub fn get_command_prompt_script() -> &'static str {
         };
 
         let searchDebounce = null;
+        // What the user actually typed, without any inline completion appended to the input.
+        let typedQuery = '';
+        let autofillAllowed = false;
 
-        window.showHistorySuggestions = function(results) {
+        window.showHistorySuggestions = function(results, query) {
+            if (query !== undefined && query !== typedQuery.trim()) return;
             renderSuggestions(results);
+            applyAutofill(results);
         };
 
-        function updateSuggestions() {
+        // Completes the top ranked result inline, selecting the added text so typing replaces it.
+        function applyAutofill(results) {
             const input = document.getElementById('command-prompt-input');
-            const query = input.value.trim();
+            const typed = typedQuery;
+            if (!autofillAllowed || !typed || /\s/.test(typed)) return;
+            if (input.value !== typed || input.selectionStart !== typed.length) return;
+
+            for (const entry of results) {
+                const completion = completionFor(entry.url, typed.toLowerCase());
+                if (completion) {
+                    input.value = typed + completion;
+                    input.setSelectionRange(typed.length, input.value.length);
+                    return;
+                }
+            }
+        }
+
+        function completionFor(url, typedLower) {
+            const withoutScheme = url.replace(/^[a-z][a-z0-9+.-]*:\/\//i, '');
+            const withoutWww = withoutScheme.replace(/^www\./i, '');
+
+            for (const candidate of [withoutWww, withoutScheme]) {
+                if (!candidate.toLowerCase().startsWith(typedLower)) continue;
+                // Complete to the end of the host first, like the address bar in other browsers.
+                const hostEnd = candidate.indexOf('/');
+                const target = hostEnd > typedLower.length ? candidate.slice(0, hostEnd) : candidate;
+                const completion = target.slice(typedLower.length);
+                return completion.length > 0 ? completion : null;
+            }
+            return null;
+        }
+
+        function updateSuggestions() {
+            const query = typedQuery.trim();
+
+            if (query.startsWith('>')) {
+                if (searchDebounce) clearTimeout(searchDebounce);
+                renderActions(query.slice(1).trim().toLowerCase());
+                return;
+            }
+
+            if (query.startsWith('@')) {
+                if (searchDebounce) clearTimeout(searchDebounce);
+                renderWorkspaces(query.slice(1).trim().toLowerCase());
+                return;
+            }
 
             if (!query) {
                 const recent = window.historyEntries.slice(0, 8);
                 renderSuggestions(recent);
+                renderRecentlyClosed(window.recentlyClosed.slice(0, 5));
                 return;
             }
 
@@ -37,7 +104,7 @@ pub fn get_command_prompt_script() -> &'static str {
                 const q = query.toLowerCase();
                 return entry.url.toLowerCase().includes(q) ||
                        entry.title.toLowerCase().includes(q);
-            }).slice(0, 5);
+            }).slice(0, 5).map(entry => ({ ...entry, type: 'history' }));
             renderSuggestions(localMatches);
 
             // IPC search for full history
@@ -68,14 +135,25 @@ pub fn get_command_prompt_script() -> &'static str {
                 // Use generic globe icon since we prioritize privacy and don't fetch 3rd party likely
                 const iconHtml = GLOBE_ICON;
 
+                const isTab = entry.type === 'tab';
+                const badge = isTab ? 'Tab' : entry.type === 'bookmark' ? 'Bookmark' : 'History';
+
                 div.innerHTML = `
                     <div class="suggestion-icon">${iconHtml}</div>
                     <div class="suggestion-info">
                         <div class="suggestion-title">${escapeHtml(entry.title || entry.url)}</div>
                     </div>
+                    <div class="suggestion-badge${isTab ? ' tab' : ''}${entry.type === 'bookmark' ? ' bookmark' : ''}">${badge}</div>
                     <div class="suggestion-url">${escapeHtml(entry.url)}</div>
                 `;
                 div.onclick = () => {
+                    if (isTab) {
+                        window.ipc.postMessage(JSON.stringify({
+                            action: 'switch_to_open_tab',
+                            tabId: entry.tabId
+                        }));
+                        return;
+                    }
                     window.ipc.postMessage(JSON.stringify({
                         action: 'command_prompt_navigate',
                         url: entry.url
@@ -85,6 +163,204 @@ pub fn get_command_prompt_script() -> &'static str {
             });
         }
 
+        const WINDOW_ICON = `<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="currentColor" shape-rendering="crispEdges"><path d="M2 4h20v16H2V4zm2 4v10h16V8H4z"/></svg>`;
+
+        function describeClosedEntry(entry) {
+            const item = entry.item;
+            if (item.kind === 'window') {
+                const firstTab = item.tabs[0];
+                const count = item.tabs.length;
+                return {
+                    icon: WINDOW_ICON,
+                    title: `Window · ${count} tab${count === 1 ? '' : 's'}`,
+                    url: firstTab ? (firstTab.title || firstTab.url) : ''
+                };
+            }
+            return {
+                icon: GLOBE_ICON,
+                title: item.title || item.url,
+                url: item.url
+            };
+        }
+
+        function renderRecentlyClosed(entries) {
+            if (entries.length === 0) {
+                return;
+            }
+
+            const suggestionsDiv = document.getElementById('command-prompt-suggestions');
+            const header = document.createElement('div');
+            header.className = 'command-prompt-section';
+            header.textContent = 'Recently closed';
+            suggestionsDiv.insertBefore(header, suggestionsDiv.firstChild);
+
+            entries.slice().reverse().forEach(entry => {
+                const info = describeClosedEntry(entry);
+                const div = document.createElement('div');
+                div.className = 'command-prompt-suggestion';
+                div.innerHTML = `
+                    <div class="suggestion-icon">${info.icon}</div>
+                    <div class="suggestion-info">
+                        <div class="suggestion-title">${escapeHtml(info.title)}</div>
+                    </div>
+                    <div class="suggestion-url">${escapeHtml(info.url)}</div>
+                `;
+                div.onclick = () => {
+                    window.ipc.postMessage(JSON.stringify({
+                        action: 'reopen_closed_entry',
+                        entryId: entry.id
+                    }));
+                };
+                header.after(div);
+            });
+
+            if (suggestionsDiv.children.length > entries.length + 1) {
+                const historyHeader = document.createElement('div');
+                historyHeader.className = 'command-prompt-section';
+                historyHeader.textContent = 'History';
+                suggestionsDiv.children[entries.length].after(historyHeader);
+            }
+        }
+
+        const GROUP_COLOR_VALUES = {
+            blue: '#5b8def',
+            red: '#e5534b',
+            yellow: '#d4b106',
+            green: '#57ab5a',
+            pink: '#e275ad',
+            purple: '#986ee2',
+            cyan: '#39c5cf',
+            orange: '#e0823d',
+            grey: '#888888'
+        };
+
+        function renderWorkspaces(query) {
+            const suggestionsDiv = document.getElementById('command-prompt-suggestions');
+            suggestionsDiv.innerHTML = '';
+            window.selectedIndex = -1;
+
+            const matches = group => !query || group.name.toLowerCase().includes(query);
+            const sections = [
+                {
+                    title: 'Workspaces',
+                    groups: window.workspaces.open.filter(matches),
+                    message: group => ({action: 'switch_tab_group', groupId: group.id})
+                },
+                {
+                    title: 'Saved groups',
+                    groups: window.workspaces.saved.filter(matches),
+                    message: group => ({action: 'open_saved_tab_group', savedId: group.id})
+                }
+            ];
+
+            sections.forEach(section => {
+                if (section.groups.length === 0) {
+                    return;
+                }
+
+                const header = document.createElement('div');
+                header.className = 'command-prompt-section';
+                header.textContent = section.title;
+                suggestionsDiv.appendChild(header);
+
+                section.groups.forEach(group => {
+                    const color = GROUP_COLOR_VALUES[group.color] || GROUP_COLOR_VALUES.grey;
+                    const count = group.tabCount;
+                    const div = document.createElement('div');
+                    div.className = 'command-prompt-suggestion';
+                    div.innerHTML = `
+                        <div class="suggestion-icon"><span class="workspace-dot" style="background: ${color}"></span></div>
+                        <div class="suggestion-info">
+                            <div class="suggestion-title">${escapeHtml(group.name)}</div>
+                        </div>
+                        <div class="suggestion-url">${count} tab${count === 1 ? '' : 's'}</div>
+                    `;
+                    div.onclick = () => {
+                        window.ipc.postMessage(JSON.stringify(section.message(group)));
+                    };
+                    suggestionsDiv.appendChild(div);
+                });
+            });
+        }
+
+        function renderActions(query) {
+            const suggestionsDiv = document.getElementById('command-prompt-suggestions');
+            suggestionsDiv.innerHTML = '';
+            window.selectedIndex = -1;
+
+            const actions = [
+                {
+                    title: 'Open history',
+                    detail: 'calm://history',
+                    message: {action: 'command_prompt_navigate', url: 'calm://history'}
+                },
+                {
+                    title: 'Open privacy log',
+                    detail: 'calm://privacy-log',
+                    message: {action: 'command_prompt_navigate', url: 'calm://privacy-log'}
+                },
+                {
+                    title: 'Save page to reading list',
+                    detail: '',
+                    message: {action: 'save_page_for_later'}
+                },
+                {
+                    title: 'Open reading list',
+                    detail: '',
+                    message: {action: 'open_reading_list'}
+                },
+                {
+                    title: 'Open private window',
+                    detail: '',
+                    message: {action: 'new_private_window'}
+                },
+                {
+                    title: 'Switch profile',
+                    detail: 'calm://profiles',
+                    message: {action: 'open_profile_picker'}
+                },
+                {
+                    title: 'Move tab to new window',
+                    detail: '',
+                    message: {action: 'move_active_tab_to_window'}
+                }
+            ].concat(window.otherWindows.map(win => ({
+                title: `Move tab to window ${win.number}`,
+                detail: win.title || '',
+                message: {action: 'move_active_tab_to_window', toWindow: win.number}
+            })));
+
+            const matching = actions.filter(action =>
+                !query ||
+                action.title.toLowerCase().includes(query) ||
+                action.detail.toLowerCase().includes(query)
+            );
+            if (matching.length === 0) {
+                return;
+            }
+
+            const header = document.createElement('div');
+            header.className = 'command-prompt-section';
+            header.textContent = 'Actions';
+            suggestionsDiv.appendChild(header);
+
+            matching.forEach(action => {
+                const div = document.createElement('div');
+                div.className = 'command-prompt-suggestion';
+                div.innerHTML = `
+                    <div class="suggestion-icon">${WINDOW_ICON}</div>
+                    <div class="suggestion-info">
+                        <div class="suggestion-title">${escapeHtml(action.title)}</div>
+                    </div>
+                    <div class="suggestion-url">${escapeHtml(action.detail)}</div>
+                `;
+                div.onclick = () => {
+                    window.ipc.postMessage(JSON.stringify(action.message));
+                };
+                suggestionsDiv.appendChild(div);
+            });
+        }
+
         function escapeHtml(text) {
             const div = document.createElement('div');
             div.textContent = text;
@@ -112,10 +388,13 @@ pub fn get_command_prompt_script() -> &'static str {
             setTimeout(() => {
                 input.focus();
                 input.select();
+                typedQuery = input.value;
                 updateSuggestions();
             }, 100);
 
-            input.addEventListener('input', () => {
+            input.addEventListener('input', (e) => {
+                typedQuery = input.value;
+                autofillAllowed = (e.inputType || '').startsWith('insert');
                 updateSuggestions();
             });
 
@@ -140,7 +419,9 @@ pub fn get_command_prompt_script() -> &'static str {
                         suggestions[window.selectedIndex].click();
                     } else {
                         const url = input.value.trim();
-                        if (url) {
+                        if (url.startsWith('@') || url.startsWith('>')) {
+                            if (suggestions[0]) suggestions[0].click();
+                        } else if (url) {
                             window.ipc.postMessage(JSON.stringify({
                                 action: 'command_prompt_navigate',
                                 url: url
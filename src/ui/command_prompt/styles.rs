@@ -93,6 +93,14 @@ pub fn get_command_prompt_styles() -> String {
             overflow-y: auto;
         }}
 
+        .command-prompt-section {{
+            padding: 8px 16px 4px 16px;
+            color: #666666;
+            font-size: 10px;
+            text-transform: uppercase;
+            letter-spacing: 1px;
+        }}
+
         .command-prompt-suggestion {{
             padding: 10px 16px;
             cursor: pointer;
@@ -122,7 +130,13 @@ pub fn get_command_prompt_styles() -> String {
             color: #888888;
         }}
 
-        .suggestion-info {{
+        .workspace-dot {{
+            width: 8px;
+            height: 8px;
+            display: block;
+        }}
+
+                .suggestion-info {{
             flex: 1;
             min-width: 0;
             display: flex;
@@ -139,6 +153,27 @@ pub fn get_command_prompt_styles() -> String {
             line-height: 1.2;
         }}
 
+        .suggestion-badge {{
+            flex-shrink: 0;
+            margin-left: 12px;
+            padding: 1px 6px;
+            border: 1px solid #333333;
+            color: #666666;
+            font-size: 10px;
+            text-transform: uppercase;
+            letter-spacing: 0.5px;
+        }}
+
+        .suggestion-badge.tab {{
+            border-color: #555555;
+            color: #aaaaaa;
+        }}
+
+        .suggestion-badge.bookmark {{
+            border-color: #aaaaaa;
+            color: #ffffff;
+        }}
+
         .suggestion-url {{
             color: #444444;
             font-size: 11px;
@@ -0,0 +1,413 @@
+use crate::ui::fonts;
+
+/// Returns the HTML content for the history page served at `calm://history`.
+/// Visits are requested from the browser over IPC and listed by day, newest first.
+pub fn get_history_page_html() -> String {
+    let styles = format!(
+        r#"
+        <style>
+            {}
+
+            * {{
+                margin: 0;
+                padding: 0;
+                box-sizing: border-box;
+            }}
+
+            html {{
+                background: #101010 !important;
+            }}
+
+            body {{
+                {}
+                background: #101010 !important;
+                color: #e8e8e8;
+                padding: 120px 40px 60px 40px;
+                line-height: 1.6;
+                font-size: 13px;
+                min-height: 100vh;
+            }}
+
+            .history-container {{
+                max-width: 820px;
+                margin: 0 auto;
+            }}
+
+            h1 {{
+                font-size: 32px;
+                margin-bottom: 24px;
+                color: #ffffff;
+            }}
+
+            .toolbar {{
+                display: flex;
+                gap: 12px;
+                margin-bottom: 24px;
+                position: sticky;
+                top: 0;
+                padding: 12px 0;
+                background: #101010;
+                z-index: 1;
+            }}
+
+            input[type="search"], select {{
+                background: #141414;
+                border: 1px solid #2a2a2a;
+                color: #e8e8e8;
+                padding: 10px 12px;
+                font-size: 13px;
+                font-family: 'gohu', monospace;
+                outline: none;
+            }}
+
+            input[type="search"] {{
+                flex: 1;
+                min-width: 0;
+            }}
+
+            input[type="search"]:focus, select:focus {{
+                border-color: #555555;
+            }}
+
+            select {{
+                max-width: 220px;
+            }}
+
+            .day-section {{
+                margin-bottom: 24px;
+            }}
+
+            .day-header {{
+                color: #888888;
+                font-size: 12px;
+                text-transform: uppercase;
+                letter-spacing: 0.5px;
+                padding-bottom: 8px;
+                border-bottom: 1px solid #2a2a2a;
+            }}
+
+            .visit-row {{
+                display: flex;
+                align-items: center;
+                gap: 12px;
+                padding: 8px 0;
+                border-bottom: 1px solid #1c1c1c;
+            }}
+
+            .visit-row.selected {{
+                background: #161616;
+            }}
+
+            .visit-time {{
+                color: #666666;
+                font-size: 12px;
+                width: 48px;
+                flex-shrink: 0;
+            }}
+
+            .visit-info {{
+                min-width: 0;
+                flex: 1;
+                cursor: pointer;
+            }}
+
+            .visit-title {{
+                color: #e8e8e8;
+                white-space: nowrap;
+                overflow: hidden;
+                text-overflow: ellipsis;
+            }}
+
+            .visit-info:hover .visit-title {{
+                color: #ffffff;
+                text-decoration: underline;
+            }}
+
+            .visit-url {{
+                color: #666666;
+                font-size: 12px;
+                white-space: nowrap;
+                overflow: hidden;
+                text-overflow: ellipsis;
+            }}
+
+            .visit-host {{
+                color: #555555;
+                font-size: 12px;
+                cursor: pointer;
+                flex-shrink: 0;
+            }}
+
+            .visit-host:hover {{
+                color: #aaaaaa;
+            }}
+
+            input[type="checkbox"] {{
+                width: 16px;
+                height: 16px;
+                accent-color: #ffffff;
+                flex-shrink: 0;
+            }}
+
+            button {{
+                border: 1px solid #333333;
+                background: #1a1a1a;
+                color: #e8e8e8;
+                padding: 10px 16px;
+                font-size: 13px;
+                font-family: 'gohu', monospace;
+                cursor: pointer;
+                transition: all 0.2s ease;
+                white-space: nowrap;
+            }}
+
+            button.danger {{
+                border-color: #5a2a2a;
+                color: #ff8a8a;
+            }}
+
+            button:hover {{
+                transform: translateY(-1px);
+            }}
+
+            button:disabled {{
+                background: #1a1a1a;
+                color: #555555;
+                border-color: #2a2a2a;
+                cursor: not-allowed;
+                transform: none;
+            }}
+
+            .empty {{
+                color: #666666;
+                padding: 40px 0;
+                text-align: center;
+            }}
+
+            #load-more {{
+                display: block;
+                margin: 24px auto 0 auto;
+            }}
+        </style>
+    "#,
+        fonts::get_gohu_font_face(),
+        fonts::get_gohu_font_family()
+    );
+
+    let script = r#"
+        <script>
+            let visits = [];
+            let hasMore = false;
+            let query = '';
+            let domain = '';
+            let forgetArmed = false;
+            const selected = new Set();
+            let searchDebounce = null;
+
+            function escapeHtml(text) {
+                const div = document.createElement('div');
+                div.textContent = text;
+                return div.innerHTML;
+            }
+
+            function hostOf(url) {
+                try {
+                    return new URL(url).hostname.replace(/^www\./, '');
+                } catch (e) {
+                    return '';
+                }
+            }
+
+            function matchesDomain(host, site) {
+                return host === site || host.endsWith('.' + site);
+            }
+
+            function dayLabel(date) {
+                const today = new Date();
+                today.setHours(0, 0, 0, 0);
+                const day = new Date(date);
+                day.setHours(0, 0, 0, 0);
+                const daysAgo = Math.round((today - day) / 86400000);
+                if (daysAgo === 0) return 'Today';
+                if (daysAgo === 1) return 'Yesterday';
+                return day.toLocaleDateString(undefined, { weekday: 'long', year: 'numeric', month: 'long', day: 'numeric' });
+            }
+
+            function requestVisits(before) {
+                window.ipc.postMessage(JSON.stringify({
+                    action: 'history_page_query',
+                    query: query,
+                    before: before
+                }));
+            }
+
+            window.showHistoryVisits = function(results, append, more) {
+                visits = append ? visits.concat(results) : results;
+                hasMore = more;
+                if (!append) selected.clear();
+                render();
+            };
+
+            function renderDomains() {
+                const select = document.getElementById('domain-filter');
+                const counts = new Map();
+                visits.forEach(visit => {
+                    const host = hostOf(visit.url);
+                    if (host) counts.set(host, (counts.get(host) || 0) + 1);
+                });
+                if (domain && !counts.has(domain)) counts.set(domain, 0);
+
+                const hosts = Array.from(counts.keys()).sort((a, b) => counts.get(b) - counts.get(a) || a.localeCompare(b));
+                select.innerHTML = '<option value="">All sites</option>' + hosts
+                    .map(host => `<option value="${escapeHtml(host)}">${escapeHtml(host)}</option>`)
+                    .join('');
+                select.value = domain;
+            }
+
+            function render() {
+                renderDomains();
+
+                const container = document.getElementById('days');
+                container.innerHTML = '';
+
+                const shown = visits.filter(visit => !domain || matchesDomain(hostOf(visit.url), domain));
+                if (shown.length === 0) {
+                    container.innerHTML = `<div class="empty">${query || domain ? 'No matching history' : 'No history yet'}</div>`;
+                }
+
+                let section = null;
+                let currentLabel = null;
+                shown.forEach(visit => {
+                    const date = new Date(visit.timestamp * 1000);
+                    const label = dayLabel(date);
+                    if (label !== currentLabel) {
+                        currentLabel = label;
+                        section = document.createElement('div');
+                        section.className = 'day-section';
+                        section.innerHTML = `<div class="day-header">${escapeHtml(label)}</div>`;
+                        container.appendChild(section);
+                    }
+
+                    const host = hostOf(visit.url);
+                    const row = document.createElement('div');
+                    row.className = 'visit-row' + (selected.has(visit.id) ? ' selected' : '');
+                    row.innerHTML = `
+                        <input type="checkbox" ${selected.has(visit.id) ? 'checked' : ''}>
+                        <div class="visit-time">${date.toLocaleTimeString(undefined, { hour: '2-digit', minute: '2-digit' })}</div>
+                        <div class="visit-info">
+                            <div class="visit-title">${escapeHtml(visit.title || visit.url)}</div>
+                            <div class="visit-url">${escapeHtml(visit.url)}</div>
+                        </div>
+                        <div class="visit-host" title="Show only this site">${escapeHtml(host)}</div>
+                    `;
+                    row.querySelector('input').addEventListener('change', (e) => {
+                        if (e.target.checked) {
+                            selected.add(visit.id);
+                        } else {
+                            selected.delete(visit.id);
+                        }
+                        row.classList.toggle('selected', e.target.checked);
+                        updateButtons();
+                    });
+                    row.querySelector('.visit-info').addEventListener('click', () => {
+                        window.location.href = visit.url;
+                    });
+                    row.querySelector('.visit-host').addEventListener('click', () => setDomain(host));
+                    section.appendChild(row);
+                });
+
+                document.getElementById('load-more').style.display = hasMore ? 'block' : 'none';
+                updateButtons();
+            }
+
+            function updateButtons() {
+                const deleteBtn = document.getElementById('delete-btn');
+                deleteBtn.disabled = selected.size === 0;
+                deleteBtn.textContent = selected.size > 0 ? `Delete ${selected.size}` : 'Delete selected';
+
+                const forgetBtn = document.getElementById('forget-btn');
+                forgetBtn.disabled = !domain;
+                forgetBtn.textContent = forgetArmed ? `Forget ${domain}?` : 'Forget this site';
+            }
+
+            function setDomain(site) {
+                domain = site;
+                forgetArmed = false;
+                render();
+            }
+
+            function deleteSelected() {
+                if (selected.size === 0) return;
+                window.ipc.postMessage(JSON.stringify({
+                    action: 'history_page_delete',
+                    ids: Array.from(selected)
+                }));
+                visits = visits.filter(visit => !selected.has(visit.id));
+                selected.clear();
+                render();
+            }
+
+            function forgetSite() {
+                if (!domain) return;
+                if (!forgetArmed) {
+                    forgetArmed = true;
+                    updateButtons();
+                    return;
+                }
+                window.ipc.postMessage(JSON.stringify({
+                    action: 'history_forget_site',
+                    host: domain
+                }));
+                const site = domain;
+                visits = visits.filter(visit => !matchesDomain(hostOf(visit.url), site));
+                visits.forEach(visit => selected.delete(visit.id));
+                setDomain('');
+            }
+
+            document.addEventListener('DOMContentLoaded', () => {
+                const search = document.getElementById('search');
+                search.addEventListener('input', () => {
+                    if (searchDebounce) clearTimeout(searchDebounce);
+                    searchDebounce = setTimeout(() => {
+                        query = search.value.trim();
+                        requestVisits(null);
+                    }, 200);
+                });
+                document.getElementById('domain-filter').addEventListener('change', (e) => setDomain(e.target.value));
+                document.getElementById('delete-btn').addEventListener('click', deleteSelected);
+                document.getElementById('forget-btn').addEventListener('click', forgetSite);
+                document.getElementById('load-more').addEventListener('click', () => {
+                    if (visits.length > 0) requestVisits(visits[visits.length - 1].timestamp);
+                });
+                search.focus();
+                requestVisits(null);
+            });
+        </script>
+    "#;
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <title>History</title>
+    {}
+</head>
+<body>
+    <div class="history-container">
+        <h1>History</h1>
+        <div class="toolbar">
+            <input type="search" id="search" placeholder="Search history" autocomplete="off">
+            <select id="domain-filter"><option value="">All sites</option></select>
+            <button id="delete-btn" disabled>Delete selected</button>
+            <button id="forget-btn" class="danger" disabled>Forget this site</button>
+        </div>
+        <div id="days"></div>
+        <button id="load-more" style="display: none">Load older visits</button>
+    </div>
+    {}
+</body>
+</html>"#,
+        styles, script
+    )
+}
@@ -1,10 +1,16 @@
 pub mod animations;
+pub mod bookmarks_panel;
 pub mod command_prompt;
 pub mod download_overlay;
 pub mod fonts;
+pub mod history_page;
+pub mod privacy_log_page;
+pub mod profiles_page;
 pub mod renderers;
+pub mod session_recovery;
 pub mod settings;
 pub mod sounds;
+pub mod split_divider;
 pub mod tab_bar;
 pub mod template;
 pub mod welcome;
@@ -13,9 +19,15 @@ pub use animations::{
     get_audio_indicator_script, get_interaction_animations, get_loading_animation,
     get_navigation_loader, get_page_transitions,
 };
+pub use bookmarks_panel::get_bookmarks_panel_html;
 pub use command_prompt::get_command_prompt_html;
 pub use download_overlay::get_download_overlay_html;
+pub use history_page::get_history_page_html;
+pub use privacy_log_page::get_privacy_log_page_html;
+pub use profiles_page::get_profiles_page_html;
+pub use session_recovery::get_session_recovery_html;
 pub use settings::get_settings_html;
 pub use sounds::get_sounds_script;
+pub use split_divider::get_split_divider_html;
 pub use tab_bar::get_complete_tab_bar_html;
 pub use welcome::get_welcome_html;
@@ -0,0 +1,375 @@
+use crate::privacy::shields;
+use crate::ui::fonts;
+
+/// Returns the HTML content for the privacy log page served at `calm://privacy-log`.
+/// Block events are requested from the browser over IPC, filtered there, and listed newest first.
+pub fn get_privacy_log_page_html() -> String {
+    let styles = format!(
+        r#"
+        <style>
+            {}
+
+            * {{
+                margin: 0;
+                padding: 0;
+                box-sizing: border-box;
+            }}
+
+            html {{
+                background: #101010 !important;
+            }}
+
+            body {{
+                {}
+                background: #101010 !important;
+                color: #e8e8e8;
+                padding: 120px 40px 60px 40px;
+                line-height: 1.6;
+                font-size: 13px;
+                min-height: 100vh;
+            }}
+
+            .privacy-log-container {{
+                max-width: 1080px;
+                margin: 0 auto;
+            }}
+
+            h1 {{
+                font-size: 32px;
+                margin-bottom: 8px;
+                color: #ffffff;
+            }}
+
+            .subtitle {{
+                color: #888888;
+                margin-bottom: 24px;
+            }}
+
+            .toolbar {{
+                display: flex;
+                gap: 12px;
+                margin-bottom: 12px;
+                position: sticky;
+                top: 0;
+                padding: 12px 0;
+                background: #101010;
+                z-index: 1;
+            }}
+
+            input[type="search"], input[type="text"], select {{
+                background: #141414;
+                border: 1px solid #2a2a2a;
+                color: #e8e8e8;
+                padding: 10px 12px;
+                font-size: 13px;
+                font-family: 'gohu', monospace;
+                outline: none;
+            }}
+
+            input[type="search"], input[type="text"] {{
+                flex: 1;
+                min-width: 0;
+            }}
+
+            input[type="search"]:focus, input[type="text"]:focus, select:focus {{
+                border-color: #555555;
+            }}
+
+            select {{
+                max-width: 220px;
+            }}
+
+            button {{
+                border: 1px solid #333333;
+                background: #1a1a1a;
+                color: #e8e8e8;
+                padding: 10px 16px;
+                font-size: 13px;
+                font-family: 'gohu', monospace;
+                cursor: pointer;
+                transition: all 0.2s ease;
+                white-space: nowrap;
+            }}
+
+            button.danger {{
+                border-color: #5a2a2a;
+                color: #ff8a8a;
+            }}
+
+            button:hover {{
+                transform: translateY(-1px);
+            }}
+
+            .summary {{
+                color: #666666;
+                font-size: 12px;
+                margin-bottom: 8px;
+            }}
+
+            table {{
+                width: 100%;
+                border-collapse: collapse;
+                table-layout: fixed;
+            }}
+
+            th {{
+                color: #888888;
+                font-size: 12px;
+                font-weight: normal;
+                text-transform: uppercase;
+                letter-spacing: 0.5px;
+                text-align: left;
+                padding: 8px 8px 8px 0;
+                border-bottom: 1px solid #2a2a2a;
+            }}
+
+            td {{
+                padding: 6px 8px 6px 0;
+                border-bottom: 1px solid #1c1c1c;
+                white-space: nowrap;
+                overflow: hidden;
+                text-overflow: ellipsis;
+                vertical-align: top;
+            }}
+
+            td.muted {{
+                color: #666666;
+                font-size: 12px;
+            }}
+
+            td.event-url {{
+                color: #e8e8e8;
+            }}
+
+            .col-time {{ width: 80px; }}
+            .col-tab {{ width: 48px; }}
+            .col-defense {{ width: 170px; }}
+            .col-type {{ width: 110px; }}
+            .col-rule {{ width: 180px; }}
+            .col-list {{ width: 160px; }}
+
+            .empty {{
+                color: #666666;
+                padding: 40px 0;
+                text-align: center;
+            }}
+
+            .export {{
+                display: flex;
+                gap: 12px;
+                margin-top: 24px;
+            }}
+
+            #export-status {{
+                margin-top: 8px;
+                font-size: 12px;
+                color: #888888;
+            }}
+
+            #export-status.error {{
+                color: #ff8a8a;
+            }}
+        </style>
+    "#,
+        fonts::get_gohu_font_face(),
+        fonts::get_gohu_font_family()
+    );
+
+    let script = r#"
+        <script>
+            const POLL_INTERVAL = 2000;
+            const filter = { query: '', defense: '', resourceType: '', tabId: null };
+            let events = [];
+            let tabIds = [];
+            let searchDebounce = null;
+
+            function escapeHtml(text) {
+                const div = document.createElement('div');
+                div.textContent = text;
+                return div.innerHTML;
+            }
+
+            function defenseLabel(name) {
+                const defense = defenses.find(defense => defense.name === name);
+                return defense ? defense.label : name;
+            }
+
+            function requestEvents() {
+                window.ipc.postMessage(JSON.stringify({
+                    action: 'privacy_log_query',
+                    filter: filter
+                }));
+            }
+
+            window.showPrivacyLog = function(results, tabs) {
+                events = results.slice().reverse();
+                tabIds = tabs;
+                render();
+            };
+
+            window.showPrivacyLogExportResult = function(message, ok) {
+                const status = document.getElementById('export-status');
+                status.textContent = message;
+                status.className = ok ? '' : 'error';
+            };
+
+            function renderTabs() {
+                const select = document.getElementById('tab-filter');
+                const shown = tabIds.slice();
+                if (filter.tabId !== null && !shown.includes(filter.tabId)) shown.push(filter.tabId);
+                select.innerHTML = '<option value="">All tabs</option>' + shown
+                    .map(id => `<option value="${id}">Tab ${id}</option>`)
+                    .join('');
+                select.value = filter.tabId === null ? '' : String(filter.tabId);
+            }
+
+            function render() {
+                renderTabs();
+
+                const anyFilter = filter.query || filter.defense || filter.resourceType || filter.tabId !== null;
+                document.getElementById('summary').textContent =
+                    `${events.length} ${events.length === 1 ? 'event' : 'events'}${anyFilter ? ' matching' : ''}`;
+
+                const body = document.getElementById('events');
+                body.innerHTML = '';
+                document.getElementById('empty').style.display = events.length ? 'none' : 'block';
+                document.getElementById('empty').textContent = anyFilter ? 'No matching events' : 'Nothing blocked yet';
+
+                events.forEach(event => {
+                    const date = new Date(event.timestamp);
+                    const row = document.createElement('tr');
+                    row.title = `${event.url}\nOn ${event.pageUrl}`;
+                    row.innerHTML = `
+                        <td class="muted">${date.toLocaleTimeString(undefined, { hour: '2-digit', minute: '2-digit', second: '2-digit' })}</td>
+                        <td class="muted">${event.tabId}</td>
+                        <td>${escapeHtml(defenseLabel(event.defense))}</td>
+                        <td class="muted">${escapeHtml(event.resourceType)}</td>
+                        <td class="event-url">${escapeHtml(event.url)}</td>
+                        <td class="muted">${escapeHtml(event.rule || '')}</td>
+                        <td class="muted">${escapeHtml(event.list || '')}</td>
+                    `;
+                    body.appendChild(row);
+                });
+            }
+
+            function clearLog() {
+                window.ipc.postMessage(JSON.stringify({ action: 'privacy_log_clear' }));
+                events = [];
+                tabIds = [];
+                render();
+            }
+
+            function exportLog() {
+                const path = document.getElementById('export-path').value.trim();
+                if (!path) {
+                    window.showPrivacyLogExportResult('Enter a file to export to', false);
+                    return;
+                }
+                window.ipc.postMessage(JSON.stringify({
+                    action: 'privacy_log_export',
+                    path: path,
+                    filter: filter
+                }));
+            }
+
+            document.addEventListener('DOMContentLoaded', () => {
+                const defenseFilter = document.getElementById('defense-filter');
+                defenseFilter.innerHTML = '<option value="">All defenses</option>' + defenses
+                    .map(defense => `<option value="${escapeHtml(defense.name)}">${escapeHtml(defense.label)}</option>`)
+                    .join('');
+
+                const search = document.getElementById('search');
+                search.addEventListener('input', () => {
+                    if (searchDebounce) clearTimeout(searchDebounce);
+                    searchDebounce = setTimeout(() => {
+                        filter.query = search.value.trim();
+                        requestEvents();
+                    }, 200);
+                });
+                defenseFilter.addEventListener('change', (e) => {
+                    filter.defense = e.target.value;
+                    requestEvents();
+                });
+                document.getElementById('type-filter').addEventListener('change', (e) => {
+                    filter.resourceType = e.target.value;
+                    requestEvents();
+                });
+                document.getElementById('tab-filter').addEventListener('change', (e) => {
+                    filter.tabId = e.target.value === '' ? null : Number(e.target.value);
+                    requestEvents();
+                });
+                document.getElementById('clear-btn').addEventListener('click', clearLog);
+                document.getElementById('export-btn').addEventListener('click', exportLog);
+                search.focus();
+                requestEvents();
+                setInterval(requestEvents, POLL_INTERVAL);
+            });
+        </script>
+    "#;
+
+    let defenses: Vec<serde_json::Value> = shields::DEFENSES
+        .iter()
+        .map(|(name, label)| serde_json::json!({ "name": name, "label": label }))
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <title>Privacy Log</title>
+    {}
+</head>
+<body>
+    <div class="privacy-log-container">
+        <h1>Privacy Log</h1>
+        <div class="subtitle">Requests and fingerprinting attempts stopped by Calm's defenses, kept in memory until Calm quits. Private windows are not logged.</div>
+        <div class="toolbar">
+            <input type="search" id="search" placeholder="Search URLs, pages and rules" autocomplete="off">
+            <select id="defense-filter"></select>
+            <select id="type-filter">
+                <option value="">All types</option>
+                <option value="script">script</option>
+                <option value="image">image</option>
+                <option value="stylesheet">stylesheet</option>
+                <option value="subdocument">subdocument</option>
+                <option value="xmlhttprequest">xmlhttprequest</option>
+                <option value="api">api</option>
+                <option value="other">other</option>
+            </select>
+            <select id="tab-filter"></select>
+            <button class="danger" id="clear-btn">Clear log</button>
+        </div>
+        <div class="summary" id="summary"></div>
+        <table>
+            <thead>
+                <tr>
+                    <th class="col-time">Time</th>
+                    <th class="col-tab">Tab</th>
+                    <th class="col-defense">Defense</th>
+                    <th class="col-type">Type</th>
+                    <th>URL</th>
+                    <th class="col-rule">Rule</th>
+                    <th class="col-list">List</th>
+                </tr>
+            </thead>
+            <tbody id="events"></tbody>
+        </table>
+        <div class="empty" id="empty"></div>
+        <div class="export">
+            <input type="text" id="export-path" placeholder="~/Downloads/calm-privacy-log.json" value="~/Downloads/calm-privacy-log.json" autocomplete="off">
+            <button id="export-btn">Export shown events</button>
+        </div>
+        <div id="export-status"></div>
+    </div>
+    <script>
+        const defenses = {};
+    </script>
+    {}
+</body>
+</html>"#,
+        styles,
+        serde_json::to_string(&defenses).unwrap_or_else(|_| "[]".to_string()),
+        script
+    )
+}
@@ -0,0 +1,223 @@
+use crate::ui::fonts;
+
+/// Returns the HTML content for the profile picker served at `calm://profiles`.
+/// Each profile opens in its own Calm instance, with its own configuration and data.
+pub fn get_profiles_page_html(profiles: &[String], current: &str) -> String {
+    let styles = format!(
+        r#"
+        <style>
+            {}
+
+            * {{
+                margin: 0;
+                padding: 0;
+                box-sizing: border-box;
+            }}
+
+            html {{
+                background: #101010 !important;
+            }}
+
+            body {{
+                {}
+                background: #101010 !important;
+                color: #e8e8e8;
+                padding: 120px 40px 60px 40px;
+                line-height: 1.6;
+                font-size: 13px;
+                min-height: 100vh;
+            }}
+
+            .profiles-container {{
+                max-width: 560px;
+                margin: 0 auto;
+            }}
+
+            h1 {{
+                font-size: 32px;
+                margin-bottom: 8px;
+                color: #ffffff;
+            }}
+
+            .subtitle {{
+                color: #888888;
+                margin-bottom: 24px;
+            }}
+
+            .profile-row {{
+                display: flex;
+                align-items: center;
+                gap: 12px;
+                padding: 12px 0;
+                border-bottom: 1px solid #1c1c1c;
+            }}
+
+            .profile-name {{
+                flex: 1;
+                min-width: 0;
+                color: #e8e8e8;
+                overflow: hidden;
+                text-overflow: ellipsis;
+            }}
+
+            .profile-current {{
+                color: #666666;
+                font-size: 12px;
+            }}
+
+            .new-profile {{
+                display: flex;
+                gap: 12px;
+                margin-top: 24px;
+            }}
+
+            input[type="text"] {{
+                flex: 1;
+                min-width: 0;
+                background: #141414;
+                border: 1px solid #2a2a2a;
+                color: #e8e8e8;
+                padding: 10px 12px;
+                font-size: 13px;
+                font-family: 'gohu', monospace;
+                outline: none;
+            }}
+
+            input[type="text"]:focus {{
+                border-color: #555555;
+            }}
+
+            button {{
+                border: 1px solid #333333;
+                background: #1a1a1a;
+                color: #e8e8e8;
+                padding: 10px 16px;
+                font-size: 13px;
+                font-family: 'gohu', monospace;
+                cursor: pointer;
+                transition: all 0.2s ease;
+                white-space: nowrap;
+            }}
+
+            button:hover {{
+                transform: translateY(-1px);
+            }}
+
+            button:disabled {{
+                background: #1a1a1a;
+                color: #555555;
+                border-color: #2a2a2a;
+                cursor: not-allowed;
+                transform: none;
+            }}
+
+            #status {{
+                color: #888888;
+                margin-top: 12px;
+                min-height: 20px;
+            }}
+
+            #status.error {{
+                color: #ff8a8a;
+            }}
+        </style>
+    "#,
+        fonts::get_gohu_font_face(),
+        fonts::get_gohu_font_family()
+    );
+
+    let script = r#"
+        <script>
+            const PROFILE_NAME = /^[A-Za-z0-9_-]{1,64}$/;
+
+            function escapeHtml(text) {
+                const div = document.createElement('div');
+                div.textContent = text;
+                return div.innerHTML;
+            }
+
+            window.showProfileStatus = function(message, isError) {
+                const status = document.getElementById('status');
+                status.textContent = message;
+                status.classList.toggle('error', !!isError);
+            };
+
+            function render() {
+                const list = document.getElementById('profile-list');
+                list.innerHTML = '';
+                profiles.forEach(name => {
+                    const row = document.createElement('div');
+                    row.className = 'profile-row';
+                    const isCurrent = name === currentProfile;
+                    row.innerHTML = `
+                        <div class="profile-name">${escapeHtml(name)}</div>
+                        ${isCurrent ? '<div class="profile-current">this window</div>' : ''}
+                        <button ${isCurrent ? 'disabled' : ''}>Open</button>
+                    `;
+                    row.querySelector('button').addEventListener('click', () => {
+                        window.ipc.postMessage(JSON.stringify({ action: 'open_profile', name: name }));
+                    });
+                    list.appendChild(row);
+                });
+            }
+
+            function createProfile() {
+                const input = document.getElementById('new-profile-name');
+                const name = input.value.trim();
+                if (!PROFILE_NAME.test(name)) {
+                    window.showProfileStatus('Use letters, digits, - and _ only', true);
+                    return;
+                }
+                if (profiles.includes(name)) {
+                    window.showProfileStatus(`${name} already exists`, true);
+                    return;
+                }
+                window.ipc.postMessage(JSON.stringify({ action: 'create_profile', name: name }));
+                profiles.push(name);
+                input.value = '';
+                render();
+            }
+
+            document.addEventListener('DOMContentLoaded', () => {
+                const input = document.getElementById('new-profile-name');
+                input.addEventListener('keydown', (e) => {
+                    if (e.key === 'Enter') createProfile();
+                });
+                document.getElementById('create-btn').addEventListener('click', createProfile);
+                render();
+            });
+        </script>
+    "#;
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <title>Profiles</title>
+    {}
+</head>
+<body>
+    <div class="profiles-container">
+        <h1>Profiles</h1>
+        <div class="subtitle">Each profile has its own settings, history, bookmarks, downloads, session and cookies, and opens in its own windows.</div>
+        <div id="profile-list"></div>
+        <div class="new-profile">
+            <input type="text" id="new-profile-name" placeholder="New profile name" autocomplete="off">
+            <button id="create-btn">Create and open</button>
+        </div>
+        <div id="status"></div>
+    </div>
+    <script>
+        const profiles = {};
+        const currentProfile = {};
+    </script>
+    {}
+</body>
+</html>"#,
+        styles,
+        serde_json::to_string(profiles).unwrap_or_else(|_| "[]".to_string()),
+        serde_json::to_string(current).unwrap_or_else(|_| "\"\"".to_string()),
+        script
+    )
+}
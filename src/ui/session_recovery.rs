@@ -0,0 +1,297 @@
+use crate::session::BrowserSession;
+use crate::ui::fonts;
+
+/// Returns the HTML content for the session recovery page shown after an unclean shutdown.
+pub fn get_session_recovery_html(session: &BrowserSession, default_url: &str) -> String {
+    let styles = format!(
+        r#"
+        <style>
+            {}
+
+            * {{
+                margin: 0;
+                padding: 0;
+                box-sizing: border-box;
+            }}
+
+            html {{
+                background: #101010 !important;
+            }}
+
+            body {{
+                {}
+                background: #101010 !important;
+                color: #e8e8e8;
+                padding: 120px 40px 60px 40px;
+                line-height: 1.6;
+                font-size: 13px;
+                min-height: 100vh;
+            }}
+
+            .recovery-container {{
+                max-width: 720px;
+                margin: 0 auto;
+            }}
+
+            h1 {{
+                font-size: 32px;
+                margin-bottom: 12px;
+                color: #ffffff;
+            }}
+
+            .subtitle {{
+                color: #888888;
+                font-size: 14px;
+                margin-bottom: 32px;
+            }}
+
+            .window-section {{
+                background: #141414;
+                border: 1px solid #2a2a2a;
+                padding: 20px 24px;
+                margin-bottom: 16px;
+            }}
+
+            .window-header {{
+                display: flex;
+                align-items: center;
+                gap: 12px;
+                font-size: 15px;
+                color: #ffffff;
+                margin-bottom: 12px;
+                cursor: pointer;
+            }}
+
+            .tab-row {{
+                display: flex;
+                align-items: center;
+                gap: 12px;
+                padding: 8px 0;
+                border-top: 1px solid #222222;
+                cursor: pointer;
+            }}
+
+            .tab-info {{
+                min-width: 0;
+                flex: 1;
+            }}
+
+            .tab-title {{
+                color: #e8e8e8;
+                white-space: nowrap;
+                overflow: hidden;
+                text-overflow: ellipsis;
+            }}
+
+            .tab-url {{
+                color: #666666;
+                font-size: 12px;
+                white-space: nowrap;
+                overflow: hidden;
+                text-overflow: ellipsis;
+            }}
+
+            input[type="checkbox"] {{
+                width: 16px;
+                height: 16px;
+                accent-color: #ffffff;
+                flex-shrink: 0;
+            }}
+
+            .actions {{
+                display: flex;
+                gap: 12px;
+                margin-top: 24px;
+            }}
+
+            button {{
+                border: 1px solid #333333;
+                background: #1a1a1a;
+                color: #e8e8e8;
+                padding: 12px 24px;
+                font-size: 14px;
+                font-family: 'gohu', monospace;
+                cursor: pointer;
+                transition: all 0.2s ease;
+            }}
+
+            button.primary {{
+                background: #ffffff;
+                color: #101010;
+                border-color: #ffffff;
+            }}
+
+            button:hover {{
+                transform: translateY(-1px);
+            }}
+
+            button:disabled {{
+                background: #333333;
+                color: #666666;
+                border-color: #333333;
+                cursor: not-allowed;
+                transform: none;
+            }}
+        </style>
+    "#,
+        fonts::get_gohu_font_face(),
+        fonts::get_gohu_font_family()
+    );
+
+    let script = r#"
+        <script>
+            function escapeHtml(text) {
+                const div = document.createElement('div');
+                div.textContent = text;
+                return div.innerHTML;
+            }
+
+            function renderSession() {
+                const container = document.getElementById('windows');
+                container.innerHTML = '';
+
+                SESSION.windows.forEach((win, windowIndex) => {
+                    const section = document.createElement('div');
+                    section.className = 'window-section';
+
+                    const header = document.createElement('label');
+                    header.className = 'window-header';
+                    header.innerHTML = `<input type="checkbox" class="window-checkbox" data-window="${windowIndex}" checked>
+                        <span>Window ${windowIndex + 1} &middot; ${win.tabs.length} tab${win.tabs.length === 1 ? '' : 's'}</span>`;
+                    section.appendChild(header);
+
+                    win.tabs.forEach((tab, tabIndex) => {
+                        const row = document.createElement('label');
+                        row.className = 'tab-row';
+                        row.innerHTML = `<input type="checkbox" class="tab-checkbox" data-window="${windowIndex}" data-tab="${tabIndex}" checked>
+                            <div class="tab-info">
+                                <div class="tab-title">${escapeHtml(tab.title || tab.url)}</div>
+                                <div class="tab-url">${escapeHtml(tab.url)}</div>
+                            </div>`;
+                        section.appendChild(row);
+                    });
+
+                    container.appendChild(section);
+                });
+
+                document.querySelectorAll('.window-checkbox').forEach(box => {
+                    box.addEventListener('change', () => {
+                        document.querySelectorAll(`.tab-checkbox[data-window="${box.dataset.window}"]`)
+                            .forEach(tabBox => { tabBox.checked = box.checked; });
+                        updateRestoreButton();
+                    });
+                });
+
+                document.querySelectorAll('.tab-checkbox').forEach(box => {
+                    box.addEventListener('change', () => {
+                        const siblings = document.querySelectorAll(`.tab-checkbox[data-window="${box.dataset.window}"]`);
+                        const windowBox = document.querySelector(`.window-checkbox[data-window="${box.dataset.window}"]`);
+                        windowBox.checked = Array.from(siblings).some(s => s.checked);
+                        updateRestoreButton();
+                    });
+                });
+
+                updateRestoreButton();
+            }
+
+            function updateRestoreButton() {
+                const anySelected = document.querySelectorAll('.tab-checkbox:checked').length > 0;
+                document.getElementById('restore-btn').disabled = !anySelected;
+            }
+
+            function remapSplitTree(node, remap) {
+                if (node.type === 'pane') {
+                    return Object.assign({}, node, { tab_index: remap.get(node.tab_index) });
+                }
+                return Object.assign({}, node, {
+                    children: node.children.map(child => remapSplitTree(child, remap))
+                });
+            }
+
+            function buildSelectedSession() {
+                const windows = [];
+
+                SESSION.windows.forEach((win, windowIndex) => {
+                    const keptIndices = [];
+                    win.tabs.forEach((tab, tabIndex) => {
+                        const box = document.querySelector(`.tab-checkbox[data-window="${windowIndex}"][data-tab="${tabIndex}"]`);
+                        if (box && box.checked) keptIndices.push(tabIndex);
+                    });
+                    if (keptIndices.length === 0) return;
+
+                    const remap = new Map(keptIndices.map((oldIndex, newIndex) => [oldIndex, newIndex]));
+                    const activeIndex = remap.has(win.active_tab_index) ? remap.get(win.active_tab_index) : 0;
+                    const splitGroups = (win.split_groups || [])
+                        .filter(group => group.tab_indices.every(i => remap.has(i)))
+                        .map(group => Object.assign({}, group, {
+                            tab_indices: group.tab_indices.map(i => remap.get(i)),
+                            tree: group.tree ? remapSplitTree(group.tree, remap) : null
+                        }));
+                    const tabGroups = (win.tab_groups || [])
+                        .map(group => Object.assign({}, group, {
+                            tab_indices: group.tab_indices.filter(i => remap.has(i)).map(i => remap.get(i))
+                        }))
+                        .filter(group => group.tab_indices.length > 0);
+
+                    windows.push(Object.assign({}, win, {
+                        tabs: keptIndices.map(i => win.tabs[i]),
+                        active_tab_index: activeIndex,
+                        split_groups: splitGroups,
+                        tab_groups: tabGroups
+                    }));
+                });
+
+                return Object.assign({}, SESSION, { windows });
+            }
+
+            function restoreSelected() {
+                window.ipc.postMessage(JSON.stringify({
+                    action: 'restore_session',
+                    session: buildSelectedSession()
+                }));
+            }
+
+            function startFresh() {
+                window.ipc.postMessage(JSON.stringify({ action: 'dismiss_session_recovery' }));
+                window.location.href = DEFAULT_URL;
+            }
+
+            document.addEventListener('DOMContentLoaded', renderSession);
+        </script>
+    "#;
+
+    let session_json = serde_json::to_string(session)
+        .unwrap_or_else(|_| "{\"windows\":[]}".to_string())
+        .replace("</", "<\\/");
+    let default_url_json = serde_json::to_string(default_url)
+        .unwrap_or_else(|_| "\"about:blank\"".to_string())
+        .replace("</", "<\\/");
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <title>Restore Session - Calm Browser</title>
+    {}
+</head>
+<body>
+    <div class="recovery-container">
+        <h1>Restore session?</h1>
+        <p class="subtitle">Calm did not shut down cleanly last time. Choose which windows and tabs to reopen.</p>
+        <div id="windows"></div>
+        <div class="actions">
+            <button class="primary" id="restore-btn" onclick="restoreSelected()">Restore selected</button>
+            <button onclick="startFresh()">Start fresh</button>
+        </div>
+    </div>
+    <script>
+        const SESSION = {};
+        const DEFAULT_URL = {};
+    </script>
+    {}
+</body>
+</html>"#,
+        styles, session_json, default_url_json, script
+    )
+}
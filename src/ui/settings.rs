@@ -399,6 +399,66 @@ pub fn get_settings_html() -> String {
                 transform: none;
             }}
 
+            textarea {{
+                background: #1a1a1a;
+                border: 1px solid #333333;
+                color: #e8e8e8;
+                padding: 10px 14px;
+                font-size: 13px;
+                font-family: 'gohu', monospace;
+                min-width: 280px;
+                min-height: 80px;
+                resize: vertical;
+            }}
+
+            textarea:focus {{
+                outline: none;
+                border: 1px solid #666666;
+                background: #222222;
+            }}
+
+            .history-rules-status {{
+                display: none;
+                align-items: center;
+                justify-content: space-between;
+                gap: 16px;
+                margin-top: 12px;
+            }}
+
+            .history-rules-status.show {{
+                display: flex;
+            }}
+
+            .filter-lists {{
+                margin-top: 12px;
+            }}
+
+            .filter-list {{
+                display: flex;
+                align-items: center;
+                justify-content: space-between;
+                gap: 16px;
+                padding: 10px 0;
+                border-top: 1px solid #222222;
+                font-size: 13px;
+                font-family: 'gohu', monospace;
+            }}
+
+            .filter-list-source {{
+                color: #e8e8e8;
+                overflow: hidden;
+                text-overflow: ellipsis;
+                white-space: nowrap;
+            }}
+
+            .filter-list-status {{
+                color: #888888;
+            }}
+
+            .filter-list-status.failed {{
+                color: #ff6b6b;
+            }}
+
             .update-status {{
                 font-size: 13px;
                 color: #888888;
@@ -477,7 +537,11 @@ pub fn get_settings_html() -> String {
                 'focus_sidebar': '',
                 'find': '',
                 'new_window': '',
-                'toggle_split_view': ''
+                'new_private_window': '',
+                'toggle_split_view': '',
+                'reopen_closed_tab': '',
+                'bookmark_page': '',
+                'toggle_bookmarks': ''
             };
 
             let recordingKey = null;
@@ -610,6 +674,16 @@ pub fn get_settings_html() -> String {
                     blockTrackers: document.getElementById('block-trackers').checked,
                     blockFingerprinting: document.getElementById('block-fingerprinting').checked,
                     blockCookies: document.getElementById('block-cookies').checked,
+                    historyExcludedHosts: document.getElementById('history-excluded-hosts').value
+                        .split('\n')
+                        .map(host => host.trim())
+                        .filter(host => host),
+                    historyMaxAgeDays: Math.max(0, parseInt(document.getElementById('history-max-age').value, 10) || 0),
+                    historyStripQueryStrings: document.getElementById('history-strip-query').checked,
+                    filterSubscriptions: Object.fromEntries(
+                        Array.from(document.querySelectorAll('.filter-list-toggle'))
+                            .map(toggle => [toggle.dataset.url, toggle.checked])
+                    ),
                     shortcuts: shortcuts
                 };
 
@@ -659,6 +733,109 @@ pub fn get_settings_html() -> String {
                 }, 300);
             }
 
+            window.offerHistoryPurge = function(count) {
+                document.getElementById('history-rules-message').textContent =
+                    `${count} ${count === 1 ? 'visit' : 'visits'} already in history ${count === 1 ? 'does' : 'do'} not follow these rules.`;
+                document.getElementById('apply-history-rules-btn').disabled = false;
+                document.getElementById('history-rules-status').classList.add('show');
+            };
+
+            window.showHistoryPurgeResult = function(count) {
+                document.getElementById('history-rules-message').textContent =
+                    `Updated ${count} ${count === 1 ? 'visit' : 'visits'} in history.`;
+                document.getElementById('apply-history-rules-btn').disabled = true;
+            };
+
+            function filterListStatus(list) {
+                const updated = list.lastUpdated
+                    ? new Date(list.lastUpdated * 1000).toLocaleString()
+                    : null;
+                if (!list.enabled) return 'Disabled';
+                if (list.error && !list.available) return `Failed: ${list.error}`;
+                if (list.error) return `Update failed, using the copy from ${updated}: ${list.error}`;
+                if (!list.subscribed) return `${list.ruleCount} rules, local file`;
+                if (!list.available) return 'Waiting for the first download';
+                return `${list.ruleCount} rules, updated ${updated}`;
+            }
+
+            function showFilterLists(lists) {
+                const container = document.getElementById('filter-lists');
+                container.replaceChildren();
+                if (lists.length === 0) {
+                    const empty = document.createElement('div');
+                    empty.className = 'filter-list-status';
+                    empty.textContent = 'No filter lists. Add files to filter_lists or URLs to filter_subscriptions in the config file.';
+                    container.appendChild(empty);
+                    return;
+                }
+                for (const list of lists) {
+                    const row = document.createElement('div');
+                    row.className = 'filter-list';
+
+                    const info = document.createElement('div');
+                    info.style.minWidth = '0';
+                    const source = document.createElement('div');
+                    source.className = 'filter-list-source';
+                    source.textContent = list.source;
+                    source.title = list.source;
+                    const status = document.createElement('div');
+                    status.className = 'filter-list-status' + (list.error && list.enabled ? ' failed' : '');
+                    status.textContent = filterListStatus(list);
+                    info.append(source, status);
+                    row.appendChild(info);
+
+                    if (list.subscribed) {
+                        const toggle = document.createElement('input');
+                        toggle.type = 'checkbox';
+                        toggle.className = 'filter-list-toggle';
+                        toggle.dataset.url = list.source;
+                        toggle.checked = list.enabled;
+                        toggle.addEventListener('change', saveSettings);
+                        row.appendChild(toggle);
+                    }
+                    container.appendChild(row);
+                }
+            }
+
+            function applyHistoryRules() {
+                document.getElementById('apply-history-rules-btn').disabled = true;
+                window.ipc.postMessage(JSON.stringify({
+                    action: 'apply_history_rules'
+                }));
+            }
+
+            function importBrowserData() {
+                const path = document.getElementById('import-path').value.trim();
+                if (!path) return;
+                setImportExportStatus('Importing...', 'checking');
+                window.ipc.postMessage(JSON.stringify({
+                    action: 'import_browser_data',
+                    path: path
+                }));
+            }
+
+            function exportBookmarks() {
+                const path = document.getElementById('export-path').value.trim();
+                if (!path) return;
+                setImportExportStatus('Exporting...', 'checking');
+                window.ipc.postMessage(JSON.stringify({
+                    action: 'export_bookmarks',
+                    path: path
+                }));
+            }
+
+            function setImportExportStatus(message, state) {
+                const status = document.getElementById('import-export-status');
+                status.className = 'update-status' + (state ? ` ${state}` : '');
+                status.textContent = message;
+                document.getElementById('import-btn').disabled = state === 'checking';
+                document.getElementById('export-btn').disabled = state === 'checking';
+            }
+
+            window.showImportExportResult = function(message, ok) {
+                setImportExportStatus(ok ? message : `Error: ${message}`, ok ? 'available' : 'error');
+            };
+
             function checkForUpdates() {
                 const button = document.getElementById('check-update-btn');
                 const status = document.getElementById('update-status');
@@ -795,6 +972,18 @@ pub fn get_settings_html() -> String {
                     console.log('Setting block cookies to:', settings.blockCookies);
                     document.getElementById('block-cookies').checked = settings.blockCookies;
                 }
+                if (settings.historyExcludedHosts) {
+                    document.getElementById('history-excluded-hosts').value = settings.historyExcludedHosts.join('\n');
+                }
+                if (settings.historyMaxAgeDays !== undefined) {
+                    document.getElementById('history-max-age').value = settings.historyMaxAgeDays;
+                }
+                if (settings.historyStripQueryStrings !== undefined) {
+                    document.getElementById('history-strip-query').checked = settings.historyStripQueryStrings;
+                }
+                if (settings.filterLists) {
+                    showFilterLists(settings.filterLists);
+                }
                 if (settings.shortcuts) {
                     for (const [key, value] of Object.entries(settings.shortcuts)) {
                         shortcuts[key] = value;
@@ -824,11 +1013,19 @@ pub fn get_settings_html() -> String {
                     if (el) el.addEventListener('input', debouncedSaveSettings);
                 });
 
-                const checkboxes = ['youtube-redirect', 'vim-mode', 'ui-sounds', 'block-trackers', 'block-fingerprinting', 'block-cookies'];
+                const checkboxes = ['youtube-redirect', 'vim-mode', 'ui-sounds', 'block-trackers', 'block-fingerprinting', 'block-cookies', 'history-strip-query'];
                 checkboxes.forEach(id => {
                     const el = document.getElementById(id);
                     if (el) el.addEventListener('change', saveSettings);
                 });
+
+                // History rules are saved once editing is done, so a half-typed pattern never
+                // offers to purge history.
+                const historyInputs = ['history-excluded-hosts', 'history-max-age'];
+                historyInputs.forEach(id => {
+                    const el = document.getElementById(id);
+                    if (el) el.addEventListener('change', saveSettings);
+                });
             });
 
             let isTabActive = true;
@@ -1039,6 +1236,48 @@ pub fn get_settings_html() -> String {
                     <input type="checkbox" id="block-cookies" checked>
                 </div>
             </div>
+            <div class="setting-item">
+                <div class="setting-info">
+                    <div class="setting-label">Filter Lists</div>
+                    <div class="setting-description">Ad blocking lists from the config file. Subscriptions are updated in the background and keep their last good copy when an update fails</div>
+                </div>
+            </div>
+            <div class="filter-lists" id="filter-lists"></div>
+        </div>
+
+        <div class="setting-section">
+            <h2>History</h2>
+            <div class="setting-item">
+                <div class="setting-info">
+                    <div class="setting-label">Never Record</div>
+                    <div class="setting-description">One site per line. A site also covers its subdomains, and * matches anything, as in intranet.*</div>
+                </div>
+                <div class="setting-control">
+                    <textarea id="history-excluded-hosts" placeholder="bank.com" spellcheck="false"></textarea>
+                </div>
+            </div>
+            <div class="setting-item">
+                <div class="setting-info">
+                    <div class="setting-label">Keep History For</div>
+                    <div class="setting-description">Days before visits are deleted, 0 keeps them forever</div>
+                </div>
+                <div class="setting-control">
+                    <input type="number" id="history-max-age" min="0" placeholder="0">
+                </div>
+            </div>
+            <div class="setting-item">
+                <div class="setting-info">
+                    <div class="setting-label">Strip Query Strings</div>
+                    <div class="setting-description">Record pages without the part after ? or #, so searches and session tokens are not kept</div>
+                </div>
+                <div class="setting-control">
+                    <input type="checkbox" id="history-strip-query">
+                </div>
+            </div>
+            <div class="history-rules-status" id="history-rules-status">
+                <span class="update-status" id="history-rules-message"></span>
+                <button id="apply-history-rules-btn" onclick="applyHistoryRules()">Apply to Existing History</button>
+            </div>
         </div>
 
         <div class="setting-section">
@@ -1156,6 +1395,20 @@ pub fn get_settings_html() -> String {
                     <div class="conflict-warning" id="conflict-new_window"></div>
                 </div>
             </div>
+            <div class="setting-item">
+                <div class="setting-info">
+                    <div class="setting-label">New Private Window</div>
+                    <div class="setting-description">Open a window that keeps no history, session or web data</div>
+                </div>
+                <div class="setting-control">
+                    <input type="text" class="shortcut-input" id="shortcut-new_private_window" readonly
+                           onclick="startRecording('new_private_window')"
+                           onkeydown="recordShortcut(event, 'new_private_window')"
+                           onblur="stopRecording('new_private_window')"
+                           placeholder="Cmd+Shift+N">
+                    <div class="conflict-warning" id="conflict-new_private_window"></div>
+                </div>
+            </div>
             <div class="setting-item">
                 <div class="setting-info">
                     <div class="setting-label">Toggle Split View</div>
@@ -1170,6 +1423,73 @@ pub fn get_settings_html() -> String {
                     <div class="conflict-warning" id="conflict-toggle_split_view"></div>
                 </div>
             </div>
+            <div class="setting-item">
+                <div class="setting-info">
+                    <div class="setting-label">Reopen Closed Tab</div>
+                    <div class="setting-description">Bring back the last closed tab or window</div>
+                </div>
+                <div class="setting-control">
+                    <input type="text" class="shortcut-input" id="shortcut-reopen_closed_tab" readonly
+                           onclick="startRecording('reopen_closed_tab')"
+                           onkeydown="recordShortcut(event, 'reopen_closed_tab')"
+                           onblur="stopRecording('reopen_closed_tab')"
+                           placeholder="Cmd+Shift+T">
+                    <div class="conflict-warning" id="conflict-reopen_closed_tab"></div>
+                </div>
+            </div>
+            <div class="setting-item">
+                <div class="setting-info">
+                    <div class="setting-label">Bookmark Page</div>
+                    <div class="setting-description">Bookmark the current page and edit it</div>
+                </div>
+                <div class="setting-control">
+                    <input type="text" class="shortcut-input" id="shortcut-bookmark_page" readonly
+                           onclick="startRecording('bookmark_page')"
+                           onkeydown="recordShortcut(event, 'bookmark_page')"
+                           onblur="stopRecording('bookmark_page')"
+                           placeholder="Cmd+D">
+                    <div class="conflict-warning" id="conflict-bookmark_page"></div>
+                </div>
+            </div>
+            <div class="setting-item">
+                <div class="setting-info">
+                    <div class="setting-label">Toggle Bookmarks</div>
+                    <div class="setting-description">Show/hide the bookmarks panel</div>
+                </div>
+                <div class="setting-control">
+                    <input type="text" class="shortcut-input" id="shortcut-toggle_bookmarks" readonly
+                           onclick="startRecording('toggle_bookmarks')"
+                           onkeydown="recordShortcut(event, 'toggle_bookmarks')"
+                           onblur="stopRecording('toggle_bookmarks')"
+                           placeholder="Cmd+Shift+B">
+                    <div class="conflict-warning" id="conflict-toggle_bookmarks"></div>
+                </div>
+            </div>
+        </div>
+
+        <div class="setting-section">
+            <h2>Import &amp; Export</h2>
+            <div class="setting-item">
+                <div class="setting-info">
+                    <div class="setting-label">Import Bookmarks &amp; History</div>
+                    <div class="setting-description">Bookmark HTML, a Firefox JSON backup or places.sqlite, or Chromium's Bookmarks or History file</div>
+                </div>
+                <div class="setting-control">
+                    <input type="text" id="import-path" placeholder="~/Downloads/bookmarks.html">
+                    <button id="import-btn" onclick="importBrowserData()">Import</button>
+                </div>
+            </div>
+            <div class="setting-item">
+                <div class="setting-info">
+                    <div class="setting-label">Export Bookmarks</div>
+                    <div class="setting-description">Saved as bookmark HTML when the file ends in .html, as JSON otherwise</div>
+                </div>
+                <div class="setting-control">
+                    <input type="text" id="export-path" placeholder="~/calm-bookmarks.html">
+                    <button id="export-btn" onclick="exportBookmarks()">Export</button>
+                </div>
+            </div>
+            <div class="update-status" id="import-export-status"></div>
         </div>
 
         <div class="setting-section">
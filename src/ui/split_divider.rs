@@ -0,0 +1,124 @@
+/// Returns the HTML content for the transparent overlay that sits on a split view divider.
+/// Dragging it reports the pointer position as a fraction of the overlay, which covers the whole
+/// split while a drag is in progress.
+pub fn get_split_divider_html() -> &'static str {
+    r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <style>
+        * {
+            margin: 0;
+            padding: 0;
+            box-sizing: border-box;
+        }
+
+        html, body {
+            width: 100vw;
+            height: 100vh;
+            background: transparent !important;
+            overflow: hidden;
+            user-select: none;
+        }
+
+        body.vertical {
+            cursor: col-resize;
+        }
+
+        body.horizontal {
+            cursor: row-resize;
+        }
+
+        #divider-line {
+            position: absolute;
+            background: rgba(255, 255, 255, 0.08);
+            transition: background 0.15s ease;
+        }
+
+        body.vertical #divider-line {
+            top: 0;
+            bottom: 0;
+            left: 50%;
+            width: 1px;
+            transform: translateX(-50%);
+        }
+
+        body.horizontal #divider-line {
+            left: 0;
+            right: 0;
+            top: 50%;
+            height: 1px;
+            transform: translateY(-50%);
+        }
+
+        body:hover #divider-line {
+            background: rgba(255, 255, 255, 0.35);
+        }
+
+        body.vertical:hover #divider-line {
+            width: 2px;
+        }
+
+        body.horizontal:hover #divider-line {
+            height: 2px;
+        }
+
+        body.dragging #divider-line {
+            display: none;
+        }
+    </style>
+</head>
+<body class="vertical">
+    <div id="divider-line"></div>
+    <script>
+        let dragging = false;
+        let expanded = false;
+        let pendingFrame = null;
+        let lastPointer = null;
+
+        function postDividerMessage(phase, x, y) {
+            window.ipc.postMessage(JSON.stringify({ action: 'split_divider_drag', phase, x, y }));
+        }
+
+        window.setDividerOrientation = function(orientation) {
+            document.body.classList.toggle('vertical', orientation !== 'horizontal');
+            document.body.classList.toggle('horizontal', orientation === 'horizontal');
+        };
+
+        window.setDividerExpanded = function(isExpanded) {
+            expanded = isExpanded;
+            document.body.classList.toggle('dragging', isExpanded);
+        };
+
+        document.addEventListener('mousedown', (e) => {
+            if (e.button !== 0) return;
+            e.preventDefault();
+            dragging = true;
+            postDividerMessage('start', 0, 0);
+        });
+
+        document.addEventListener('mousemove', (e) => {
+            if (!dragging || !expanded) return;
+            lastPointer = {
+                x: e.clientX / Math.max(window.innerWidth, 1),
+                y: e.clientY / Math.max(window.innerHeight, 1)
+            };
+            if (pendingFrame) return;
+            pendingFrame = requestAnimationFrame(() => {
+                pendingFrame = null;
+                postDividerMessage('move', lastPointer.x, lastPointer.y);
+            });
+        });
+
+        function endDrag() {
+            if (!dragging) return;
+            dragging = false;
+            postDividerMessage('end', 0, 0);
+        }
+
+        document.addEventListener('mouseup', endDrag);
+        window.addEventListener('blur', endDrag);
+    </script>
+</body>
+</html>"#
+}
@@ -29,6 +29,16 @@ pub fn get_tab_bar_html_structure() -> &'static str {
                     <path d="M15 9V7h2v2h-2zm2 6v-2h-4v-2h4V9h2v2h2v2h-2v2h-2zm0 0v2h-2v-2h2zm-6-4v2H7v2H5v-2H3v-2h2V9h2v2h4zm-4 4h2v2H7v-2zm2-8v2H7V7h2z"/>
                 </svg>
             </button>
+            <button class="add-split-pane-btn" id="add-split-pane-btn" onclick="if (!this.disabled) { playUISound('cursorMove'); window.ipc.postMessage(JSON.stringify({action: 'add_split_pane'})); }" title="Add Pane">
+                <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" shape-rendering="crispEdges">
+                    <path d="M2 5h20v14H2V5zm2 2v10h7V7H4zm12 2h2v2h2v2h-2v2h-2v-2h-2v-2h2V9z"/>
+                </svg>
+            </button>
+            <button class="split-layout-btn" id="split-layout-btn" onclick="window.ipc.postMessage(JSON.stringify({action: 'set_split_layout', layout: this.dataset.nextLayout || 'grid'}))" title="Switch to Grid Layout">
+                <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" shape-rendering="crispEdges">
+                    <path d="M2 5h20v14H2V5zm2 2v4h7V7H4zm9 0v4h7V7h-7zm7 6h-7v4h7v-4zm-9 4v-4H4v4h7z"/>
+                </svg>
+            </button>
             <button class="split-view-btn" id="split-view-btn" onclick="if (!this.disabled) { playUISound('cursorMove'); window.ipc.postMessage(JSON.stringify({action: 'toggle_split_view'})); }" title="Toggle Split View (Cmd+Shift+S)">
                 <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" shape-rendering="crispEdges">
                     <path d="M2 5h20v14H2V5zm2 2v10h7V7H4zm9 0v10h7V7h-7z"/>
@@ -37,6 +47,11 @@ pub fn get_tab_bar_html_structure() -> &'static str {
         </div>
         <div class="url-bar-container">
             <input type="text" class="url-bar" id="url-bar" placeholder="search or enter address" />
+            <button class="shield-btn" id="shield-btn" onclick="event.stopPropagation(); playUISound('cursorMove'); toggleShields()" title="Site Shields">
+                <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" shape-rendering="crispEdges">
+                    <path d="M4 2h16v2h2v8h-2v4h-2v2h-2v2h-2v2h-4v-2H8v-2H6v-2H4v-4H2V4h2V2zm0 2v8h2v4h2v2h2v2h4v-2h2v-2h2v-4h2V4H4z"/>
+                </svg>
+            </button>
             <button class="reload-btn" id="reload-btn" onclick="playUISound('cursorMove'); window.ipc.postMessage(JSON.stringify({action: 'reload_tab'}))" title="Reload (Cmd+R)">
                 <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" shape-rendering="crispEdges">
                     <path d="M16 2h-2v2h2v2H4v2H2v5h2V8h12v2h-2v2h2v-2h2V8h2V6h-2V4h-2V2zM6 20h2v2h2v-2H8v-2h12v-2h2v-5h-2v5H8v-2h2v-2H8v2H6v2H4v2h2v2z"/>
@@ -6,9 +6,41 @@ pub use html::get_tab_bar_html_structure;
 pub use script::get_tab_bar_script;
 pub use styles::get_tab_bar_styles;
 
-pub fn get_complete_tab_bar_html(vim_mode: bool, sounds_enabled: bool) -> String {
+use crate::config::ContainerDefinition;
+
+/// Returns the tab bar page. Private windows get a tinted sidebar and a "Private" badge, and
+/// windows of a named profile show the profile's name in a badge. `containers` are offered in
+/// the tab context menu, and tab groups can be saved, except in private windows.
+pub fn get_complete_tab_bar_html(
+    vim_mode: bool,
+    sounds_enabled: bool,
+    private_window: bool,
+    containers: &[ContainerDefinition],
+) -> String {
     let styles = get_tab_bar_styles();
     let sounds_script = crate::ui::get_sounds_script(sounds_enabled);
+    let profile = (!crate::paths::is_default_profile()).then(crate::paths::profile);
+    let body_class = if private_window { r#" class="private-window""# } else { "" };
+    let badge = match (private_window, profile) {
+        (true, _) => format!(
+            r#"<div class="private-badge" title="History, session and website data of this window are not kept">
+        <svg width="14" height="14" viewBox="0 0 24 24" fill="currentColor" shape-rendering="crispEdges">
+            <path d="M6 4h12v2h2v4h2v2H2v-2h2V6h2V4zm0 10h4v2h4v-2h4v2h2v4h-6v-2h-4v2H4v-4h2v-2zm0 2v2h2v-2H6zm10 0v2h2v-2h-2z"/>
+        </svg>
+        Private{}
+    </div>"#,
+            profile.map(|name| format!(" · {}", name)).unwrap_or_default()
+        ),
+        (false, Some(name)) => format!(r#"<div class="profile-badge" title="Profile">{}</div>"#, name),
+        (false, None) => String::new(),
+    };
+    let containers_json = if private_window {
+        "[]".to_string()
+    } else {
+        serde_json::to_string(containers)
+            .map(|json| json.replace("</", "<\\/"))
+            .unwrap_or_else(|_| "[]".to_string())
+    };
     format!(
         r#"<!DOCTYPE html>
 <html>
@@ -20,18 +52,25 @@ pub fn get_complete_tab_bar_html(vim_mode: bool, sounds_enabled: bool) -> String
         {}
     </style>
 </head>
-<body>
+<body{}>
+    {}
     {}
     <script>
         window.vimMode = {};
+        window.privateWindow = {};
+        window.containers = {};
         {}
         {}
     </script>
 </body>
 </html>"#,
         styles,
+        body_class,
+        badge,
         get_tab_bar_html_structure(),
         vim_mode,
+        private_window,
+        containers_json,
         sounds_script,
         get_tab_bar_script()
     )
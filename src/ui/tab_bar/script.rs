@@ -3,6 +3,8 @@ pub fn get_tab_bar_script() -> &'static str {
         window.tabs = [];
         window.currentUrl = '';
         window.tabAudioState = {};
+        window.tabBlockedCounts = {};
+        window.tabContainers = {};
         window.focusedTabIndex = -1;
         window.lastGKeyTime = 0;
         window.sidebarFocused = false;
@@ -13,6 +15,41 @@ pub fn get_tab_bar_script() -> &'static str {
             orientation: 'vertical'
         };
         window.splitGroups = [];
+        window.tabGroups = [];
+
+        const GROUP_COLOR_VALUES = {
+            blue: '#5b8def',
+            red: '#e5534b',
+            yellow: '#d4b106',
+            green: '#57ab5a',
+            pink: '#e275ad',
+            purple: '#986ee2',
+            cyan: '#39c5cf',
+            orange: '#e0823d',
+            grey: '#888888'
+        };
+
+        function applyTabContainer(tabEl, tabId) {
+            const container = window.tabContainers[tabId];
+            tabEl.classList.toggle('in-container', !!container);
+            if (container) {
+                tabEl.style.setProperty('--container-color', GROUP_COLOR_VALUES[container.color] || GROUP_COLOR_VALUES.grey);
+                tabEl.dataset.container = container.name;
+            } else {
+                tabEl.style.removeProperty('--container-color');
+                delete tabEl.dataset.container;
+            }
+        }
+
+        window.setTabContainer = function(tabId, container) {
+            if (container) {
+                window.tabContainers[tabId] = container;
+            } else {
+                delete window.tabContainers[tabId];
+            }
+            const tabEl = document.querySelector(`.tab[data-tab-id="${tabId}"]`);
+            if (tabEl) applyTabContainer(tabEl, tabId);
+        };
 
         window.showSidebarFocus = function() {
             const tabBar = document.getElementById('tab-bar');
@@ -58,6 +95,10 @@ pub fn get_tab_bar_script() -> &'static str {
                 const isSettingsPage = url === 'calm://settings';
                 settingsBtn.disabled = isSettingsPage;
             }
+
+            if (document.getElementById('shield-panel')) {
+                window.ipc.postMessage(JSON.stringify({action: 'show_shields'}));
+            }
         };
 
         window.updateNavigationButtons = function(canGoBack, canGoForward) {
@@ -84,13 +125,37 @@ pub fn get_tab_bar_script() -> &'static str {
             });
         }
 
-        window.addTab = function(tabId, url) {
+        // Number of requests and fingerprinting attempts blocked on the tab's page.
+        function createBlockedCount(tabId) {
+            const blockedCount = document.createElement('span');
+            blockedCount.className = 'tab-blocked-count';
+            blockedCount.id = `blocked-count-${tabId}`;
+            const count = window.tabBlockedCounts[tabId] || 0;
+            blockedCount.textContent = count > 99 ? '99+' : String(count);
+            blockedCount.title = `${count} blocked on this page`;
+            blockedCount.classList.toggle('visible', count > 0);
+            return blockedCount;
+        }
+
+        window.updateTabBlockedCount = function(tabId, count) {
+            window.tabBlockedCounts[tabId] = count;
+            const blockedCount = document.getElementById(`blocked-count-${tabId}`);
+            if (blockedCount) {
+                blockedCount.replaceWith(createBlockedCount(tabId));
+            }
+        };
+
+        window.addTab = function(tabId, url, index) {
             const tab = {
                 id: tabId,
                 url: url,
                 title: getDisplayTitle(url)
             };
-            window.tabs.push(tab);
+            const insertAt = Number.isInteger(index) && index >= 0 && index < window.tabs.length
+                ? index
+                : window.tabs.length;
+            const nextTab = window.tabs[insertAt];
+            window.tabs.splice(insertAt, 0, tab);
             window.tabAudioState[tabId] = false;
 
             const tabEl = document.createElement('div');
@@ -122,6 +187,7 @@ pub fn get_tab_bar_script() -> &'static str {
 
             tabEl.appendChild(favicon);
             tabEl.appendChild(titleSpan);
+            tabEl.appendChild(createBlockedCount(tabId));
             tabEl.appendChild(audioIndicator);
             tabEl.appendChild(closeBtn);
 
@@ -130,10 +196,18 @@ pub fn get_tab_bar_script() -> &'static str {
                 window.ipc.postMessage(JSON.stringify({action: 'switch_tab', tabId: tabId}));
                 window.hideSidebarFocus();
             };
+            tabEl.oncontextmenu = (event) => window.showTabContextMenu(event, tabId);
+            attachTabDragHandlers(tabEl, tabId);
+            applyTabContainer(tabEl, tabId);
 
             const tabBar = document.getElementById('tab-bar');
             if (tabBar) {
-                tabBar.appendChild(tabEl);
+                const nextEl = nextTab && tabBar.querySelector(`.tab[data-tab-id="${nextTab.id}"]`);
+                if (nextEl && nextEl.parentElement === tabBar) {
+                    tabBar.insertBefore(tabEl, nextEl);
+                } else {
+                    tabBar.appendChild(tabEl);
+                }
             }
 
             setTimeout(() => {
@@ -158,6 +232,8 @@ pub fn get_tab_bar_script() -> &'static str {
             }
             window.tabs = window.tabs.filter(t => t.id !== tabId);
             delete window.tabAudioState[tabId];
+            delete window.tabBlockedCounts[tabId];
+            delete window.tabContainers[tabId];
             window.updateSplitButtonState();
         };
 
@@ -181,6 +257,9 @@ pub fn get_tab_bar_script() -> &'static str {
                 const tabEl = document.querySelector(`.tab[data-tab-id="${tabId}"] .tab-title`);
                 if (tabEl) {
                     tabEl.textContent = title || 'New Tab';
+                    if (tab.pinned) {
+                        tabEl.parentElement.title = title || 'New Tab';
+                    }
                 }
             }
         };
@@ -197,6 +276,527 @@ pub fn get_tab_bar_script() -> &'static str {
             }
         };
 
+        window.setTabSuspended = function(tabId, suspended) {
+            const tab = window.tabs.find(t => t.id === tabId);
+            if (tab) {
+                tab.suspended = suspended;
+            }
+            const tabEl = document.querySelector(`.tab[data-tab-id="${tabId}"]`);
+            if (tabEl) {
+                tabEl.classList.toggle('suspended', suspended);
+            }
+        };
+
+        window.moveTabInList = function(tabId, index) {
+            const currentIndex = window.tabs.findIndex(t => t.id === tabId);
+            if (currentIndex < 0) return;
+
+            const [tab] = window.tabs.splice(currentIndex, 1);
+            const insertAt = Math.min(Math.max(index, 0), window.tabs.length);
+            window.tabs.splice(insertAt, 0, tab);
+            if (window.focusedTabIndex === currentIndex) {
+                window.focusedTabIndex = insertAt;
+            }
+            window.refreshTabs();
+        };
+
+        window.setTabOrder = function(tabIds) {
+            const byId = new Map(window.tabs.map(t => [t.id, t]));
+            const ordered = tabIds.map(id => byId.get(id)).filter(Boolean);
+            const remaining = window.tabs.filter(t => !tabIds.includes(t.id));
+            window.tabs = ordered.concat(remaining);
+        };
+
+        window.setTabGroups = function(groups) {
+            window.tabGroups = groups;
+            window.refreshTabs();
+        };
+
+        function startTabGroupRename(group) {
+            const nameEl = document.querySelector(`.tab-group-section[data-group-id="${group.id}"] .tab-group-name`);
+            if (!nameEl) return;
+
+            const input = document.createElement('input');
+            input.className = 'tab-group-name-input';
+            input.value = group.name;
+            nameEl.replaceWith(input);
+            input.focus();
+            input.select();
+
+            let done = false;
+            const finish = (save) => {
+                if (done) return;
+                done = true;
+                const name = input.value.trim();
+                if (save && name && name !== group.name) {
+                    postTabGroupAction('rename_tab_group', {groupId: group.id, name: name});
+                } else {
+                    window.refreshTabs();
+                }
+            };
+            input.addEventListener('keydown', (e) => {
+                e.stopPropagation();
+                if (e.key === 'Enter') finish(true);
+                if (e.key === 'Escape') finish(false);
+            });
+            input.addEventListener('blur', () => finish(true));
+            input.addEventListener('click', (e) => e.stopPropagation());
+        }
+
+        function createTabGroupSection(group) {
+            const section = document.createElement('div');
+            section.className = 'tab-group-section';
+            section.dataset.groupId = group.id;
+            section.style.setProperty('--group-color', GROUP_COLOR_VALUES[group.color] || GROUP_COLOR_VALUES.grey);
+            if (group.collapsed) {
+                section.classList.add('collapsed');
+            }
+
+            const header = document.createElement('div');
+            header.className = 'tab-group-header';
+
+            const dot = document.createElement('span');
+            dot.className = 'tab-group-dot';
+
+            const name = document.createElement('span');
+            name.className = 'tab-group-name';
+            name.textContent = group.name;
+
+            const count = document.createElement('span');
+            count.className = 'tab-group-count';
+            count.textContent = group.tabIds.length;
+
+            header.appendChild(dot);
+            header.appendChild(name);
+            header.appendChild(count);
+
+            header.onclick = () => {
+                playUISound('cursorMove');
+                postTabGroupAction('set_tab_group_collapsed', {groupId: group.id, collapsed: !group.collapsed});
+            };
+            header.ondblclick = (e) => {
+                e.stopPropagation();
+                startTabGroupRename(group);
+            };
+            header.oncontextmenu = (event) => window.showTabGroupContextMenu(event, group);
+
+            const body = document.createElement('div');
+            body.className = 'tab-group-body';
+
+            section.appendChild(header);
+            section.appendChild(body);
+            return { section, body };
+        }
+
+        window.setTabPinned = function(tabId, pinned, index) {
+            const tab = window.tabs.find(t => t.id === tabId);
+            if (!tab) return;
+            tab.pinned = pinned;
+            window.moveTabInList(tabId, index);
+        };
+
+        window.requestMoveTab = function(tabId, toIndex) {
+            window.ipc.postMessage(JSON.stringify({action: 'move_tab', tabId: tabId, toIndex: toIndex}));
+        };
+
+        window.draggedTabId = null;
+
+        function clearDropIndicators() {
+            document.querySelectorAll('.tab.drop-before, .tab.drop-after').forEach(el => {
+                el.classList.remove('drop-before', 'drop-after');
+            });
+        }
+
+        function isDropAfter(event, tabEl) {
+            const rect = tabEl.getBoundingClientRect();
+            if (tabEl.classList.contains('pinned')) {
+                return event.clientX > rect.left + rect.width / 2;
+            }
+            return event.clientY > rect.top + rect.height / 2;
+        }
+
+        function attachTabDragHandlers(tabEl, tabId) {
+            tabEl.draggable = true;
+
+            tabEl.addEventListener('dragstart', (e) => {
+                window.draggedTabId = tabId;
+                e.dataTransfer.effectAllowed = 'move';
+                e.dataTransfer.setData('text/plain', String(tabId));
+                e.dataTransfer.setData(CALM_TAB_TYPE, JSON.stringify({windowNumber: window.windowNumber, tabId: tabId}));
+                tabEl.classList.add('dragging');
+            });
+
+            tabEl.addEventListener('dragend', (e) => {
+                window.draggedTabId = null;
+                tabEl.classList.remove('dragging');
+                clearDropIndicators();
+
+                // Dropping a tab outside the sidebar, where nothing accepted it, detaches it
+                // into a new window. Other windows' sidebars accept the drop themselves.
+                const outside = e.clientX < 0 || e.clientY < 0 ||
+                    e.clientX > window.innerWidth || e.clientY > window.innerHeight;
+                if (outside && e.dataTransfer.dropEffect === 'none' && window.tabs.length > 1) {
+                    window.requestMoveTabToWindow(tabId, null);
+                }
+            });
+
+            tabEl.addEventListener('dragover', (e) => {
+                if (window.draggedTabId === null || window.draggedTabId === tabId) return;
+                e.preventDefault();
+                e.dataTransfer.dropEffect = 'move';
+                const after = isDropAfter(e, tabEl);
+                clearDropIndicators();
+                tabEl.classList.add(after ? 'drop-after' : 'drop-before');
+            });
+
+            tabEl.addEventListener('drop', (e) => {
+                e.preventDefault();
+                const draggedId = window.draggedTabId;
+                clearDropIndicators();
+                if (draggedId === null || draggedId === tabId) return;
+
+                const fromIndex = window.tabs.findIndex(t => t.id === draggedId);
+                let toIndex = window.tabs.findIndex(t => t.id === tabId);
+                if (fromIndex < 0 || toIndex < 0) return;
+                if (isDropAfter(e, tabEl)) toIndex += 1;
+                if (fromIndex < toIndex) toIndex -= 1;
+                if (toIndex !== fromIndex) {
+                    playUISound('cursorMove');
+                    window.requestMoveTab(draggedId, toIndex);
+                }
+            });
+        }
+
+        const CALM_TAB_TYPE = 'application/x-calm-tab';
+
+        window.windowNumber = null;
+        window.otherWindows = [];
+
+        window.setWindowList = function(windowNumber, windows) {
+            window.windowNumber = windowNumber;
+            window.otherWindows = windows;
+        };
+
+        window.requestMoveTabToWindow = function(tabId, toWindow) {
+            const message = {action: 'move_tab_to_window', tabId: tabId};
+            if (toWindow !== null) message.toWindow = toWindow;
+            window.ipc.postMessage(JSON.stringify(message));
+        };
+
+        function describeWindow(win) {
+            const title = win.title || 'New Tab';
+            return `Window ${win.number} · ${title}`;
+        }
+
+        // Tabs dragged in from another window's sidebar.
+        document.addEventListener('dragover', (e) => {
+            if (window.draggedTabId !== null || !e.dataTransfer.types.includes(CALM_TAB_TYPE)) return;
+            e.preventDefault();
+            e.dataTransfer.dropEffect = 'move';
+            document.body.classList.add('tab-drop-target');
+        });
+
+        document.addEventListener('dragleave', (e) => {
+            if (e.relatedTarget === null) {
+                document.body.classList.remove('tab-drop-target');
+            }
+        });
+
+        document.addEventListener('drop', (e) => {
+            document.body.classList.remove('tab-drop-target');
+            if (window.draggedTabId !== null) return;
+
+            let dragged = null;
+            try {
+                dragged = JSON.parse(e.dataTransfer.getData(CALM_TAB_TYPE));
+            } catch (err) {
+                return;
+            }
+            if (!dragged || dragged.windowNumber === window.windowNumber) return;
+
+            e.preventDefault();
+            playUISound('cursorMove');
+            window.ipc.postMessage(JSON.stringify({
+                action: 'move_tab_to_window',
+                tabId: dragged.tabId,
+                fromWindow: dragged.windowNumber,
+                toWindow: window.windowNumber
+            }));
+        });
+
+        window.requestPinTab = function(tabId, pinned) {
+            window.ipc.postMessage(JSON.stringify({action: 'pin_tab', tabId: tabId, pinned: pinned}));
+        };
+
+        function postTabGroupAction(action, fields) {
+            window.ipc.postMessage(JSON.stringify(Object.assign({action: action}, fields)));
+        }
+
+        window.showTabContextMenu = function(event, tabId) {
+            const tab = window.tabs.find(t => t.id === tabId);
+            if (!tab) return;
+
+            const items = [
+                {
+                    label: tab.pinned ? 'Unpin Tab' : 'Pin Tab',
+                    run: () => window.requestPinTab(tabId, !tab.pinned)
+                }
+            ];
+
+            if (!tab.pinned) {
+                const currentGroup = window.tabGroups.find(g => g.tabIds.includes(tabId));
+                items.push({
+                    label: 'Add to New Group',
+                    run: () => postTabGroupAction('create_tab_group', {tabId: tabId})
+                });
+                window.tabGroups
+                    .filter(group => group !== currentGroup)
+                    .forEach(group => items.push({
+                        label: `Add to ${group.name}`,
+                        run: () => postTabGroupAction('add_tab_to_group', {tabId: tabId, groupId: group.id})
+                    }));
+                if (currentGroup) {
+                    items.push({
+                        label: 'Remove from Group',
+                        run: () => postTabGroupAction('remove_tab_from_group', {tabId: tabId})
+                    });
+                }
+            }
+
+            const currentContainer = window.tabContainers[tabId];
+            window.containers
+                .filter(container => !currentContainer || container.name !== currentContainer.name)
+                .forEach(container => items.push({
+                    label: `Reopen in ${container.name}`,
+                    run: () => postTabGroupAction('set_tab_container', {tabId: tabId, container: container.name})
+                }));
+            if (currentContainer) {
+                items.push({
+                    label: 'Reopen Without Container',
+                    run: () => postTabGroupAction('set_tab_container', {tabId: tabId, container: null})
+                });
+                items.push({
+                    label: `Always Open This Site in ${currentContainer.name}`,
+                    run: () => postTabGroupAction('add_container_rule', {tabId: tabId})
+                });
+            }
+
+            if (window.tabs.length > 1) {
+                items.push({
+                    label: 'Move to New Window',
+                    run: () => window.requestMoveTabToWindow(tabId, null)
+                });
+            }
+            window.otherWindows.forEach(win => items.push({
+                label: `Move to ${describeWindow(win)}`,
+                run: () => window.requestMoveTabToWindow(tabId, win.number)
+            }));
+
+            items.push({
+                label: 'Close Tab',
+                run: () => closeTabWithAnimation(tabId)
+            });
+
+            showContextMenu(event, items);
+        };
+
+        window.showTabGroupContextMenu = function(event, group) {
+            const colorNames = Object.keys(GROUP_COLOR_VALUES);
+            const nextColor = colorNames[(colorNames.indexOf(group.color) + 1) % colorNames.length];
+
+            const items = [
+                {
+                    label: 'Rename Group',
+                    run: () => startTabGroupRename(group)
+                },
+                {
+                    label: 'Change Color',
+                    run: () => postTabGroupAction('set_tab_group_color', {groupId: group.id, color: nextColor})
+                },
+                {
+                    label: group.collapsed ? 'Expand Group' : 'Collapse Group',
+                    run: () => postTabGroupAction('set_tab_group_collapsed', {groupId: group.id, collapsed: !group.collapsed})
+                },
+                {
+                    label: 'Move Group to New Window',
+                    run: () => postTabGroupAction('move_tab_group_to_new_window', {groupId: group.id})
+                }
+            ];
+            // Saved groups are written to disk, which private windows never do.
+            if (!window.privateWindow) {
+                items.push({
+                    label: 'Save and Close Group',
+                    run: () => postTabGroupAction('save_tab_group', {groupId: group.id})
+                });
+            }
+            items.push({
+                label: 'Close Group',
+                run: () => postTabGroupAction('close_tab_group', {groupId: group.id})
+            });
+
+            showContextMenu(event, items);
+        };
+
+        function showContextMenu(event, items) {
+            event.preventDefault();
+            event.stopPropagation();
+            window.hideTabContextMenu();
+
+            const menu = document.createElement('div');
+            menu.id = 'tab-context-menu';
+            menu.className = 'tab-context-menu';
+            items.forEach(item => {
+                const itemEl = document.createElement('div');
+                itemEl.className = 'tab-context-menu-item';
+                itemEl.textContent = item.label;
+                itemEl.onclick = (e) => {
+                    e.stopPropagation();
+                    window.hideTabContextMenu();
+                    item.run();
+                };
+                menu.appendChild(itemEl);
+            });
+
+            document.body.appendChild(menu);
+            const left = Math.min(event.clientX, window.innerWidth - menu.offsetWidth - 4);
+            const top = Math.min(event.clientY, window.innerHeight - menu.offsetHeight - 4);
+            menu.style.left = `${Math.max(left, 0)}px`;
+            menu.style.top = `${Math.max(top, 0)}px`;
+        }
+
+        window.hideTabContextMenu = function() {
+            const menu = document.getElementById('tab-context-menu');
+            if (menu) menu.remove();
+        };
+
+        document.addEventListener('click', () => window.hideTabContextMenu());
+
+        window.toggleShields = function() {
+            if (document.getElementById('shield-panel')) {
+                window.hideShields();
+            } else {
+                window.ipc.postMessage(JSON.stringify({action: 'show_shields'}));
+            }
+        };
+
+        window.hideShields = function() {
+            const panel = document.getElementById('shield-panel');
+            if (panel) panel.remove();
+            const shieldBtn = document.getElementById('shield-btn');
+            if (shieldBtn) shieldBtn.classList.remove('active');
+        };
+
+        function appendShieldNote(panel, text) {
+            const note = document.createElement('div');
+            note.className = 'shield-panel-note';
+            note.textContent = text;
+            panel.appendChild(note);
+        }
+
+        // Shows the defenses in effect on the active tab's site and what they blocked on the
+        // page. `shields` is null for pages that are not on the web.
+        window.showShields = function(shields) {
+            window.hideShields();
+
+            const panel = document.createElement('div');
+            panel.id = 'shield-panel';
+            panel.className = 'shield-panel';
+            panel.onclick = (e) => e.stopPropagation();
+
+            const header = document.createElement('div');
+            header.className = 'shield-panel-header';
+            header.textContent = shields ? shields.site : 'Site Shields';
+            panel.appendChild(header);
+
+            if (!shields) {
+                appendShieldNote(panel, 'Shields apply to websites.');
+            } else {
+                if (!shields.editable) {
+                    appendShieldNote(panel, 'Site settings cannot be changed in a private window.');
+                }
+
+                const labels = {};
+                shields.defenses.forEach(defense => {
+                    labels[defense.name] = defense.label;
+                    const row = document.createElement('div');
+                    row.className = 'shield-defense';
+                    row.classList.toggle('enabled', defense.enabled);
+                    row.classList.toggle('overridden', defense.overridden);
+                    row.classList.toggle('readonly', !shields.editable);
+                    row.title = defense.overridden ? 'Changed for this site' : 'Default setting';
+
+                    const label = document.createElement('span');
+                    label.className = 'shield-defense-label';
+                    label.textContent = defense.label;
+                    const state = document.createElement('span');
+                    state.className = 'shield-defense-state';
+                    state.textContent = defense.enabled ? 'on' : 'off';
+                    row.appendChild(label);
+                    row.appendChild(state);
+
+                    if (shields.editable) {
+                        row.onclick = () => {
+                            playUISound('cursorMove');
+                            window.ipc.postMessage(JSON.stringify({
+                                action: 'set_site_shield',
+                                tabId: shields.tabId,
+                                name: defense.name,
+                                enabled: !defense.enabled
+                            }));
+                        };
+                    }
+                    panel.appendChild(row);
+                });
+
+                if (shields.editable && shields.defenses.some(defense => defense.overridden)) {
+                    const reset = document.createElement('button');
+                    reset.className = 'shield-reset';
+                    reset.textContent = 'Use default settings';
+                    reset.onclick = () => {
+                        playUISound('cursorMove');
+                        window.ipc.postMessage(JSON.stringify({
+                            action: 'reset_site_shields',
+                            tabId: shields.tabId
+                        }));
+                    };
+                    panel.appendChild(reset);
+                }
+
+                const title = document.createElement('div');
+                title.className = 'shield-section-title';
+                title.textContent = `Blocked on this page (${shields.blocked.length})`;
+                panel.appendChild(title);
+                if (shields.blocked.length === 0) {
+                    appendShieldNote(panel, 'Nothing blocked yet.');
+                }
+                shields.blocked.forEach(blocked => {
+                    const item = document.createElement('div');
+                    item.className = 'shield-blocked-item';
+                    item.textContent = `${labels[blocked.defense] || blocked.defense}: ${blocked.url}`;
+                    item.title = [blocked.url, blocked.rule, blocked.list].filter(Boolean).join('\n');
+                    panel.appendChild(item);
+                });
+
+                const logButton = document.createElement('button');
+                logButton.className = 'shield-reset';
+                logButton.textContent = 'Open privacy log';
+                logButton.onclick = () => {
+                    playUISound('cursorMove');
+                    window.hideShields();
+                    window.ipc.postMessage(JSON.stringify({action: 'open_url_new_tab', url: 'calm://privacy-log'}));
+                };
+                panel.appendChild(logButton);
+            }
+
+            const shieldBtn = document.getElementById('shield-btn');
+            const top = shieldBtn ? shieldBtn.getBoundingClientRect().bottom + 4 : 56;
+            panel.style.top = `${top}px`;
+            document.body.appendChild(panel);
+            if (shieldBtn) shieldBtn.classList.add('active');
+        };
+
+        document.addEventListener('click', () => window.hideShields());
+
         window.updateTabAudioState = function(tabId, isPlaying) {
             window.tabAudioState[tabId] = isPlaying;
             const indicator = document.getElementById(`audio-indicator-${tabId}`);
@@ -331,9 +931,11 @@ pub fn get_tab_bar_script() -> &'static str {
             }
         };
 
-        window.closeFocusedTab = function() {
+        window.closeFocusedTab = function(force) {
             if (window.focusedTabIndex >= 0 && window.focusedTabIndex < window.tabs.length) {
-                const tabId = window.tabs[window.focusedTabIndex].id;
+                const tab = window.tabs[window.focusedTabIndex];
+                if (tab.pinned && !force) return;
+                const tabId = tab.id;
                 closeTabWithAnimation(tabId);
                 if (window.focusedTabIndex >= window.tabs.length - 1) {
                     window.focusedTabIndex = Math.max(0, window.tabs.length - 2);
@@ -344,6 +946,21 @@ pub fn get_tab_bar_script() -> &'static str {
             }
         };
 
+        window.moveFocusedTab = function(offset) {
+            if (window.focusedTabIndex >= 0 && window.focusedTabIndex < window.tabs.length) {
+                const toIndex = window.focusedTabIndex + offset;
+                if (toIndex < 0 || toIndex >= window.tabs.length) return;
+                window.requestMoveTab(window.tabs[window.focusedTabIndex].id, toIndex);
+            }
+        };
+
+        window.toggleFocusedTabPin = function() {
+            if (window.focusedTabIndex >= 0 && window.focusedTabIndex < window.tabs.length) {
+                const tab = window.tabs[window.focusedTabIndex];
+                window.requestPinTab(tab.id, !tab.pinned);
+            }
+        };
+
         window.jumpToFirstTab = function() {
             if (window.tabs.length > 0) {
                 window.updateFocusedTab(0);
@@ -369,7 +986,15 @@ pub fn get_tab_bar_script() -> &'static str {
             }
 
             if (!isUrlBarFocused) {
-                if (e.key === 'j') {
+                if (e.key === 'J' && e.shiftKey) {
+                    e.preventDefault();
+                    window.moveFocusedTab(1);
+                    return;
+                } else if (e.key === 'K' && e.shiftKey) {
+                    e.preventDefault();
+                    window.moveFocusedTab(-1);
+                    return;
+                } else if (e.key === 'j') {
                     e.preventDefault();
                     window.moveFocusDown();
                     return;
@@ -381,9 +1006,17 @@ pub fn get_tab_bar_script() -> &'static str {
                     e.preventDefault();
                     window.activateFocusedTab();
                     return;
+                } else if (e.key === 'D' && e.shiftKey) {
+                    e.preventDefault();
+                    window.closeFocusedTab(true);
+                    return;
                 } else if (e.key === 'd') {
                     e.preventDefault();
-                    window.closeFocusedTab();
+                    window.closeFocusedTab(false);
+                    return;
+                } else if (e.key === 'p') {
+                    e.preventDefault();
+                    window.toggleFocusedTabPin();
                     return;
                 } else if (e.key === 'n') {
                     e.preventDefault();
@@ -416,6 +1049,7 @@ pub fn get_tab_bar_script() -> &'static str {
 
         document.addEventListener('keydown', (e) => {
             if (e.key === 'Escape') {
+                window.hideTabContextMenu();
                 const urlBar = document.getElementById('url-bar');
                 if (urlBar && document.activeElement === urlBar) {
                     urlBar.blur();
@@ -433,15 +1067,41 @@ pub fn get_tab_bar_script() -> &'static str {
             }
         }, true);
 
+        const MAX_SPLIT_PANES = 4;
+
         window.setSplitGroups = function(groups) {
             window.splitGroups = groups;
+            updateSplitPaneControls();
         };
 
+        // Shows the add pane button while the active tab is split, and the layout toggle once
+        // its group has enough panes to be tiled more than one way.
+        function updateSplitPaneControls() {
+            const addPaneBtn = document.getElementById('add-split-pane-btn');
+            const layoutBtn = document.getElementById('split-layout-btn');
+            const activeGroup = window.splitGroups.find(group => group.tabIds.includes(window.getActiveTabId()));
+            const paneCount = activeGroup ? activeGroup.tabIds.length : 0;
+
+            if (addPaneBtn) {
+                addPaneBtn.classList.toggle('visible', paneCount > 0);
+                addPaneBtn.classList.toggle('hiding', paneCount === 0);
+                addPaneBtn.disabled = paneCount >= MAX_SPLIT_PANES;
+                addPaneBtn.style.opacity = addPaneBtn.disabled ? '0.3' : '';
+            }
+
+            if (layoutBtn) {
+                layoutBtn.classList.toggle('visible', paneCount >= 3);
+                layoutBtn.classList.toggle('hiding', paneCount < 3);
+                const isGrid = activeGroup && activeGroup.layout === 'grid';
+                layoutBtn.title = isGrid ? 'Switch to Main and Stack Layout' : 'Switch to Grid Layout';
+                layoutBtn.dataset.nextLayout = isGrid ? 'main_stack' : 'grid';
+            }
+        }
+
         window.updateSplitUIState = function(activeTabInSplit, canCreateSplit, orientation) {
             const splitViewBtn = document.getElementById('split-view-btn');
             const splitOrientationBtn = document.getElementById('split-orientation-btn');
             const swapPanesBtn = document.getElementById('swap-panes-btn');
-
             const previousOrientation = window.splitViewState.orientation;
             const orientationChanged = previousOrientation !== orientation;
 
@@ -465,6 +1125,8 @@ pub fn get_tab_bar_script() -> &'static str {
                     swapPanesBtn.classList.add('visible');
                 }
 
+                updateSplitPaneControls();
+
                 window.splitViewState.orientation = orientation;
                 if (orientationChanged) {
                     window.updateSplitOrientationIcon(true);
@@ -497,6 +1159,8 @@ pub fn get_tab_bar_script() -> &'static str {
                     swapPanesBtn.classList.remove('visible');
                     swapPanesBtn.classList.add('hiding');
                 }
+
+                updateSplitPaneControls();
             }
         };
 
@@ -565,39 +1229,80 @@ pub fn get_tab_bar_script() -> &'static str {
             const tabBar = document.getElementById('tab-bar');
             if (!tabBar) return;
 
-            const existingTabs = tabBar.querySelectorAll('.tab, .tab-group');
+            const activeTabId = window.getActiveTabId();
+            const existingTabs = tabBar.querySelectorAll('.tab, .tab-group, .pinned-tabs, .tab-group-section');
             existingTabs.forEach(el => el.remove());
 
             if (window.tabs.length === 0) return;
 
             const renderedTabIds = new Set();
+            const inSplitGroup = new Set(window.splitGroups.flatMap(group => group.tabIds));
+
+            const pinnedTabs = window.tabs.filter(tab => tab.pinned && !inSplitGroup.has(tab.id));
+            if (pinnedTabs.length > 0) {
+                const pinnedEl = document.createElement('div');
+                pinnedEl.className = 'pinned-tabs';
+                pinnedTabs.forEach(tab => {
+                    pinnedEl.appendChild(createTabElement(tab, false, null));
+                    renderedTabIds.add(tab.id);
+                });
+                tabBar.appendChild(pinnedEl);
+            }
+
+            const appendTabOrSplit = (container, tab) => {
+                if (renderedTabIds.has(tab.id)) return;
 
-            window.splitGroups.forEach(group => {
-                const primaryTab = window.tabs.find(t => t.id === group.primary);
-                const secondaryTab = window.tabs.find(t => t.id === group.secondary);
+                const splitGroup = window.splitGroups.find(group => group.tabIds.includes(tab.id));
+                const paneTabs = splitGroup
+                    ? splitGroup.tabIds.map(id => window.tabs.find(t => t.id === id)).filter(Boolean)
+                    : [];
 
-                if (primaryTab && secondaryTab) {
+                if (paneTabs.length >= 2) {
                     const groupEl = document.createElement('div');
                     groupEl.className = 'tab-group';
-
-                    const primaryEl = createTabElement(primaryTab, true, 'primary');
-                    const secondaryEl = createTabElement(secondaryTab, true, 'secondary');
-
-                    groupEl.appendChild(primaryEl);
-                    groupEl.appendChild(secondaryEl);
-                    tabBar.appendChild(groupEl);
-
-                    renderedTabIds.add(primaryTab.id);
-                    renderedTabIds.add(secondaryTab.id);
+                    paneTabs.forEach((paneTab, index) => {
+                        groupEl.appendChild(createTabElement(paneTab, true, index === 0 ? 'primary' : 'secondary'));
+                        renderedTabIds.add(paneTab.id);
+                    });
+                    container.appendChild(groupEl);
+                } else {
+                    container.appendChild(createTabElement(tab, false, null));
+                    renderedTabIds.add(tab.id);
                 }
-            });
+            };
 
+            const renderedGroupIds = new Set();
             window.tabs.forEach(tab => {
-                if (!renderedTabIds.has(tab.id)) {
-                    const tabEl = createTabElement(tab, false, null);
-                    tabBar.appendChild(tabEl);
+                if (renderedTabIds.has(tab.id)) return;
+
+                const tabGroup = window.tabGroups.find(group => group.tabIds.includes(tab.id));
+                if (!tabGroup) {
+                    appendTabOrSplit(tabBar, tab);
+                    return;
                 }
+                if (renderedGroupIds.has(tabGroup.id)) return;
+                renderedGroupIds.add(tabGroup.id);
+
+                const { section, body } = createTabGroupSection(tabGroup);
+                tabGroup.tabIds.forEach(id => {
+                    const groupTab = window.tabs.find(t => t.id === id);
+                    if (!groupTab) return;
+                    if (tabGroup.collapsed) {
+                        renderedTabIds.add(id);
+                    } else {
+                        appendTabOrSplit(body, groupTab);
+                    }
+                });
+                tabBar.appendChild(section);
             });
+
+            if (activeTabId !== null) {
+                const activeEl = tabBar.querySelector(`.tab[data-tab-id="${activeTabId}"]`);
+                if (activeEl) activeEl.classList.add('active');
+            }
+            if (window.sidebarFocused && window.focusedTabIndex >= 0) {
+                window.updateFocusedTab(window.focusedTabIndex);
+            }
         };
 
         function createTabElement(tab, isInSplitView, splitPosition) {
@@ -605,6 +1310,15 @@ pub fn get_tab_bar_script() -> &'static str {
             tabEl.className = 'tab';
             tabEl.dataset.tabId = tab.id;
 
+            if (tab.suspended) {
+                tabEl.classList.add('suspended');
+            }
+
+            if (tab.pinned) {
+                tabEl.classList.add('pinned');
+                tabEl.title = tab.title || 'New Tab';
+            }
+
             if (isInSplitView) {
                 tabEl.classList.add('in-split-view');
                 if (splitPosition) {
@@ -645,6 +1359,7 @@ pub fn get_tab_bar_script() -> &'static str {
 
             tabEl.appendChild(favicon);
             tabEl.appendChild(titleSpan);
+            tabEl.appendChild(createBlockedCount(tab.id));
             tabEl.appendChild(audioIndicator);
             tabEl.appendChild(closeBtn);
 
@@ -653,6 +1368,9 @@ pub fn get_tab_bar_script() -> &'static str {
                 window.ipc.postMessage(JSON.stringify({action: 'switch_tab', tabId: tab.id}));
                 window.hideSidebarFocus();
             };
+            tabEl.oncontextmenu = (event) => window.showTabContextMenu(event, tab.id);
+            attachTabDragHandlers(tabEl, tab.id);
+            applyTabContainer(tabEl, tab.id);
 
             return tabEl;
         }
@@ -92,7 +92,7 @@ pub fn get_tab_bar_styles() -> String {
             background: #101010;
         }}
 
-        .tab, .new-tab-btn, .reload-btn, .back-btn, .forward-btn, .downloads-btn, .close-tab, .split-view-btn, .split-orientation-btn, .swap-panes-btn, .close-split-btn {{
+        .tab, .new-tab-btn, .reload-btn, .shield-btn, .back-btn, .forward-btn, .downloads-btn, .close-tab, .split-view-btn, .split-orientation-btn, .swap-panes-btn, .add-split-pane-btn, .split-layout-btn, .close-split-btn {{
             -webkit-app-region: no-drag;
         }}
 
@@ -143,6 +143,17 @@ pub fn get_tab_bar_styles() -> String {
             }}
         }}
 
+        .tab.in-container::after {{
+            content: '';
+            position: absolute;
+            top: 0;
+            left: 0;
+            width: 3px;
+            height: 100%;
+            background: var(--container-color);
+            z-index: 2;
+        }}
+
         .tab > * {{
             position: relative;
             z-index: 1;
@@ -199,6 +210,248 @@ pub fn get_tab_bar_styles() -> String {
             }}
         }}
 
+        .tab.suspended:not(.active) {{
+            border-style: dashed;
+        }}
+
+        .tab.suspended:not(.active) .tab-title,
+        .tab.suspended:not(.active) .tab-favicon {{
+            opacity: 0.4;
+        }}
+
+        .tab.dragging {{
+            opacity: 0.4;
+        }}
+
+        .tab.drop-before {{
+            box-shadow: 0 -2px 0 #ffffff;
+        }}
+
+        .tab.drop-after {{
+            box-shadow: 0 2px 0 #ffffff;
+        }}
+
+        .pinned-tabs .tab.drop-before {{
+            box-shadow: -2px 0 0 #ffffff;
+        }}
+
+        .pinned-tabs .tab.drop-after {{
+            box-shadow: 2px 0 0 #ffffff;
+        }}
+
+        .pinned-tabs {{
+            display: flex;
+            flex-wrap: wrap;
+            gap: 6px;
+            flex-shrink: 0;
+        }}
+
+        .tab.pinned {{
+            width: 40px;
+            justify-content: center;
+            padding: 12px 0;
+        }}
+
+        .tab.pinned .tab-title,
+        .tab.pinned .tab-close {{
+            display: none;
+        }}
+
+        .tab-context-menu {{
+            position: fixed;
+            z-index: 1000;
+            min-width: 140px;
+            background: #1a1a1a;
+            border: 1px solid #333333;
+            padding: 4px 0;
+            -webkit-app-region: no-drag;
+        }}
+
+        .tab-context-menu-item {{
+            padding: 8px 12px;
+            cursor: pointer;
+            color: #e8e8e8;
+            max-width: 220px;
+            overflow: hidden;
+            text-overflow: ellipsis;
+            white-space: nowrap;
+        }}
+
+        .tab-context-menu-item:hover {{
+            background: #ffffff;
+            color: #000000;
+        }}
+
+        .shield-btn.active {{
+            background: #ffffff;
+            color: #000000;
+        }}
+
+        .shield-panel {{
+            position: fixed;
+            z-index: 1000;
+            left: 12px;
+            right: 12px;
+            max-height: 70vh;
+            overflow-y: auto;
+            background: #1a1a1a;
+            border: 1px solid #333333;
+            color: #e8e8e8;
+            font-size: 12px;
+            -webkit-app-region: no-drag;
+        }}
+
+        .shield-panel-header {{
+            padding: 8px 12px;
+            border-bottom: 1px solid #333333;
+            overflow: hidden;
+            text-overflow: ellipsis;
+            white-space: nowrap;
+        }}
+
+        .shield-panel-note {{
+            padding: 8px 12px;
+            color: #888888;
+        }}
+
+        .shield-defense {{
+            display: flex;
+            align-items: center;
+            justify-content: space-between;
+            gap: 8px;
+            padding: 6px 12px;
+            cursor: pointer;
+        }}
+
+        .shield-defense:hover {{
+            background: #ffffff;
+            color: #000000;
+        }}
+
+        .shield-defense.readonly {{
+            cursor: default;
+        }}
+
+        .shield-defense.readonly:hover {{
+            background: transparent;
+            color: #e8e8e8;
+        }}
+
+        .shield-defense-state {{
+            flex-shrink: 0;
+            color: #888888;
+        }}
+
+        .shield-defense.overridden .shield-defense-label::after {{
+            content: ' *';
+        }}
+
+        .shield-defense.enabled .shield-defense-state {{
+            color: #57ab5a;
+        }}
+
+        .shield-section-title {{
+            padding: 8px 12px 4px;
+            border-top: 1px solid #333333;
+            color: #888888;
+        }}
+
+        .shield-blocked-item {{
+            padding: 2px 12px;
+            color: #aaaaaa;
+            overflow: hidden;
+            text-overflow: ellipsis;
+            white-space: nowrap;
+        }}
+
+        .shield-reset {{
+            margin: 8px 12px;
+            padding: 6px 8px;
+            background: #101010;
+            border: 1px solid #333333;
+            color: #e8e8e8;
+            font-family: inherit;
+            font-size: 12px;
+            cursor: pointer;
+        }}
+
+        .shield-reset:hover {{
+            background: #ffffff;
+            color: #000000;
+        }}
+
+        body.tab-drop-target #tab-bar {{
+            box-shadow: inset 0 0 0 2px #ffffff;
+        }}
+
+        .tab-group-section {{
+            display: flex;
+            flex-direction: column;
+            gap: 6px;
+            flex-shrink: 0;
+            padding-left: 6px;
+            border-left: 2px solid var(--group-color);
+        }}
+
+        .tab-group-header {{
+            display: flex;
+            align-items: center;
+            gap: 8px;
+            padding: 4px 6px;
+            cursor: pointer;
+            color: #e8e8e8;
+            font-size: 12px;
+            user-select: none;
+            -webkit-app-region: no-drag;
+        }}
+
+        .tab-group-header:hover {{
+            background: #1a1a1a;
+        }}
+
+        .tab-group-dot {{
+            width: 8px;
+            height: 8px;
+            flex-shrink: 0;
+            background: var(--group-color);
+        }}
+
+        .tab-group-name {{
+            flex: 1;
+            overflow: hidden;
+            text-overflow: ellipsis;
+            white-space: nowrap;
+        }}
+
+        .tab-group-count {{
+            color: #888888;
+        }}
+
+        .tab-group-section.collapsed .tab-group-body {{
+            display: none;
+        }}
+
+        .tab-group-section.collapsed .tab-group-name {{
+            color: #888888;
+        }}
+
+        .tab-group-body {{
+            display: flex;
+            flex-direction: column;
+            gap: 6px;
+        }}
+
+        .tab-group-name-input {{
+            flex: 1;
+            min-width: 0;
+            background: #000000;
+            border: 1px solid var(--group-color);
+            color: #e8e8e8;
+            font: inherit;
+            padding: 2px 4px;
+            outline: none;
+        }}
+
         .tab-favicon {{
             width: 16px;
             height: 16px;
@@ -226,6 +479,25 @@ pub fn get_tab_bar_styles() -> String {
         }}
 
 
+        .tab-blocked-count {{
+            display: none;
+            flex-shrink: 0;
+            padding: 0 4px;
+            font-size: 10px;
+            line-height: 14px;
+            color: #888888;
+            border: 1px solid #333333;
+        }}
+
+        .tab-blocked-count.visible {{
+            display: block;
+        }}
+
+        .tab.active .tab-blocked-count {{
+            color: #000000;
+            border-color: #000000;
+        }}
+
         .tab-audio-indicator {{
             display: none;
             width: 12px;
@@ -288,7 +560,7 @@ pub fn get_tab_bar_styles() -> String {
             -webkit-app-region: no-drag;
         }}
 
-        .new-tab-btn, .reload-btn, .back-btn, .forward-btn, .split-view-btn, .split-orientation-btn, .swap-panes-btn, .close-split-btn {{
+        .new-tab-btn, .reload-btn, .shield-btn, .back-btn, .forward-btn, .split-view-btn, .split-orientation-btn, .swap-panes-btn, .add-split-pane-btn, .split-layout-btn, .close-split-btn {{
             width: 32px;
             height: 32px;
             background: #1a1a1a;
@@ -303,13 +575,13 @@ pub fn get_tab_bar_styles() -> String {
             flex-shrink: 0;
         }}
 
-        .new-tab-btn:hover, .reload-btn:hover, .back-btn:hover, .forward-btn:hover, .split-view-btn:hover, .split-orientation-btn:hover, .swap-panes-btn:hover, .close-split-btn:hover {{
+        .new-tab-btn:hover, .reload-btn:hover, .shield-btn:hover, .back-btn:hover, .forward-btn:hover, .split-view-btn:hover, .split-orientation-btn:hover, .swap-panes-btn:hover, .add-split-pane-btn:hover, .split-layout-btn:hover, .close-split-btn:hover {{
             background: #ffffff;
             color: #000000;
             border-color: #ffffff;
         }}
 
-        .new-tab-btn:active, .reload-btn:active, .back-btn:active, .forward-btn:active, .split-view-btn:active, .split-orientation-btn:active, .swap-panes-btn:active, .close-split-btn:active {{
+        .new-tab-btn:active, .reload-btn:active, .shield-btn:active, .back-btn:active, .forward-btn:active, .split-view-btn:active, .split-orientation-btn:active, .swap-panes-btn:active, .add-split-pane-btn:active, .split-layout-btn:active, .close-split-btn:active {{
             background: #101010;
             color: #ffffff;
         }}
@@ -319,20 +591,20 @@ pub fn get_tab_bar_styles() -> String {
             color: #000000;
         }}
 
-        .split-orientation-btn, .swap-panes-btn {{
+        .split-orientation-btn, .swap-panes-btn, .add-split-pane-btn, .split-layout-btn {{
             opacity: 0;
             transform: translateX(40px);
             pointer-events: none;
             transition: opacity 0.3s ease-out, transform 0.3s ease-out;
         }}
 
-        .split-orientation-btn.visible, .swap-panes-btn.visible {{
+        .split-orientation-btn.visible, .swap-panes-btn.visible, .add-split-pane-btn.visible, .split-layout-btn.visible {{
             opacity: 1;
             transform: translateX(0);
             pointer-events: auto;
         }}
 
-        .split-orientation-btn.hiding, .swap-panes-btn.hiding {{
+        .split-orientation-btn.hiding, .swap-panes-btn.hiding, .add-split-pane-btn.hiding, .split-layout-btn.hiding {{
             opacity: 0;
             transform: translateX(40px);
         }}
@@ -638,6 +910,40 @@ pub fn get_tab_bar_styles() -> String {
             }}
         }}
 
+        .private-badge, .profile-badge {{
+            position: absolute;
+            top: 48px;
+            left: 12px;
+            height: 32px;
+            display: flex;
+            align-items: center;
+            gap: 6px;
+            padding: 0 8px;
+            background: #1a1a1a;
+            border: 1px solid #333333;
+            color: #cccccc;
+            z-index: 101;
+        }}
+
+        .private-badge {{
+            background: #2a1f3d;
+            border-color: #6d4fa8;
+            color: #d9c8ff;
+        }}
+
+        body.private-window #sidebar-container {{
+            background: #16111f;
+        }}
+
+        body.private-window::after {{
+            background: #6d4fa8;
+        }}
+
+        body.private-window .url-bar {{
+            background: #16111f;
+            border-color: #4a3870;
+        }}
+
         .split-view-controls {{
             position: absolute;
             top: 48px;
@@ -1,5 +1,7 @@
 use crate::config::Config;
 use crate::debug_log;
+use crate::session::{BrowserSession, WindowState};
+use crate::tabs::manager::DetachedTab;
 use crate::tabs::TabManager;
 use crate::ui;
 use std::cell::RefCell;
@@ -16,9 +18,13 @@ const DOWNLOAD_SIDEBAR_WIDTH: i32 = 360;
 /// Components that make up a complete browser window.
 pub struct BrowserWindowComponents {
     pub window: Rc<tao::window::Window>,
+    /// Identifies the window to the user and in tab move requests.
+    pub number: usize,
     pub tab_manager: Rc<RefCell<TabManager>>,
     pub tab_bar_webview: Rc<WebView>,
     pub download_overlay: Rc<WebView>,
+    pub bookmarks_panel: Rc<WebView>,
+    pub bookmarks_visible: Rc<RefCell<bool>>,
     #[allow(dead_code)]
     pub command_prompt_overlay: Rc<RefCell<Option<WebView>>>,
     #[allow(dead_code)]
@@ -27,6 +33,9 @@ pub struct BrowserWindowComponents {
     pub should_quit: Rc<RefCell<bool>>,
     pub toggle_downloads_debouncer: Rc<RefCell<crate::utils::debouncer::Debouncer>>,
     pub config: Rc<RefCell<Config>>,
+    /// Private windows keep their web data in a temporary directory and are never saved to the
+    /// session or the recently closed stack.
+    pub private: bool,
 }
 
 /// Creates a new browser window with all necessary components including tab bar, download overlay, and initial tab.
@@ -37,6 +46,7 @@ pub struct BrowserWindowComponents {
 /// * `config` - Application configuration
 /// * `initial_url` - URL to load in the first tab
 /// * `use_welcome_html` - Whether to show welcome page instead of initial URL
+/// * `private` - Whether to open a private window with isolated, non-persistent web data
 ///
 /// # Returns
 ///
@@ -46,11 +56,124 @@ pub fn create_browser_window<T>(
     config: Rc<RefCell<Config>>,
     initial_url: String,
     use_welcome_html: bool,
+    private: bool,
 ) -> wry::Result<BrowserWindowComponents> {
+    let components = build_browser_window(event_loop, config, private)?;
+    open_initial_tab(&components, &initial_url, use_welcome_html);
+    Ok(components)
+}
+
+/// Recreates a browser window from a saved session window state.
+/// Falls back to the default URL if none of the saved tabs could be restored.
+///
+/// # Arguments
+///
+/// * `event_loop` - The event loop window target
+/// * `config` - Application configuration
+/// * `state` - The saved tabs, active tab and split groups of the window
+/// * `private` - Whether to rebuild the tabs in a private window
+///
+/// # Returns
+///
+/// Browser window components on success
+pub fn restore_browser_window<T>(
+    event_loop: &EventLoopWindowTarget<T>,
+    config: Rc<RefCell<Config>>,
+    state: &WindowState,
+    private: bool,
+) -> wry::Result<BrowserWindowComponents> {
+    let components = build_browser_window(event_loop, Rc::clone(&config), private)?;
+
+    let restored_tabs = components
+        .tab_manager
+        .borrow_mut()
+        .restore_window_state(&components.window, state);
+    debug_log!("Restored {} tabs into window {:?}", restored_tabs, components.window.id());
+
+    if restored_tabs == 0 {
+        let default_url = crate::convert_file_url(&config.borrow().default_url);
+        open_initial_tab(&components, &default_url, false);
+    }
+
+    Ok(components)
+}
+
+/// Creates a browser window holding a tab moved out of another window.
+///
+/// # Arguments
+///
+/// * `event_loop` - The event loop window target
+/// * `config` - Application configuration
+/// * `tab` - The tab detached from its previous window
+/// * `private` - Whether the tab came from, and goes to, a private window
+///
+/// # Returns
+///
+/// Browser window components on success
+pub fn create_window_with_tab<T>(
+    event_loop: &EventLoopWindowTarget<T>,
+    config: Rc<RefCell<Config>>,
+    tab: DetachedTab,
+    private: bool,
+) -> wry::Result<BrowserWindowComponents> {
+    let components = build_browser_window(event_loop, config, private)?;
+    components.tab_manager.borrow_mut().attach_tab(tab);
+    Ok(components)
+}
+
+/// Creates a browser window showing the session recovery page after an unclean shutdown.
+///
+/// # Arguments
+///
+/// * `event_loop` - The event loop window target
+/// * `config` - Application configuration
+/// * `session` - The session left behind by the previous run
+///
+/// # Returns
+///
+/// Browser window components on success
+pub fn create_session_recovery_window<T>(
+    event_loop: &EventLoopWindowTarget<T>,
+    config: Rc<RefCell<Config>>,
+    session: &BrowserSession,
+) -> wry::Result<BrowserWindowComponents> {
+    let components = build_browser_window(event_loop, config, false)?;
+
+    // The page is served from its own origin so that only it can restore or dismiss the session.
+    crate::window::set_recovery_session(Some(session.clone()));
+    let tab_result = components
+        .tab_manager
+        .borrow_mut()
+        .create_tab(&components.window, "calm://recovery");
+    if let Ok(tab_id) = tab_result {
+        components.tab_manager.borrow_mut().switch_to_tab(tab_id);
+        let script = format!(
+            "window.addTab({}, 'calm://recovery'); window.setActiveTab({}); window.updateUrlBar('calm://recovery');",
+            tab_id, tab_id
+        );
+        let _ = components.tab_bar_webview.evaluate_script(&script);
+    }
+
+    Ok(components)
+}
+
+/// Builds the window, tab bar and download overlay without opening any tab.
+fn build_browser_window<T>(
+    event_loop: &EventLoopWindowTarget<T>,
+    config: Rc<RefCell<Config>>,
+    private: bool,
+) -> wry::Result<BrowserWindowComponents> {
+    let title = match (private, crate::paths::is_default_profile()) {
+        (true, true) => "Calm Browser - Private Window".to_string(),
+        (true, false) => format!("Calm Browser - {} - Private Window", crate::paths::profile()),
+        (false, true) => "Calm Browser - Privacy-Focused".to_string(),
+        (false, false) => format!("Calm Browser - {}", crate::paths::profile()),
+    };
+
     #[cfg(target_os = "macos")]
     let window = Rc::new(
         WindowBuilder::new()
-            .with_title("Calm Browser - Privacy-Focused")
+            .with_title(&title)
             .with_inner_size(LogicalSize::new(1200, 800))
             .with_title_hidden(true)
             .with_titlebar_transparent(true)
@@ -62,16 +185,20 @@ pub fn create_browser_window<T>(
     #[cfg(not(target_os = "macos"))]
     let window = Rc::new(
         WindowBuilder::new()
-            .with_title("Calm Browser - Privacy-Focused")
+            .with_title(&title)
             .with_inner_size(LogicalSize::new(1200, 800))
             .build(event_loop)
             .unwrap(),
     );
 
-    let tab_manager = Rc::new(RefCell::new(TabManager::new(
-        TAB_SIDEBAR_WIDTH,
-        Rc::clone(&config),
-    )));
+    let window_number = super::next_window_number();
+    let tab_manager = if private {
+        TabManager::new_private(TAB_SIDEBAR_WIDTH, Rc::clone(&config))
+    } else {
+        TabManager::new(TAB_SIDEBAR_WIDTH, Rc::clone(&config))
+    };
+    let tab_manager = Rc::new(RefCell::new(tab_manager));
+    tab_manager.borrow_mut().set_window(Rc::clone(&window));
     let tab_bar_webview_ref: Rc<RefCell<Option<Rc<WebView>>>> = Rc::new(RefCell::new(None));
     let download_overlay_ref: Rc<RefCell<Option<Rc<WebView>>>> = Rc::new(RefCell::new(None));
     let sidebar_visible = Rc::new(RefCell::new(false));
@@ -80,12 +207,19 @@ pub fn create_browser_window<T>(
         Rc::new(RefCell::new(crate::utils::debouncer::Debouncer::new(500)));
     let command_prompt_visible = Rc::new(RefCell::new(false));
     let command_prompt_overlay_ref: Rc<RefCell<Option<WebView>>> = Rc::new(RefCell::new(None));
+    let bookmarks_panel_ref: Rc<RefCell<Option<Rc<WebView>>>> = Rc::new(RefCell::new(None));
+    let bookmarks_visible = Rc::new(RefCell::new(false));
 
     let window_size = window.inner_size();
 
     let tab_bar_webview = Rc::new(
         WebViewBuilder::new()
-            .with_html(ui::get_complete_tab_bar_html(config.borrow().ui.vim_mode, config.borrow().ui.sounds))
+            .with_html(ui::get_complete_tab_bar_html(
+                config.borrow().ui.vim_mode,
+                config.borrow().ui.sounds,
+                private,
+                &config.borrow().containers,
+            ))
             .with_transparent(true)
             .with_bounds(Rect {
                 position: tao::dpi::LogicalPosition::new(0, 0).into(),
@@ -102,6 +236,8 @@ pub fn create_browser_window<T>(
                 let toggle_downloads_debouncer = Rc::clone(&toggle_downloads_debouncer);
                 let command_prompt_visible = Rc::clone(&command_prompt_visible);
                 let command_prompt_overlay_ref = Rc::clone(&command_prompt_overlay_ref);
+                let bookmarks_panel_ref = Rc::clone(&bookmarks_panel_ref);
+                let bookmarks_visible = Rc::clone(&bookmarks_visible);
                 move |request| {
                     let body = request.body();
 
@@ -151,9 +287,133 @@ pub fn create_browser_window<T>(
                                     }
                                 }
                             }
+                            Some("move_tab") => {
+                                if let (Some(tab_id), Some(to_index)) = (data["tabId"].as_u64(), data["toIndex"].as_u64()) {
+                                    if let Some(index) = tab_manager.borrow_mut().move_tab(tab_id as usize, to_index as usize) {
+                                        debug_log!("Tab {} moved to index {}", tab_id, index);
+                                    }
+                                }
+                            }
+                            Some("create_tab_group") => {
+                                if let Some(tab_id) = data["tabId"].as_u64() {
+                                    let name = data["name"].as_str();
+                                    if let Some(group_id) = tab_manager.borrow_mut().create_tab_group(tab_id as usize, name) {
+                                        debug_log!("Created tab group {} with tab {}", group_id, tab_id);
+                                    }
+                                }
+                            }
+                            Some("add_tab_to_group") => {
+                                if let (Some(tab_id), Some(group_id)) = (data["tabId"].as_u64(), data["groupId"].as_u64()) {
+                                    tab_manager.borrow_mut().add_tab_to_group(tab_id as usize, group_id as usize);
+                                }
+                            }
+                            Some("remove_tab_from_group") => {
+                                if let Some(tab_id) = data["tabId"].as_u64() {
+                                    tab_manager.borrow_mut().remove_tab_from_tab_group(tab_id as usize);
+                                }
+                            }
+                            Some("rename_tab_group") => {
+                                if let (Some(group_id), Some(name)) = (data["groupId"].as_u64(), data["name"].as_str()) {
+                                    tab_manager.borrow_mut().rename_tab_group(group_id as usize, name);
+                                }
+                            }
+                            Some("set_tab_group_color") => {
+                                if let (Some(group_id), Some(color)) = (data["groupId"].as_u64(), data["color"].as_str()) {
+                                    tab_manager.borrow_mut().set_tab_group_color(group_id as usize, color);
+                                }
+                            }
+                            Some("set_tab_group_collapsed") => {
+                                if let (Some(group_id), Some(collapsed)) = (data["groupId"].as_u64(), data["collapsed"].as_bool()) {
+                                    tab_manager.borrow_mut().set_tab_group_collapsed(group_id as usize, collapsed);
+                                }
+                            }
+                            Some(action @ ("close_tab_group" | "move_tab_group_to_new_window" | "save_tab_group")) => {
+                                if let Some(group_id) = data["groupId"].as_u64() {
+                                    let group_id = group_id as usize;
+                                    let closed_ids = match action {
+                                        "close_tab_group" => tab_manager.borrow_mut().close_tab_group(group_id),
+                                        "move_tab_group_to_new_window" => tab_manager.borrow_mut().move_tab_group_to_new_window(group_id),
+                                        _ => tab_manager.borrow_mut().save_tab_group(group_id),
+                                    };
+                                    debug_log!("{} closed {} tabs of group {}", action, closed_ids.len(), group_id);
+
+                                    if let Some(ref webview) = *tab_bar_webview_ref.borrow() {
+                                        for tab_id in &closed_ids {
+                                            let script = format!("window.removeTab({});", tab_id);
+                                            let _ = webview.evaluate_script(&script);
+                                        }
+                                    }
+                                    if !closed_ids.is_empty() && tab_manager.borrow().get_tab_count() == 0 {
+                                        *should_quit.borrow_mut() = true;
+                                    }
+                                }
+                            }
+                            Some("pin_tab") => {
+                                if let (Some(tab_id), Some(pinned)) = (data["tabId"].as_u64(), data["pinned"].as_bool()) {
+                                    if tab_manager.borrow_mut().set_tab_pinned(tab_id as usize, pinned) {
+                                        debug_log!("Tab {} pinned: {}", tab_id, pinned);
+                                    }
+                                }
+                            }
+                            Some("set_tab_container") => {
+                                if let Some(tab_id) = data["tabId"].as_u64() {
+                                    let container = data["container"].as_str().map(str::to_string);
+                                    if tab_manager.borrow_mut().set_tab_container(tab_id as usize, container, None) {
+                                        debug_log!("Tab {} reopened in container {:?}", tab_id, data["container"]);
+                                    }
+                                }
+                            }
+                            Some("show_shields") => {
+                                tab_manager.borrow().show_shields();
+                            }
+                            Some("set_site_shield") => {
+                                if let (Some(tab_id), Some(name), Some(enabled)) =
+                                    (data["tabId"].as_u64(), data["name"].as_str(), data["enabled"].as_bool())
+                                {
+                                    if tab_manager.borrow_mut().set_site_shield(tab_id as usize, Some(name), enabled) {
+                                        debug_log!("Turned {} {} for the site of tab {}", name, if enabled { "on" } else { "off" }, tab_id);
+                                    }
+                                }
+                            }
+                            Some("reset_site_shields") => {
+                                if let Some(tab_id) = data["tabId"].as_u64() {
+                                    if tab_manager.borrow_mut().set_site_shield(tab_id as usize, None, true) {
+                                        debug_log!("Reset the site settings of tab {}", tab_id);
+                                    }
+                                }
+                            }
+                            Some("add_container_rule") => {
+                                if let Some(tab_id) = data["tabId"].as_u64() {
+                                    if let Some(host) = tab_manager.borrow_mut().add_container_rule_for_tab(tab_id as usize) {
+                                        debug_log!("{} now always opens in the container of tab {}", host, tab_id);
+                                    }
+                                }
+                            }
+                            Some("move_tab_to_window") => {
+                                if let Some(tab_id) = data["tabId"].as_u64() {
+                                    let from_window = data["fromWindow"]
+                                        .as_u64()
+                                        .map_or(window_number, |number| number as usize);
+                                    super::push_window_request(super::WindowRequest::MoveTab {
+                                        from_window,
+                                        tab_id: tab_id as usize,
+                                        to_window: data["toWindow"].as_u64().map(|number| number as usize),
+                                    });
+                                }
+                            }
                             Some("quit_app") => {
                                 *should_quit.borrow_mut() = true;
                             }
+                            Some("reopen_closed_tab") => {
+                                if !tab_manager.borrow_mut().reopen_closed(&window, None) {
+                                    debug_log!("Nothing to reopen");
+                                }
+                            }
+                            Some("reopen_closed_entry") => {
+                                if let Some(entry_id) = data["entryId"].as_u64() {
+                                    tab_manager.borrow_mut().reopen_closed(&window, Some(entry_id));
+                                }
+                            }
                             Some("new_tab") => {
                                 debug_log!("=== IPC new_tab action received ===");
                                 let tab_count_before = tab_manager.borrow().get_tab_count();
@@ -183,18 +443,32 @@ pub fn create_browser_window<T>(
                             }
                             Some("open_url_new_tab") => {
                                 if let Some(url) = data["url"].as_str() {
-                                    let tab_result = tab_manager.borrow_mut().create_tab(&window, url);
-                                    if let Ok(tab_id) = tab_result {
-                                        tab_manager.borrow_mut().switch_to_tab(tab_id);
+                                    let background = data["background"].as_bool().unwrap_or(false)
+                                        && config.borrow().performance.lazy_tab_loading;
+                                    let escaped_url = serde_json::to_string(&url)
+                                        .unwrap_or_else(|_| "\"\"".to_string());
+
+                                    if background {
+                                        let tab_id = tab_manager.borrow_mut().create_lazy_tab(url);
                                         if let Some(ref webview) = *tab_bar_webview_ref.borrow() {
-                                            let escaped_url = serde_json::to_string(&url)
-                                                .unwrap_or_else(|_| "\"\"".to_string());
                                             let script = format!(
-                                                "window.addTab({}, {}); window.setActiveTab({}); window.updateUrlBar({});",
-                                                tab_id, escaped_url, tab_id, escaped_url
+                                                "window.addTab({}, {}); window.setTabSuspended({}, true);",
+                                                tab_id, escaped_url, tab_id
                                             );
                                             let _ = webview.evaluate_script(&script);
                                         }
+                                    } else {
+                                        let tab_result = tab_manager.borrow_mut().create_tab(&window, url);
+                                        if let Ok(tab_id) = tab_result {
+                                            tab_manager.borrow_mut().switch_to_tab(tab_id);
+                                            if let Some(ref webview) = *tab_bar_webview_ref.borrow() {
+                                                let script = format!(
+                                                    "window.addTab({}, {}); window.setActiveTab({}); window.updateUrlBar({});",
+                                                    tab_id, escaped_url, tab_id, escaped_url
+                                                );
+                                                let _ = webview.evaluate_script(&script);
+                                            }
+                                        }
                                     }
                                 }
                             }
@@ -229,6 +503,9 @@ pub fn create_browser_window<T>(
                                     if let Some(ref overlay) = *download_overlay_ref.borrow() {
                                         if should_show {
                                             debug_log!("Opening download manager");
+                                            if let Some(ref panel) = *bookmarks_panel_ref.borrow() {
+                                                hide_bookmarks_panel(panel, &bookmarks_visible);
+                                            }
                                             let _ = overlay.set_visible(true);
                                             std::thread::sleep(std::time::Duration::from_millis(10));
                                             let script = "window.toggleVisibility(true);";
@@ -273,8 +550,7 @@ pub fn create_browser_window<T>(
                                 };
                             }
                             Some("open_settings") => {
-                                let settings_html = ui::get_settings_html();
-                                let tab_result = tab_manager.borrow_mut().create_tab_with_html(&window, &settings_html);
+                                let tab_result = tab_manager.borrow_mut().create_tab(&window, "calm://settings");
                                 if let Ok(tab_id) = tab_result {
                                     tab_manager.borrow_mut().switch_to_tab(tab_id);
 
@@ -376,7 +652,7 @@ pub fn create_browser_window<T>(
                                     }
 
                                     match cfg.save() {
-                                        Ok(_) => debug_log!("Settings saved successfully to ~/.calm.yml"),
+                                        Ok(_) => debug_log!("Settings saved successfully to {}", crate::paths::config_file().display()),
                                         Err(e) => debug_log!("ERROR: Failed to save settings: {:?}", e),
                                     }
 
@@ -402,6 +678,8 @@ pub fn create_browser_window<T>(
                                     let cfg = config.borrow();
                                     let url = if url_str.is_empty() {
                                         crate::convert_file_url(&cfg.default_url)
+                                    } else if let Some(url) = crate::bookmarks::with_bookmarks(|store| store.expand_keyword(url_str)) {
+                                        url
                                     } else if url_str.contains("://") {
                                         crate::convert_file_url(url_str)
                                     } else {
@@ -425,6 +703,7 @@ pub fn create_browser_window<T>(
                                     } else {
                                         let tab_result = tab_manager.borrow_mut().create_tab(&window, &url);
                                         if let Ok(tab_id) = tab_result {
+                                            tab_manager.borrow().mark_typed_navigation(tab_id);
                                             tab_manager.borrow_mut().switch_to_tab(tab_id);
                                             if let Some(ref webview) = *tab_bar_webview_ref.borrow() {
                                                 let escaped_url = serde_json::to_string(&url)
@@ -494,6 +773,52 @@ pub fn create_browser_window<T>(
                                     let _ = webview.evaluate_script("window.refreshTabs();");
                                 }
                             }
+                            Some("add_split_pane") => {
+                                debug_log!("=== IPC add_split_pane action received ===");
+                                tab_manager.borrow_mut().add_split_pane(&window);
+                                if let Some(ref webview) = *tab_bar_webview_ref.borrow() {
+                                    let _ = webview.evaluate_script(&tab_manager.borrow().get_split_ui_script());
+                                    let _ = webview.evaluate_script("window.refreshTabs();");
+                                }
+                            }
+                            Some("set_split_layout") => {
+                                if let Some(layout) = data["layout"].as_str() {
+                                    tab_manager
+                                        .borrow_mut()
+                                        .set_split_layout(&window, crate::tabs::split_view::SplitLayout::from_name(layout));
+                                    if let Some(ref webview) = *tab_bar_webview_ref.borrow() {
+                                        let _ = webview.evaluate_script(&tab_manager.borrow().get_split_ui_script());
+                                        let _ = webview.evaluate_script("window.refreshTabs();");
+                                    }
+                                }
+                            }
+                            Some("split_divider_drag") => {
+                                if let (Some(divider), Some(phase)) = (data["divider"].as_u64(), data["phase"].as_str()) {
+                                    tab_manager.borrow_mut().drag_split_divider(
+                                        &window,
+                                        divider as usize,
+                                        phase,
+                                        data["x"].as_f64().unwrap_or(0.0),
+                                        data["y"].as_f64().unwrap_or(0.0),
+                                    );
+                                }
+                            }
+                            Some("focus_split_pane") => {
+                                let direction = data["direction"]
+                                    .as_str()
+                                    .and_then(crate::tabs::split_view::PaneDirection::from_name);
+                                if let (Some(tab_id), Some(direction)) = (data["tabId"].as_u64(), direction) {
+                                    let moved = tab_manager
+                                        .borrow_mut()
+                                        .focus_split_pane(&window, tab_id as usize, direction);
+                                    if moved {
+                                        if let Some(ref webview) = *tab_bar_webview_ref.borrow() {
+                                            let _ = webview.evaluate_script(&tab_manager.borrow().get_split_ui_script());
+                                            let _ = webview.evaluate_script("window.refreshTabs();");
+                                        }
+                                    }
+                                }
+                            }
                             Some("keyboard_shortcut") => {
                                 if let Some(shortcut) = data["shortcut"].as_str() {
                                     debug_log!("=== IPC keyboard_shortcut '{}' received ===", shortcut);
@@ -517,6 +842,9 @@ pub fn create_browser_window<T>(
 
                                                 if let Some(ref overlay) = *download_overlay_ref.borrow() {
                                                     if should_show {
+                                                        if let Some(ref panel) = *bookmarks_panel_ref.borrow() {
+                                                            hide_bookmarks_panel(panel, &bookmarks_visible);
+                                                        }
                                                         let _ = overlay.set_visible(true);
                                                         std::thread::sleep(std::time::Duration::from_millis(10));
                                                         let script = "window.toggleVisibility(true);";
@@ -577,11 +905,110 @@ pub fn create_browser_window<T>(
                                                                     *command_prompt_visible_for_ipc.borrow_mut() = false;
                                                                     *command_prompt_overlay_for_ipc.borrow_mut() = None;
                                                                 }
+                                                                Some("search_history") => {
+                                                                    if let Some(query) = data["query"].as_str() {
+                                                                        let suggestions_json = tab_manager_for_prompt.borrow().get_suggestions_json(query, 8);
+                                                                        if let Some(ref webview) = *command_prompt_overlay_for_ipc.borrow() {
+                                                                            let script = format!(
+                                                                                "if (window.showHistorySuggestions) {{ window.showHistorySuggestions({}, {}); }}",
+                                                                                suggestions_json,
+                                                                                serde_json::to_string(query).unwrap_or_else(|_| "\"\"".to_string())
+                                                                            );
+                                                                            let _ = webview.evaluate_script(&script);
+                                                                        }
+                                                                    }
+                                                                }
+                                                                Some("switch_to_open_tab") => {
+                                                                    if let Some(tab_id) = data["tabId"].as_u64() {
+                                                                        let tab_id = tab_id as usize;
+                                                                        tab_manager_for_prompt.borrow_mut().switch_to_tab(tab_id);
+                                                                        if tab_manager_for_prompt.borrow().is_tab_in_split_group(tab_id) {
+                                                                            tab_manager_for_prompt.borrow_mut().update_split_view_layout(&window_for_prompt, None);
+                                                                        } else {
+                                                                            tab_manager_for_prompt.borrow_mut().resize_all_tabs(&window_for_prompt);
+                                                                        }
+                                                                        if let Some(ref webview) = *tab_bar_for_prompt.borrow() {
+                                                                            let _ = webview.evaluate_script(&tab_manager_for_prompt.borrow().get_split_ui_script());
+                                                                            let _ = webview.evaluate_script("window.refreshTabs();");
+                                                                        }
+                                                                    }
+                                                                    *command_prompt_visible_for_ipc.borrow_mut() = false;
+                                                                    *command_prompt_overlay_for_ipc.borrow_mut() = None;
+                                                                }
+                                                                Some("switch_tab_group") => {
+                                                                    if let Some(group_id) = data["groupId"].as_u64() {
+                                                                        tab_manager_for_prompt
+                                                                            .borrow_mut()
+                                                                            .switch_to_tab_group(&window_for_prompt, group_id as usize);
+                                                                    }
+                                                                    *command_prompt_visible_for_ipc.borrow_mut() = false;
+                                                                    *command_prompt_overlay_for_ipc.borrow_mut() = None;
+                                                                }
+                                                                Some("open_saved_tab_group") => {
+                                                                    if let Some(saved_id) = data["savedId"].as_u64() {
+                                                                        tab_manager_for_prompt
+                                                                            .borrow_mut()
+                                                                            .open_saved_tab_group(&window_for_prompt, saved_id);
+                                                                    }
+                                                                    *command_prompt_visible_for_ipc.borrow_mut() = false;
+                                                                    *command_prompt_overlay_for_ipc.borrow_mut() = None;
+                                                                }
+                                                                Some("move_active_tab_to_window") => {
+                                                                    let active_tab_id = tab_manager_for_prompt.borrow().get_active_tab_id();
+                                                                    if let Some(tab_id) = active_tab_id {
+                                                                        super::push_window_request(super::WindowRequest::MoveTab {
+                                                                            from_window: window_number,
+                                                                            tab_id,
+                                                                            to_window: data["toWindow"].as_u64().map(|number| number as usize),
+                                                                        });
+                                                                    }
+                                                                    *command_prompt_visible_for_ipc.borrow_mut() = false;
+                                                                    *command_prompt_overlay_for_ipc.borrow_mut() = None;
+                                                                }
+                                                                Some("open_profile_picker") => {
+                                                                    super::push_window_request(super::WindowRequest::OpenProfilePicker);
+                                                                    *command_prompt_visible_for_ipc.borrow_mut() = false;
+                                                                    *command_prompt_overlay_for_ipc.borrow_mut() = None;
+                                                                }
+                                                                Some("new_private_window") => {
+                                                                    super::push_window_request(super::WindowRequest::NewPrivateWindow);
+                                                                    *command_prompt_visible_for_ipc.borrow_mut() = false;
+                                                                    *command_prompt_overlay_for_ipc.borrow_mut() = None;
+                                                                }
+                                                                Some("save_page_for_later") => {
+                                                                    if let Some(webview) = tab_manager_for_prompt.borrow().get_active_tab_webview() {
+                                                                        let _ = webview.evaluate_script(
+                                                                            "if (window.__calmSaveForLater) { window.__calmSaveForLater(); }",
+                                                                        );
+                                                                    }
+                                                                    *command_prompt_visible_for_ipc.borrow_mut() = false;
+                                                                    *command_prompt_overlay_for_ipc.borrow_mut() = None;
+                                                                }
+                                                                Some("open_reading_list") => {
+                                                                    if let Some(ref webview) = *tab_bar_for_prompt.borrow() {
+                                                                        let _ = webview.evaluate_script(
+                                                                            "window.ipc.postMessage(JSON.stringify({action: 'keyboard_shortcut', shortcut: 'open_reading_list'}));",
+                                                                        );
+                                                                    }
+                                                                    *command_prompt_visible_for_ipc.borrow_mut() = false;
+                                                                    *command_prompt_overlay_for_ipc.borrow_mut() = None;
+                                                                }
+                                                                Some("reopen_closed_entry") => {
+                                                                    if let Some(entry_id) = data["entryId"].as_u64() {
+                                                                        tab_manager_for_prompt
+                                                                            .borrow_mut()
+                                                                            .reopen_closed(&window_for_prompt, Some(entry_id));
+                                                                    }
+                                                                    *command_prompt_visible_for_ipc.borrow_mut() = false;
+                                                                    *command_prompt_overlay_for_ipc.borrow_mut() = None;
+                                                                }
                                                                 Some("command_prompt_navigate") => {
                                                                     if let Some(url_str) = data["url"].as_str() {
                                                                         let cfg = config_for_prompt.borrow();
                                                                         let url = if url_str.is_empty() {
                                                                             crate::convert_file_url(&cfg.default_url)
+                                                                        } else if let Some(url) = crate::bookmarks::with_bookmarks(|store| store.expand_keyword(url_str)) {
+                                                                            url
                                                                         } else if url_str.contains("://") {
                                                                             crate::convert_file_url(url_str)
                                                                         } else {
@@ -604,6 +1031,7 @@ pub fn create_browser_window<T>(
 
                                                                         let tab_result = tab_manager_for_prompt.borrow_mut().create_tab(&window_for_prompt, &url);
                                                                         if let Ok(tab_id) = tab_result {
+                                                                            tab_manager_for_prompt.borrow().mark_typed_navigation(tab_id);
                                                                             tab_manager_for_prompt.borrow_mut().switch_to_tab(tab_id);
 
                                                                             if let Some(ref webview) = *tab_bar_for_prompt.borrow() {
@@ -630,6 +1058,19 @@ pub fn create_browser_window<T>(
                                                         let script = format!("if (window.setHistory) {{ window.setHistory({}); }}", history_json);
                                                         let _ = webview.evaluate_script(&script);
 
+                                                        let closed_entries = crate::tabs::recently_closed::with_recently_closed(|store| store.get_recent(10));
+                                                        let closed_json = serde_json::to_string(&closed_entries).unwrap_or_else(|_| "[]".to_string());
+                                                        let script = format!("if (window.setRecentlyClosed) {{ window.setRecentlyClosed({}); }}", closed_json);
+                                                        let _ = webview.evaluate_script(&script);
+
+                                                        let workspaces_json = tab_manager.borrow().get_workspaces_json();
+                                                        let script = format!("if (window.setWorkspaces) {{ window.setWorkspaces({}); }}", workspaces_json);
+                                                        let _ = webview.evaluate_script(&script);
+
+                                                        let windows_json = super::other_windows_json(window_number);
+                                                        let script = format!("if (window.setWindows) {{ window.setWindows({}); }}", windows_json);
+                                                        let _ = webview.evaluate_script(&script);
+
                                                         *command_prompt_overlay_ref.borrow_mut() = Some(webview);
                                                         *command_prompt_visible.borrow_mut() = true;
                                                     }
@@ -645,6 +1086,7 @@ pub fn create_browser_window<T>(
                                                 *should_quit.borrow_mut() = true;
                                             } else {
                                                 let active_tab_id = tab_manager.borrow().get_active_tab_id();
+                                                let active_tab_id = active_tab_id.filter(|&id| !tab_manager.borrow().is_tab_pinned(id));
                                                 if let Some(active_tab_id) = active_tab_id {
                                                     tab_manager.borrow_mut().close_tab(active_tab_id);
                                                     if let Some(ref webview) = *tab_bar_webview_ref.borrow() {
@@ -657,6 +1099,54 @@ pub fn create_browser_window<T>(
                                         "new_window" => {
                                             debug_log!("New window shortcut not supported from IPC (requires event loop)");
                                         }
+                                        "toggle_bookmarks" => {
+                                            if let Some(ref panel) = *bookmarks_panel_ref.borrow() {
+                                                if *bookmarks_visible.borrow() {
+                                                    hide_bookmarks_panel(panel, &bookmarks_visible);
+                                                    tab_manager.borrow_mut().resize_all_tabs(&window);
+                                                } else {
+                                                    show_bookmarks_panel(panel, &bookmarks_visible, &download_overlay_ref, &sidebar_visible);
+                                                    tab_manager.borrow_mut().resize_all_tabs_with_sidebar(
+                                                        &window,
+                                                        DOWNLOAD_SIDEBAR_WIDTH as u32,
+                                                    );
+                                                }
+                                            }
+                                        }
+                                        "open_reading_list" => {
+                                            if let Some(ref panel) = *bookmarks_panel_ref.borrow() {
+                                                show_bookmarks_panel(panel, &bookmarks_visible, &download_overlay_ref, &sidebar_visible);
+                                                tab_manager.borrow_mut().resize_all_tabs_with_sidebar(
+                                                    &window,
+                                                    DOWNLOAD_SIDEBAR_WIDTH as u32,
+                                                );
+                                                let _ = panel.evaluate_script("window.showPanelView('reading');");
+                                            }
+                                        }
+                                        "bookmark_page" => {
+                                            let active_url = tab_manager.borrow().get_active_tab_url();
+                                            if let (Some(url), Some(panel)) = (active_url, &*bookmarks_panel_ref.borrow()) {
+                                                let title = tab_manager.borrow().get_active_tab_title().unwrap_or_else(|| url.clone());
+                                                let (bookmark_id, is_new) = crate::bookmarks::update_bookmarks(|store| match store.find_by_url(&url) {
+                                                    Some(bookmark) => (bookmark.id, false),
+                                                    None => (store.add(&url, &title, None), true),
+                                                });
+                                                debug_log!("Bookmark {} for {} (new: {})", bookmark_id, url, is_new);
+
+                                                show_bookmarks_panel(panel, &bookmarks_visible, &download_overlay_ref, &sidebar_visible);
+                                                tab_manager.borrow_mut().resize_all_tabs_with_sidebar(
+                                                    &window,
+                                                    DOWNLOAD_SIDEBAR_WIDTH as u32,
+                                                );
+                                                let script = format!("window.editBookmark({}, {});", bookmark_id, is_new);
+                                                let _ = panel.evaluate_script(&script);
+                                            }
+                                        }
+                                        "reopen_closed_tab" => {
+                                            if !tab_manager.borrow_mut().reopen_closed(&window, None) {
+                                                debug_log!("Nothing to reopen");
+                                            }
+                                        }
                                         "toggle_split_view" => {
                                             let _ = tab_manager.borrow_mut().toggle_split_view(&window);
                                             if let Some(ref webview) = *tab_bar_webview_ref.borrow() {
@@ -768,58 +1258,252 @@ pub fn create_browser_window<T>(
         }
     }
 
-    {
-        let mut manager = tab_manager.borrow_mut();
-        let tab_result = if use_welcome_html {
-            let welcome_html = ui::get_welcome_html();
-            manager.create_tab_with_html(&window, &welcome_html)
-        } else {
-            manager.create_tab(&window, &initial_url)
-        };
+    let bookmarks_panel = Rc::new(
+        WebViewBuilder::new()
+            .with_html(ui::get_bookmarks_panel_html())
+            .with_bounds(Rect {
+                position: tao::dpi::LogicalPosition::new(
+                    (window_size.width as i32) - DOWNLOAD_SIDEBAR_WIDTH,
+                    0,
+                )
+                .into(),
+                size: tao::dpi::LogicalSize::new(DOWNLOAD_SIDEBAR_WIDTH as u32, window_size.height)
+                    .into(),
+            })
+            .with_visible(false)
+            .with_ipc_handler({
+                let tab_manager = Rc::clone(&tab_manager);
+                let window = Rc::clone(&window);
+                let tab_bar_webview = Rc::clone(&tab_bar_webview);
+                let bookmarks_panel_ref = Rc::clone(&bookmarks_panel_ref);
+                let bookmarks_visible = Rc::clone(&bookmarks_visible);
+                move |request| {
+                    let body = request.body();
+                    let Ok(data) = serde_json::from_str::<serde_json::Value>(body) else {
+                        return;
+                    };
 
-        if let Ok(tab_id) = tab_result {
-            manager.switch_to_tab(tab_id);
-            let display_url = if use_welcome_html {
-                "calm://welcome".to_string()
-            } else {
-                initial_url.clone()
-            };
-            let escaped_url =
-                serde_json::to_string(&display_url).unwrap_or_else(|_| "\"\"".to_string());
-            let url_bar_display = if use_welcome_html {
-                "''".to_string()
-            } else {
-                escaped_url.clone()
-            };
-            let script = format!(
-                "window.addTab({}, {}); window.setActiveTab({}); window.updateUrlBar({});",
-                tab_id, escaped_url, tab_id, url_bar_display
-            );
-            let _ = tab_bar_webview.evaluate_script(&script);
-
-            let ui_state = manager.get_split_ui_state();
-            let orientation_str = ui_state
-                .active_group_orientation
-                .as_deref()
-                .unwrap_or("vertical");
-            let state_script = format!(
-                "if (window.updateSplitUIState) {{ window.updateSplitUIState({}, {}, '{}'); }}",
-                ui_state.active_tab_in_split, ui_state.can_create_split, orientation_str
-            );
-            let _ = tab_bar_webview.evaluate_script(&state_script);
-        }
-    }
+                    let changed = match data["action"].as_str() {
+                        Some("add_bookmark") => {
+                            if let Some(url) = data["url"].as_str() {
+                                let title = data["title"].as_str().unwrap_or(url);
+                                let folder_id = data["folderId"].as_u64();
+                                crate::bookmarks::update_bookmarks(|store| store.add(url, title, folder_id));
+                            }
+                            true
+                        }
+                        Some("update_bookmark") => {
+                            if let Some(id) = data["id"].as_u64() {
+                                match serde_json::from_value::<crate::bookmarks::BookmarkEdit>(data.clone()) {
+                                    Ok(edit) => {
+                                        crate::bookmarks::update_bookmarks(|store| store.update(id, edit));
+                                    }
+                                    Err(e) => debug_log!("Invalid bookmark edit: {}", e),
+                                }
+                            }
+                            true
+                        }
+                        Some("remove_bookmark") => {
+                            if let Some(id) = data["id"].as_u64() {
+                                crate::bookmarks::update_bookmarks(|store| store.remove(id));
+                            }
+                            true
+                        }
+                        Some("add_bookmark_folder") => {
+                            let name = data["name"].as_str().unwrap_or("");
+                            let parent_id = data["parentId"].as_u64();
+                            crate::bookmarks::update_bookmarks(|store| store.add_folder(name, parent_id));
+                            true
+                        }
+                        Some("rename_bookmark_folder") => {
+                            if let (Some(folder_id), Some(name)) = (data["folderId"].as_u64(), data["name"].as_str()) {
+                                crate::bookmarks::update_bookmarks(|store| store.rename_folder(folder_id, name));
+                            }
+                            true
+                        }
+                        Some("move_bookmark_folder") => {
+                            if let Some(folder_id) = data["folderId"].as_u64() {
+                                let parent_id = data["parentId"].as_u64();
+                                crate::bookmarks::update_bookmarks(|store| store.move_folder(folder_id, parent_id));
+                            }
+                            true
+                        }
+                        Some("remove_bookmark_folder") => {
+                            if let Some(folder_id) = data["folderId"].as_u64() {
+                                let removed = crate::bookmarks::update_bookmarks(|store| store.remove_folder(folder_id));
+                                debug_log!("Removed bookmark folder {} with {} bookmarks", folder_id, removed);
+                            }
+                            true
+                        }
+                        Some(action @ ("open_bookmark" | "open_reading_item")) => {
+                            let url = if action == "open_bookmark" {
+                                data["url"].as_str().map(crate::convert_file_url)
+                            } else {
+                                let original = data["original"].as_bool().unwrap_or(false);
+                                data["id"]
+                                    .as_u64()
+                                    .and_then(|id| crate::reading_list::update_reading_list(|list| list.open(id, original)))
+                            };
+                            if let Some(url) = url {
+                                let active_tab_id = tab_manager.borrow().get_active_tab_id();
+                                match active_tab_id.filter(|_| !data["newTab"].as_bool().unwrap_or(false)) {
+                                    Some(tab_id) => tab_manager.borrow_mut().navigate_to(tab_id, &url),
+                                    None => {
+                                        let tab_result = tab_manager.borrow_mut().create_tab(&window, &url);
+                                        if let Ok(tab_id) = tab_result {
+                                            tab_manager.borrow_mut().switch_to_tab(tab_id);
+                                            tab_manager.borrow_mut().resize_all_tabs_with_sidebar(
+                                                &window,
+                                                DOWNLOAD_SIDEBAR_WIDTH as u32,
+                                            );
+                                            let escaped_url = serde_json::to_string(&url).unwrap_or_else(|_| "\"\"".to_string());
+                                            let script = format!(
+                                                "window.addTab({}, {}); window.setActiveTab({}); window.updateUrlBar({});",
+                                                tab_id, escaped_url, tab_id, escaped_url
+                                            );
+                                            let _ = tab_bar_webview.evaluate_script(&script);
+                                        }
+                                    }
+                                }
+                            }
+                            action == "open_reading_item"
+                        }
+                        Some("set_reading_item_read") => {
+                            if let Some(id) = data["id"].as_u64() {
+                                let read = data["read"].as_bool().unwrap_or(true);
+                                crate::reading_list::update_reading_list(|list| list.set_read(id, read));
+                            }
+                            true
+                        }
+                        Some("remove_reading_item") => {
+                            if let Some(id) = data["id"].as_u64() {
+                                crate::reading_list::remove_article(id);
+                            }
+                            true
+                        }
+                        Some("close_bookmarks_panel") => {
+                            if let Some(ref panel) = *bookmarks_panel_ref.borrow() {
+                                hide_bookmarks_panel(panel, &bookmarks_visible);
+                            }
+                            tab_manager.borrow_mut().resize_all_tabs(&window);
+                            false
+                        }
+                        _ => false,
+                    };
+
+                    if changed {
+                        if let Some(ref panel) = *bookmarks_panel_ref.borrow() {
+                            send_panel_data(panel);
+                        }
+                    }
+                }
+            })
+            .build_as_child(window.as_ref())?,
+    );
+    *bookmarks_panel_ref.borrow_mut() = Some(Rc::clone(&bookmarks_panel));
 
     Ok(BrowserWindowComponents {
         window,
+        number: window_number,
         tab_manager,
         tab_bar_webview,
         download_overlay,
+        bookmarks_panel,
+        bookmarks_visible,
         command_prompt_overlay: command_prompt_overlay_ref,
         command_prompt_visible,
         sidebar_visible,
         should_quit,
         toggle_downloads_debouncer,
         config,
+        private,
     })
 }
+
+/// Opens the first tab of a freshly built window, either the welcome page or the given URL.
+fn open_initial_tab(
+    components: &BrowserWindowComponents,
+    initial_url: &str,
+    use_welcome_html: bool,
+) {
+    let mut manager = components.tab_manager.borrow_mut();
+    let tab_result = if use_welcome_html {
+        let welcome_html = ui::get_welcome_html();
+        manager.create_tab_with_html(&components.window, &welcome_html)
+    } else {
+        manager.create_tab(&components.window, initial_url)
+    };
+
+    if let Ok(tab_id) = tab_result {
+        manager.switch_to_tab(tab_id);
+        let display_url = if use_welcome_html {
+            "calm://welcome".to_string()
+        } else {
+            initial_url.to_string()
+        };
+        let escaped_url =
+            serde_json::to_string(&display_url).unwrap_or_else(|_| "\"\"".to_string());
+        let url_bar_display = if use_welcome_html {
+            "''".to_string()
+        } else {
+            escaped_url.clone()
+        };
+        let script = format!(
+            "window.addTab({}, {}); window.setActiveTab({}); window.updateUrlBar({});",
+            tab_id, escaped_url, tab_id, url_bar_display
+        );
+        let _ = components.tab_bar_webview.evaluate_script(&script);
+
+        let ui_state = manager.get_split_ui_state();
+        let orientation_str = ui_state
+            .active_group_orientation
+            .as_deref()
+            .unwrap_or("vertical");
+        let state_script = format!(
+            "if (window.updateSplitUIState) {{ window.updateSplitUIState({}, {}, '{}'); }}",
+            ui_state.active_tab_in_split, ui_state.can_create_split, orientation_str
+        );
+        let _ = components.tab_bar_webview.evaluate_script(&state_script);
+    }
+}
+
+/// Sends the bookmarks and the reading list, which share the bookmarks panel.
+fn send_panel_data(panel: &WebView) {
+    let bookmarks_json = crate::bookmarks::with_bookmarks(|store| store.to_json());
+    let reading_list_json = crate::reading_list::with_reading_list(|list| list.to_json());
+    let script = format!(
+        "window.setBookmarks({}); window.setReadingList({});",
+        bookmarks_json, reading_list_json
+    );
+    let _ = panel.evaluate_script(&script);
+}
+
+/// Slides the bookmarks panel in with fresh bookmarks, closing the downloads sidebar that
+/// takes the same place. The caller resizes the tabs around it.
+fn show_bookmarks_panel(
+    panel: &WebView,
+    bookmarks_visible: &RefCell<bool>,
+    download_overlay_ref: &RefCell<Option<Rc<WebView>>>,
+    downloads_visible: &RefCell<bool>,
+) {
+    if std::mem::take(&mut *downloads_visible.borrow_mut()) {
+        if let Some(ref overlay) = *download_overlay_ref.borrow() {
+            let _ = overlay.evaluate_script("window.toggleVisibility(false);");
+            let _ = overlay.set_visible(false);
+        }
+    }
+
+    *bookmarks_visible.borrow_mut() = true;
+    send_panel_data(panel);
+    let _ = panel.set_visible(true);
+    let _ = panel.evaluate_script("window.toggleVisibility(true);");
+    let _ = panel.focus();
+}
+
+/// Hides the bookmarks panel. The caller resizes the tabs to fill its place.
+pub fn hide_bookmarks_panel(panel: &WebView, bookmarks_visible: &RefCell<bool>) {
+    if std::mem::take(&mut *bookmarks_visible.borrow_mut()) {
+        let _ = panel.evaluate_script("window.toggleVisibility(false);");
+        let _ = panel.set_visible(false);
+    }
+}
@@ -113,6 +113,8 @@ impl WindowManager {
                 .with_html(ui::get_complete_tab_bar_html(
                     self.config.borrow().ui.vim_mode,
                     self.config.borrow().ui.sounds,
+                    false,
+                    &self.config.borrow().containers,
                 ))
                 .with_transparent(true)
                 .with_bounds(Rect {
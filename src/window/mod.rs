@@ -1,6 +1,14 @@
 mod browser_window;
 pub mod builder;
 mod manager;
+mod requests;
 mod session;
 
-pub use builder::{create_browser_window, BrowserWindowComponents};
+pub use builder::{
+    create_browser_window, create_session_recovery_window, create_window_with_tab,
+    hide_bookmarks_panel, restore_browser_window, BrowserWindowComponents,
+};
+pub use requests::{
+    next_window_number, other_windows_json, push_window_request, recovery_session,
+    set_open_windows, set_recovery_session, take_window_requests, WindowRequest, WindowSummary,
+};
@@ -0,0 +1,96 @@
+use crate::session::{BrowserSession, WindowState};
+use serde::Serialize;
+use std::cell::{Cell, RefCell};
+
+/// Window-level work requested from webview IPC handlers that needs the event loop to carry out.
+pub enum WindowRequest {
+    /// Reopen the windows and tabs of a (possibly filtered) saved session.
+    RestoreSession(BrowserSession),
+    /// The user declined to restore the previous session after a crash.
+    DismissSessionRecovery,
+    /// Reopen a window taken from the recently closed stack.
+    ReopenClosedWindow(WindowState),
+    /// Open a new window holding tabs moved out of another window, private if they came from a
+    /// private window.
+    OpenWindow { state: WindowState, private: bool },
+    /// Open a new private window at the default URL.
+    NewPrivateWindow,
+    /// Open a window showing the profile picker.
+    OpenProfilePicker,
+    /// Move a tab from one window to another, or to a new window when `to_window` is `None`.
+    /// Windows are identified by their number.
+    MoveTab {
+        from_window: usize,
+        tab_id: usize,
+        to_window: Option<usize>,
+    },
+}
+
+/// An open window as listed in "Move to window" menus.
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowSummary {
+    pub number: usize,
+    pub title: String,
+    #[serde(rename = "tabCount")]
+    pub tab_count: usize,
+    /// Tabs only move between windows that are both private or both not.
+    pub private: bool,
+}
+
+thread_local! {
+    static PENDING_REQUESTS: RefCell<Vec<WindowRequest>> = RefCell::new(Vec::new());
+    static OPEN_WINDOWS: RefCell<Vec<WindowSummary>> = RefCell::new(Vec::new());
+    static NEXT_WINDOW_NUMBER: Cell<usize> = Cell::new(1);
+    static RECOVERY_SESSION: RefCell<Option<BrowserSession>> = RefCell::new(None);
+}
+
+/// Returns a number identifying a new window to the user and across webviews.
+pub fn next_window_number() -> usize {
+    NEXT_WINDOW_NUMBER.with(|next| {
+        let number = next.get();
+        next.set(number + 1);
+        number
+    })
+}
+
+/// Replaces the list of open windows offered as tab move targets.
+pub fn set_open_windows(windows: Vec<WindowSummary>) {
+    OPEN_WINDOWS.with(|open| *open.borrow_mut() = windows);
+}
+
+/// Returns the open windows other than `number` that its tabs can move to as JSON.
+pub fn other_windows_json(number: usize) -> String {
+    OPEN_WINDOWS.with(|open| {
+        let open = open.borrow();
+        let private = open
+            .iter()
+            .any(|window| window.number == number && window.private);
+        let others: Vec<WindowSummary> = open
+            .iter()
+            .filter(|window| window.number != number && window.private == private)
+            .cloned()
+            .collect();
+        serde_json::to_string(&others).unwrap_or_else(|_| "[]".to_string())
+    })
+}
+
+/// Sets the session the recovery page at `calm://recovery` offers, or clears it once the user
+/// restored or dismissed it.
+pub fn set_recovery_session(session: Option<BrowserSession>) {
+    RECOVERY_SESSION.with(|recovery| *recovery.borrow_mut() = session);
+}
+
+/// Returns the session the recovery page offers, if recovery is still pending.
+pub fn recovery_session() -> Option<BrowserSession> {
+    RECOVERY_SESSION.with(|recovery| recovery.borrow().clone())
+}
+
+/// Queues a request to be handled on the next event loop iteration.
+pub fn push_window_request(request: WindowRequest) {
+    PENDING_REQUESTS.with(|requests| requests.borrow_mut().push(request));
+}
+
+/// Takes all queued requests in the order they were pushed.
+pub fn take_window_requests() -> Vec<WindowRequest> {
+    PENDING_REQUESTS.with(|requests| std::mem::take(&mut *requests.borrow_mut()))
+}
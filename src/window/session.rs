@@ -56,9 +56,9 @@ pub struct WindowSessionManager {
 impl WindowSessionManager {
     /// Creates a new session manager with default session file path.
     pub fn new() -> Self {
-        let mut path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-        path.push(".calm_windows.yml");
-        Self { session_file: path }
+        Self {
+            session_file: crate::paths::session_file("windows.yml"),
+        }
     }
 
     /// Loads window sessions from disk.